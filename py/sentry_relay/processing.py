@@ -32,6 +32,8 @@ __all__ = [
     "validate_sampling_configuration",
     "normalize_project_config",
     "normalize_global_config",
+    "preview_event",
+    "preview_span",
 ]
 
 
@@ -350,3 +352,77 @@ def normalize_global_config(
     except Exception:
         # Catch all errors since json.loads implementation can change.
         raise ValueError(rv)
+
+
+def _preview_config(
+    project_config, global_config, root_sampling_config, dsc, client_ip
+):
+    return {
+        "project_config": project_config,
+        "global_config": global_config or {},
+        "root_sampling_config": root_sampling_config,
+        "dsc": dsc,
+        "client_ip": client_ip,
+    }
+
+
+def preview_event(
+    event,
+    project_config,
+    global_config=None,
+    root_sampling_config=None,
+    dsc=None,
+    client_ip=None,
+    json_dumps: Callable[[Any], Any] = json.dumps,
+    json_loads: Callable[[str | bytes], Any] = json.loads,
+):
+    """Preview inbound filters, dynamic sampling and metric extraction for an event.
+
+    Returns a dictionary with the ``filter`` that drops the event, a matching
+    ``report_only_filter``, the ``sampling`` match with ``sample_rate``,
+    ``decision`` and ``matched_rules``, and the extracted ``metrics``.
+
+    :param event: the normalized event.
+    :param project_config: the config of the project the event is sent to.
+    :param global_config: the global config.
+    :param root_sampling_config: the sampling config of the trace root project,
+        defaults to the sampling config of ``project_config``.
+    :param dsc: the dynamic sampling context of the trace.
+    :param client_ip: the IP address of the client that sent the event.
+    :param json_dumps: a function that stringifies python objects
+    :param json_loads: a function that parses and converts JSON strings
+    """
+    config = _preview_config(
+        project_config, global_config, root_sampling_config, dsc, client_ip
+    )
+    raw_rv = rustcall(
+        lib.relay_preview_event,
+        encode_str(json_dumps(event)),
+        encode_str(json_dumps(config)),
+    )
+    return json_loads(decode_str(raw_rv, free=True))
+
+
+def preview_span(
+    span,
+    project_config,
+    global_config=None,
+    root_sampling_config=None,
+    dsc=None,
+    client_ip=None,
+    json_dumps: Callable[[Any], Any] = json.dumps,
+    json_loads: Callable[[str | bytes], Any] = json.loads,
+):
+    """Preview inbound filters, dynamic sampling and metric extraction for a span.
+
+    See :func:`preview_event` for the parameters and the result.
+    """
+    config = _preview_config(
+        project_config, global_config, root_sampling_config, dsc, client_ip
+    )
+    raw_rv = rustcall(
+        lib.relay_preview_span,
+        encode_str(json_dumps(span)),
+        encode_str(json_dumps(config)),
+    )
+    return json_loads(decode_str(raw_rv, free=True))
@@ -351,3 +351,42 @@ def test_global_config_unparsable():
         str(e.value)
         == "invalid value: integer `-5`, expected usize at line 1 column 45"
     )
+
+
+def test_preview_event():
+    project_config = {
+        "filterSettings": {"releases": {"releases": ["1.0.*"]}},
+        "sampling": {
+            "version": 2,
+            "rules": [
+                {
+                    "id": 1,
+                    "type": "transaction",
+                    "samplingValue": {"type": "sampleRate", "value": 1.0},
+                    "condition": {"op": "and", "inner": []},
+                }
+            ],
+        },
+        "metricExtraction": {
+            "version": 1,
+            "metrics": [
+                {"category": "transaction", "mri": "c:transactions/preview@none"}
+            ],
+        },
+    }
+    event = {
+        "event_id": "52df9022835246eeb317dbd739ccd059",
+        "type": "transaction",
+        "release": "1.0.1",
+        "start_timestamp": 1597976300.0,
+        "timestamp": 1597976302.0,
+    }
+
+    preview = sentry_relay.preview_event(event, project_config)
+    assert preview["filter"] == "release-version"
+    assert preview["sampling"] == {
+        "sample_rate": 1.0,
+        "decision": "keep",
+        "matched_rules": [1],
+    }
+    assert [m["name"] for m in preview["metrics"]] == ["c:transactions/preview@none"]
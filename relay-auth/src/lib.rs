@@ -826,6 +826,150 @@ impl Signature {
 /// This type is typically obtained by borrowing from an owned [`Signature`].
 pub struct SignatureRef<'a>(pub &'a str);
 
+/// Raised to indicate errors when parsing or verifying an [`IngestToken`].
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum IngestTokenError {
+    /// Raised if the token is structurally invalid.
+    #[error("malformed ingest token")]
+    Malformed,
+    /// Raised if the token is not signed by any of the given keys.
+    #[error("ingest token cannot be verified")]
+    Unverifiable,
+    /// Raised if the token has expired.
+    #[error("ingest token is expired")]
+    Expired,
+}
+
+/// The claims carried by an [`IngestToken`].
+///
+/// Claims are issued by the backend of a customer and bind a token to a project key. Optionally,
+/// a token can be restricted to a set of data categories, a user and a release.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct IngestTokenClaims {
+    /// The public key of the DSN this token is issued for.
+    #[serde(rename = "pk")]
+    pub public_key: String,
+    /// The time after which the token is no longer accepted.
+    #[serde(rename = "exp")]
+    pub expires: UnixTimestamp,
+    /// Names of data categories that may be ingested with this token.
+    ///
+    /// If empty, all data categories are allowed.
+    #[serde(rename = "cat", default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<String>,
+    /// The user ID this token is bound to, if any.
+    #[serde(rename = "uid", default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    /// The release this token is bound to, if any.
+    #[serde(rename = "rel", default, skip_serializing_if = "Option::is_none")]
+    pub release: Option<String>,
+}
+
+/// A short-lived token signed by a per-project key that authorizes ingestion.
+///
+/// The token has the format `{claims}.{signature}`, where each component is:
+///  - `claims`: A URL-safe base64 encoding of the JSON serialized [`IngestTokenClaims`].
+///  - `signature`: A URL-safe base64 encoding of the ed25519 signature of the encoded claims.
+///
+/// Parsing a token only validates its structure. Use [`IngestToken::verify`] to check the
+/// signature and expiry before trusting the claims.
+#[derive(Clone, PartialEq)]
+pub struct IngestToken {
+    encoded_claims: String,
+    signature: ed25519_dalek::Signature,
+    claims: IngestTokenClaims,
+}
+
+impl IngestToken {
+    /// Signs the given claims with the secret key of a project.
+    pub fn sign(claims: IngestTokenClaims, key: &SecretKey) -> Self {
+        let json = serde_json::to_vec(&claims).expect("ingest token claims serialize to JSON");
+        let encoded_claims = BASE64URL_NOPAD.encode(&json);
+        let signature = key.inner.sign(encoded_claims.as_bytes());
+
+        Self {
+            encoded_claims,
+            signature,
+            claims,
+        }
+    }
+
+    /// Returns the claims of the token without verifying them.
+    pub fn unverified_claims(&self) -> &IngestTokenClaims {
+        &self.claims
+    }
+
+    /// Verifies the token against any of the provided public keys and returns its claims.
+    ///
+    /// The token is valid if it is signed by one of the keys and has not expired at `now`. Signatures
+    /// are checked strictly, rejecting weak keys and malleable signatures.
+    pub fn verify(
+        &self,
+        public_keys: &[PublicKey],
+        now: DateTime<Utc>,
+    ) -> Result<&IngestTokenClaims, IngestTokenError> {
+        let verified = public_keys.iter().any(|key| {
+            key.inner
+                .verify_strict(self.encoded_claims.as_bytes(), &self.signature)
+                .is_ok()
+        });
+
+        if !verified {
+            return Err(IngestTokenError::Unverifiable);
+        }
+
+        match self.claims.expires.as_datetime() {
+            Some(expires) if expires > now => Ok(&self.claims),
+            _ => Err(IngestTokenError::Expired),
+        }
+    }
+}
+
+impl FromStr for IngestToken {
+    type Err = IngestTokenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (encoded_claims, encoded_signature) =
+            s.split_once('.').ok_or(IngestTokenError::Malformed)?;
+
+        let signature = BASE64URL_NOPAD
+            .decode(encoded_signature.as_bytes())
+            .ok()
+            .and_then(|bytes| ed25519_dalek::Signature::from_slice(&bytes).ok())
+            .ok_or(IngestTokenError::Malformed)?;
+
+        let claims = BASE64URL_NOPAD
+            .decode(encoded_claims.as_bytes())
+            .ok()
+            .and_then(|json| serde_json::from_slice(&json).ok())
+            .ok_or(IngestTokenError::Malformed)?;
+
+        Ok(Self {
+            encoded_claims: encoded_claims.to_owned(),
+            signature,
+            claims,
+        })
+    }
+}
+
+impl fmt::Display for IngestToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let signature = BASE64URL_NOPAD.encode(&self.signature.to_bytes());
+        write!(f, "{}.{}", self.encoded_claims, signature)
+    }
+}
+
+impl fmt::Debug for IngestToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The token is a bearer credential, so only the claims are printed.
+        f.debug_struct("IngestToken")
+            .field("claims", &self.claims)
+            .finish_non_exhaustive()
+    }
+}
+
+relay_common::impl_str_serde!(IngestToken, "an ingest token");
+
 /// Verifies a timestamp `ts` is not in the future and not expired.
 fn is_valid_time(ts: DateTime<Utc>, start_time: DateTime<Utc>, max_age: Duration) -> bool {
     let diff = start_time - ts;
@@ -1217,4 +1361,88 @@ mod tests {
             )
             .unwrap();
     }
+
+    fn ingest_claims(expires: UnixTimestamp) -> IngestTokenClaims {
+        IngestTokenClaims {
+            public_key: "e12d836b15bb49d7bbf99e64295d995b".to_owned(),
+            expires,
+            categories: vec!["error".to_owned()],
+            user_id: None,
+            release: Some("1.0.0".to_owned()),
+        }
+    }
+
+    #[test]
+    fn test_ingest_token_roundtrip() {
+        let (sk, pk) = generate_key_pair();
+        let expires = UnixTimestamp::now() + std::time::Duration::from_secs(60);
+        let token = IngestToken::sign(ingest_claims(expires), &sk);
+
+        let parsed: IngestToken = token.to_string().parse().unwrap();
+        assert_eq!(parsed, token);
+        assert_eq!(
+            parsed.verify(&[pk], Utc::now()).unwrap(),
+            &ingest_claims(expires)
+        );
+    }
+
+    #[test]
+    fn test_ingest_token_verify_any() {
+        let (sk, pk) = generate_key_pair();
+        let (_, other_pk) = generate_key_pair();
+        let expires = UnixTimestamp::now() + std::time::Duration::from_secs(60);
+        let token = IngestToken::sign(ingest_claims(expires), &sk);
+
+        assert_eq!(
+            token.verify(std::slice::from_ref(&other_pk), Utc::now()),
+            Err(IngestTokenError::Unverifiable)
+        );
+        assert!(token.verify(&[other_pk, pk], Utc::now()).is_ok());
+        assert_eq!(
+            token.verify(&[], Utc::now()),
+            Err(IngestTokenError::Unverifiable)
+        );
+    }
+
+    #[test]
+    fn test_ingest_token_expired() {
+        let (sk, pk) = generate_key_pair();
+        let expires = UnixTimestamp::now();
+        let token = IngestToken::sign(ingest_claims(expires), &sk);
+
+        assert_eq!(
+            token.verify(&[pk], Utc::now() + Duration::seconds(1)),
+            Err(IngestTokenError::Expired)
+        );
+    }
+
+    #[test]
+    fn test_ingest_token_tampered_claims() {
+        let (sk, pk) = generate_key_pair();
+        let expires = UnixTimestamp::now() + std::time::Duration::from_secs(60);
+        let token = IngestToken::sign(ingest_claims(expires), &sk).to_string();
+        let (_, signature) = token.split_once('.').unwrap();
+
+        let mut claims = ingest_claims(expires);
+        claims.categories.clear();
+        let json = serde_json::to_vec(&claims).unwrap();
+        let forged = format!("{}.{signature}", BASE64URL_NOPAD.encode(&json));
+
+        let parsed: IngestToken = forged.parse().unwrap();
+        assert_eq!(
+            parsed.verify(&[pk], Utc::now()),
+            Err(IngestTokenError::Unverifiable)
+        );
+    }
+
+    #[test]
+    fn test_ingest_token_malformed() {
+        for token in ["", "foo", "foo.bar", "e30.AAAA"] {
+            assert_eq!(
+                token.parse::<IngestToken>(),
+                Err(IngestTokenError::Malformed),
+                "{token}"
+            );
+        }
+    }
 }
@@ -788,6 +788,25 @@ bool relay_is_glob_match(const struct RelayBuf *value,
                          const struct RelayStr *pat,
                          GlobFlags flags);
 
+/**
+ * Previews inbound filters, dynamic sampling and metric extraction for an event.
+ *
+ * The event is expected to be normalized. Transaction rules are matched against the event,
+ * project and trace rules against the dynamic sampling context in the config, in the same order
+ * as in Relay.
+ */
+struct RelayStr relay_preview_event(const struct RelayStr *event,
+                                    const struct RelayStr *config);
+
+/**
+ * Previews inbound filters, dynamic sampling and metric extraction for a span.
+ *
+ * Spans are only sampled by project and trace rules, which are matched against the dynamic
+ * sampling context in the config.
+ */
+struct RelayStr relay_preview_span(const struct RelayStr *span,
+                                   const struct RelayStr *config);
+
 /**
  * Chunks the given text based on remarks.
  */
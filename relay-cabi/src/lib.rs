@@ -103,6 +103,7 @@ mod constants;
 mod core;
 mod ffi;
 mod glob;
+mod preview;
 mod processing;
 
 pub use crate::auth::*;
@@ -111,4 +112,5 @@ pub use crate::constants::*;
 pub use crate::core::*;
 pub use crate::ffi::*;
 pub use crate::glob::*;
+pub use crate::preview::*;
 pub use crate::processing::*;
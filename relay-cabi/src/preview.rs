@@ -0,0 +1,303 @@
+//! Previews of inbound filters, dynamic sampling and metric extraction.
+
+use std::borrow::Cow;
+use std::net::IpAddr;
+
+use relay_common::time::UnixTimestamp;
+use relay_dynamic_config::{
+    CombinedMetricExtractionConfig, ErrorBoundary, Extractable, GlobalConfig, ProjectConfig,
+};
+use relay_event_schema::protocol::{Event, Span};
+use relay_filter::{FilterStatKey, Filterable};
+use relay_protocol::{Annotated, Getter};
+use relay_sampling::config::RuleId;
+use relay_sampling::evaluation::SamplingMatch;
+use relay_sampling::{DynamicSamplingContext, SamplingConfig};
+use serde::{Deserialize, Serialize};
+
+use crate::core::RelayStr;
+
+/// Configuration for a rule preview.
+#[derive(Debug, Deserialize)]
+struct PreviewConfig {
+    /// The config of the project the item is sent to.
+    project_config: ProjectConfig,
+    /// The global config, containing generic filters and metric extraction defaults.
+    #[serde(default)]
+    global_config: GlobalConfig,
+    /// The sampling config of the trace root project.
+    ///
+    /// Falls back to the sampling config of `project_config` if missing.
+    #[serde(default)]
+    root_sampling_config: Option<SamplingConfig>,
+    /// The dynamic sampling context of the trace, required to match project and trace rules.
+    #[serde(default)]
+    dsc: Option<DynamicSamplingContext>,
+    /// The IP address of the client that sent the item.
+    #[serde(default)]
+    client_ip: Option<IpAddr>,
+}
+
+/// Result of a dynamic sampling rule match.
+#[derive(Debug, Serialize)]
+struct PreviewSampling {
+    /// The sample rate applied to the item.
+    sample_rate: f64,
+    /// Either `keep` or `drop`.
+    decision: &'static str,
+    /// Ids of all rules that contributed to the sample rate.
+    matched_rules: Vec<RuleId>,
+}
+
+impl From<SamplingMatch> for PreviewSampling {
+    fn from(sampling_match: SamplingMatch) -> Self {
+        Self {
+            sample_rate: sampling_match.sample_rate(),
+            decision: sampling_match.decision().as_str(),
+            matched_rules: sampling_match.into_matched_rules().0,
+        }
+    }
+}
+
+/// The result of a rule preview.
+#[derive(Debug, Serialize)]
+struct Preview {
+    /// The inbound filter that drops the item.
+    filter: Option<Cow<'static, str>>,
+    /// A report-only inbound filter matching the item, which does not drop it.
+    report_only_filter: Option<Cow<'static, str>>,
+    /// The matching dynamic sampling rules, if any.
+    sampling: Option<PreviewSampling>,
+    /// Metrics extracted from the item with the project's and global metric extraction config.
+    metrics: serde_json::Value,
+}
+
+impl PreviewConfig {
+    fn sampling_config(&self) -> Option<&SamplingConfig> {
+        match self.project_config.sampling {
+            Some(ErrorBoundary::Ok(ref config)) if !config.unsupported() => Some(config),
+            _ => None,
+        }
+    }
+
+    fn root_sampling_config(&self) -> Option<&SamplingConfig> {
+        match self.root_sampling_config {
+            Some(ref config) => (!config.unsupported()).then_some(config),
+            None => self.sampling_config(),
+        }
+    }
+
+    fn metric_extraction_config(&self) -> Option<CombinedMetricExtractionConfig<'_>> {
+        let local = match self.project_config.metric_extraction {
+            ErrorBoundary::Ok(ref config) if config.is_enabled() => config,
+            _ => return None,
+        };
+        let global = self.global_config.metric_extraction.as_ref().ok()?;
+        Some(CombinedMetricExtractionConfig::new(global, local))
+    }
+
+    /// Matches the event and the dynamic sampling context against the sampling rules.
+    ///
+    /// Spans are only sampled by project and trace rules, which are matched if no event is given.
+    fn sampling_match(&self, event: Option<&Event>) -> Option<SamplingMatch> {
+        // Configs with unsupported rules are skipped by `sampling_config`.
+        relay_sampling::evaluation::compute_sampling_decision(
+            false,
+            self.sampling_config(),
+            event,
+            self.root_sampling_config(),
+            self.dsc.as_ref(),
+        )
+    }
+
+    fn preview<T>(&self, item: &T, sampling: Option<SamplingMatch>) -> anyhow::Result<Preview>
+    where
+        T: Filterable + Getter + Extractable,
+    {
+        let (filter, report_only_filter) = match relay_filter::should_filter(
+            item,
+            self.client_ip,
+            &self.project_config.filter_settings,
+            self.global_config.filters(),
+        ) {
+            Ok(reported) => (None, reported),
+            Err(key) => (Some(key), None),
+        };
+
+        let metrics = match self.metric_extraction_config() {
+            Some(config) => {
+                relay_dynamic_config::extract_metrics(item, config, UnixTimestamp::now())
+            }
+            None => Vec::new(),
+        };
+
+        Ok(Preview {
+            filter: filter.map(FilterStatKey::name),
+            report_only_filter: report_only_filter.map(FilterStatKey::name),
+            sampling: sampling.map(Into::into),
+            metrics: serde_json::to_value(metrics)?,
+        })
+    }
+}
+
+/// Previews inbound filters, dynamic sampling and metric extraction for an event.
+///
+/// The event is expected to be normalized. Transaction rules are matched against the event,
+/// project and trace rules against the dynamic sampling context in the config, in the same order
+/// as in Relay.
+#[unsafe(no_mangle)]
+#[relay_ffi::catch_unwind]
+pub unsafe extern "C" fn relay_preview_event(
+    event: *const RelayStr,
+    config: *const RelayStr,
+) -> RelayStr {
+    let event = Annotated::<Event>::from_json(unsafe { (*event).as_str() })?;
+    let config: PreviewConfig = serde_json::from_str(unsafe { (*config).as_str() })?;
+    let Some(event) = event.value() else {
+        return Err(anyhow::anyhow!("missing event"));
+    };
+
+    let sampling = config.sampling_match(Some(event));
+    let preview = config.preview(event, sampling)?;
+    RelayStr::from_string(serde_json::to_string(&preview)?)
+}
+
+/// Previews inbound filters, dynamic sampling and metric extraction for a span.
+///
+/// Spans are only sampled by project and trace rules, which are matched against the dynamic
+/// sampling context in the config.
+#[unsafe(no_mangle)]
+#[relay_ffi::catch_unwind]
+pub unsafe extern "C" fn relay_preview_span(
+    span: *const RelayStr,
+    config: *const RelayStr,
+) -> RelayStr {
+    let span = Annotated::<Span>::from_json(unsafe { (*span).as_str() })?;
+    let config: PreviewConfig = serde_json::from_str(unsafe { (*config).as_str() })?;
+    let Some(span) = span.value() else {
+        return Err(anyhow::anyhow!("missing span"));
+    };
+
+    let sampling = config.sampling_match(None);
+    let preview = config.preview(span, sampling)?;
+    RelayStr::from_string(serde_json::to_string(&preview)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"{
+        "project_config": {
+            "filterSettings": {
+                "releases": {"releases": ["1.0.*"]}
+            },
+            "sampling": {
+                "version": 2,
+                "rules": [
+                    {
+                        "id": 1,
+                        "type": "transaction",
+                        "samplingValue": {"type": "factor", "value": 2.0},
+                        "condition": {"op": "eq", "name": "event.environment", "value": "prod"}
+                    },
+                    {
+                        "id": 2,
+                        "type": "trace",
+                        "samplingValue": {"type": "sampleRate", "value": 0.25},
+                        "condition": {"op": "and", "inner": []}
+                    }
+                ]
+            },
+            "metricExtraction": {
+                "version": 1,
+                "metrics": [
+                    {
+                        "category": "transaction",
+                        "mri": "c:transactions/preview@none",
+                        "tags": [{"key": "environment", "field": "event.environment"}]
+                    }
+                ]
+            }
+        },
+        "dsc": {
+            "trace_id": "67e5504410b1426f9247bb680e5fe0c8",
+            "public_key": "abd0f232775f45feab79864e580d160b"
+        }
+    }"#;
+
+    fn preview_event(event: &str) -> serde_json::Value {
+        let preview =
+            unsafe { relay_preview_event(&RelayStr::from(event), &RelayStr::from(CONFIG)) };
+        serde_json::from_str(unsafe { preview.as_str() }).unwrap()
+    }
+
+    #[test]
+    fn test_preview_event() {
+        let preview = preview_event(
+            r#"{
+                "event_id": "52df9022835246eeb317dbd739ccd059",
+                "type": "transaction",
+                "transaction": "/hello",
+                "environment": "prod",
+                "release": "2.0.0",
+                "start_timestamp": 1597976300.0,
+                "timestamp": 1597976302.0
+            }"#,
+        );
+
+        assert_eq!(preview["filter"], serde_json::Value::Null);
+        assert_eq!(
+            preview["sampling"],
+            serde_json::json!({
+                "sample_rate": 0.5,
+                "decision": "drop",
+                "matched_rules": [1, 2],
+            })
+        );
+
+        let metrics = preview["metrics"].as_array().unwrap();
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(metrics[0]["name"], "c:transactions/preview@none");
+        assert_eq!(
+            metrics[0]["tags"],
+            serde_json::json!({"environment": "prod"})
+        );
+    }
+
+    #[test]
+    fn test_preview_event_filtered() {
+        let preview = preview_event(
+            r#"{
+                "event_id": "52df9022835246eeb317dbd739ccd059",
+                "type": "transaction",
+                "environment": "dev",
+                "release": "1.0.1",
+                "start_timestamp": 1597976300.0,
+                "timestamp": 1597976302.0
+            }"#,
+        );
+
+        assert_eq!(preview["filter"], "release-version");
+        assert_eq!(preview["sampling"]["matched_rules"], serde_json::json!([2]));
+        assert_eq!(preview["sampling"]["sample_rate"], 0.25);
+    }
+
+    #[test]
+    fn test_preview_span() {
+        let span = r#"{
+            "span_id": "bd429c44b67a3eb4",
+            "trace_id": "67e5504410b1426f9247bb680e5fe0c8",
+            "op": "db",
+            "start_timestamp": 1597976300.0,
+            "timestamp": 1597976302.0
+        }"#;
+
+        let preview = unsafe { relay_preview_span(&RelayStr::from(span), &RelayStr::from(CONFIG)) };
+        let preview: serde_json::Value = serde_json::from_str(unsafe { preview.as_str() }).unwrap();
+
+        assert_eq!(preview["filter"], serde_json::Value::Null);
+        assert_eq!(preview["sampling"]["matched_rules"], serde_json::json!([2]));
+        assert_eq!(preview["metrics"], serde_json::json!([]));
+    }
+}
@@ -1,5 +1,7 @@
 //! Metrics aggregator configuration.
 
+use std::path::PathBuf;
+
 use relay_metrics::aggregator::AggregatorConfig;
 use relay_metrics::{MetricNamespace, UnixTimestamp};
 use serde::{Deserialize, Serialize};
@@ -34,6 +36,23 @@ pub struct AggregatorServiceConfig {
     /// adds some additional overhead, this number is approximate and some safety margin should be
     /// left to hard limits.
     pub max_flush_bytes: usize,
+
+    /// Path to a file, in which unflushed buckets are persisted across restarts.
+    ///
+    /// If set, the aggregator writes a snapshot of all its buckets to this file periodically and
+    /// on shutdown, instead of flushing them early. On start, the snapshot is merged back into the
+    /// aggregator, unless it is older than [`AggregatorConfig::max_secs_in_past`].
+    ///
+    /// Every aggregator requires its own file. Defaults to `None`, which disables snapshots.
+    pub snapshot_path: Option<PathBuf>,
+
+    /// The interval in seconds in which snapshots are written to [`Self::snapshot_path`].
+    ///
+    /// Buckets flushed after the last snapshot are flushed again after a crash, this limits the
+    /// amount of duplicated buckets.
+    ///
+    /// Defaults to `60` seconds.
+    pub snapshot_interval: u64,
 }
 
 impl AggregatorServiceConfig {
@@ -58,6 +77,8 @@ impl Default for AggregatorServiceConfig {
             max_tag_key_length: 200,
             max_tag_value_length: 200,
             max_flush_bytes: 5_000_000, // 5 MB
+            snapshot_path: None,
+            snapshot_interval: 60,
         }
     }
 }
@@ -35,6 +35,7 @@ use serde::{Serialize, de};
 /// let size = ByteSize::kibibytes(42);
 /// assert_eq!("42KiB", size.to_string());
 /// ```
+#[derive(Clone)]
 pub struct ByteSize(Size);
 
 impl ByteSize {
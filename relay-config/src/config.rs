@@ -707,6 +707,14 @@ pub struct Limits {
     ///
     /// Defaults to `1024`, a value [google has been using for a long time](https://git.kernel.org/pub/scm/linux/kernel/git/torvalds/linux.git/commit/?id=19f92a030ca6d772ab44b22ee6a01378a8cb32d4).
     pub tcp_listen_backlog: u32,
+    /// Bounds the inbound message queue of the store service.
+    ///
+    /// By default, the queue is unbounded.
+    pub store_queue: Option<ServiceQueue>,
+    /// Bounds the inbound message queue of the upstream service.
+    ///
+    /// By default, the queue is unbounded.
+    pub upstream_queue: Option<ServiceQueue>,
 }
 
 impl Default for Limits {
@@ -744,10 +752,41 @@ impl Default for Limits {
             max_connections: None,
             tcp_listen_backlog: 1024,
             max_removed_attribute_key_size: ByteSize::kibibytes(10),
+            store_queue: None,
+            upstream_queue: None,
         }
     }
 }
 
+/// Behavior of a bounded service queue once it is full.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceBackpressure {
+    /// Senders that support backpressure wait for capacity.
+    ///
+    /// The envelope processor waits before submitting envelopes and metrics, which slows down
+    /// processing. Other senders that cannot wait still enqueue their messages beyond the
+    /// capacity.
+    #[default]
+    Wait,
+    /// New messages are rejected.
+    Reject,
+    /// The oldest messages in the queue are dropped.
+    DropOldest,
+}
+
+/// Configuration of a bounded inbound message queue for a service.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct ServiceQueue {
+    /// The maximum number of messages in the queue.
+    pub capacity: usize,
+    /// Behavior once the queue is full.
+    ///
+    /// Defaults to `wait`.
+    #[serde(default)]
+    pub backpressure: ServiceBackpressure,
+}
+
 /// Controls traffic steering.
 #[derive(Debug, Default, Deserialize, Serialize)]
 #[serde(default)]
@@ -1389,6 +1428,32 @@ impl<'de> Deserialize<'de> for EmitOutcomes {
     }
 }
 
+/// Configuration for the local outcome ledger.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct OutcomeLedger {
+    /// Keeps a rolling record of all outcomes in memory, exposed on an internal endpoint.
+    ///
+    /// The endpoint is not authenticated and therefore only available if a dedicated internal
+    /// listener is configured with `relay.internal_port`.
+    ///
+    /// Defaults to `false`.
+    pub enabled: bool,
+    /// The time in seconds that outcomes are retained in the ledger.
+    ///
+    /// Defaults to one hour.
+    pub retention: u64,
+}
+
+impl Default for OutcomeLedger {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            retention: 3600,
+        }
+    }
+}
+
 /// Outcome generation specific configuration values.
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(default)]
@@ -1406,6 +1471,8 @@ pub struct Outcomes {
     /// Defines the source string registered in the outcomes originating from
     /// this Relay (typically something like the region or the layer).
     pub source: Option<String>,
+    /// Configuration for the local outcome ledger.
+    pub ledger: OutcomeLedger,
 }
 
 impl Default for Outcomes {
@@ -1415,6 +1482,7 @@ impl Default for Outcomes {
             batch_size: 1000,
             batch_interval: 500,
             source: None,
+            ledger: OutcomeLedger::default(),
         }
     }
 }
@@ -1535,6 +1603,34 @@ impl Default for AuthConfig {
 pub struct GeoIpConfig {
     /// The path to GeoIP database.
     pub path: Option<PathBuf>,
+    /// The path to an ASN database, such as GeoLite2-ASN.
+    ///
+    /// Adds the autonomous system number and organization to geo information.
+    pub asn_path: Option<PathBuf>,
+    /// The path to an anonymous IP database, such as GeoIP2-Anonymous-IP.
+    ///
+    /// Adds hosting provider, VPN, public proxy and Tor exit node flags to geo information.
+    pub anonymous_ip_path: Option<PathBuf>,
+}
+
+/// User agent parser configuration options.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct UserAgentConfig {
+    /// The path to user agent parser definitions in the format of uap-core's `regexes.yaml`.
+    ///
+    /// Replaces the built-in definitions. Definitions from the global config take precedence.
+    pub definitions_path: Option<PathBuf>,
+}
+
+/// Semantic conventions configuration options.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct ConventionsConfig {
+    /// The path to a JSON file with a list of attribute definitions in the format of
+    /// `sentry-conventions`.
+    ///
+    /// The definitions are merged over the built-in attribute registry. Definitions from the
+    /// global config take precedence.
+    pub attribute_overrides_path: Option<PathBuf>,
 }
 
 /// Cardinality Limiter configuration options.
@@ -1653,6 +1749,14 @@ pub struct Upload {
     ///
     /// If omitted, relay's default [`Credentials`] are used.
     pub credentials: Option<UploadCredentials>,
+
+    /// Minimum size of attachments in envelopes that are uploaded while the envelope is received.
+    ///
+    /// Such attachments are streamed from the request body directly into the upload service and
+    /// replaced by attachment placeholders, instead of being held in memory. Requires the
+    /// `projects:relay-envelope-attachment-uploads` feature. If omitted, envelopes are always read
+    /// into memory entirely.
+    pub envelope_attachment_threshold: Option<ByteSize>,
 }
 
 impl Default for Upload {
@@ -1662,6 +1766,7 @@ impl Default for Upload {
             timeout: 5 * 60,  // five minutes
             max_age: 60 * 60, // 1h
             credentials: None,
+            envelope_attachment_threshold: None,
         }
     }
 }
@@ -1691,6 +1796,34 @@ impl fmt::Debug for UploadCredentials {
     }
 }
 
+/// Configuration for processing sessions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Sessions {
+    /// Aggregates individual session updates into session aggregates before processing them.
+    ///
+    /// Session updates which start and end a session at the same time are bucketed by the
+    /// minute they started, their release, environment and status. The aggregates are then
+    /// processed like session aggregates sent by a client. Other session updates are processed
+    /// unchanged.
+    ///
+    /// Defaults to `false`.
+    pub aggregate: bool,
+    /// The time in seconds that session updates are aggregated before they are processed.
+    ///
+    /// Defaults to `10`.
+    pub flush_interval: u64,
+}
+
+impl Default for Sessions {
+    fn default() -> Self {
+        Self {
+            aggregate: false,
+            flush_interval: 10,
+        }
+    }
+}
+
 /// All configuration values that can be deserialized from `config.yml`.
 #[derive(Serialize, Deserialize, Debug, Default)]
 #[serde(default)]
@@ -1711,11 +1844,14 @@ pub struct ConfigValues {
     pub secondary_aggregators: Vec<ScopedAggregatorConfig>,
     pub auth: AuthConfig,
     pub geoip: GeoIpConfig,
+    pub user_agent: UserAgentConfig,
+    pub conventions: ConventionsConfig,
     pub normalization: Normalization,
     pub cardinality_limiter: CardinalityLimiter,
     pub health: Health,
     pub cogs: Cogs,
     pub upload: Upload,
+    pub sessions: Sessions,
 }
 
 impl ConfigObject for ConfigValues {
@@ -2179,6 +2315,14 @@ impl Config {
         self.values.outcomes.source.as_deref()
     }
 
+    /// Returns the retention of the local outcome ledger, if the ledger is enabled.
+    pub fn outcome_ledger_retention(&self) -> Option<Duration> {
+        let ledger = &self.values.outcomes.ledger;
+        ledger
+            .enabled
+            .then(|| Duration::from_secs(ledger.retention))
+    }
+
     /// Returns logging configuration.
     pub fn logging(&self) -> &relay_log::LogConfig {
         &self.values.logging
@@ -2548,6 +2692,16 @@ impl Config {
         self.values.limits.tcp_listen_backlog
     }
 
+    /// Returns the bounds of the store service's inbound queue, if configured.
+    pub fn store_queue(&self) -> Option<&ServiceQueue> {
+        self.values.limits.store_queue.as_ref()
+    }
+
+    /// Returns the bounds of the upstream service's inbound queue, if configured.
+    pub fn upstream_queue(&self) -> Option<&ServiceQueue> {
+        self.values.limits.upstream_queue.as_ref()
+    }
+
     /// Returns the number of cores to use for thread pools.
     pub fn cpu_concurrency(&self) -> usize {
         self.values.limits.max_thread_count
@@ -2587,6 +2741,26 @@ impl Config {
             .or(self.values.processing.geoip_path.as_deref())
     }
 
+    /// The path to the optional ASN database used alongside the GeoIp database.
+    pub fn geoip_asn_path(&self) -> Option<&Path> {
+        self.values.geoip.asn_path.as_deref()
+    }
+
+    /// The path to the optional anonymous IP database used alongside the GeoIp database.
+    pub fn geoip_anonymous_ip_path(&self) -> Option<&Path> {
+        self.values.geoip.anonymous_ip_path.as_deref()
+    }
+
+    /// The path to user agent parser definitions which replace the built-in definitions.
+    pub fn user_agent_definitions_path(&self) -> Option<&Path> {
+        self.values.user_agent.definitions_path.as_deref()
+    }
+
+    /// The path to attribute definitions which override the built-in attribute registry.
+    pub fn attribute_overrides_path(&self) -> Option<&Path> {
+        self.values.conventions.attribute_overrides_path.as_deref()
+    }
+
     /// Maximum future timestamp of ingested data.
     ///
     /// Events past this timestamp will be adjusted to `now()`. Sessions will be dropped.
@@ -2630,6 +2804,16 @@ impl Config {
         &self.values.upload
     }
 
+    /// Returns `true` if session updates are aggregated before they are processed.
+    pub fn aggregate_sessions(&self) -> bool {
+        self.values.sessions.aggregate
+    }
+
+    /// Returns the interval in which aggregated sessions are processed.
+    pub fn sessions_flush_interval(&self) -> Duration {
+        Duration::from_secs(self.values.sessions.flush_interval)
+    }
+
     /// Returns the key used to sign upload locations.
     #[cfg(feature = "processing")]
     pub fn upload_signing_key(&self) -> Option<&SecretKey> {
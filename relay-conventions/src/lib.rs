@@ -29,6 +29,14 @@
 //! ### I've changed something in `sentry-conventions`, how do I get Relay to pick it up?
 //! Relay parses `sentry-conventions` at compile time, so any change requires a PR to Relay and needs to be deployed.
 //!
+//! To roll out a change before that, attribute definitions can be overridden at runtime with
+//! [`set_attribute_overrides`]. Relay loads overrides from the `attributeOverrides` section of the global config
+//! and from the file configured in `conventions.attribute_overrides_path`. Overrides use the format of attribute
+//! definitions in `sentry-conventions` and only need to contain the fields that change:
+//! ```json
+//! [{"key": "my.old.attribute", "deprecation": {"replacement": "my.new.attribute", "_status": "backfill"}}]
+//! ```
+//!
 //! In Relay, Update the `sentry-conventions` submodule:
 //! ```bash
 //! cd relay-conventions/sentry-conventions
@@ -54,7 +62,20 @@
 //! ### I want to reference an attribute in Relay but it's not defined in `sentry-conventions`, what should I do?
 //! **Always** define it in `sentry-conventions` before using it in Relay. This makes sure we have proper
 
+use std::borrow::Cow;
 use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+mod overrides;
+
+pub use self::overrides::{
+    AttributeOverride, AttributeOverrides, DeprecationOverride, DeprecationStatus, OverrideError,
+    reset_attribute_overrides, set_attribute_overrides,
+};
+
 pub mod attributes {
     //! Attribute constant definitions.
     #![allow(rustdoc::bare_urls)]
@@ -94,7 +115,8 @@ include!(concat!(env!("OUT_DIR"), "/canonical_fn.rs"));
 include!(concat!(env!("OUT_DIR"), "/measurement_replacement_fn.rs"));
 
 /// Whether an attribute should be scrubbed.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "key")]
 pub enum ApplyScrubbing {
     /// The attribute will be stripped by default.
     Auto,
@@ -106,7 +128,7 @@ pub enum ApplyScrubbing {
 }
 
 /// The name of the replacement of a deprecated attribute.
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub enum ReplacementName {
     /// The replacement attribute has a fixed name,
     /// i.e., doesn't contain a placeholder.
@@ -117,6 +139,30 @@ pub enum ReplacementName {
     /// has to be inserted into the placeholder. The contained
     /// function performs this insertion.
     Dynamic(fn(&str) -> String),
+    /// The replacement attribute was defined by a runtime override.
+    ///
+    /// If the name contains a placeholder, the matched fragment is inserted for it.
+    Runtime(Arc<str>),
+}
+
+impl ReplacementName {
+    /// Resolves the replacement name for an attribute.
+    ///
+    /// `fragment` is the part of the original attribute key that matched its placeholder. Returns
+    /// `None` if exactly one of the original and the replacement attribute contains a placeholder.
+    pub fn resolve(&self, fragment: Option<&str>) -> Option<Cow<'static, str>> {
+        match (self, fragment) {
+            (Self::Static(name), None) => Some(Cow::Borrowed(name)),
+            (Self::Dynamic(name_fn), Some(fragment)) => Some(Cow::Owned(name_fn(fragment))),
+            (Self::Runtime(name), None) if !name.contains(PLACEHOLDER_SEGMENT) => {
+                Some(Cow::Owned(name.to_string()))
+            }
+            (Self::Runtime(name), Some(fragment)) if name.contains(PLACEHOLDER_SEGMENT) => {
+                Some(Cow::Owned(name.replace(PLACEHOLDER_SEGMENT, fragment)))
+            }
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Debug for ReplacementName {
@@ -124,12 +170,13 @@ impl fmt::Debug for ReplacementName {
         match self {
             Self::Static(arg0) => f.debug_tuple("Static").field(arg0).finish(),
             Self::Dynamic(_) => f.debug_tuple("Dynamic").finish(),
+            Self::Runtime(arg0) => f.debug_tuple("Runtime").field(arg0).finish(),
         }
     }
 }
 
 /// Under which names an attribute should be saved.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum WriteBehavior {
     /// Save the attribute under its current name.
     ///
@@ -153,31 +200,61 @@ pub struct AttributeInfo {
     pub aliases: &'static [&'static str],
 }
 
+/// A reference to an [`AttributeInfo`] returned by [`attribute_info`].
+///
+/// Compiled-in definitions are borrowed from the static registry, runtime overrides are shared
+/// with the set of overrides they were loaded from.
+#[derive(Debug, Clone)]
+pub enum AttributeInfoRef {
+    /// A compiled-in attribute definition.
+    Static(&'static AttributeInfo),
+    /// An attribute definition from a runtime override.
+    Override(Arc<AttributeInfo>),
+}
+
+impl Deref for AttributeInfoRef {
+    type Target = AttributeInfo;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Static(info) => info,
+            Self::Override(info) => info,
+        }
+    }
+}
+
 /// Returns information about an attribute, as defined in `sentry-conventions`.
 ///
 /// If the matched attribute contains a placeholder (`<key>`), the second returned
 /// value is the part of the attribute key that was inserted for the placeholder.
-pub fn attribute_info_with_fragment(key: &str) -> Option<(&'static AttributeInfo, Option<&str>)> {
-    ATTRIBUTES.find(key)
+///
+/// Runtime overrides set with [`set_attribute_overrides`] take precedence over the
+/// compiled-in definitions.
+pub fn attribute_info_with_fragment(key: &str) -> Option<(AttributeInfoRef, Option<&str>)> {
+    overrides::lookup(key)
 }
 
 /// Returns information about an attribute, as defined in `sentry-conventions`.
-pub fn attribute_info(key: &str) -> Option<&'static AttributeInfo> {
+pub fn attribute_info(key: &str) -> Option<AttributeInfoRef> {
     attribute_info_with_fragment(key).map(|(info, _)| info)
 }
 
 /// Special path segment in attribute keys that matches any value.
 const PLACEHOLDER_SEGMENT: &str = "<key>";
 
-struct Node<T: 'static> {
-    info: Option<T>,
-    children: phf::Map<&'static str, Node<T>>,
-}
+/// A tree of attribute key segments, which supports placeholder segments.
+trait Trie: Sized {
+    type Info;
+
+    /// Returns the information stored for the key ending at this node.
+    fn info(&self) -> Option<&Self::Info>;
 
-impl<T> Node<T> {
-    fn find<'a>(&self, key: &'a str) -> Option<(&T, Option<&'a str>)> {
+    /// Returns the child node for a key segment.
+    fn child(&self, segment: &str) -> Option<&Self>;
+
+    fn find<'a>(&self, key: &'a str) -> Option<(&Self::Info, Option<&'a str>)> {
         if key.is_empty() {
-            return self.info.as_ref().map(|info| (info, None));
+            return self.info().map(|info| (info, None));
         }
         let (prefix, suffix) = key.split_once('.').unwrap_or((key, ""));
 
@@ -185,10 +262,7 @@ impl<T> Node<T> {
         // If the prefix is `"<key>"`, we skip this and fall through
         // to the second attempt.
         if prefix != PLACEHOLDER_SEGMENT
-            && let Some(info) = self
-                .children
-                .get(prefix)
-                .and_then(|child| child.find(suffix))
+            && let Some(info) = self.child(prefix).and_then(|child| child.find(suffix))
         {
             return Some(info);
         }
@@ -196,8 +270,7 @@ impl<T> Node<T> {
         // If the literal lookup doesn't succeed, try a placeholder
         // lookup and bubble up the current `prefix` if it succeeds.
         if let Some((info, _)) = self
-            .children
-            .get(PLACEHOLDER_SEGMENT)
+            .child(PLACEHOLDER_SEGMENT)
             .and_then(|child| child.find(suffix))
         {
             return Some((info, Some(prefix)));
@@ -206,6 +279,23 @@ impl<T> Node<T> {
     }
 }
 
+struct Node<T: 'static> {
+    info: Option<T>,
+    children: phf::Map<&'static str, Node<T>>,
+}
+
+impl<T> Trie for Node<T> {
+    type Info = T;
+
+    fn info(&self) -> Option<&T> {
+        self.info.as_ref()
+    }
+
+    fn child(&self, segment: &str) -> Option<&Self> {
+        self.children.get(segment)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
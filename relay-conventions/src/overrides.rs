@@ -0,0 +1,339 @@
+//! Runtime overrides of the compiled-in attribute registry.
+//!
+//! Overrides use the same format as attribute definitions in `sentry-conventions` and are merged
+//! over the compiled-in definitions on a per-field basis. This allows rolling out new or renamed
+//! attributes, as well as changed PII flags, without a release of Relay.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use arc_swap::ArcSwapOption;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ATTRIBUTES, ApplyScrubbing, AttributeInfo, AttributeInfoRef, PLACEHOLDER_SEGMENT,
+    ReplacementName, Trie, WriteBehavior,
+};
+
+/// The active overrides, if any.
+static OVERRIDES: ArcSwapOption<AttributeOverrides> = ArcSwapOption::const_empty();
+
+/// How to handle an attribute's deprecation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeprecationStatus {
+    /// Write both the original and replacement name.
+    Backfill,
+    /// Only write the replacement name.
+    Normalize,
+}
+
+/// Information about an attribute's deprecation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeprecationOverride {
+    /// The attribute's new name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replacement: Option<String>,
+    /// How to handle the attribute's deprecation.
+    ///
+    /// If this is `null`, the attribute is not rewritten.
+    #[serde(default, rename = "_status")]
+    pub status: Option<DeprecationStatus>,
+}
+
+/// An attribute definition which overrides the compiled-in definition.
+///
+/// Fields which are not set keep their compiled-in value. Attributes which are not defined in
+/// the compiled-in registry are added, missing fields default to an attribute that is scrubbed
+/// and not rewritten.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttributeOverride {
+    /// The attribute's name, may contain `<key>` placeholders.
+    pub key: String,
+    /// Whether the attribute should be scrubbed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub apply_scrubbing: Option<ApplyScrubbing>,
+    /// The deprecation of the attribute.
+    ///
+    /// Replaces the entire compiled-in deprecation, including its status.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecation: Option<DeprecationOverride>,
+}
+
+/// An error returned when building [`AttributeOverrides`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OverrideError {
+    /// The same attribute is overridden more than once.
+    Duplicate(String),
+    /// One of the attribute and its replacement contains a placeholder and the other doesn't.
+    PlaceholderMismatch(String),
+}
+
+impl fmt::Display for OverrideError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Duplicate(key) => write!(f, "attribute `{key}` is overridden more than once"),
+            Self::PlaceholderMismatch(key) => write!(
+                f,
+                "attribute `{key}` and its replacement must both or neither contain a placeholder"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for OverrideError {}
+
+/// A validated set of attribute overrides, merged with the compiled-in registry.
+#[derive(Debug, Default)]
+pub struct AttributeOverrides {
+    root: RuntimeNode,
+    len: usize,
+}
+
+impl AttributeOverrides {
+    /// Validates attribute overrides and merges them with the compiled-in definitions.
+    pub fn new(overrides: &[AttributeOverride]) -> Result<Self, OverrideError> {
+        let mut root = RuntimeNode::default();
+
+        for attribute in overrides {
+            let info = Arc::new(merge(attribute)?);
+            let node = attribute.key.split('.').fold(&mut root, |node, segment| {
+                node.children.entry(segment.to_owned()).or_default()
+            });
+
+            if node.info.replace(info).is_some() {
+                return Err(OverrideError::Duplicate(attribute.key.clone()));
+            }
+        }
+
+        Ok(Self {
+            root,
+            len: overrides.len(),
+        })
+    }
+
+    /// Returns the number of overridden attributes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no attributes are overridden.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn find<'a>(&self, key: &'a str) -> Option<(&Arc<AttributeInfo>, Option<&'a str>)> {
+        self.root.find(key)
+    }
+}
+
+/// Replaces the attribute overrides used by [`attribute_info`](crate::attribute_info).
+///
+/// The overrides are swapped atomically, concurrent lookups either observe the previous or the
+/// new overrides.
+pub fn set_attribute_overrides(overrides: impl Into<Arc<AttributeOverrides>>) {
+    OVERRIDES.store(Some(overrides.into()));
+}
+
+/// Removes all attribute overrides, restoring the compiled-in definitions.
+pub fn reset_attribute_overrides() {
+    OVERRIDES.store(None);
+}
+
+/// Returns information about an attribute, with runtime overrides applied.
+pub(crate) fn lookup(key: &str) -> Option<(AttributeInfoRef, Option<&str>)> {
+    resolve(OVERRIDES.load().as_deref(), key)
+}
+
+/// Looks up an attribute in the overrides and the compiled-in registry.
+///
+/// As within the registry, literal definitions take precedence over placeholders. An override
+/// which only matches through a placeholder does not shadow a literal compiled-in definition.
+fn resolve<'a>(
+    overrides: Option<&AttributeOverrides>,
+    key: &'a str,
+) -> Option<(AttributeInfoRef, Option<&'a str>)> {
+    let compiled = ATTRIBUTES.find(key);
+    let compiled_literal = compiled.is_some_and(|(_, fragment)| fragment.is_none());
+
+    if let Some((info, fragment)) = overrides.and_then(|overrides| overrides.find(key))
+        && (fragment.is_none() || !compiled_literal)
+    {
+        return Some((AttributeInfoRef::Override(Arc::clone(info)), fragment));
+    }
+
+    compiled.map(|(info, fragment)| (AttributeInfoRef::Static(info), fragment))
+}
+
+/// Merges an override with the compiled-in definition of the same attribute.
+fn merge(attribute: &AttributeOverride) -> Result<AttributeInfo, OverrideError> {
+    // Only inherit from a definition of the same attribute, not from a placeholder attribute
+    // which happens to match the overridden key.
+    let compiled = ATTRIBUTES
+        .find(&attribute.key)
+        .filter(|(_, fragment)| fragment.is_none_or(|f| f == PLACEHOLDER_SEGMENT))
+        .map(|(info, _)| info);
+
+    let write_behavior = match &attribute.deprecation {
+        Some(deprecation) => write_behavior(&attribute.key, deprecation)?,
+        None => compiled.map_or(WriteBehavior::CurrentName, |info| {
+            info.write_behavior.clone()
+        }),
+    };
+
+    let apply_scrubbing = attribute
+        .apply_scrubbing
+        .or(compiled.map(|info| info.apply_scrubbing))
+        .unwrap_or(ApplyScrubbing::Auto);
+
+    Ok(AttributeInfo {
+        write_behavior,
+        apply_scrubbing,
+        aliases: compiled.map_or(&[], |info| info.aliases),
+    })
+}
+
+fn write_behavior(
+    key: &str,
+    deprecation: &DeprecationOverride,
+) -> Result<WriteBehavior, OverrideError> {
+    let (Some(status), Some(replacement)) = (deprecation.status, &deprecation.replacement) else {
+        return Ok(WriteBehavior::CurrentName);
+    };
+
+    if key.contains(PLACEHOLDER_SEGMENT) != replacement.contains(PLACEHOLDER_SEGMENT) {
+        return Err(OverrideError::PlaceholderMismatch(key.to_owned()));
+    }
+
+    let name = ReplacementName::Runtime(replacement.as_str().into());
+    Ok(match status {
+        DeprecationStatus::Backfill => WriteBehavior::BothNames(name),
+        DeprecationStatus::Normalize => WriteBehavior::NewName(name),
+    })
+}
+
+#[derive(Debug, Default)]
+struct RuntimeNode {
+    info: Option<Arc<AttributeInfo>>,
+    children: HashMap<String, RuntimeNode>,
+}
+
+impl Trie for RuntimeNode {
+    type Info = Arc<AttributeInfo>;
+
+    fn info(&self) -> Option<&Arc<AttributeInfo>> {
+        self.info.as_ref()
+    }
+
+    fn child(&self, segment: &str) -> Option<&Self> {
+        self.children.get(segment)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> AttributeOverrides {
+        let overrides: Vec<AttributeOverride> = serde_json::from_str(json).unwrap();
+        AttributeOverrides::new(&overrides).unwrap()
+    }
+
+    #[test]
+    fn test_new_attribute() {
+        let overrides = parse(
+            r#"[{
+                "key": "my.old.<key>",
+                "apply_scrubbing": {"key": "never"},
+                "deprecation": {"replacement": "my.new.<key>", "_status": "normalize"}
+            }]"#,
+        );
+
+        let (info, fragment) = overrides.find("my.old.foo").unwrap();
+        assert_eq!(fragment, Some("foo"));
+        assert_eq!(info.apply_scrubbing, ApplyScrubbing::Never);
+
+        let WriteBehavior::NewName(name) = &info.write_behavior else {
+            panic!("expected normalization");
+        };
+        assert_eq!(name.resolve(fragment).as_deref(), Some("my.new.foo"));
+
+        assert!(overrides.find("my.old").is_none());
+    }
+
+    #[test]
+    fn test_merge_keeps_compiled_fields() {
+        // Only override the PII status, the deprecation is taken from the compiled-in registry.
+        let overrides = parse(
+            r#"[{"key": "http.response_content_length", "apply_scrubbing": {"key": "auto"}}]"#,
+        );
+
+        let (info, _) = overrides.find("http.response_content_length").unwrap();
+        assert_eq!(info.apply_scrubbing, ApplyScrubbing::Auto);
+        assert!(matches!(
+            info.write_behavior,
+            WriteBehavior::BothNames(ReplacementName::Static("http.response.body.size"))
+        ));
+    }
+
+    #[test]
+    fn test_disable_rewrite() {
+        let overrides =
+            parse(r#"[{"key": "http.response_content_length", "deprecation": {"_status": null}}]"#);
+
+        let (info, _) = overrides.find("http.response_content_length").unwrap();
+        assert_eq!(info.apply_scrubbing, ApplyScrubbing::Manual);
+        assert!(matches!(info.write_behavior, WriteBehavior::CurrentName));
+    }
+
+    #[test]
+    fn test_placeholder_does_not_shadow_compiled_literal() {
+        let overrides = parse(r#"[{"key": "http.<key>", "apply_scrubbing": {"key": "never"}}]"#);
+
+        let (info, fragment) = resolve(Some(&overrides), "http.response_content_length").unwrap();
+        assert!(matches!(info, AttributeInfoRef::Static(_)));
+        assert_eq!(fragment, None);
+        assert_eq!(info.apply_scrubbing, ApplyScrubbing::Manual);
+
+        let (info, fragment) = resolve(Some(&overrides), "http.foo").unwrap();
+        assert!(matches!(info, AttributeInfoRef::Override(_)));
+        assert_eq!(fragment, Some("foo"));
+        assert_eq!(info.apply_scrubbing, ApplyScrubbing::Never);
+    }
+
+    #[test]
+    fn test_literal_override_shadows_compiled() {
+        let overrides = parse(
+            r#"[{"key": "http.response_content_length", "apply_scrubbing": {"key": "never"}}]"#,
+        );
+
+        let (info, _) = resolve(Some(&overrides), "http.response_content_length").unwrap();
+        assert!(matches!(info, AttributeInfoRef::Override(_)));
+        assert_eq!(info.apply_scrubbing, ApplyScrubbing::Never);
+    }
+
+    #[test]
+    fn test_placeholder_mismatch() {
+        let overrides: Vec<AttributeOverride> = serde_json::from_str(
+            r#"[{"key": "a.<key>", "deprecation": {"replacement": "b", "_status": "backfill"}}]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            AttributeOverrides::new(&overrides).unwrap_err(),
+            OverrideError::PlaceholderMismatch("a.<key>".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_duplicate() {
+        let overrides: Vec<AttributeOverride> =
+            serde_json::from_str(r#"[{"key": "a.b"}, {"key": "a.b"}]"#).unwrap();
+
+        assert_eq!(
+            AttributeOverrides::new(&overrides).unwrap_err(),
+            OverrideError::Duplicate("a.b".to_owned())
+        );
+    }
+}
@@ -64,6 +64,14 @@ pub enum Feature {
     /// Serialized as `organizations:continuous-profiling-perfetto`.
     #[serde(rename = "organizations:continuous-profiling-perfetto")]
     ContinuousProfilingPerfetto,
+    /// Enable pprof and OTLP profile processing for continuous profiling.
+    ///
+    /// When enabled, compound profile chunk items containing a pprof or OTLP profile are
+    /// expanded into the Sample v2 JSON format.
+    ///
+    /// Serialized as `organizations:continuous-profiling-pprof`.
+    #[serde(rename = "organizations:continuous-profiling-pprof")]
+    ContinuousProfilingPprof,
     /// Enable log ingestion for our log product (this is not internal logging).
     ///
     /// Serialized as `organizations:ourlogs-ingestion`.
@@ -113,6 +121,9 @@ pub enum Feature {
     /// Stream minidumps to objectstore.
     #[serde(rename = "projects:relay-minidump-uploads")]
     MinidumpUploads,
+    /// Stream large attachments in envelopes to objectstore.
+    #[serde(rename = "projects:relay-envelope-attachment-uploads")]
+    EnvelopeAttachmentUploads,
     /// Allow additional exceptions to accompany minidumps.
     #[serde(rename = "projects:minidump-multi-exception")]
     MinidumpMultiException,
@@ -4,13 +4,14 @@ use std::io::BufReader;
 use std::path::Path;
 
 use relay_base_schema::metrics::MetricNamespace;
+use relay_conventions::AttributeOverride;
 use relay_event_normalization::{MeasurementsConfig, ModelMetadata, SpanOpDefaults};
 use relay_filter::GenericFiltersConfig;
 use relay_quotas::Quota;
 use serde::{Deserialize, Serialize, de};
 use serde_json::Value;
 
-use crate::{ErrorBoundary, MetricExtractionGroups};
+use crate::{ErrorBoundary, MetricExtractionGroups, TrimmingConfigs};
 
 /// A dynamic configuration for all Relays passed down from Sentry.
 ///
@@ -55,6 +56,29 @@ pub struct GlobalConfig {
         skip_serializing_if = "is_default"
     )]
     pub span_op_defaults: SpanOpDefaults,
+
+    /// Trimming settings for all projects.
+    ///
+    /// Attribute policies apply in addition to the policies in project configs, the maximum size
+    /// of project configs takes precedence.
+    #[serde(
+        deserialize_with = "default_on_error",
+        skip_serializing_if = "TrimmingConfigs::is_empty"
+    )]
+    pub trimming: TrimmingConfigs,
+
+    /// User agent parser definitions which replace the built-in definitions.
+    #[serde(
+        deserialize_with = "default_on_error",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub user_agent_definitions: Option<UserAgentDefinitions>,
+
+    /// Attribute definitions which are merged over the built-in `sentry-conventions` registry.
+    ///
+    /// If the definitions cannot be parsed, the previously loaded overrides remain active.
+    #[serde(skip_serializing_if = "is_attribute_overrides_empty")]
+    pub attribute_overrides: ErrorBoundary<Vec<AttributeOverride>>,
 }
 
 impl GlobalConfig {
@@ -245,6 +269,15 @@ where
     }
 }
 
+/// User agent parser definitions distributed with the global config.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UserAgentDefinitions {
+    /// Identifies the definitions, for example the uap-core release they were built from.
+    pub version: String,
+    /// The definitions in the format of uap-core's `regexes.yaml`.
+    pub regexes: String,
+}
+
 /// All supported metric bucket encodings.
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -303,6 +336,10 @@ fn is_model_metadata_empty(value: &ErrorBoundary<ModelMetadata>) -> bool {
     matches!(value, ErrorBoundary::Ok(metadata) if metadata.is_empty())
 }
 
+fn is_attribute_overrides_empty(value: &ErrorBoundary<Vec<AttributeOverride>>) -> bool {
+    matches!(value, ErrorBoundary::Ok(overrides) if overrides.is_empty())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
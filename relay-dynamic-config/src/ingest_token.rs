@@ -0,0 +1,55 @@
+use relay_auth::PublicKey;
+use serde::{Deserialize, Serialize};
+
+/// Configuration for signed ingestion tokens.
+///
+/// Ingestion tokens are issued by the backend of a customer and signed with one of the configured
+/// keys. They authorize ingestion for a limited time and can replace public DSN keys in clients.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct IngestTokenConfig {
+    /// Public keys that are permitted to sign ingestion tokens for this project.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub keys: Vec<PublicKey>,
+    /// Rejects all requests that do not carry a valid ingestion token if enabled.
+    #[serde(skip_serializing_if = "is_false")]
+    pub required: bool,
+}
+
+impl IngestTokenConfig {
+    /// Checks whether the config can be considered empty.
+    ///
+    /// Empty here means that all values are equal to their default values.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty() && !self.required
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let json = r#"{"keys":["JOaR2bHZ31zYjFojC7UhPOidzfT3qOQgT9WEBw1JAKU"],"required":true}"#;
+        let result: IngestTokenConfig = serde_json::from_str(json).unwrap();
+        assert!(result.required);
+        assert_eq!(result.keys.len(), 1);
+
+        let serialized = serde_json::to_string(&result).unwrap();
+        assert_eq!(json, serialized);
+    }
+
+    #[test]
+    fn test_default_serialize() {
+        let config = IngestTokenConfig::default();
+        assert!(config.is_empty());
+
+        let serialized = serde_json::to_string(&config).unwrap();
+        assert_eq!(serialized, r#"{}"#);
+    }
+}
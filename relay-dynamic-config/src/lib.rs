@@ -63,15 +63,19 @@
 
 mod defaults;
 mod error_boundary;
+mod extraction;
 mod feature;
 mod global;
+mod ingest_token;
 mod metrics;
 mod project;
 mod trusted_relay;
 
 pub use error_boundary::*;
+pub use extraction::*;
 pub use feature::*;
 pub use global::*;
+pub use ingest_token::*;
 pub use metrics::*;
 pub use project::*;
 pub use trusted_relay::*;
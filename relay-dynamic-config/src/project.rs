@@ -1,4 +1,5 @@
 use relay_auth::PublicKey;
+use relay_event_normalization::eap::AttributePolicy;
 use relay_event_normalization::{
     BreakdownsConfig, MeasurementsConfig, PerformanceScoreConfig, SpanDescriptionRule,
     TransactionNameRule,
@@ -12,6 +13,7 @@ use serde_json::Value;
 
 use crate::error_boundary::ErrorBoundary;
 use crate::feature::FeatureSet;
+use crate::ingest_token::IngestTokenConfig;
 use crate::metrics::{self, MetricExtractionConfig, SessionMetricsConfig, TaggingRule};
 use crate::trusted_relay::TrustedRelayConfig;
 use crate::{GRADUATED_FEATURE_FLAGS, defaults};
@@ -27,6 +29,9 @@ pub struct ProjectConfig {
     /// Configuration for trusted Relay behaviour.
     #[serde(skip_serializing_if = "TrustedRelayConfig::is_empty")]
     pub trusted_relay_settings: TrustedRelayConfig,
+    /// Configuration for signed ingestion tokens.
+    #[serde(skip_serializing_if = "IngestTokenConfig::is_empty")]
+    pub ingest_tokens: IngestTokenConfig,
     /// Configuration for PII stripping.
     pub pii_config: Option<PiiConfig>,
     /// The grouping configuration.
@@ -146,6 +151,7 @@ impl Default for ProjectConfig {
             allowed_domains: vec!["*".to_owned()],
             trusted_relays: vec![],
             trusted_relay_settings: TrustedRelayConfig::default(),
+            ingest_tokens: IngestTokenConfig::default(),
             pii_config: None,
             grouping_config: None,
             filter_settings: ProjectFiltersConfig::default(),
@@ -186,6 +192,8 @@ fn skip_metrics_extraction(boundary: &ErrorBoundary<MetricExtractionConfig>) ->
 pub struct LimitedProjectConfig {
     pub allowed_domains: Vec<String>,
     pub trusted_relays: Vec<PublicKey>,
+    #[serde(skip_serializing_if = "IngestTokenConfig::is_empty")]
+    pub ingest_tokens: IngestTokenConfig,
     pub pii_config: Option<PiiConfig>,
     #[serde(skip_serializing_if = "ProjectFiltersConfig::is_empty")]
     pub filter_settings: ProjectFiltersConfig,
@@ -264,26 +272,44 @@ impl RetentionsConfig {
 }
 
 /// Per-category settings for item trimming.
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TrimmingConfig {
     /// The maximum size in bytes above which an item should be trimmed.
-    pub max_size: u32,
+    ///
+    /// Currently, only spans are trimmed to a maximum size.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<u32>,
+    /// Policy for retaining, truncating and renaming attributes.
+    #[serde(default, skip_serializing_if = "AttributePolicy::is_empty")]
+    pub attributes: AttributePolicy,
 }
 
 /// Settings for item trimming.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct TrimmingConfigs {
+    /// Trimming settings for logs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log: Option<TrimmingConfig>,
     /// Trimming settings for spans.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub span: Option<TrimmingConfig>,
+    /// Trimming settings for trace metrics.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace_metric: Option<TrimmingConfig>,
 }
 
 impl TrimmingConfigs {
-    fn is_empty(&self) -> bool {
-        let Self { span } = self;
-        span.is_none()
+    /// Returns `true` if there are no trimming settings for any item type.
+    pub fn is_empty(&self) -> bool {
+        let Self {
+            log,
+            span,
+            trace_metric,
+        } = self;
+
+        log.is_none() && span.is_none() && trace_metric.is_none()
     }
 }
 
@@ -8,7 +8,7 @@ use std::net::IpAddr;
 use chrono::{DateTime, Utc};
 use relay_common::time::UnixTimestamp;
 use relay_conventions::attributes::*;
-use relay_conventions::{AttributeInfo, ReplacementName, WriteBehavior};
+use relay_conventions::{AttributeInfoRef, WriteBehavior};
 use relay_event_schema::protocol::{
     Attribute, AttributeType, Attributes, BrowserContext, Geo, SpanV2,
 };
@@ -26,6 +26,7 @@ use crate::{ClientHints, FromUserAgentInfo as _, RawUserAgentInfo};
 
 mod ai;
 mod mobile;
+mod policy;
 mod size;
 pub mod time;
 pub mod trace_metric;
@@ -33,6 +34,7 @@ mod trimming;
 
 pub use self::ai::normalize_ai;
 pub use self::mobile::{normalize_mobile_attributes, normalize_mobile_measurements};
+pub use self::policy::{AttributePolicy, AttributeRule, apply_attribute_policies};
 pub use self::size::*;
 pub use self::trimming::TrimmingProcessor;
 
@@ -378,6 +380,12 @@ pub fn normalize_user_geo(
         USER__GEO__CITY,
         USER__GEO__SUBDIVISION,
         USER__GEO__REGION,
+        USER__GEO__ASN,
+        USER__GEO__ASN_ORGANIZATION,
+        USER__GEO__IS_HOSTING_PROVIDER,
+        USER__GEO__IS_VPN,
+        USER__GEO__IS_PUBLIC_PROXY,
+        USER__GEO__IS_TOR_EXIT_NODE,
     ]
     .into_iter()
     .any(|a| attributes.contains_key(a))
@@ -398,6 +406,12 @@ pub fn normalize_user_geo(
     attributes.insert_if_missing(USER__GEO__CITY, || geo.city);
     attributes.insert_if_missing(USER__GEO__SUBDIVISION, || geo.subdivision);
     attributes.insert_if_missing(USER__GEO__REGION, || geo.region);
+    attributes.insert_if_missing(USER__GEO__ASN, || geo.asn.map_value(|asn| asn as i64));
+    attributes.insert_if_missing(USER__GEO__ASN_ORGANIZATION, || geo.asn_organization);
+    attributes.insert_if_missing(USER__GEO__IS_HOSTING_PROVIDER, || geo.is_hosting_provider);
+    attributes.insert_if_missing(USER__GEO__IS_VPN, || geo.is_vpn);
+    attributes.insert_if_missing(USER__GEO__IS_PUBLIC_PROXY, || geo.is_public_proxy);
+    attributes.insert_if_missing(USER__GEO__IS_TOR_EXIT_NODE, || geo.is_tor_exit_node);
 }
 
 /// Normalizes the dynamic sampling context into [`Attributes`].
@@ -488,7 +502,7 @@ pub fn normalize_attribute_names(attributes: &mut Annotated<Attributes>) {
     normalize_attribute_names_inner(attributes, relay_conventions::attribute_info_with_fragment)
 }
 
-type AttributeInfoFn = fn(&str) -> Option<(&'static AttributeInfo, Option<&str>)>;
+type AttributeInfoFn = fn(&str) -> Option<(AttributeInfoRef, Option<&str>)>;
 
 fn normalize_attribute_names_inner(
     attributes: &mut Annotated<Attributes>,
@@ -505,14 +519,14 @@ fn normalize_attribute_names_inner(
             continue;
         };
 
-        match attribute_info.write_behavior {
+        match &attribute_info.write_behavior {
             WriteBehavior::CurrentName => continue,
             WriteBehavior::NewName(new_name) => {
                 let Some(old_attribute) = attributes.0.get_mut(&name) else {
                     continue;
                 };
 
-                let Some(new_name) = resolve_attribute_name(new_name, fragment) else {
+                let Some(new_name) = new_name.resolve(fragment) else {
                     relay_log::error!(
                         attribute = name,
                         ?fragment,
@@ -531,7 +545,7 @@ fn normalize_attribute_names_inner(
                 }
             }
             WriteBehavior::BothNames(new_name) => {
-                let Some(new_name) = resolve_attribute_name(new_name, fragment) else {
+                let Some(new_name) = new_name.resolve(fragment) else {
                     relay_log::error!(
                         attribute = name,
                         ?fragment,
@@ -552,32 +566,6 @@ fn normalize_attribute_names_inner(
     }
 }
 
-/// Resolves the name of a replacement attribute for rewriting.
-///
-/// There are two cases to consider:
-/// - `name` is `Static` and `fragment` is `None`: This means that both
-///   the source and target attribute don't have a placeholder.
-/// - `name` is `Dynamic` and `fragment` is `Some`: This means that both the
-///   source and target attribute do have a placeholder.
-fn resolve_attribute_name(
-    name: ReplacementName,
-    fragment: Option<&str>,
-) -> Option<Cow<'static, str>> {
-    match (name, fragment) {
-        // Neither the original nor the replacement attribute contains a placeholder.
-        // Simply use the replacement attribute's static name.
-        (ReplacementName::Static(name), None) => Some(Cow::Borrowed(name)),
-        // Both the original and replacement attribute contain a placeholder.
-        // Use the replacement's interpolation function and the matched fragment
-        // to obtain the new name.
-        (ReplacementName::Dynamic(name_fn), Some(fragment)) => Some(Cow::Owned(name_fn(fragment))),
-        // The other cases would mean that either the original attribute contains a placeholder
-        // and the replacement doesn't, or vice versa. This is ruled out by a compile-time check
-        // in `relay-conventions`.
-        _ => None,
-    }
-}
-
 /// Normalizes the values of a set of attributes if present in the span.
 ///
 /// Each span type has a set of important attributes containing the main relevant information displayed
@@ -1365,7 +1353,10 @@ mod tests {
                 city: addr.to_string().into(),
                 subdivision: Annotated::empty(),
                 region: "Illu".to_owned().into(),
-                other: Default::default(),
+                asn: Annotated::new(16509),
+                asn_organization: "AMAZON-02".to_owned().into(),
+                is_hosting_provider: Annotated::new(true),
+                ..Default::default()
             })
         });
 
@@ -1375,6 +1366,14 @@ mod tests {
             "type": "string",
             "value": "192.168.2.1"
           },
+          "user.geo.asn": {
+            "type": "integer",
+            "value": 16509
+          },
+          "user.geo.asn_organization": {
+            "type": "string",
+            "value": "AMAZON-02"
+          },
           "user.geo.city": {
             "type": "string",
             "value": "192.168.2.1"
@@ -1383,6 +1382,10 @@ mod tests {
             "type": "string",
             "value": "XY"
           },
+          "user.geo.is_hosting_provider": {
+            "type": "boolean",
+            "value": true
+          },
           "user.geo.region": {
             "type": "string",
             "value": "Illu"
@@ -1434,66 +1437,66 @@ mod tests {
             format!("placeholder.backfilled.{fragment}")
         }
 
-        fn mock_attribute_info(name: &str) -> Option<(&'static AttributeInfo, Option<&str>)> {
-            use relay_conventions::ApplyScrubbing;
+        fn mock_attribute_info(name: &str) -> Option<(AttributeInfoRef, Option<&str>)> {
+            use relay_conventions::{ApplyScrubbing, AttributeInfo, ReplacementName};
 
             match name {
                 "replace.empty" => Some((
-                    &AttributeInfo {
+                    AttributeInfoRef::Static(&AttributeInfo {
                         write_behavior: WriteBehavior::NewName(ReplacementName::Static("replaced")),
                         apply_scrubbing: ApplyScrubbing::Manual,
                         aliases: &["replaced"],
-                    },
+                    }),
                     None,
                 )),
                 "replace.existing" => Some((
-                    &AttributeInfo {
+                    AttributeInfoRef::Static(&AttributeInfo {
                         write_behavior: WriteBehavior::NewName(ReplacementName::Static(
                             "not.replaced",
                         )),
                         apply_scrubbing: ApplyScrubbing::Manual,
                         aliases: &["not.replaced"],
-                    },
+                    }),
                     None,
                 )),
                 "backfill.empty" => Some((
-                    &AttributeInfo {
+                    AttributeInfoRef::Static(&AttributeInfo {
                         write_behavior: WriteBehavior::BothNames(ReplacementName::Static(
                             "backfilled",
                         )),
                         apply_scrubbing: ApplyScrubbing::Manual,
                         aliases: &["backfilled"],
-                    },
+                    }),
                     None,
                 )),
                 "backfill.existing" => Some((
-                    &AttributeInfo {
+                    AttributeInfoRef::Static(&AttributeInfo {
                         write_behavior: WriteBehavior::BothNames(ReplacementName::Static(
                             "not.backfilled",
                         )),
                         apply_scrubbing: ApplyScrubbing::Manual,
                         aliases: &["not.backfilled"],
-                    },
+                    }),
                     None,
                 )),
                 _ if let Some(fragment) = name.strip_prefix("placeholder.replace.") => Some((
-                    &AttributeInfo {
+                    AttributeInfoRef::Static(&AttributeInfo {
                         write_behavior: WriteBehavior::NewName(ReplacementName::Dynamic(
                             replace_key,
                         )),
                         apply_scrubbing: ApplyScrubbing::Manual,
                         aliases: &["placeholder.replaced.<key>"],
-                    },
+                    }),
                     Some(fragment),
                 )),
                 _ if let Some(fragment) = name.strip_prefix("placeholder.backfill.") => Some((
-                    &AttributeInfo {
+                    AttributeInfoRef::Static(&AttributeInfo {
                         write_behavior: WriteBehavior::BothNames(ReplacementName::Dynamic(
                             backfill_key,
                         )),
                         apply_scrubbing: ApplyScrubbing::Manual,
                         aliases: &["placeholder.backfilled.<key>"],
-                    },
+                    }),
                     Some(fragment),
                 )),
 
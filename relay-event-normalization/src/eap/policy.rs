@@ -0,0 +1,338 @@
+use std::collections::BTreeMap;
+
+use relay_event_schema::processor;
+use relay_event_schema::protocol::Attributes;
+use relay_pattern::{Patterns, TypedPatterns};
+use relay_protocol::{Annotated, Value};
+use serde::{Deserialize, Serialize};
+
+/// The rule ID of remarks left on attributes removed by an [`AttributePolicy`].
+const REMARK_RULE_ID: &str = "attribute_policy";
+
+/// Prefix of attributes set by Sentry, which are always retained by allowlists.
+const SENTRY_PREFIX: &str = "sentry.";
+
+/// Limits and priorities of attributes matching a set of key patterns.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AttributeRule {
+    /// Patterns matching the keys of attributes this rule applies to.
+    #[serde(skip_serializing_if = "Patterns::is_empty")]
+    pub keys: TypedPatterns,
+    /// The maximum length of string values in characters, longer values are truncated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    /// The priority of matching attributes when trimming an item to its maximum size.
+    ///
+    /// Attributes with a lower priority are removed first. Attributes without a rule have a
+    /// priority of `0`.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub priority: i32,
+}
+
+/// Policy for retaining, truncating and renaming attributes of an item type.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AttributePolicy {
+    /// Attributes to rename, mapping the original key to the new key.
+    ///
+    /// Renames are applied before all other rules of the policy. An attribute is not renamed if an
+    /// attribute with the new key already exists.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub rename: BTreeMap<String, String>,
+    /// Patterns of attribute keys to retain.
+    ///
+    /// If not empty, all attributes which do not match one of the patterns are removed. Attributes
+    /// prefixed with `sentry.` are required by Sentry and always retained, unless they are denied.
+    #[serde(skip_serializing_if = "Patterns::is_empty")]
+    pub allow: TypedPatterns,
+    /// Patterns of attribute keys to remove, takes precedence over [`Self::allow`].
+    #[serde(skip_serializing_if = "Patterns::is_empty")]
+    pub deny: TypedPatterns,
+    /// Limits and priorities of individual attributes.
+    ///
+    /// Only the first rule matching an attribute applies.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<AttributeRule>,
+}
+
+impl AttributePolicy {
+    /// Returns `true` if the policy does not modify any attributes.
+    pub fn is_empty(&self) -> bool {
+        let Self {
+            rename,
+            allow,
+            deny,
+            rules,
+        } = self;
+
+        rename.is_empty() && allow.is_empty() && deny.is_empty() && rules.is_empty()
+    }
+
+    fn is_retained(&self, key: &str) -> bool {
+        let is_allowed =
+            self.allow.is_empty() || key.starts_with(SENTRY_PREFIX) || self.allow.is_match(key);
+        is_allowed && !self.deny.is_match(key)
+    }
+
+    fn rule(&self, key: &str) -> Option<&AttributeRule> {
+        self.rules.iter().find(|rule| rule.keys.is_match(key))
+    }
+}
+
+/// Returns the priority of an attribute for trimming.
+///
+/// The first policy with a matching rule determines the priority.
+pub(crate) fn attribute_priority(policies: &[&AttributePolicy], key: &str) -> i32 {
+    policies
+        .iter()
+        .find_map(|policy| policy.rule(key))
+        .map_or(0, |rule| rule.priority)
+}
+
+/// Applies attribute policies to [`Attributes`].
+///
+/// Policies are passed in order of precedence and applied one after another. Each policy first
+/// renames attributes, then removes attributes which are not allowed or denied and finally
+/// truncates values exceeding their maximum length.
+///
+/// Like in [`TrimmingProcessor`](super::TrimmingProcessor), removed attributes leave a remark as
+/// long as the lengths of their keys fit into `removed_key_byte_budget`. Once the budget is
+/// exhausted, attributes are removed without a trace.
+pub fn apply_attribute_policies(
+    attributes: &mut Annotated<Attributes>,
+    policies: &[&AttributePolicy],
+    removed_key_byte_budget: &mut usize,
+) {
+    let Some(attributes) = attributes.value_mut() else {
+        return;
+    };
+
+    for policy in policies {
+        for (from, to) in &policy.rename {
+            if attributes.contains_key(to.as_str()) {
+                continue;
+            }
+            if let Some(attribute) = attributes.remove(from.as_str()) {
+                attributes.0.insert(to.clone(), attribute);
+            }
+        }
+
+        attributes.0.retain(|key, attribute| {
+            // Attributes removed by a previous policy already count against the budget.
+            if attribute.value().is_none() || policy.is_retained(key) {
+                return true;
+            }
+
+            if key.len() > *removed_key_byte_budget {
+                return false;
+            }
+
+            *removed_key_byte_budget -= key.len();
+            processor::delete_with_remark(attribute, REMARK_RULE_ID);
+            true
+        });
+
+        for (key, attribute) in attributes.0.iter_mut() {
+            let Some(max_length) = policy.rule(key).and_then(|rule| rule.max_length) else {
+                continue;
+            };
+
+            let Some(attribute) = attribute.value_mut() else {
+                continue;
+            };
+
+            if let Annotated(Some(Value::String(value)), meta) = &mut attribute.value.value {
+                crate::trimming::trim_string(value, meta, max_length, 0);
+            }
+        }
+    }
+}
+
+fn is_zero(value: &i32) -> bool {
+    *value == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use relay_protocol::assert_annotated_snapshot;
+
+    use super::*;
+
+    fn policy(json: &str) -> AttributePolicy {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn attributes() -> Annotated<Attributes> {
+        Annotated::from_json(
+            r#"{
+                "http.request.method": {"type": "string", "value": "GET"},
+                "http.request.header.cookie": {"type": "string", "value": "session=123"},
+                "internal.debug": {"type": "boolean", "value": true},
+                "db.query.text": {"type": "string", "value": "SELECT * FROM users"},
+                "legacy.name": {"type": "string", "value": "checkout"}
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_apply_attribute_policies() {
+        let policy = policy(
+            r#"{
+                "rename": {"legacy.name": "app.name"},
+                "allow": ["http.*", "db.*", "app.*"],
+                "deny": ["http.request.header.*"],
+                "rules": [{"keys": ["db.query.text"], "maxLength": 9}]
+            }"#,
+        );
+
+        let mut attributes = attributes();
+        let mut budget = 100;
+        apply_attribute_policies(&mut attributes, &[&policy], &mut budget);
+
+        assert_eq!(
+            budget,
+            100 - "http.request.header.cookie".len() - "internal.debug".len()
+        );
+        assert_annotated_snapshot!(attributes, @r#"
+        {
+          "app.name": {
+            "type": "string",
+            "value": "checkout"
+          },
+          "db.query.text": {
+            "type": "string",
+            "value": "SELECT..."
+          },
+          "http.request.header.cookie": null,
+          "http.request.method": {
+            "type": "string",
+            "value": "GET"
+          },
+          "internal.debug": null,
+          "_meta": {
+            "db.query.text": {
+              "value": {
+                "": {
+                  "rem": [
+                    [
+                      "!limit",
+                      "s",
+                      6,
+                      9
+                    ]
+                  ],
+                  "len": 19
+                }
+              }
+            },
+            "http.request.header.cookie": {
+              "": {
+                "rem": [
+                  [
+                    "attribute_policy",
+                    "x"
+                  ]
+                ]
+              }
+            },
+            "internal.debug": {
+              "": {
+                "rem": [
+                  [
+                    "attribute_policy",
+                    "x"
+                  ]
+                ]
+              }
+            }
+          }
+        }
+        "#);
+    }
+
+    #[test]
+    fn test_apply_attribute_policies_budget() {
+        let policy = policy(r#"{"deny": ["http.*", "internal.*"]}"#);
+
+        let mut attributes = attributes();
+        let mut budget = "internal.debug".len();
+        apply_attribute_policies(&mut attributes, &[&policy], &mut budget);
+
+        // Only the key of `internal.debug` fits into the budget, the others are removed entirely.
+        let attributes = attributes.value().unwrap();
+        assert_eq!(budget, 0);
+        assert!(!attributes.contains_key("http.request.header.cookie"));
+        assert!(!attributes.contains_key("http.request.method"));
+        assert!(attributes.contains_key("internal.debug"));
+        assert!(attributes.get_value("internal.debug").is_none());
+    }
+
+    #[test]
+    fn test_apply_attribute_policies_in_order() {
+        let project = policy(r#"{"rename": {"legacy.name": "app.name"}}"#);
+        let global =
+            policy(r#"{"deny": ["legacy.*"], "rules": [{"keys": ["app.*"], "maxLength": 5}]}"#);
+
+        let mut attributes = attributes();
+        apply_attribute_policies(&mut attributes, &[&project, &global], &mut 0);
+
+        let attributes = attributes.value().unwrap();
+        assert!(!attributes.contains_key("legacy.name"));
+        assert_eq!(
+            attributes.get_value("app.name").and_then(Value::as_str),
+            Some("ch...")
+        );
+    }
+
+    #[test]
+    fn test_apply_attribute_policies_removed_once() {
+        let project = policy(r#"{"deny": ["internal.*"]}"#);
+        let global = policy(r#"{"allow": ["http.*"]}"#);
+
+        let mut attributes = attributes();
+        let mut budget = 100;
+        apply_attribute_policies(&mut attributes, &[&project, &global], &mut budget);
+
+        // `internal.debug` is removed by both policies, but its key only counts once.
+        assert_eq!(
+            budget,
+            100 - "internal.debug".len() - "db.query.text".len() - "legacy.name".len()
+        );
+    }
+
+    #[test]
+    fn test_apply_attribute_policies_sentry_attributes() {
+        let policy = policy(r#"{"allow": ["http.*"], "deny": ["sentry.internal.*"]}"#);
+
+        let mut attributes = Annotated::<Attributes>::from_json(
+            r#"{
+                "sentry.environment": {"type": "string", "value": "prod"},
+                "sentry.internal.flag": {"type": "boolean", "value": true},
+                "db.system": {"type": "string", "value": "postgresql"}
+            }"#,
+        )
+        .unwrap();
+        apply_attribute_policies(&mut attributes, &[&policy], &mut 0);
+
+        // Allowlists do not apply to `sentry.` attributes, but denylists do.
+        let attributes = attributes.value().unwrap();
+        assert!(attributes.get_value("sentry.environment").is_some());
+        assert!(!attributes.contains_key("sentry.internal.flag"));
+        assert!(!attributes.contains_key("db.system"));
+    }
+
+    #[test]
+    fn test_attribute_priority() {
+        let project = policy(r#"{"rules": [{"keys": ["http.*"], "priority": 10}]}"#);
+        let global = policy(
+            r#"{"rules": [{"keys": ["http.*"], "priority": -10}, {"keys": ["db.*"], "priority": -1}]}"#,
+        );
+        let policies = [&project, &global];
+
+        assert_eq!(attribute_priority(&policies, "http.request.method"), 10);
+        assert_eq!(attribute_priority(&policies, "db.query.text"), -1);
+        assert_eq!(attribute_priority(&policies, "app.name"), 0);
+    }
+}
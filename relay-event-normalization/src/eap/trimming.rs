@@ -1,4 +1,4 @@
-use std::cmp::Ordering;
+use std::cmp::Reverse;
 use std::ops::Bound;
 
 use relay_event_schema::processor::{
@@ -7,6 +7,7 @@ use relay_event_schema::processor::{
 use relay_event_schema::protocol::Attributes;
 use relay_protocol::{Array, Empty, Meta, Object};
 
+use crate::eap::policy::{self, AttributePolicy};
 use crate::eap::size;
 
 #[derive(Clone, Debug)]
@@ -44,22 +45,36 @@ impl From<DeleteAction> for ProcessingAction {
 ///    aren't trimmed—if a key is too long, the attribute is simply discarded.
 /// 3. If we run out of space, all subsequent attributes are discarded.
 ///
-/// This means that large attributes will be trimmed or discarded before small ones.
+/// This means that large attributes will be trimmed or discarded before small ones. With
+/// [`Self::with_policies`], attributes are sorted by their priority first, so attributes with a low
+/// priority are discarded before attributes with a high priority regardless of their size.
 #[derive(Default)]
-pub struct TrimmingProcessor {
+pub struct TrimmingProcessor<'a> {
     size_state: Vec<SizeState>,
     removed_key_byte_budget: usize,
+    policies: &'a [&'a AttributePolicy],
 }
 
-impl TrimmingProcessor {
+impl<'a> TrimmingProcessor<'a> {
     /// Creates a new trimming processor.
     pub fn new(removed_key_byte_budget: usize) -> Self {
         Self {
             size_state: Default::default(),
             removed_key_byte_budget,
+            policies: &[],
         }
     }
 
+    /// Uses the priorities of attribute policies when trimming attributes.
+    ///
+    /// Policies are passed in order of precedence, see [`apply_attribute_policies`].
+    ///
+    /// [`apply_attribute_policies`]: crate::eap::apply_attribute_policies
+    pub fn with_policies(mut self, policies: &'a [&'a AttributePolicy]) -> Self {
+        self.policies = policies;
+        self
+    }
+
     fn should_remove_container<T: Empty>(&self, value: &T, state: &ProcessingState<'_>) -> bool {
         // Heuristic to avoid trimming a value like `[1, 1, 1, 1, ...]` into `[null, null, null,
         // null, ...]`, making it take up more space.
@@ -116,7 +131,7 @@ impl TrimmingProcessor {
     }
 }
 
-impl Processor for TrimmingProcessor {
+impl Processor for TrimmingProcessor<'_> {
     fn before_process<T: ProcessValue>(
         &mut self,
         _: Option<&T>,
@@ -367,19 +382,20 @@ impl Processor for TrimmingProcessor {
         // discrepancy for now. In any case this is fine to change.
         let original_length = size::attributes_size(attributes);
 
-        // Sort attributes by key + value size so small attributes are more likely to be preserved.
-        // Attributes with missing values will be sorted at the beginning.
+        // Sort attributes by priority and then by key + value size so high priority and small
+        // attributes are more likely to be preserved. Attributes with missing values will be
+        // sorted at the beginning.
         let inner = std::mem::take(&mut attributes.0);
         let mut sorted: Vec<_> = inner.into_iter().collect();
-        sorted.sort_by(
-            |(k1, v1), (k2, v2)| match (v1.value().is_some(), v2.value().is_some()) {
-                (false, false) => k1.len().cmp(&k2.len()),
-                (false, true) => Ordering::Less,
-                (true, false) => Ordering::Greater,
-                (true, true) => (k1.len() + size::attribute_size(v1))
-                    .cmp(&(k2.len() + size::attribute_size(v2))),
-            },
-        );
+        // Priorities require matching the policies, so compute each key only once.
+        sorted.sort_by_cached_key(|(k, v)| match v.value() {
+            None => (false, Reverse(0), k.len()),
+            Some(_) => (
+                true,
+                Reverse(policy::attribute_priority(self.policies, k)),
+                k.len() + size::attribute_size(v),
+            ),
+        });
 
         // Drop keys without values once we run out of
         // `removed_key_budget`.
@@ -626,6 +642,41 @@ mod tests {
         "###);
     }
 
+    #[test]
+    fn test_attribute_priorities() {
+        let mut attributes = Attributes::new();
+
+        attributes.insert("small", "abcdefghij"); // 15B
+        attributes.insert("important", "This value is kept"); // 27B
+
+        let mut value = Annotated::new(TestObject {
+            attributes: Annotated::new(attributes),
+            number: Annotated::empty(),
+            other_number: Annotated::empty(),
+            body: Annotated::empty(),
+            footer: Annotated::empty(),
+        });
+
+        let policy: AttributePolicy =
+            serde_json::from_str(r#"{"rules": [{"keys": ["important"], "priority": 1}]}"#).unwrap();
+        let policies = [&policy];
+        let mut processor = TrimmingProcessor::new(100).with_policies(&policies);
+
+        let state = ProcessingState::new_root(Default::default(), []);
+        processor::process_value(&mut value, &mut processor, &state).unwrap();
+
+        // Without the policy, the smaller attribute would be retained and the larger one trimmed.
+        let attributes = value.value().unwrap().attributes.value().unwrap();
+        assert_eq!(
+            attributes.get_value("important").and_then(Value::as_str),
+            Some("This value is kept")
+        );
+        assert_ne!(
+            attributes.get_value("small").and_then(Value::as_str),
+            Some("abcdefghij")
+        );
+    }
+
     #[test]
     fn test_overaccept_number() {
         let mut attributes = Attributes::new();
@@ -18,9 +18,16 @@ pub type GeoIpError = maxminddb::MaxMindDbError;
 
 /// A geo ip lookup helper based on maxmind db files.
 ///
+/// Besides the city-level database, the lookup can be extended with an ASN database and an
+/// anonymous IP database, which add network information to the [`Geo`] records.
+///
 /// The helper is internally reference counted and can be cloned cheaply.
 #[derive(Clone, Default)]
-pub struct GeoIpLookup(Option<Arc<maxminddb::Reader<ReaderType>>>);
+pub struct GeoIpLookup {
+    city: Option<Arc<maxminddb::Reader<ReaderType>>>,
+    asn: Option<Arc<maxminddb::Reader<ReaderType>>>,
+    anonymous_ip: Option<Arc<maxminddb::Reader<ReaderType>>>,
+}
 
 impl GeoIpLookup {
     /// Opens a maxminddb file by path.
@@ -28,50 +35,108 @@ impl GeoIpLookup {
     where
         P: AsRef<Path>,
     {
-        #[cfg(feature = "mmap")]
-        let reader = unsafe { maxminddb::Reader::open_mmap(path)? };
-        #[cfg(not(feature = "mmap"))]
-        let reader = maxminddb::Reader::open_readfile(path)?;
-        Ok(GeoIpLookup(Some(Arc::new(reader))))
+        Ok(GeoIpLookup {
+            city: Some(open_reader(path)?),
+            ..Default::default()
+        })
     }
 
     /// Creates a new [`GeoIpLookup`] instance without any data loaded.
     pub fn empty() -> Self {
-        Self(None)
+        Self::default()
+    }
+
+    /// Adds an ASN database, such as GeoLite2-ASN, by path.
+    ///
+    /// The database provides the autonomous system number and organization of an IP address.
+    pub fn with_asn<P>(mut self, path: P) -> Result<Self, GeoIpError>
+    where
+        P: AsRef<Path>,
+    {
+        self.asn = Some(open_reader(path)?);
+        Ok(self)
+    }
+
+    /// Adds an anonymous IP database, such as GeoIP2-Anonymous-IP, by path.
+    ///
+    /// The database flags IP addresses of hosting providers, VPNs, public proxies and Tor exit
+    /// nodes.
+    pub fn with_anonymous_ip<P>(mut self, path: P) -> Result<Self, GeoIpError>
+    where
+        P: AsRef<Path>,
+    {
+        self.anonymous_ip = Some(open_reader(path)?);
+        Ok(self)
     }
 
     /// Unix timestamp when the database was built.
     ///
     /// Returns `None` for an [`Self::empty`] database.
     pub fn build_epoch(&self) -> Option<UnixTimestamp> {
-        let reader = self.0.as_ref()?;
+        let reader = self.city.as_ref()?;
         Some(UnixTimestamp::from_secs(reader.metadata.build_epoch))
     }
 
     /// Looks up an IP address.
+    ///
+    /// Returns `None` if none of the databases contain the address.
     pub fn try_lookup(&self, ip_address: IpAddr) -> Result<Option<Geo>, GeoIpError> {
-        let Some(reader) = self.0.as_ref() else {
-            return Ok(None);
-        };
-
-        let Some(city) = reader
-            .lookup(ip_address)?
-            .decode::<maxminddb::geoip2::City>()?
-        else {
-            return Ok(None);
-        };
-
-        Ok(Some(Geo {
-            country_code: Annotated::from(city.country.iso_code.map(String::from)),
-            city: Annotated::from(city.city.names.english.map(String::from)),
-            subdivision: Annotated::from(
-                city.subdivisions
-                    .first()
-                    .and_then(|subdivision| subdivision.names.english.map(String::from)),
-            ),
-            region: Annotated::from(city.country.names.english.map(String::from)),
-            ..Default::default()
-        }))
+        let mut geo = None;
+
+        // Enterprise databases contain the same network traits as the ASN and anonymous IP
+        // databases. For city databases, the traits are empty.
+        if let Some(reader) = &self.city
+            && let Some(city) = reader
+                .lookup(ip_address)?
+                .decode::<maxminddb::geoip2::Enterprise>()?
+        {
+            let traits = city.traits;
+            geo = Some(Geo {
+                country_code: Annotated::from(city.country.iso_code.map(String::from)),
+                city: Annotated::from(city.city.names.english.map(String::from)),
+                subdivision: Annotated::from(
+                    city.subdivisions
+                        .first()
+                        .and_then(|subdivision| subdivision.names.english.map(String::from)),
+                ),
+                region: Annotated::from(city.country.names.english.map(String::from)),
+                asn: Annotated::from(traits.autonomous_system_number.map(u64::from)),
+                asn_organization: Annotated::from(
+                    traits.autonomous_system_organization.map(String::from),
+                ),
+                is_hosting_provider: Annotated::from(traits.is_hosting_provider),
+                is_vpn: Annotated::from(traits.is_anonymous_vpn),
+                is_public_proxy: Annotated::from(traits.is_public_proxy),
+                is_tor_exit_node: Annotated::from(traits.is_tor_exit_node),
+                ..Default::default()
+            });
+        }
+
+        // Errors from the auxiliary databases, for example when looking up an IPv6 address in an
+        // IPv4-only database, must not discard the results of the other databases.
+        if let Some(reader) = &self.asn
+            && let Some(asn) = lookup_auxiliary::<maxminddb::geoip2::Asn>(reader, ip_address)
+        {
+            let geo = geo.get_or_insert_with(Geo::default);
+            set_if_some(&mut geo.asn, asn.autonomous_system_number.map(u64::from));
+            set_if_some(
+                &mut geo.asn_organization,
+                asn.autonomous_system_organization.map(String::from),
+            );
+        }
+
+        if let Some(reader) = &self.anonymous_ip
+            && let Some(anonymous) =
+                lookup_auxiliary::<maxminddb::geoip2::AnonymousIp>(reader, ip_address)
+        {
+            let geo = geo.get_or_insert_with(Geo::default);
+            set_if_some(&mut geo.is_hosting_provider, anonymous.is_hosting_provider);
+            set_if_some(&mut geo.is_vpn, anonymous.is_anonymous_vpn);
+            set_if_some(&mut geo.is_public_proxy, anonymous.is_public_proxy);
+            set_if_some(&mut geo.is_tor_exit_node, anonymous.is_tor_exit_node);
+        }
+
+        Ok(geo)
     }
 
     /// Like [`Self::try_lookup`], but swallows errors.
@@ -85,3 +150,126 @@ impl fmt::Debug for GeoIpLookup {
         f.debug_struct("GeoIpLookup").finish()
     }
 }
+
+fn open_reader<P>(path: P) -> Result<Arc<maxminddb::Reader<ReaderType>>, GeoIpError>
+where
+    P: AsRef<Path>,
+{
+    #[cfg(feature = "mmap")]
+    let reader = unsafe { maxminddb::Reader::open_mmap(path)? };
+    #[cfg(not(feature = "mmap"))]
+    let reader = maxminddb::Reader::open_readfile(path)?;
+    Ok(Arc::new(reader))
+}
+
+/// Looks up an IP address in an auxiliary database, logging and ignoring errors.
+fn lookup_auxiliary<'de, T>(
+    reader: &'de maxminddb::Reader<ReaderType>,
+    ip_address: IpAddr,
+) -> Option<T>
+where
+    T: serde::Deserialize<'de>,
+{
+    match reader.lookup(ip_address).and_then(|result| result.decode()) {
+        Ok(value) => value,
+        Err(error) => {
+            relay_log::debug!(
+                error = &error as &dyn std::error::Error,
+                database = reader.metadata.database_type.as_str(),
+                "failed to look up ip address in geoip database"
+            );
+            None
+        }
+    }
+}
+
+/// Overwrites the value of `field` unless the database has no value.
+fn set_if_some<T>(field: &mut Annotated<T>, value: Option<T>) {
+    if value.is_some() {
+        field.set_value(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENTERPRISE: &str = "tests/fixtures/GeoIP2-Enterprise-Test.mmdb";
+    const ASN: &str = "tests/fixtures/GeoLite2-ASN-Test.mmdb";
+    const ANONYMOUS_IP: &str = "tests/fixtures/GeoIP2-Anonymous-IP-Test.mmdb";
+
+    #[test]
+    fn test_lookup_auxiliary_databases() {
+        let lookup = GeoIpLookup::empty()
+            .with_asn(ASN)
+            .unwrap()
+            .with_anonymous_ip(ANONYMOUS_IP)
+            .unwrap();
+
+        let geo = lookup.lookup("2.125.160.218".parse().unwrap()).unwrap();
+        assert_eq!(
+            geo,
+            Geo {
+                asn: Annotated::new(5089),
+                asn_organization: Annotated::new("Virgin Media Limited".to_owned()),
+                is_hosting_provider: Annotated::new(true),
+                is_vpn: Annotated::new(true),
+                ..Default::default()
+            }
+        );
+
+        let geo = lookup.lookup("81.2.69.160".parse().unwrap()).unwrap();
+        assert_eq!(
+            geo,
+            Geo {
+                is_public_proxy: Annotated::new(true),
+                is_tor_exit_node: Annotated::new(true),
+                ..Default::default()
+            }
+        );
+
+        assert_eq!(lookup.lookup("10.0.0.1".parse().unwrap()), None);
+    }
+
+    #[test]
+    fn test_lookup_merges_auxiliary_databases() {
+        let lookup = GeoIpLookup::open(ENTERPRISE)
+            .unwrap()
+            .with_asn(ASN)
+            .unwrap()
+            .with_anonymous_ip(ANONYMOUS_IP)
+            .unwrap();
+
+        let geo = lookup.lookup("2.125.160.216".parse().unwrap()).unwrap();
+        assert_eq!(
+            geo,
+            Geo {
+                country_code: Annotated::new("GB".to_owned()),
+                city: Annotated::new("Boxford".to_owned()),
+                subdivision: Annotated::new("England".to_owned()),
+                region: Annotated::new("United Kingdom".to_owned()),
+                asn: Annotated::new(5089),
+                asn_organization: Annotated::new("Virgin Media Limited".to_owned()),
+                is_hosting_provider: Annotated::new(true),
+                is_vpn: Annotated::new(true),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_lookup_auxiliary_error_keeps_city() {
+        let lookup = GeoIpLookup::open(ENTERPRISE)
+            .unwrap()
+            .with_asn(ASN)
+            .unwrap()
+            .with_anonymous_ip(ANONYMOUS_IP)
+            .unwrap();
+
+        // The auxiliary databases only contain IPv4 networks and fail to look up IPv6 addresses.
+        let ip_address = "::2.125.160.216".parse().unwrap();
+        let geo = lookup.try_lookup(ip_address).unwrap().unwrap();
+        assert_eq!(geo.city.as_str(), Some("Boxford"));
+        assert_eq!(geo.asn.value(), None);
+    }
+}
@@ -516,6 +516,31 @@ mod tests {
         assert_eq!(get_value!(event.user!).geo, expected);
     }
 
+    #[test]
+    fn test_geo_network_traits_from_ip_address() {
+        let lookup = GeoIpLookup::open("tests/fixtures/GeoIP2-Enterprise-Test.mmdb").unwrap();
+
+        let json = r#"{
+            "user": {
+                "ip_address": "89.160.20.112"
+            }
+        }"#;
+        let mut event = Annotated::<Event>::from_json(json).unwrap();
+
+        normalize_event(
+            &mut event,
+            &NormalizationConfig {
+                geoip_lookup: Some(&lookup),
+                ..Default::default()
+            },
+        );
+
+        let geo = get_value!(event.user.geo!);
+        assert_eq!(geo.asn.value(), Some(&29518));
+        assert_eq!(geo.asn_organization.as_str(), Some("Bredband2 AB"));
+        assert_eq!(geo.is_hosting_provider.value(), None);
+    }
+
     #[test]
     fn test_user_ip_from_remote_addr() {
         let mut event = Annotated::new(Event {
@@ -0,0 +1,139 @@
+//! Scrubbing of Elasticsearch requests.
+//!
+//! Requests are reduced to the HTTP method, the index and endpoint, and the structure of the query
+//! DSL in the request body. All values in the body are replaced with `?`.
+//!
+//! Example: `GET /logs-2025.01.02/_search {"query":{"match":{"message":"error"}}}` becomes
+//! `GET /logs-{%s}.{%s}.{%s}/_search {"query":{"match":{"message":"?"}}}`.
+
+use std::borrow::Cow;
+
+use itertools::Itertools;
+use serde_json::Value;
+
+use crate::span::TABLE_NAME_REGEX;
+use crate::span::tag_extraction::HTTP_METHOD_EXTRACTOR_REGEX;
+
+/// Maximum nesting depth of the request body that is retained.
+///
+/// Values below this depth are replaced with `?`.
+const MAX_BODY_DEPTH: usize = 8;
+
+/// Scrubs an Elasticsearch request.
+///
+/// The request is expected either in the form `METHOD /path [body]`, or to consist of the request
+/// body only. In the latter case, the description is assembled from the operation, the index and
+/// the body. Bodies containing multiple JSON documents, such as for the `_bulk` and `_msearch`
+/// endpoints, are deduplicated after scrubbing.
+///
+/// Returns `None` if the request cannot be parsed.
+pub fn scrub_elasticsearch(
+    request: &str,
+    operation: Option<&str>,
+    index: Option<&str>,
+) -> Option<String> {
+    let request = request.trim();
+
+    let mut parts = Vec::new();
+    let body = match request.split_once(' ') {
+        Some((method, rest)) if HTTP_METHOD_EXTRACTOR_REGEX.is_match(method) => {
+            let rest = rest.trim_start();
+            let (path, body) = rest
+                .split_once(char::is_whitespace)
+                .map_or((rest, ""), |(path, body)| (path, body.trim()));
+            parts.push(method.to_uppercase());
+            parts.push(scrub_path(path)?);
+            body
+        }
+        _ if request.starts_with('{') => {
+            parts.extend(operation.map(str::to_owned));
+            parts.extend(index.map(|index| scrub_index(index).into_owned()));
+            request
+        }
+        _ => return None,
+    };
+
+    if !body.is_empty() {
+        parts.push(scrub_body(body)?);
+    }
+
+    Some(parts.join(" "))
+}
+
+/// Scrubs the path of the request, retaining endpoints and scrubbing indices and identifiers.
+fn scrub_path(path: &str) -> Option<String> {
+    let path = match path.split_once('?') {
+        Some((path, _query)) => path,
+        None => path,
+    };
+
+    let segments = path.strip_prefix('/')?.split('/');
+
+    let mut scrubbed = String::new();
+    let mut seen_endpoint = false;
+    for segment in segments {
+        scrubbed.push('/');
+        if segment.starts_with('_') {
+            seen_endpoint = true;
+            scrubbed.push_str(segment);
+        } else if seen_endpoint {
+            // Document ids, scroll ids, and names of other resources following an endpoint.
+            scrubbed.push('*');
+        } else {
+            scrubbed.push_str(&scrub_index(segment));
+        }
+    }
+
+    Some(scrubbed)
+}
+
+/// Scrubs dates and identifiers in a comma separated list of index names.
+fn scrub_index(index: &str) -> Cow<'_, str> {
+    if !index.contains(',') {
+        return TABLE_NAME_REGEX.replace_all(index, "{%s}");
+    }
+
+    Cow::Owned(
+        index
+            .split(',')
+            .map(|index| TABLE_NAME_REGEX.replace_all(index.trim(), "{%s}"))
+            .unique()
+            .join(","),
+    )
+}
+
+/// Scrubs all values in one or more JSON documents.
+fn scrub_body(body: &str) -> Option<String> {
+    let mut documents = Vec::new();
+    for document in serde_json::Deserializer::from_str(body).into_iter::<Value>() {
+        let mut document = document.ok()?;
+        scrub_value(&mut document, MAX_BODY_DEPTH);
+        documents.push(document.to_string());
+    }
+
+    Some(documents.into_iter().unique().join(" "))
+}
+
+fn scrub_value(value: &mut Value, depth: usize) {
+    match value {
+        Value::Object(map) if depth > 0 => {
+            for value in map.values_mut() {
+                scrub_value(value, depth - 1);
+            }
+        }
+        Value::Array(arr) if depth > 0 => {
+            for value in arr.iter_mut() {
+                scrub_value(value, depth - 1);
+            }
+            let mut seen = Vec::with_capacity(arr.len());
+            arr.retain(|value| {
+                let duplicate = seen.contains(value);
+                if !duplicate {
+                    seen.push(value.clone());
+                }
+                !duplicate
+            });
+        }
+        value => *value = Value::String("?".to_owned()),
+    }
+}
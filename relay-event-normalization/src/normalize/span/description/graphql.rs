@@ -0,0 +1,325 @@
+//! Scrubbing of GraphQL documents.
+//!
+//! GraphQL documents are reduced to the type and name of the executed operation and the shape of
+//! its selection set. Arguments, variables, aliases, directives and literals are removed.
+//!
+//! Example: `query GetUser($id: ID!) { u: user(id: $id) { id ...UserFields } }` becomes
+//! `query GetUser { user { id ...UserFields } }`.
+
+/// Maximum nesting depth of selection sets that are included in the scrubbed document.
+///
+/// Deeper selection sets are replaced with `{ .. }`.
+const MAX_SELECTION_DEPTH: usize = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Token<'a> {
+    /// A single punctuator, such as `{`, `(` or `$`.
+    Punctuator(char),
+    /// The spread operator `...`.
+    Spread,
+    /// A name, including keywords.
+    Name(&'a str),
+    /// A string or numeric literal.
+    Literal,
+}
+
+/// Splits a GraphQL document into tokens, skipping whitespace, commas and comments.
+///
+/// Returns `None` if the document contains invalid tokens.
+fn tokenize(document: &str) -> Option<Vec<Token<'_>>> {
+    let bytes = document.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while let Some(&byte) = bytes.get(i) {
+        match byte {
+            b' ' | b'\t' | b'\n' | b'\r' | b',' => i += 1,
+            // Byte order mark.
+            0xef if bytes[i..].starts_with("\u{feff}".as_bytes()) => i += 3,
+            b'#' => {
+                while bytes.get(i).is_some_and(|b| *b != b'\n' && *b != b'\r') {
+                    i += 1;
+                }
+            }
+            b'!' | b'$' | b'&' | b'(' | b')' | b':' | b'=' | b'@' | b'[' | b']' | b'{' | b'|'
+            | b'}' => {
+                tokens.push(Token::Punctuator(byte.into()));
+                i += 1;
+            }
+            b'.' => {
+                if !bytes[i..].starts_with(b"...") {
+                    return None;
+                }
+                tokens.push(Token::Spread);
+                i += 3;
+            }
+            b'"' if bytes[i..].starts_with(b"\"\"\"") => {
+                i += 3;
+                loop {
+                    match bytes.get(i)? {
+                        b'\\' if bytes[i + 1..].starts_with(b"\"\"\"") => i += 4,
+                        b'"' if bytes[i..].starts_with(b"\"\"\"") => break,
+                        _ => i += 1,
+                    }
+                }
+                tokens.push(Token::Literal);
+                i += 3;
+            }
+            b'"' => {
+                i += 1;
+                loop {
+                    match bytes.get(i)? {
+                        b'\\' => i += 2,
+                        b'"' => break,
+                        b'\n' | b'\r' => return None,
+                        _ => i += 1,
+                    }
+                }
+                tokens.push(Token::Literal);
+                i += 1;
+            }
+            b'-' | b'0'..=b'9' => {
+                i += 1;
+                while bytes
+                    .get(i)
+                    .is_some_and(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'+' | b'-'))
+                {
+                    i += 1;
+                }
+                tokens.push(Token::Literal);
+            }
+            b'_' | b'a'..=b'z' | b'A'..=b'Z' => {
+                let start = i;
+                while bytes
+                    .get(i)
+                    .is_some_and(|b| b.is_ascii_alphanumeric() || *b == b'_')
+                {
+                    i += 1;
+                }
+                tokens.push(Token::Name(&document[start..i]));
+            }
+            _ => return None,
+        }
+    }
+
+    Some(tokens)
+}
+
+/// An executable operation of a GraphQL document.
+struct Operation<'a> {
+    /// One of `query`, `mutation` or `subscription`.
+    ty: &'a str,
+    /// The optional name of the operation.
+    name: Option<&'a str>,
+    /// The scrubbed selection set of the operation.
+    selection: String,
+}
+
+impl Operation<'_> {
+    fn format(&self) -> String {
+        match self.name {
+            Some(name) => format!("{} {} {}", self.ty, name, self.selection),
+            None => format!("{} {}", self.ty, self.selection),
+        }
+    }
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let token = self.peek()?;
+        self.pos += 1;
+        Some(token)
+    }
+
+    fn eat(&mut self, token: Token<'_>) -> bool {
+        let matches = self.peek() == Some(token);
+        if matches {
+            self.pos += 1;
+        }
+        matches
+    }
+
+    fn expect_name(&mut self) -> Option<&'a str> {
+        match self.next()? {
+            Token::Name(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Skips a balanced group of tokens starting at the `open` punctuator, if present.
+    fn skip_group(&mut self, open: char, close: char) -> Option<()> {
+        if !self.eat(Token::Punctuator(open)) {
+            return Some(());
+        }
+
+        let mut depth = 1;
+        while depth > 0 {
+            match self.next()? {
+                Token::Punctuator(c) if c == open => depth += 1,
+                Token::Punctuator(c) if c == close => depth -= 1,
+                _ => {}
+            }
+        }
+
+        Some(())
+    }
+
+    fn skip_directives(&mut self) -> Option<()> {
+        while self.eat(Token::Punctuator('@')) {
+            self.expect_name()?;
+            self.skip_group('(', ')')?;
+        }
+        Some(())
+    }
+
+    fn selection_set(&mut self, depth: usize, out: &mut String) -> Option<()> {
+        if self.peek() != Some(Token::Punctuator('{')) {
+            return None;
+        }
+
+        if depth >= MAX_SELECTION_DEPTH {
+            out.push_str("{ .. }");
+            return self.skip_group('{', '}');
+        }
+
+        self.next();
+        out.push('{');
+        while !self.eat(Token::Punctuator('}')) {
+            out.push(' ');
+            self.selection(depth, out)?;
+        }
+        out.push_str(" }");
+
+        Some(())
+    }
+
+    fn selection(&mut self, depth: usize, out: &mut String) -> Option<()> {
+        match self.next()? {
+            Token::Spread => match self.peek()? {
+                Token::Name("on") => {
+                    self.next();
+                    let ty = self.expect_name()?;
+                    out.push_str("... on ");
+                    out.push_str(ty);
+                    out.push(' ');
+                    self.skip_directives()?;
+                    self.selection_set(depth + 1, out)
+                }
+                Token::Name(fragment) => {
+                    self.next();
+                    out.push_str("...");
+                    out.push_str(fragment);
+                    self.skip_directives()
+                }
+                _ => {
+                    out.push_str("... ");
+                    self.skip_directives()?;
+                    self.selection_set(depth + 1, out)
+                }
+            },
+            Token::Name(name) => {
+                // Aliases are replaced with the name of the field.
+                let name = if self.eat(Token::Punctuator(':')) {
+                    self.expect_name()?
+                } else {
+                    name
+                };
+                out.push_str(name);
+
+                self.skip_group('(', ')')?;
+                self.skip_directives()?;
+                if self.peek() == Some(Token::Punctuator('{')) {
+                    out.push(' ');
+                    self.selection_set(depth + 1, out)?;
+                }
+                Some(())
+            }
+            _ => None,
+        }
+    }
+
+    /// Parses all operations of the document and skips fragment definitions.
+    ///
+    /// Returns `None` if the document contains anything other than executable definitions.
+    fn operations(&mut self) -> Option<Vec<Operation<'a>>> {
+        let mut operations = Vec::new();
+
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Punctuator('{') => {
+                    let mut selection = String::new();
+                    self.selection_set(0, &mut selection)?;
+                    operations.push(Operation {
+                        ty: "query",
+                        name: None,
+                        selection,
+                    });
+                }
+                Token::Name(ty @ ("query" | "mutation" | "subscription")) => {
+                    self.next();
+                    let name = match self.peek()? {
+                        Token::Name(name) => {
+                            self.next();
+                            Some(name)
+                        }
+                        _ => None,
+                    };
+                    self.skip_group('(', ')')?;
+                    self.skip_directives()?;
+
+                    let mut selection = String::new();
+                    self.selection_set(0, &mut selection)?;
+                    operations.push(Operation {
+                        ty,
+                        name,
+                        selection,
+                    });
+                }
+                Token::Name("fragment") => {
+                    self.next();
+                    self.expect_name()?;
+                    if self.next()? != Token::Name("on") {
+                        return None;
+                    }
+                    self.expect_name()?;
+                    self.skip_directives()?;
+                    self.selection_set(0, &mut String::new())?;
+                }
+                _ => return None,
+            }
+        }
+
+        Some(operations)
+    }
+}
+
+/// Reduces a GraphQL document to the type, name and selection shape of its operation.
+///
+/// If the document contains multiple operations, the one matching `operation_name` is selected,
+/// falling back to the first operation. Fragment definitions are dropped, fragment spreads are
+/// retained by name.
+///
+/// Returns `None` if the document cannot be parsed or does not contain an operation.
+pub fn scrub_graphql(document: &str, operation_name: Option<&str>) -> Option<String> {
+    let mut parser = Parser {
+        tokens: tokenize(document)?,
+        pos: 0,
+    };
+
+    let operations = parser.operations()?;
+    let operation = operations
+        .iter()
+        .find(|op| operation_name.is_some() && op.name == operation_name)
+        .or_else(|| operations.first())?;
+
+    Some(operation.format())
+}
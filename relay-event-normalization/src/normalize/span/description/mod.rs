@@ -1,4 +1,6 @@
 //! Span description scrubbing logic.
+mod elasticsearch;
+mod graphql;
 mod redis;
 mod resource;
 mod sql;
@@ -9,7 +11,8 @@ use serde_json::Value;
 pub use sql::{Mode, scrub_queries};
 use std::sync::LazyLock;
 
-use relay_event_schema::protocol::Span;
+use itertools::Itertools;
+use relay_event_schema::protocol::{Span, SpanData};
 use std::borrow::Cow;
 use std::net::{Ipv4Addr, Ipv6Addr};
 use std::path::Path;
@@ -72,9 +75,15 @@ pub(crate) fn scrub_span_description(
                     .and_then(|data| data.db_operation.value())
                     .and_then(|op| op.as_str());
 
-                let collection_name = data
-                    .and_then(|data| data.db_collection_name.value())
-                    .and_then(|collection| collection.as_str());
+                let table_names;
+                let collection_name = match data.and_then(|data| data.db_collection_name.as_str()) {
+                    Some(collection) => Some(collection),
+                    None if db_system == Some("dynamodb") => {
+                        table_names = dynamodb_table_names(data);
+                        table_names.as_deref()
+                    }
+                    None => None,
+                };
 
                 let (scrubbed, parsed_sql_statement) = scrub_db_query(
                     description,
@@ -89,6 +98,7 @@ pub(crate) fn scrub_span_description(
 
                 scrubbed
             }
+            ("graphql", _) => scrub_graphql_span(description, data),
             ("resource", ty) => scrub_resource(ty, description),
             ("ai", sub) => match sub.split_once('.').unwrap_or((sub, "")) {
                 ("run" | "pipeline", _) => {
@@ -161,6 +171,10 @@ pub fn scrub_db_query(
         } else {
             None
         }
+    } else if db_system == Some("elasticsearch") {
+        elasticsearch::scrub_elasticsearch(raw_query, db_operation, collection_name)
+    } else if db_system == Some("dynamodb") {
+        scrub_dynamodb(raw_query, db_operation, collection_name)
     } else if sub_op.contains("clickhouse")
         || sub_op.contains("mongodb")
         || sub_op.contains("redis")
@@ -206,6 +220,72 @@ fn is_legacy_activerecord(sub_op: &str, db_system: Option<&str>) -> bool {
     db_system.is_none() && (sub_op.contains("active_record") || sub_op.contains("activerecord"))
 }
 
+/// Returns a description of the format "{operation} {tables}" for DynamoDB spans.
+///
+/// The operation is taken from `db.operation`, or parsed from descriptions like
+/// `DynamoDB.GetItem`. Multiple tables are sorted and comma separated.
+fn scrub_dynamodb(
+    description: &str,
+    operation: Option<&str>,
+    tables: Option<&str>,
+) -> Option<String> {
+    let operation = operation.filter(|op| !op.is_empty()).or_else(|| {
+        let (service, operation) = description.trim().rsplit_once('.')?;
+        let is_operation = !operation.is_empty()
+            && operation.bytes().all(|b| b.is_ascii_alphanumeric())
+            && service.to_ascii_lowercase().ends_with("dynamodb");
+        is_operation.then_some(operation)
+    })?;
+
+    let tables = tables
+        .into_iter()
+        .flat_map(|tables| tables.split(','))
+        .map(str::trim)
+        .filter(|table| !table.is_empty())
+        .map(|table| TABLE_NAME_REGEX.replace_all(table, "{%s}"))
+        .sorted()
+        .dedup()
+        .join(",");
+
+    if tables.is_empty() {
+        Some(operation.to_owned())
+    } else {
+        Some(format!("{operation} {tables}"))
+    }
+}
+
+/// Returns the comma separated table names of a DynamoDB span from `aws.dynamodb.table_names`.
+fn dynamodb_table_names(data: Option<&SpanData>) -> Option<String> {
+    match data?.other.get("aws.dynamodb.table_names")?.value()? {
+        relay_protocol::Value::Array(tables) => {
+            let tables = tables.iter().filter_map(|table| table.as_str()).join(",");
+            (!tables.is_empty()).then_some(tables)
+        }
+        relay_protocol::Value::String(tables) => Some(tables.clone()),
+        _ => None,
+    }
+}
+
+/// Scrubber for spans with `span.op` "graphql.*".
+///
+/// Scrubs the GraphQL document in `graphql.document`, or the description if it contains a
+/// document. Falls back to the operation type and name from the span data.
+fn scrub_graphql_span(description: &str, data: Option<&SpanData>) -> Option<String> {
+    let attribute = |key: &str| data?.other.get(key)?.as_str();
+    let operation_name = attribute("graphql.operation.name");
+
+    attribute("graphql.document")
+        .and_then(|document| graphql::scrub_graphql(document, operation_name))
+        .or_else(|| graphql::scrub_graphql(description, operation_name))
+        .or_else(|| {
+            let ty = attribute("graphql.operation.type")?;
+            Some(match operation_name {
+                Some(name) => format!("{ty} {name}"),
+                None => ty.to_owned(),
+            })
+        })
+}
+
 fn scrub_core_data(string: &str) -> Option<String> {
     match DB_SQL_TRANSACTION_CORE_DATA_REGEX.replace_all(string, "*") {
         Cow::Owned(scrubbed) => Some(scrubbed),
@@ -1594,4 +1674,219 @@ mod tests {
         "documents",
         r#"{"documents":["..."],"insert":"documents"}"#
     );
+
+    span_description_test!(
+        graphql_query,
+        "query GetUser($id: ID!) { user(id: $id) { id name posts(first: 10) { title } } }",
+        "graphql.execute",
+        "query GetUser { user { id name posts { title } } }"
+    );
+
+    span_description_test!(
+        graphql_shorthand_query,
+        r#"{ search(term: "foo", limit: 5) { id } }"#,
+        "graphql.execute",
+        "query { search { id } }"
+    );
+
+    span_description_test!(
+        graphql_aliases_directives_and_fragments,
+        r#"mutation Update($skip: Boolean = false) {
+            first: updateUser(input: {name: "alice", tags: ["a", "b"]}) @skip(if: $skip) {
+                ...UserFields
+                ... on Admin { permissions }
+            }
+        }
+        fragment UserFields on User { id email }"#,
+        "graphql.execute",
+        "mutation Update { updateUser { ...UserFields ... on Admin { permissions } } }"
+    );
+
+    span_description_test!(
+        graphql_comments_and_block_strings,
+        "# comment\nquery Q { a(text: \"\"\"multi\nline \\\"\"\" string\"\"\") { b } }",
+        "graphql.execute",
+        "query Q { a { b } }"
+    );
+
+    span_description_test!(
+        graphql_max_depth,
+        "query Deep { a { b { c { d { e { f { g } } } } } } }",
+        "graphql.execute",
+        "query Deep { a { b { c { d { e { .. } } } } } }"
+    );
+
+    span_description_test!(
+        graphql_invalid,
+        "resolving: Query.user",
+        "graphql.resolve",
+        ""
+    );
+
+    span_description_test!(
+        graphql_type_system_definition,
+        "type User { id: ID! }",
+        "graphql.execute",
+        ""
+    );
+
+    #[test]
+    fn graphql_document_from_data() {
+        let json = r#"{
+            "description": "query",
+            "op": "graphql.execute",
+            "data": {
+                "graphql.document": "query A { a(id: 1) { id } } query B { b(id: 2) { id } }",
+                "graphql.operation.name": "B"
+            }
+        }"#;
+
+        let mut span = Annotated::<Span>::from_json(json).unwrap();
+
+        let scrubbed = scrub_span_description(span.value_mut().as_mut().unwrap(), &[]);
+
+        assert_eq!(scrubbed.0.as_deref(), Some("query B { b { id } }"));
+    }
+
+    #[test]
+    fn graphql_operation_from_data() {
+        let json = r#"{
+            "description": "GraphQL Operation",
+            "op": "graphql.execute",
+            "data": {
+                "graphql.operation.type": "mutation",
+                "graphql.operation.name": "CreateUser"
+            }
+        }"#;
+
+        let mut span = Annotated::<Span>::from_json(json).unwrap();
+
+        let scrubbed = scrub_span_description(span.value_mut().as_mut().unwrap(), &[]);
+
+        assert_eq!(scrubbed.0.as_deref(), Some("mutation CreateUser"));
+    }
+
+    macro_rules! db_system_scrubbing_test {
+        // Tests the scrubbed description of a db span for the given `db.system`.
+
+        // An empty output `""` means the input wasn't scrubbed and Relay didn't scrub it.
+        ($name:ident, $description_in:expr, $system_in:literal, $data_in:literal, $expected:literal) => {
+            #[test]
+            fn $name() {
+                let mut data: serde_json::Map<String, Value> = serde_json::from_str($data_in).unwrap();
+                data.insert("db.system".to_owned(), $system_in.into());
+                let json = serde_json::json!({
+                    "description": $description_in,
+                    "op": "db",
+                    "data": data,
+                });
+
+                let mut span = Annotated::<Span>::from_json(&json.to_string()).unwrap();
+
+                let scrubbed = scrub_span_description(span.value_mut().as_mut().unwrap(), &[]);
+
+                if $expected == "" {
+                    assert!(scrubbed.0.is_none());
+                } else {
+                    assert_eq!($expected, scrubbed.0.unwrap());
+                }
+            }
+        };
+    }
+
+    db_system_scrubbing_test!(
+        elasticsearch_search,
+        r#"GET /logs-2025.01.02/_search?size=10 {"query": {"bool": {"must": [{"match": {"message": "error"}}, {"range": {"@timestamp": {"gte": "now-1h"}}}]}}, "size": 10}"#,
+        "elasticsearch",
+        "{}",
+        r#"GET /logs-{%s}.{%s}.{%s}/_search {"query":{"bool":{"must":[{"match":{"message":"?"}},{"range":{"@timestamp":{"gte":"?"}}}]}},"size":"?"}"#
+    );
+
+    db_system_scrubbing_test!(
+        elasticsearch_document_id,
+        "PUT /users/_doc/8f14e45fceea167a?refresh=true",
+        "elasticsearch",
+        "{}",
+        "PUT /users/_doc/*"
+    );
+
+    db_system_scrubbing_test!(
+        elasticsearch_multiple_indices,
+        "post /orders-2024,orders-2025/_count",
+        "elasticsearch",
+        "{}",
+        "POST /orders-{%s}/_count"
+    );
+
+    db_system_scrubbing_test!(
+        elasticsearch_terms_deduplicated,
+        r#"POST /users/_search {"query": {"terms": {"id": [1, 2, 3]}}}"#,
+        "elasticsearch",
+        "{}",
+        r#"POST /users/_search {"query":{"terms":{"id":["?"]}}}"#
+    );
+
+    db_system_scrubbing_test!(
+        elasticsearch_bulk,
+        "POST /_bulk\n{\"index\": {\"_index\": \"a\"}}\n{\"user\": \"alice\"}\n{\"index\": {\"_index\": \"b\"}}\n{\"user\": \"bob\"}\n",
+        "elasticsearch",
+        "{}",
+        r#"POST /_bulk {"index":{"_index":"?"}} {"user":"?"}"#
+    );
+
+    db_system_scrubbing_test!(
+        elasticsearch_body_only,
+        r#"{"query": {"term": {"status": "active"}}}"#,
+        "elasticsearch",
+        r#"{"db.operation": "search", "db.collection.name": "events-20250102"}"#,
+        r#"search events-{%s} {"query":{"term":{"status":"?"}}}"#
+    );
+
+    db_system_scrubbing_test!(
+        elasticsearch_invalid_body,
+        "GET /users/_search {not json",
+        "elasticsearch",
+        "{}",
+        ""
+    );
+
+    db_system_scrubbing_test!(
+        elasticsearch_operation_only,
+        "search",
+        "elasticsearch",
+        "{}",
+        ""
+    );
+
+    db_system_scrubbing_test!(
+        dynamodb_operation_and_table,
+        "DynamoDB.GetItem",
+        "dynamodb",
+        r#"{"db.operation": "GetItem", "db.collection.name": "users_2025"}"#,
+        "GetItem users_{%s}"
+    );
+
+    db_system_scrubbing_test!(
+        dynamodb_from_description_and_table_names,
+        "DynamoDB.BatchGetItem",
+        "dynamodb",
+        r#"{"aws.dynamodb.table_names": ["users", "orders", "users"]}"#,
+        "BatchGetItem orders,users"
+    );
+
+    db_system_scrubbing_test!(
+        dynamodb_without_table,
+        "aws.dynamodb.ListTables",
+        "dynamodb",
+        "{}",
+        "ListTables"
+    );
+
+    db_system_scrubbing_test!(
+        dynamodb_unknown_operation,
+        "SELECT * FROM users WHERE id = 1",
+        "dynamodb",
+        "{}",
+        ""
+    );
 }
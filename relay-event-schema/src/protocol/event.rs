@@ -664,6 +664,40 @@ impl Getter for Event {
                 .into(),
             "user.geo.region" => self.user.value()?.geo.value()?.region.as_str()?.into(),
             "user.geo.subdivision" => self.user.value()?.geo.value()?.subdivision.as_str()?.into(),
+            "user.geo.asn" => self.user.value()?.geo.value()?.asn.value()?.into(),
+            "user.geo.asn_organization" => self
+                .user
+                .value()?
+                .geo
+                .value()?
+                .asn_organization
+                .as_str()?
+                .into(),
+            "user.geo.is_hosting_provider" => self
+                .user
+                .value()?
+                .geo
+                .value()?
+                .is_hosting_provider
+                .value()?
+                .into(),
+            "user.geo.is_vpn" => self.user.value()?.geo.value()?.is_vpn.value()?.into(),
+            "user.geo.is_public_proxy" => self
+                .user
+                .value()?
+                .geo
+                .value()?
+                .is_public_proxy
+                .value()?
+                .into(),
+            "user.geo.is_tor_exit_node" => self
+                .user
+                .value()?
+                .geo
+                .value()?
+                .is_tor_exit_node
+                .value()?
+                .into(),
             "request.method" => self.request.value()?.method.as_str()?.into(),
             "request.url" => self.request.value()?.url.as_str()?.into(),
             "transaction.source" => self
@@ -837,7 +871,7 @@ mod tests {
 
     use super::*;
     use crate::protocol::{
-        Headers, IpAddr, JsonLenientString, PairList, TagEntry, TransactionSource,
+        Geo, Headers, IpAddr, JsonLenientString, PairList, TagEntry, TransactionSource,
     };
 
     #[test]
@@ -1117,6 +1151,11 @@ mod tests {
                 id: Annotated::new(LenientString("user-id".into())),
                 segment: Annotated::new("user-seg".into()),
                 sentry_user: Annotated::new("id:user-id".into()),
+                geo: Annotated::new(Geo {
+                    asn: Annotated::new(16509),
+                    is_hosting_provider: Annotated::new(true),
+                    ..Default::default()
+                }),
                 ..Default::default()
             }),
             client_sdk: Annotated::new(ClientSdkInfo {
@@ -1218,6 +1257,12 @@ mod tests {
             Some(Val::String("user-seg")),
             event.get_value("event.user.segment")
         );
+        assert_eq!(Some(Val::U64(16509)), event.get_value("event.user.geo.asn"));
+        assert_eq!(
+            Some(Val::Bool(true)),
+            event.get_value("event.user.geo.is_hosting_provider")
+        );
+        assert_eq!(None, event.get_value("event.user.geo.is_vpn"));
         assert_eq!(
             Some(Val::String("some-transaction")),
             event.get_value("event.transaction")
@@ -23,6 +23,30 @@ pub struct Geo {
     #[metastructure(pii = "true", max_chars = 1024, max_chars_allowance = 100)]
     pub region: Annotated<String>,
 
+    /// Number of the autonomous system (ASN) announcing the IP address.
+    #[metastructure(pii = "true")]
+    pub asn: Annotated<u64>,
+
+    /// Name of the organization operating the autonomous system.
+    #[metastructure(pii = "true", max_chars = 1024, max_chars_allowance = 100)]
+    pub asn_organization: Annotated<String>,
+
+    /// Whether the IP address belongs to a hosting provider or data center.
+    #[metastructure(pii = "true")]
+    pub is_hosting_provider: Annotated<bool>,
+
+    /// Whether the IP address belongs to a VPN provider.
+    #[metastructure(pii = "true")]
+    pub is_vpn: Annotated<bool>,
+
+    /// Whether the IP address belongs to a public proxy.
+    #[metastructure(pii = "true")]
+    pub is_public_proxy: Annotated<bool>,
+
+    /// Whether the IP address is a Tor exit node.
+    #[metastructure(pii = "true")]
+    pub is_tor_exit_node: Annotated<bool>,
+
     /// Additional arbitrary fields for forwards compatibility.
     #[metastructure(additional_properties)]
     pub other: Object<Value>,
@@ -104,6 +128,12 @@ mod tests {
   "city": "San Francisco",
   "subdivision": "California",
   "region": "CA",
+  "asn": 7018,
+  "asn_organization": "AT&T Services, Inc.",
+  "is_hosting_provider": false,
+  "is_vpn": false,
+  "is_public_proxy": false,
+  "is_tor_exit_node": false,
   "other": "value"
 }"#;
         let geo = Annotated::new(Geo {
@@ -111,6 +141,12 @@ mod tests {
             city: Annotated::new("San Francisco".to_owned()),
             subdivision: Annotated::new("California".to_owned()),
             region: Annotated::new("CA".to_owned()),
+            asn: Annotated::new(7018),
+            asn_organization: Annotated::new("AT&T Services, Inc.".to_owned()),
+            is_hosting_provider: Annotated::new(false),
+            is_vpn: Annotated::new(false),
+            is_public_proxy: Annotated::new(false),
+            is_tor_exit_node: Annotated::new(false),
             other: {
                 let mut map = Map::new();
                 map.insert(
@@ -133,6 +169,12 @@ mod tests {
             city: Annotated::empty(),
             subdivision: Annotated::empty(),
             region: Annotated::empty(),
+            asn: Annotated::empty(),
+            asn_organization: Annotated::empty(),
+            is_hosting_provider: Annotated::empty(),
+            is_vpn: Annotated::empty(),
+            is_public_proxy: Annotated::empty(),
+            is_tor_exit_node: Annotated::empty(),
             other: Object::default(),
         });
 
@@ -206,7 +206,13 @@ mod tests {
         ];
 
         for event in &events {
-            let filter_result = should_filter(event, &FilterConfig { is_enabled: false });
+            let filter_result = should_filter(
+                event,
+                &FilterConfig {
+                    is_enabled: false,
+                    report_only: false,
+                },
+            );
             assert_eq!(
                 filter_result,
                 Ok(()),
@@ -236,7 +242,13 @@ mod tests {
 
         for source_name in &sources {
             let event = get_event_with_exception_source(source_name);
-            let filter_result = should_filter(&event, &FilterConfig { is_enabled: true });
+            let filter_result = should_filter(
+                &event,
+                &FilterConfig {
+                    is_enabled: true,
+                    report_only: false,
+                },
+            );
 
             assert_ne!(
                 filter_result,
@@ -283,7 +295,13 @@ mod tests {
 
         for exc_value in &exceptions {
             let event = get_event_with_exception_value(exc_value);
-            let filter_result = should_filter(&event, &FilterConfig { is_enabled: true });
+            let filter_result = should_filter(
+                &event,
+                &FilterConfig {
+                    is_enabled: true,
+                    report_only: false,
+                },
+            );
             assert_ne!(
                 filter_result,
                 Ok(()),
@@ -302,7 +320,13 @@ mod tests {
         ];
 
         for event in &events {
-            let filter_result = should_filter(event, &FilterConfig { is_enabled: true });
+            let filter_result = should_filter(
+                event,
+                &FilterConfig {
+                    is_enabled: true,
+                    report_only: false,
+                },
+            );
             assert_eq!(
                 filter_result,
                 Ok(()),
@@ -19,6 +19,9 @@ use serde::{Deserialize, Serialize, Serializer, de};
 pub struct FilterConfig {
     /// Specifies whether this filter is enabled.
     pub is_enabled: bool,
+    /// Reports matches of this filter instead of dropping the item.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub report_only: bool,
 }
 
 impl FilterConfig {
@@ -145,6 +148,9 @@ impl Serialize for LegacyBrowser {
 pub struct ClientIpsFilterConfig {
     /// Blacklisted client ip addresses.
     pub blacklisted_ips: Vec<String>,
+    /// Reports matches of this filter instead of dropping the item.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub report_only: bool,
 }
 
 impl ClientIpsFilterConfig {
@@ -160,6 +166,9 @@ impl ClientIpsFilterConfig {
 pub struct CspFilterConfig {
     /// Disallowed sources for CSP reports.
     pub disallowed_sources: Vec<String>,
+    /// Reports matches of this filter instead of dropping the item.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub report_only: bool,
 }
 
 impl CspFilterConfig {
@@ -171,9 +180,13 @@ impl CspFilterConfig {
 
 /// Configuration for the error messages filter.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ErrorMessagesFilterConfig {
     /// List of error message patterns that will be filtered.
     pub patterns: TypedPatterns<CaseInsensitive>,
+    /// Reports matches of this filter instead of dropping the item.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub report_only: bool,
 }
 
 /// Configuration for transaction name filter.
@@ -185,6 +198,9 @@ pub struct IgnoreTransactionsFilterConfig {
     /// True if the filter is enabled
     #[serde(default)]
     pub is_enabled: bool,
+    /// Reports matches of this filter instead of dropping the item.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub report_only: bool,
 }
 
 impl IgnoreTransactionsFilterConfig {
@@ -203,9 +219,13 @@ impl ErrorMessagesFilterConfig {
 
 /// Configuration for the releases filter.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ReleasesFilterConfig {
     /// List of release names that will be filtered.
     pub releases: TypedPatterns<CaseInsensitive>,
+    /// Reports matches of this filter instead of dropping the item.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub report_only: bool,
 }
 
 impl ReleasesFilterConfig {
@@ -224,6 +244,9 @@ pub struct LegacyBrowsersFilterConfig {
     /// The browsers to filter.
     #[serde(default, rename = "options")]
     pub browsers: BTreeSet<LegacyBrowser>,
+    /// Reports matches of this filter instead of dropping the item.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub report_only: bool,
 }
 
 impl LegacyBrowsersFilterConfig {
@@ -243,6 +266,12 @@ pub struct GenericFilterConfig {
     pub is_enabled: bool,
     /// The condition for the filter.
     pub condition: Option<RuleCondition>,
+    /// Reports matches of this filter instead of dropping the item.
+    ///
+    /// If the filter is declared in both the project and the global config, the value of the
+    /// project config takes precedence. Defaults to `false` if neither of them declares it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report_only: Option<bool>,
 }
 
 impl GenericFilterConfig {
@@ -323,6 +352,7 @@ impl GenericFilterConfig {
 ///                     id: "filter1",
 ///                     is_enabled: false,
 ///                     condition: None,
+///                     report_only: None,
 ///                 },
 ///             },
 ///         ),
@@ -364,6 +394,7 @@ impl GenericFilterConfig {
 ///             id: "filter1".to_owned(),
 ///             is_enabled: true,
 ///             condition: Some(RuleCondition::eq("event.exceptions", "drop-error")),
+///             report_only: None,
 ///         },
 ///     ].into(),
 /// };
@@ -501,6 +532,10 @@ impl Serialize for GenericFiltersMap {
     }
 }
 
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
 /// Configuration for all event filters from project configs.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -576,32 +611,41 @@ mod tests {
         ProjectFiltersConfig {
             browser_extensions: FilterConfig {
                 is_enabled: false,
+                report_only: false,
             },
             client_ips: ClientIpsFilterConfig {
                 blacklisted_ips: [],
+                report_only: false,
             },
             web_crawlers: FilterConfig {
                 is_enabled: false,
+                report_only: false,
             },
             csp: CspFilterConfig {
                 disallowed_sources: [],
+                report_only: false,
             },
             error_messages: ErrorMessagesFilterConfig {
                 patterns: [],
+                report_only: false,
             },
             legacy_browsers: LegacyBrowsersFilterConfig {
                 is_enabled: false,
                 browsers: {},
+                report_only: false,
             },
             localhost: FilterConfig {
                 is_enabled: false,
+                report_only: false,
             },
             releases: ReleasesFilterConfig {
                 releases: [],
+                report_only: false,
             },
             ignore_transactions: IgnoreTransactionsFilterConfig {
                 patterns: [],
                 is_enabled: false,
+                report_only: false,
             },
             generic: GenericFiltersConfig {
                 version: 0,
@@ -623,16 +667,25 @@ mod tests {
     #[test]
     fn test_serialize_full() {
         let filters_config = ProjectFiltersConfig {
-            browser_extensions: FilterConfig { is_enabled: true },
+            browser_extensions: FilterConfig {
+                is_enabled: true,
+                report_only: false,
+            },
             client_ips: ClientIpsFilterConfig {
                 blacklisted_ips: vec!["127.0.0.1".to_owned()],
+                report_only: false,
+            },
+            web_crawlers: FilterConfig {
+                is_enabled: true,
+                report_only: false,
             },
-            web_crawlers: FilterConfig { is_enabled: true },
             csp: CspFilterConfig {
                 disallowed_sources: vec!["https://*".to_owned()],
+                report_only: false,
             },
             error_messages: ErrorMessagesFilterConfig {
                 patterns: TypedPatterns::from(["Panic".to_owned()]),
+                report_only: true,
             },
             legacy_browsers: LegacyBrowsersFilterConfig {
                 is_enabled: false,
@@ -640,14 +693,20 @@ mod tests {
                     .iter()
                     .cloned()
                     .collect(),
+                report_only: false,
+            },
+            localhost: FilterConfig {
+                is_enabled: true,
+                report_only: false,
             },
-            localhost: FilterConfig { is_enabled: true },
             releases: ReleasesFilterConfig {
                 releases: TypedPatterns::from(["1.2.3".to_owned()]),
+                report_only: false,
             },
             ignore_transactions: IgnoreTransactionsFilterConfig {
                 patterns: TypedPatterns::from(["*health*".to_owned()]),
                 is_enabled: true,
+                report_only: false,
             },
             generic: GenericFiltersConfig {
                 version: 1,
@@ -655,6 +714,7 @@ mod tests {
                     id: "hydrationError".to_owned(),
                     is_enabled: true,
                     condition: Some(RuleCondition::eq("event.exceptions", "HydrationError")),
+                    report_only: None,
                 }]
                 .into(),
             },
@@ -681,7 +741,8 @@ mod tests {
           "errorMessages": {
             "patterns": [
               "Panic"
-            ]
+            ],
+            "reportOnly": true
           },
           "legacyBrowsers": {
             "isEnabled": false,
@@ -730,6 +791,7 @@ mod tests {
         LegacyBrowsersFilterConfig {
             is_enabled: false,
             browsers: {},
+            report_only: false,
         }
         "###);
     }
@@ -774,11 +836,13 @@ mod tests {
                                 },
                             ),
                         ),
+                        report_only: None,
                     },
                     "chunkLoadError": GenericFilterConfig {
                         id: "chunkLoadError",
                         is_enabled: false,
                         condition: None,
+                        report_only: None,
                     },
                 },
             ),
@@ -400,6 +400,7 @@ mod tests {
         let event = get_csp_event(None, Some("http://known.bad.com"), None);
         let config = CspFilterConfig {
             disallowed_sources: vec!["http://known.bad.com".to_owned()],
+            report_only: false,
         };
 
         let actual = should_filter(&event, &config);
@@ -415,6 +416,7 @@ mod tests {
         let event = get_csp_event(None, Some("http://good.file.com"), None);
         let config = CspFilterConfig {
             disallowed_sources: vec!["http://known.bad.com".to_owned()],
+            report_only: false,
         };
 
         let actual = should_filter(&event, &config);
@@ -430,6 +432,7 @@ mod tests {
         let event = get_csp_event(None, None, Some("http://known.bad.com"));
         let config = CspFilterConfig {
             disallowed_sources: vec!["http://known.bad.com".to_owned()],
+            report_only: false,
         };
 
         let actual = should_filter(&event, &config);
@@ -445,6 +448,7 @@ mod tests {
         let event = get_csp_event(Some("http://known.bad.com"), None, None);
         let config = CspFilterConfig {
             disallowed_sources: vec!["http://known.bad.com".to_owned()],
+            report_only: false,
         };
 
         let actual = should_filter(&event, &config);
@@ -460,6 +464,7 @@ mod tests {
         let event = get_csp_event(Some("http://good.file.com"), None, None);
         let config = CspFilterConfig {
             disallowed_sources: vec!["http://known.bad.com".to_owned()],
+            report_only: false,
         };
 
         let actual = should_filter(&event, &config);
@@ -476,6 +481,7 @@ mod tests {
         event.ty = Annotated::from(EventType::Transaction);
         let config = CspFilterConfig {
             disallowed_sources: vec!["http://known.bad.com".to_owned()],
+            report_only: false,
         };
 
         let actual = should_filter(&event, &config);
@@ -516,6 +522,7 @@ mod tests {
             let event = get_csp_event(*blocked_uri, *source_file, None);
             let config = CspFilterConfig {
                 disallowed_sources: get_disallowed_sources(),
+                report_only: false,
             };
 
             let actual = should_filter(&event, &config);
@@ -539,6 +546,7 @@ mod tests {
             let event = get_csp_event(*blocked_uri, *source_file, None);
             let config = CspFilterConfig {
                 disallowed_sources: get_disallowed_sources(),
+                report_only: false,
             };
 
             let actual = should_filter(&event, &config);
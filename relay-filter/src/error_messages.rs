@@ -75,6 +75,7 @@ mod tests {
                     "".to_owned(),
                     "this is".to_owned(),
                 ]),
+                report_only: false,
             },
             // without globs
             ErrorMessagesFilterConfig {
@@ -83,6 +84,7 @@ mod tests {
                     "filteredexception".to_owned(),
                     "this is a filtered exception.".to_owned(),
                 ]),
+                report_only: false,
             },
         ];
 
@@ -175,6 +177,7 @@ mod tests {
             "*https://reactjs.org/docs/error-decoder.html?invariant={418,419,422,423,425}*";
         let config = ErrorMessagesFilterConfig {
             patterns: TypedPatterns::from([pattern.to_owned()]),
+            report_only: false,
         };
 
         let event = Annotated::<Event>::from_json(
@@ -206,6 +209,7 @@ mod tests {
                 "ChunkLoadError: Loading chunk *".to_owned(),
                 "*Uncaught *: ChunkLoadError: Loading chunk *".to_owned(),
             ]),
+            report_only: false,
         };
 
         for error in errors {
@@ -237,6 +241,7 @@ mod tests {
                 "ChunkLoadError: Failed to load chunk *".to_owned(),
                 "*Uncaught *: ChunkLoadError: Failed to load chunk *".to_owned(),
             ]),
+            report_only: false,
         };
 
         for error in errors {
@@ -257,6 +262,7 @@ mod tests {
         // Test exception-based matching for Turbopack
         let exception_config = ErrorMessagesFilterConfig {
             patterns: TypedPatterns::from(["ChunkLoadError: Failed to load chunk *".to_owned()]),
+            report_only: false,
         };
 
         let exception_event = Event {
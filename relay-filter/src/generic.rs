@@ -36,11 +36,13 @@ fn matches<F: Getter>(item: &F, condition: Option<&RuleCondition>) -> bool {
 /// Note that conditions may have type-specific getter strings, e.g. `"event.some_field"`. In order
 /// to make such a generic filter apply to non-Event types, make sure that the [`Getter`] implementation
 /// for that type maps `"event.some_field"` to the corresponding field on that type.
+///
+/// Returns the first matching report-only filter, if the item is not filtered.
 pub(crate) fn should_filter<F: Getter>(
     item: &F,
     project_filters: &GenericFiltersConfig,
     global_filters: Option<&GenericFiltersConfig>,
-) -> Result<(), FilterStatKey> {
+) -> Result<Option<FilterStatKey>, FilterStatKey> {
     let filters = merge_generic_filters(
         project_filters,
         global_filters,
@@ -48,13 +50,18 @@ pub(crate) fn should_filter<F: Getter>(
         MAX_SUPPORTED_VERSION,
     );
 
+    let mut reported = None;
     for filter_config in filters {
         if filter_config.is_enabled && matches(item, filter_config.condition) {
-            return Err(FilterStatKey::GenericFilter(filter_config.id.to_owned()));
+            let key = FilterStatKey::GenericFilter(filter_config.id.to_owned());
+            match filter_config.report_only {
+                true => reported = reported.or(Some(key)),
+                false => return Err(key),
+            }
         }
     }
 
-    Ok(())
+    Ok(reported)
 }
 
 /// Returns an iterator that yields merged generic configs.
@@ -152,6 +159,10 @@ fn merge_filters<'a>(
             .condition
             .as_ref()
             .or(secondary.and_then(|filter| filter.condition.as_ref())),
+        report_only: primary
+            .report_only
+            .or(secondary.and_then(|filter| filter.report_only))
+            .unwrap_or_default(),
     }
 }
 
@@ -160,6 +171,7 @@ struct GenericFilterConfigRef<'a> {
     id: &'a str,
     is_enabled: bool,
     condition: Option<&'a RuleCondition>,
+    report_only: bool,
 }
 
 impl<'a> From<&'a GenericFilterConfig> for GenericFilterConfigRef<'a> {
@@ -168,6 +180,7 @@ impl<'a> From<&'a GenericFilterConfig> for GenericFilterConfigRef<'a> {
             id: value.id.as_str(),
             is_enabled: value.is_enabled,
             condition: value.condition.as_ref(),
+            report_only: value.report_only.unwrap_or_default(),
         }
     }
 }
@@ -177,7 +190,7 @@ mod tests {
 
     use super::*;
 
-    use relay_event_schema::protocol::{Event, LenientString};
+    use relay_event_schema::protocol::{Event, Geo, LenientString, User};
     use relay_protocol::{Annotated, FromValue as _};
 
     fn mock_filters() -> GenericFiltersMap {
@@ -186,11 +199,13 @@ mod tests {
                 id: "firstReleases".to_owned(),
                 is_enabled: true,
                 condition: Some(RuleCondition::eq("event.release", "1.0")),
+                report_only: None,
             },
             GenericFilterConfig {
                 id: "helloTransactions".to_owned(),
                 is_enabled: true,
                 condition: Some(RuleCondition::eq("event.transaction", "/hello")),
+                report_only: None,
             },
         ]
         .into()
@@ -255,7 +270,42 @@ mod tests {
             transaction: Annotated::new("/world".to_owned()),
             ..Default::default()
         };
-        assert_eq!(should_filter(&event, &config, None), Ok(()));
+        assert_eq!(should_filter(&event, &config, None), Ok(None));
+    }
+
+    #[test]
+    fn test_should_filter_hosting_providers() {
+        let config = GenericFiltersConfig {
+            version: 1,
+            filters: vec![GenericFilterConfig {
+                id: "dataCenters".to_owned(),
+                is_enabled: true,
+                condition: Some(RuleCondition::eq(
+                    "event.user.geo.is_hosting_provider",
+                    true,
+                )),
+                report_only: None,
+            }]
+            .into(),
+        };
+
+        let event = |is_hosting_provider| Event {
+            user: Annotated::new(User {
+                geo: Annotated::new(Geo {
+                    asn: Annotated::new(16509),
+                    is_hosting_provider: Annotated::new(is_hosting_provider),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            should_filter(&event(true), &config, None),
+            Err(FilterStatKey::GenericFilter("dataCenters".to_owned()))
+        );
+        assert_eq!(should_filter(&event(false), &config, None), Ok(None));
     }
 
     #[test]
@@ -271,7 +321,7 @@ mod tests {
             transaction: Annotated::new("/hello".to_owned()),
             ..Default::default()
         };
-        assert_eq!(should_filter(&event, &config, None), Ok(()));
+        assert_eq!(should_filter(&event, &config, None), Ok(None));
     }
 
     #[test]
@@ -282,6 +332,7 @@ mod tests {
                 id: "firstReleases".to_owned(),
                 is_enabled: true,
                 condition: Some(RuleCondition::eq("event.release", "1.0")),
+                report_only: None,
             }]
             .into(),
         };
@@ -292,6 +343,7 @@ mod tests {
                 id: "helloTransactions".to_owned(),
                 is_enabled: true,
                 condition: Some(RuleCondition::eq("event.transaction", "/hello")),
+                report_only: None,
             }]
             .into(),
         };
@@ -307,6 +359,82 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_should_filter_report_only() {
+        let project = GenericFiltersConfig {
+            version: 1,
+            filters: vec![GenericFilterConfig {
+                id: "firstReleases".to_owned(),
+                is_enabled: true,
+                condition: None,
+                report_only: None,
+            }]
+            .into(),
+        };
+
+        let global = GenericFiltersConfig {
+            version: 1,
+            filters: vec![
+                GenericFilterConfig {
+                    id: "firstReleases".to_owned(),
+                    is_enabled: true,
+                    condition: Some(RuleCondition::eq("event.release", "1.0")),
+                    report_only: Some(true),
+                },
+                GenericFilterConfig {
+                    id: "helloTransactions".to_owned(),
+                    is_enabled: true,
+                    condition: Some(RuleCondition::eq("event.transaction", "/hello")),
+                    report_only: None,
+                },
+            ]
+            .into(),
+        };
+
+        // Only matches the report-only filter, the event is kept.
+        let event = Event {
+            release: Annotated::new(LenientString("1.0".to_owned())),
+            ..Default::default()
+        };
+        assert_eq!(
+            should_filter(&event, &project, Some(&global)),
+            Ok(Some(FilterStatKey::GenericFilter(
+                "firstReleases".to_owned()
+            )))
+        );
+
+        // Filters which are not report-only still apply.
+        let event = Event {
+            release: Annotated::new(LenientString("1.0".to_owned())),
+            transaction: Annotated::new("/hello".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(
+            should_filter(&event, &project, Some(&global)),
+            Err(FilterStatKey::GenericFilter("helloTransactions".to_owned()))
+        );
+
+        // The project config can enforce a filter which is report-only in the global config.
+        let project = GenericFiltersConfig {
+            version: 1,
+            filters: vec![GenericFilterConfig {
+                id: "firstReleases".to_owned(),
+                is_enabled: true,
+                condition: None,
+                report_only: Some(false),
+            }]
+            .into(),
+        };
+        let event = Event {
+            release: Annotated::new(LenientString("1.0".to_owned())),
+            ..Default::default()
+        };
+        assert_eq!(
+            should_filter(&event, &project, Some(&global)),
+            Err(FilterStatKey::GenericFilter("firstReleases".to_owned()))
+        );
+    }
+
     fn empty_filter() -> GenericFiltersConfig {
         GenericFiltersConfig {
             version: 1,
@@ -322,6 +450,7 @@ mod tests {
                 id: id.to_owned(),
                 is_enabled: true,
                 condition: Some(RuleCondition::eq("event.exceptions", "myError")),
+                report_only: None,
             }]
             .into(),
         }
@@ -335,6 +464,7 @@ mod tests {
                 id: id.to_owned(),
                 is_enabled: true,
                 condition: None,
+                report_only: None,
             }]
             .into(),
         }
@@ -348,6 +478,7 @@ mod tests {
                 id: id.to_owned(),
                 is_enabled: false,
                 condition: Some(RuleCondition::eq("event.exceptions", "myError")),
+                report_only: None,
             }]
             .into(),
         }
@@ -361,6 +492,7 @@ mod tests {
                 id: id.to_owned(),
                 is_enabled: false,
                 condition: None,
+                report_only: None,
             }]
             .into(),
         }
@@ -538,6 +670,7 @@ mod tests {
             id: "filter".to_owned(),
             is_enabled: false,
             condition: global.filters.first().unwrap().1.condition.clone(),
+            report_only: None,
         };
         assert!(
             merge_generic_filters(&project, Some(&global), 1).eq([expected.into()].into_iter())
@@ -637,6 +770,7 @@ mod tests {
             id: "filter".to_owned(),
             is_enabled: true,
             condition: global.filters.first().unwrap().1.condition.clone(),
+            report_only: None,
         };
         assert!(
             merge_generic_filters(&project, Some(&global), 1).eq([expected.into()].into_iter())
@@ -695,6 +829,7 @@ mod tests {
             id: "filter".to_owned(),
             is_enabled: false,
             condition: global.filters.first().unwrap().1.condition.clone(),
+            report_only: None,
         };
         assert!(
             merge_generic_filters(&project, Some(&global), 1).eq([expected.into()].into_iter())
@@ -740,16 +875,19 @@ mod tests {
                     id: "0".to_owned(),
                     is_enabled: true,
                     condition: Some(RuleCondition::eq("event.exceptions", "myError")),
+                    report_only: None,
                 },
                 GenericFilterConfig {
                     id: "1".to_owned(),
                     is_enabled: true,
                     condition: None,
+                    report_only: None,
                 },
                 GenericFilterConfig {
                     id: "2".to_owned(),
                     is_enabled: true,
                     condition: Some(RuleCondition::eq("event.exceptions", "myError")),
+                    report_only: None,
                 },
             ]
             .into(),
@@ -761,11 +899,13 @@ mod tests {
                     id: "1".to_owned(),
                     is_enabled: false,
                     condition: Some(RuleCondition::eq("event.exceptions", "myOtherError")),
+                    report_only: None,
                 },
                 GenericFilterConfig {
                     id: "3".to_owned(),
                     is_enabled: false,
                     condition: Some(RuleCondition::eq("event.exceptions", "myLastError")),
+                    report_only: None,
                 },
             ]
             .into(),
@@ -776,6 +916,7 @@ mod tests {
             id: "1".to_owned(),
             is_enabled: true,
             condition: Some(RuleCondition::eq("event.exceptions", "myOtherError")),
+            report_only: None,
         };
         let expected2 = &project.filters[2];
         let expected3 = &global.filters[1];
@@ -799,6 +940,7 @@ mod tests {
                 id: "os_name".to_owned(),
                 is_enabled: true,
                 condition: Some(RuleCondition::eq("event.contexts.os.name", "fooBar").negate()),
+                report_only: None,
             }]
             .into(),
         };
@@ -819,7 +961,7 @@ mod tests {
             let expected = if filters {
                 Err(FilterStatKey::GenericFilter("os_name".to_owned()))
             } else {
-                Ok(())
+                Ok(None)
             };
 
             assert_eq!(
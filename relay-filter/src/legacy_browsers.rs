@@ -169,6 +169,7 @@ mod tests {
                 }
                 browsers
             },
+            report_only: false,
         }
     }
 
@@ -45,6 +45,9 @@ pub use interface::{Filterable, UserAgent};
 /// If the event should be filtered, the `Err` returned contains a filter reason.
 /// The reason is the message returned by the first filter that didn't pass.
 ///
+/// Filters configured as `report_only` are evaluated but never reject the item. If the item is
+/// kept, `Ok` contains the reason of the first report-only filter that matched.
+///
 /// The `client_ip` parameter is the "client IP" extracted from the envelope. It's
 /// used for client IP filtering and should not be confused with a "user IP" that may
 /// be contained in the item, which is used for localhost filtering.
@@ -53,23 +56,99 @@ pub fn should_filter<F: Filterable + Getter>(
     client_ip: Option<IpAddr>,
     config: &ProjectFiltersConfig,
     global_config: Option<&GenericFiltersConfig>,
-) -> Result<(), FilterStatKey> {
+) -> Result<Option<FilterStatKey>, FilterStatKey> {
     // In order to maintain backwards compatibility, we still want to run the old matching logic,
     // but we will try to match generic filters first, since the goal is to eventually fade out
     // the normal filters except for the ones that have complex conditions.
-    generic::should_filter(item, &config.generic, global_config)?;
+    let mut reported = generic::should_filter(item, &config.generic, global_config)?;
+
+    let mut apply = |result, report_only| match result {
+        Err(key) if report_only => {
+            reported.get_or_insert(key);
+            Ok(())
+        }
+        result => result,
+    };
 
     // The order of applying filters should not matter as they are additive. Still, be careful
     // when making changes to this order.
-    csp::should_filter(item, &config.csp)?;
-    client_ips::should_filter(client_ip, &config.client_ips)?;
-    releases::should_filter(item, &config.releases)?;
-    error_messages::should_filter(item, &config.error_messages)?;
-    localhost::should_filter(item, &config.localhost)?;
-    browser_extensions::should_filter(item, &config.browser_extensions)?;
-    legacy_browsers::should_filter(item, &config.legacy_browsers)?;
-    web_crawlers::should_filter(item, &config.web_crawlers)?;
-    transaction_name::should_filter(item, &config.ignore_transactions)?;
-
-    Ok(())
+    apply(
+        csp::should_filter(item, &config.csp),
+        config.csp.report_only,
+    )?;
+    apply(
+        client_ips::should_filter(client_ip, &config.client_ips),
+        config.client_ips.report_only,
+    )?;
+    apply(
+        releases::should_filter(item, &config.releases),
+        config.releases.report_only,
+    )?;
+    apply(
+        error_messages::should_filter(item, &config.error_messages),
+        config.error_messages.report_only,
+    )?;
+    apply(
+        localhost::should_filter(item, &config.localhost),
+        config.localhost.report_only,
+    )?;
+    apply(
+        browser_extensions::should_filter(item, &config.browser_extensions),
+        config.browser_extensions.report_only,
+    )?;
+    apply(
+        legacy_browsers::should_filter(item, &config.legacy_browsers),
+        config.legacy_browsers.report_only,
+    )?;
+    apply(
+        web_crawlers::should_filter(item, &config.web_crawlers),
+        config.web_crawlers.report_only,
+    )?;
+    apply(
+        transaction_name::should_filter(item, &config.ignore_transactions),
+        config.ignore_transactions.report_only,
+    )?;
+
+    Ok(reported)
+}
+
+#[cfg(test)]
+mod tests {
+    use relay_event_schema::protocol::{Event, LogEntry};
+    use relay_pattern::TypedPatterns;
+    use relay_protocol::Annotated;
+
+    use super::*;
+
+    #[test]
+    fn test_should_filter_report_only() {
+        let event = Event {
+            logentry: Annotated::new(LogEntry::from("ChunkLoadError".to_owned())),
+            ..Default::default()
+        };
+
+        let mut config = ProjectFiltersConfig {
+            error_messages: ErrorMessagesFilterConfig {
+                patterns: TypedPatterns::from(["ChunkLoadError".to_owned()]),
+                report_only: true,
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            should_filter(&event, None, &config, None),
+            Ok(Some(FilterStatKey::ErrorMessage))
+        );
+
+        config.client_ips.blacklisted_ips = vec!["127.0.0.1".to_owned()];
+        assert_eq!(
+            should_filter(&event, "127.0.0.1".parse().ok(), &config, None),
+            Err(FilterStatKey::IpAddress)
+        );
+
+        config.error_messages.report_only = false;
+        assert_eq!(
+            should_filter(&event, None, &config, None),
+            Err(FilterStatKey::ErrorMessage)
+        );
+    }
 }
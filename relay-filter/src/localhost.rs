@@ -119,7 +119,13 @@ mod tests {
             get_event_with_ip_addr("127.0.0.1"),
             get_event_with_domain("localhost"),
         ] {
-            let filter_result = should_filter(event, &FilterConfig { is_enabled: false });
+            let filter_result = should_filter(
+                event,
+                &FilterConfig {
+                    is_enabled: false,
+                    report_only: false,
+                },
+            );
             assert_eq!(
                 filter_result,
                 Ok(()),
@@ -132,7 +138,13 @@ mod tests {
     fn test_filter_local_ip() {
         for ip_addr in &["127.0.0.1", "::1"] {
             let event = get_event_with_ip_addr(ip_addr);
-            let filter_result = should_filter(&event, &FilterConfig { is_enabled: true });
+            let filter_result = should_filter(
+                &event,
+                &FilterConfig {
+                    is_enabled: true,
+                    report_only: false,
+                },
+            );
             assert_ne!(
                 filter_result,
                 Ok(()),
@@ -145,7 +157,13 @@ mod tests {
     fn test_dont_filter_non_local_ip() {
         for ip_addr in &["133.12.12.1", "2001:db8:0:0:0:ff00:42:8329"] {
             let event = get_event_with_ip_addr(ip_addr);
-            let filter_result = should_filter(&event, &FilterConfig { is_enabled: true });
+            let filter_result = should_filter(
+                &event,
+                &FilterConfig {
+                    is_enabled: true,
+                    report_only: false,
+                },
+            );
             assert_eq!(
                 filter_result,
                 Ok(()),
@@ -157,7 +175,13 @@ mod tests {
     #[test]
     fn test_dont_filter_missing_ip_or_domains() {
         let event = Event::default();
-        let filter_result = should_filter(&event, &FilterConfig { is_enabled: true });
+        let filter_result = should_filter(
+            &event,
+            &FilterConfig {
+                is_enabled: true,
+                report_only: false,
+            },
+        );
         assert_eq!(
             filter_result,
             Ok(()),
@@ -174,7 +198,13 @@ mod tests {
             "foo.bar.baz.localhost",
         ] {
             let event = get_event_with_domain(domain);
-            let filter_result = should_filter(&event, &FilterConfig { is_enabled: true });
+            let filter_result = should_filter(
+                &event,
+                &FilterConfig {
+                    is_enabled: true,
+                    report_only: false,
+                },
+            );
             assert_ne!(filter_result, Ok(()), "Failed to filter domain '{domain}'");
         }
     }
@@ -190,7 +220,13 @@ mod tests {
             "alocalhostgoesintoabar",
         ] {
             let event = get_event_with_domain(domain);
-            let filter_result = should_filter(&event, &FilterConfig { is_enabled: true });
+            let filter_result = should_filter(
+                &event,
+                &FilterConfig {
+                    is_enabled: true,
+                    report_only: false,
+                },
+            );
             assert_eq!(
                 filter_result,
                 Ok(()),
@@ -203,7 +239,13 @@ mod tests {
     fn test_filter_file_urls() {
         let url = "file:///Users/Maisey/work/squirrelchasers/src/leaderboard.html";
         let event = get_event_with_url(url);
-        let filter_result = should_filter(&event, &FilterConfig { is_enabled: true });
+        let filter_result = should_filter(
+            &event,
+            &FilterConfig {
+                is_enabled: true,
+                report_only: false,
+            },
+        );
         assert_ne!(
             filter_result,
             Ok(()),
@@ -215,7 +257,13 @@ mod tests {
     fn test_dont_filter_non_file_urls() {
         let url = "http://www.squirrelchasers.com/leaderboard";
         let event = get_event_with_url(url);
-        let filter_result = should_filter(&event, &FilterConfig { is_enabled: true });
+        let filter_result = should_filter(
+            &event,
+            &FilterConfig {
+                is_enabled: true,
+                report_only: false,
+            },
+        );
         assert_eq!(filter_result, Ok(()), "Filtered valid url '{url}'");
     }
 
@@ -223,7 +271,13 @@ mod tests {
     fn test_filter_forwarded_host_header() {
         for host_value in ["localhost:3000", "127.0.0.1:3000", "localhost"] {
             let event = get_event_with_header(FORWARDED_HOST_HEADER, host_value);
-            let filter_result = should_filter(&event, &FilterConfig { is_enabled: true });
+            let filter_result = should_filter(
+                &event,
+                &FilterConfig {
+                    is_enabled: true,
+                    report_only: false,
+                },
+            );
             assert_eq!(filter_result, Err(FilterStatKey::Localhost))
         }
     }
@@ -232,7 +286,13 @@ mod tests {
     fn test_filter_request_host_header() {
         for host_value in ["localhost:3000", "127.0.0.1:3000", "localhost"] {
             let event = get_event_with_header(HOST_HEADER, host_value);
-            let filter_result = should_filter(&event, &FilterConfig { is_enabled: true });
+            let filter_result = should_filter(
+                &event,
+                &FilterConfig {
+                    is_enabled: true,
+                    report_only: false,
+                },
+            );
             assert_eq!(filter_result, Err(FilterStatKey::Localhost))
         }
     }
@@ -241,7 +301,13 @@ mod tests {
     fn test_filter_request_subdomain_host_header() {
         for domain in ["localhost.sentry.io", "localhost.sentry.io:3000"] {
             let event = get_event_with_header(HOST_HEADER, domain);
-            let filter_result = should_filter(&event, &FilterConfig { is_enabled: true });
+            let filter_result = should_filter(
+                &event,
+                &FilterConfig {
+                    is_enabled: true,
+                    report_only: false,
+                },
+            );
             assert_eq!(filter_result, Ok(()))
         }
     }
@@ -250,7 +316,13 @@ mod tests {
     fn test_filter_request_subdomain_forwarded_host_header() {
         for domain in ["localhost.sentry.io", "localhost.sentry.io:3000"] {
             let event = get_event_with_header(FORWARDED_HOST_HEADER, domain);
-            let filter_result = should_filter(&event, &FilterConfig { is_enabled: true });
+            let filter_result = should_filter(
+                &event,
+                &FilterConfig {
+                    is_enabled: true,
+                    report_only: false,
+                },
+            );
             assert_eq!(filter_result, Ok(()))
         }
     }
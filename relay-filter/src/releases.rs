@@ -73,6 +73,7 @@ mod tests {
 
             let config = ReleasesFilterConfig {
                 releases: blocked_releases.iter().map(|&r| r.to_owned()).collect(),
+                report_only: false,
             };
 
             let actual = should_filter(&event, &config) != Ok(());
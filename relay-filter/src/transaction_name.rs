@@ -60,6 +60,7 @@ mod tests {
         IgnoreTransactionsFilterConfig {
             patterns: TypedPatterns::from(patterns_raw),
             is_enabled: true,
+            report_only: false,
         }
     }
 
@@ -191,6 +192,7 @@ mod tests {
             &IgnoreTransactionsFilterConfig {
                 patterns: TypedPatterns::default(),
                 is_enabled: true,
+                report_only: false,
             },
         );
         assert_eq!(
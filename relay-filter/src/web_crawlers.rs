@@ -104,7 +104,13 @@ mod tests {
     #[test]
     fn test_filter_when_disabled() {
         let evt = testutils::get_event_with_user_agent("Googlebot");
-        let filter_result = should_filter(&evt, &FilterConfig { is_enabled: false });
+        let filter_result = should_filter(
+            &evt,
+            &FilterConfig {
+                is_enabled: false,
+                report_only: false,
+            },
+        );
         assert_eq!(
             filter_result,
             Ok(()),
@@ -168,7 +174,13 @@ mod tests {
 
         for banned_user_agent in &user_agents {
             let event = testutils::get_event_with_user_agent(banned_user_agent);
-            let filter_result = should_filter(&event, &FilterConfig { is_enabled: true });
+            let filter_result = should_filter(
+                &event,
+                &FilterConfig {
+                    is_enabled: true,
+                    report_only: false,
+                },
+            );
             assert_ne!(
                 filter_result,
                 Ok(()),
@@ -193,7 +205,13 @@ mod tests {
         ];
         for user_agent in &normal_user_agents {
             let event = testutils::get_event_with_user_agent(user_agent);
-            let filter_result = should_filter(&event, &FilterConfig { is_enabled: true });
+            let filter_result = should_filter(
+                &event,
+                &FilterConfig {
+                    is_enabled: true,
+                    report_only: false,
+                },
+            );
             assert_eq!(
                 filter_result,
                 Ok(()),
@@ -212,7 +230,13 @@ mod tests {
             }),
         };
 
-        let filter_result = should_filter(&TestFilterable(ua), &FilterConfig { is_enabled: true });
+        let filter_result = should_filter(
+            &TestFilterable(ua),
+            &FilterConfig {
+                is_enabled: true,
+                report_only: false,
+            },
+        );
         assert_ne!(filter_result, Ok(()));
     }
 
@@ -225,7 +249,13 @@ mod tests {
             parsed: Some(Default::default()),
         };
 
-        let filter_result = should_filter(&TestFilterable(ua), &FilterConfig { is_enabled: true });
+        let filter_result = should_filter(
+            &TestFilterable(ua),
+            &FilterConfig {
+                is_enabled: true,
+                report_only: false,
+            },
+        );
         assert_eq!(filter_result, Ok(()));
     }
 }
@@ -1,3 +1,6 @@
+use std::collections::BTreeMap;
+
+use relay_base_schema::metrics::MetricNamespace;
 use serde::{Deserialize, Serialize};
 
 /// Configuration value for [`AggregatorConfig::flush_batching`].
@@ -96,6 +99,16 @@ pub struct AggregatorConfig {
     /// partition, effectively allowing all the elements of that partition to be flushed together.
     #[serde(alias = "shift_key")]
     pub flush_batching: FlushBatching,
+
+    /// Maximum amount of values in a distribution by namespace, before it is converted into a
+    /// [`DistributionSketch`](crate::DistributionSketch).
+    ///
+    /// Sketches bound the memory of large distributions, but only allow to estimate quantiles.
+    /// Buckets containing sketches must be supported by the upstream and the metrics consumers.
+    ///
+    /// Defaults to an empty map, distributions of all namespaces keep all their values.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub distribution_sketch_thresholds: BTreeMap<MetricNamespace, usize>,
 }
 
 impl Default for AggregatorConfig {
@@ -110,6 +123,7 @@ impl Default for AggregatorConfig {
             max_total_bucket_bytes: None,
             flush_batching: FlushBatching::default(),
             flush_partitions: None,
+            distribution_sketch_thresholds: BTreeMap::new(),
         }
     }
 }
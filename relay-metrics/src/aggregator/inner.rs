@@ -13,7 +13,7 @@ use relay_common::time::UnixTimestamp;
 use crate::aggregator::stats;
 use crate::aggregator::{AggregateMetricsError, FlushBatching};
 use crate::utils::ByNamespace;
-use crate::{BucketMetadata, BucketValue, DistributionType, SetType};
+use crate::{BucketMetadata, BucketValue, DistributionSketch, DistributionType, SetType};
 
 #[derive(Default)]
 pub struct Partition {
@@ -126,8 +126,14 @@ impl fmt::Debug for BucketData {
 impl BucketData {
     /// Merges another bucket's data into this one.
     ///
+    /// Distributions exceeding `max_distribution_values` are converted into sketches.
+    ///
     /// Returns the value cost increase on success.
-    fn merge(&mut self, other: Self) -> Result<usize, AggregateMetricsError> {
+    fn merge(
+        &mut self,
+        other: Self,
+        max_distribution_values: Option<usize>,
+    ) -> Result<usize, AggregateMetricsError> {
         let cost_before = self.value.cost();
 
         self.value
@@ -135,6 +141,12 @@ impl BucketData {
             .map_err(|_| AggregateMetricsError::InvalidTypes)?;
         self.metadata.merge(other.metadata);
 
+        // The cost of a converted distribution shrinks, but the reserved cost is only released
+        // once the bucket is flushed.
+        if let Some(max) = max_distribution_values {
+            self.value.sketch_distribution(max);
+        }
+
         Ok(self.value.cost().saturating_sub(cost_before))
     }
 }
@@ -168,6 +180,8 @@ pub struct Config {
     pub max_secs_in_future: Option<u64>,
     /// Determines how partitions are assigned based on the input bucket.
     pub partition_by: FlushBatching,
+    /// Maximum amount of values in a distribution before it is converted into a sketch.
+    pub distribution_sketch_thresholds: ByNamespace<Option<usize>>,
 }
 
 /// A metrics aggregator.
@@ -230,6 +244,8 @@ pub struct Inner {
     partition_by: FlushBatching,
     /// Hasher used to calculate partitions.
     hasher: ahash::RandomState,
+    /// Maximum amount of values in a distribution before it is converted into a sketch.
+    distribution_sketch_thresholds: ByNamespace<Option<usize>>,
 }
 
 impl Inner {
@@ -297,6 +313,7 @@ impl Inner {
             slot_range: slot_diff,
             partition_by: config.partition_by,
             hasher: build_hasher(),
+            distribution_sketch_thresholds: config.distribution_sketch_thresholds,
         }
     }
 
@@ -335,10 +352,11 @@ impl Inner {
     pub fn merge(
         &mut self,
         mut key: BucketKey,
-        value: BucketData,
+        mut value: BucketData,
     ) -> Result<(), AggregateMetricsError> {
         let project_key = key.project_key;
         let namespace = key.metric_name.namespace();
+        let max_distribution_values = *self.distribution_sketch_thresholds.get(namespace);
 
         let time_slot = key.timestamp.as_secs() / self.bucket_interval;
         // Make sure the timestamp is normalized to the correct interval as well.
@@ -382,10 +400,21 @@ impl Inner {
                 let estimated_cost = match &value.value {
                     // Counters and Gauges aggregate without additional costs.
                     BucketValue::Counter(_) | BucketValue::Gauge(_) => 0,
-                    // Distributions are an accurate estimation, all values will be added.
-                    BucketValue::Distribution(d) => d.len() * mem::size_of::<DistributionType>(),
+                    // Distributions are an accurate estimation, all values will be added. If the
+                    // merged distribution may be converted into a sketch, this is an upper bound.
+                    BucketValue::Distribution(d) => {
+                        let sketch_cost = match max_distribution_values {
+                            Some(_) => mem::size_of::<DistributionSketch>(),
+                            None => 0,
+                        };
+                        d.len() * mem::size_of::<DistributionType>() + sketch_cost
+                    }
                     // Sets are an upper bound.
                     BucketValue::Set(s) => s.len() * mem::size_of::<SetType>(),
+                    // Sketches add at most all their bins, an upper bound.
+                    BucketValue::DistributionSketch(s) => {
+                        mem::size_of::<DistributionSketch>() + s.bins_cost()
+                    }
                 };
 
                 // Reserve for the upper bound of the value.
@@ -397,13 +426,19 @@ impl Inner {
                     &self.limits,
                 )?;
 
-                let actual_cost = occupied_entry.into_mut().merge(value)?;
+                let actual_cost = occupied_entry
+                    .into_mut()
+                    .merge(value, max_distribution_values)?;
 
                 // Track the actual cost increase, not just the reservation.
                 reservation.consume_with(actual_cost as u64);
                 slot.stats.incr_merges(namespace);
             }
             Entry::Vacant(vacant_entry) => {
+                if let Some(max) = max_distribution_values {
+                    value.value.sketch_distribution(max);
+                }
+
                 let reservation = slot.stats.reserve(
                     &mut self.stats,
                     project_key,
@@ -459,6 +494,17 @@ impl Inner {
         }
     }
 
+    /// Returns an iterator over all buckets contained in the aggregator and their partition key.
+    ///
+    /// Buckets are returned in flush order, the aggregator is not modified.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &BucketKey, &BucketData)> {
+        self.slots.iter().flat_map(|slot| {
+            slot.buckets
+                .iter()
+                .map(|(key, data)| (slot.partition_key, key, data))
+        })
+    }
+
     /// Consumes the aggregator and returns an iterator over all contained partitions.
     pub fn into_partitions(self) -> impl Iterator<Item = Partition> {
         self.slots.into_iter().map(|slot| Partition {
@@ -601,6 +647,7 @@ mod tests {
             max_total_bucket_bytes: None,
             max_project_key_bucket_bytes: None,
             start: UnixTimestamp::from_secs(70),
+            distribution_sketch_thresholds: ByNamespace::default(),
             partition_by: FlushBatching::Partition,
         });
 
@@ -676,6 +723,57 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_merge_distribution_sketch() -> Result<(), AggregateMetricsError> {
+        let mut buckets = Inner::new(Config {
+            bucket_interval: 10,
+            num_time_slots: 1,
+            num_partitions: 1,
+            delay: 0,
+            max_secs_in_past: None,
+            max_secs_in_future: None,
+            max_total_bucket_bytes: None,
+            max_project_key_bucket_bytes: None,
+            start: UnixTimestamp::from_secs(70),
+            distribution_sketch_thresholds: ByNamespace {
+                spans: Some(3),
+                ..Default::default()
+            },
+            partition_by: FlushBatching::None,
+        });
+
+        let distribution = |values: &[u32]| BucketData {
+            value: BucketValue::Distribution(values.iter().map(|&v| v.into()).collect()),
+            metadata: Default::default(),
+        };
+
+        buckets.merge(bucket_key(70, "d:spans/a@none"), distribution(&[1, 2]))?;
+        buckets.merge(bucket_key(70, "d:spans/a@none"), distribution(&[3, 4]))?;
+        buckets.merge(
+            bucket_key(70, "d:spans/b@none"),
+            distribution(&[1, 2, 3, 4]),
+        )?;
+        buckets.merge(
+            bucket_key(70, "d:custom/c@none"),
+            distribution(&[1, 2, 3, 4]),
+        )?;
+
+        let partition = buckets.flush_next();
+        let value = |name: &str| &partition.buckets[&bucket_key(70, name)].value;
+
+        let sketch = BucketValue::DistributionSketch(Box::new(DistributionSketch::from_values(
+            &crate::dist![1, 2, 3, 4],
+        )));
+        assert_eq!(value("d:spans/a@none"), &sketch);
+        assert_eq!(value("d:spans/b@none"), &sketch);
+        assert_eq!(
+            value("d:custom/c@none"),
+            &BucketValue::Distribution(crate::dist![1, 2, 3, 4])
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_merge_flush_project() -> Result<(), AggregateMetricsError> {
         let mut buckets = Inner::new(Config {
@@ -688,6 +786,7 @@ mod tests {
             max_total_bucket_bytes: None,
             max_project_key_bucket_bytes: None,
             start: UnixTimestamp::from_secs(70),
+            distribution_sketch_thresholds: ByNamespace::default(),
             partition_by: FlushBatching::Project,
         });
 
@@ -743,6 +842,7 @@ mod tests {
             // Enough for one bucket per partition.
             max_project_key_bucket_bytes: Some(ONE_BUCKET_COST * 3),
             start: UnixTimestamp::from_secs(70),
+            distribution_sketch_thresholds: ByNamespace::default(),
             partition_by: FlushBatching::Partition,
         });
 
@@ -859,6 +959,7 @@ mod tests {
             max_secs_in_future: None,
             // Truncated to 60 seconds.
             start: UnixTimestamp::from_secs(63),
+            distribution_sketch_thresholds: ByNamespace::default(),
             partition_by: FlushBatching::Partition,
         });
 
@@ -937,6 +1038,7 @@ mod tests {
             max_total_bucket_bytes: None,
             max_project_key_bucket_bytes: None,
             start: UnixTimestamp::from_secs(70),
+            distribution_sketch_thresholds: ByNamespace::default(),
             partition_by: FlushBatching::Partition,
         });
 
@@ -962,6 +1064,7 @@ mod tests {
             max_total_bucket_bytes: None,
             max_project_key_bucket_bytes: None,
             start: UnixTimestamp::from_secs(70),
+            distribution_sketch_thresholds: ByNamespace::default(),
             partition_by: FlushBatching::Partition,
         });
 
@@ -987,6 +1090,7 @@ mod tests {
             max_total_bucket_bytes: None,
             max_project_key_bucket_bytes: None,
             start: UnixTimestamp::from_secs(70),
+            distribution_sketch_thresholds: ByNamespace::default(),
             partition_by: FlushBatching::Partition,
         });
 
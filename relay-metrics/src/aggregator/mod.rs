@@ -7,15 +7,17 @@ use relay_base_schema::metrics::MetricNamespace;
 use relay_base_schema::project::ProjectKey;
 use relay_common::time::UnixTimestamp;
 
-use crate::Bucket;
 use crate::statsd::{MetricCounters, MetricGauges};
+use crate::{Bucket, ByNamespace};
 
 mod config;
 mod inner;
+mod snapshot;
 mod stats;
 
 pub use self::config::*;
 use self::inner::{BucketData, BucketKey};
+pub use self::snapshot::*;
 
 /// Default amount of partitions per second when there are no partitions configured.
 const DEFAULT_PARTITIONS_PER_SECOND: u32 = 64;
@@ -87,6 +89,13 @@ impl Aggregator {
                 max_secs_in_past: Some(config.max_secs_in_past),
                 max_secs_in_future: Some(config.max_secs_in_future),
                 partition_by: config.flush_batching,
+                distribution_sketch_thresholds: {
+                    let mut thresholds = ByNamespace::default();
+                    for (&namespace, &threshold) in &config.distribution_sketch_thresholds {
+                        *thresholds.get_mut(namespace) = Some(threshold);
+                    }
+                    thresholds
+                },
             }),
         }
     }
@@ -159,6 +168,14 @@ impl Aggregator {
         }
     }
 
+    /// Serializes a [`Snapshot`] of all buckets contained in the aggregator as JSON.
+    ///
+    /// The aggregator is not modified. Buckets are serialized directly, without copying them into
+    /// an intermediate [`Snapshot`]. The result can be written with [`Snapshot::write`].
+    pub fn serialize_snapshot(&self, now: UnixTimestamp) -> serde_json::Result<Vec<u8>> {
+        snapshot::serialize(&self.inner, &self.name, now)
+    }
+
     /// Consumes the aggregator and returns all contained partitions.
     pub fn into_partitions(self) -> impl Iterator<Item = Partition> {
         let bucket_interval = self.inner.bucket_interval();
@@ -0,0 +1,258 @@
+use std::fs::{self, File};
+use std::io::{self, BufReader, Write};
+use std::path::Path;
+
+use relay_base_schema::project::ProjectKey;
+use relay_common::time::UnixTimestamp;
+use serde::{Deserialize, Serialize, Serializer};
+
+use super::inner::Inner;
+use crate::{Bucket, BucketMetadata, BucketValue, MetricName, MetricTags};
+
+/// A serializable copy of all buckets contained in an [`Aggregator`](super::Aggregator).
+///
+/// Snapshots are used to persist unflushed buckets across restarts of Relay. They are serialized
+/// with [`Aggregator::serialize_snapshot`](super::Aggregator::serialize_snapshot) and can be merged
+/// back into an aggregator using [`Snapshot::into_buckets`].
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// The time at which the snapshot was created.
+    pub created: UnixTimestamp,
+    /// The name of the aggregator the snapshot was created from.
+    pub aggregator: String,
+    /// All buckets of the aggregator.
+    pub buckets: Vec<SnapshotBucket>,
+}
+
+/// A [`Bucket`] contained in a [`Snapshot`].
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SnapshotBucket {
+    /// The project the bucket belongs to.
+    pub project_key: ProjectKey,
+    /// The partition the bucket was assigned to when the snapshot was created.
+    ///
+    /// Restored buckets are assigned to a partition again when they are merged into the
+    /// aggregator, this is kept for inspection only.
+    pub partition_key: u32,
+    /// Whether the bucket was extracted from an indexed payload.
+    ///
+    /// This is part of the bucket's aggregation key, but is not serialized with its
+    /// [`BucketMetadata`](crate::BucketMetadata).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub extracted_from_indexed: bool,
+    /// The bucket.
+    pub bucket: Bucket,
+}
+
+impl Snapshot {
+    /// Reads a snapshot from a JSON file.
+    ///
+    /// The file is parsed while it is read. Returns `Ok(None)` if the file does not exist. This
+    /// performs blocking IO.
+    pub fn read(path: &Path) -> io::Result<Option<Self>> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error),
+        };
+
+        Ok(Some(serde_json::from_reader(BufReader::new(file))?))
+    }
+
+    /// Writes a serialized snapshot to a file.
+    ///
+    /// The data is written to a temporary file next to `path`, which then replaces the previous
+    /// snapshot. A crash while writing never leaves a partially written snapshot behind. This
+    /// performs blocking IO.
+    pub fn write(path: &Path, data: &[u8]) -> io::Result<()> {
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+
+        let mut file = File::create(&tmp)?;
+        file.write_all(data)?;
+        file.sync_all()?;
+
+        fs::rename(&tmp, path)
+    }
+
+    /// Removes the snapshot file at `path` once it has been restored.
+    ///
+    /// Removing a file that does not exist is not an error.
+    pub fn remove(path: &Path) -> io::Result<()> {
+        match fs::remove_file(path) {
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+
+    /// Returns the number of buckets in the snapshot.
+    pub fn len(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// Returns `true` if the snapshot contains no buckets.
+    pub fn is_empty(&self) -> bool {
+        self.buckets.is_empty()
+    }
+
+    /// Returns `true` if the snapshot was created more than `max_age_secs` before `now`.
+    pub fn is_expired(&self, now: UnixTimestamp, max_age_secs: u64) -> bool {
+        now.as_secs().saturating_sub(self.created.as_secs()) > max_age_secs
+    }
+
+    /// Consumes the snapshot and returns all contained buckets with their project key.
+    pub fn into_buckets(self) -> impl Iterator<Item = (ProjectKey, Bucket)> {
+        self.buckets.into_iter().map(|b| {
+            let mut bucket = b.bucket;
+            bucket.metadata.extracted_from_indexed = b.extracted_from_indexed;
+            (b.project_key, bucket)
+        })
+    }
+}
+
+/// Serializes all buckets of an aggregator in the format of a [`Snapshot`].
+///
+/// Buckets are serialized one at a time directly from the aggregator, without copying them.
+pub(super) fn serialize(
+    inner: &Inner,
+    name: &str,
+    now: UnixTimestamp,
+) -> serde_json::Result<Vec<u8>> {
+    #[derive(Serialize)]
+    struct SnapshotRef<'a> {
+        created: UnixTimestamp,
+        aggregator: &'a str,
+        buckets: BucketsRef<'a>,
+    }
+
+    struct BucketsRef<'a>(&'a Inner);
+
+    impl Serialize for BucketsRef<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let bucket_interval = self.0.bucket_interval();
+            serializer.collect_seq(self.0.iter().map(|(partition_key, key, data)| {
+                SnapshotBucketRef {
+                    project_key: key.project_key,
+                    partition_key,
+                    extracted_from_indexed: key.extracted_from_indexed,
+                    bucket: BucketRef {
+                        timestamp: key.timestamp,
+                        width: bucket_interval,
+                        name: &key.metric_name,
+                        value: &data.value,
+                        tags: &key.tags,
+                        metadata: data.metadata,
+                    },
+                }
+            }))
+        }
+    }
+
+    /// Borrowed counterpart of [`SnapshotBucket`].
+    #[derive(Serialize)]
+    struct SnapshotBucketRef<'a> {
+        project_key: ProjectKey,
+        partition_key: u32,
+        #[serde(skip_serializing_if = "std::ops::Not::not")]
+        extracted_from_indexed: bool,
+        bucket: BucketRef<'a>,
+    }
+
+    /// Borrowed counterpart of [`Bucket`], which must serialize the same way.
+    #[derive(Serialize)]
+    struct BucketRef<'a> {
+        timestamp: UnixTimestamp,
+        width: u64,
+        name: &'a MetricName,
+        #[serde(flatten)]
+        value: &'a BucketValue,
+        #[serde(skip_serializing_if = "is_empty")]
+        tags: &'a MetricTags,
+        #[serde(skip_serializing_if = "BucketMetadata::is_default")]
+        metadata: BucketMetadata,
+    }
+
+    fn is_empty(tags: &&MetricTags) -> bool {
+        tags.is_empty()
+    }
+
+    serde_json::to_vec(&SnapshotRef {
+        created: now,
+        aggregator: name,
+        buckets: BucketsRef(inner),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::aggregator::{Aggregator, AggregatorConfig};
+    use crate::{BucketMetadata, BucketValue};
+
+    use super::*;
+
+    fn bucket(timestamp: UnixTimestamp, extracted_from_indexed: bool) -> Bucket {
+        Bucket {
+            timestamp,
+            width: 0,
+            name: "c:transactions/foo@none".into(),
+            tags: BTreeMap::from([("foo".to_owned(), "bar".to_owned())]),
+            value: BucketValue::counter(42.into()),
+            metadata: BucketMetadata {
+                extracted_from_indexed,
+                ..BucketMetadata::new(timestamp)
+            },
+        }
+    }
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let project_key = ProjectKey::parse("a94ae32be2584e0bbd7a4cbb95971fee").unwrap();
+        let config = AggregatorConfig::default();
+        let now = UnixTimestamp::now();
+        let timestamp = UnixTimestamp::from_secs(now.as_secs() / 10 * 10);
+
+        let mut aggregator = Aggregator::named("default".to_owned(), &config);
+        aggregator.merge(project_key, bucket(now, false)).unwrap();
+        aggregator.merge(project_key, bucket(now, true)).unwrap();
+
+        let json = aggregator.serialize_snapshot(now).unwrap();
+        let snapshot: Snapshot = serde_json::from_slice(&json).unwrap();
+        assert_eq!(snapshot.aggregator, "default");
+        assert_eq!(snapshot.len(), 2);
+        assert!(!snapshot.is_expired(now, config.max_secs_in_past));
+
+        let mut restored = Aggregator::named("default".to_owned(), &config);
+        for (project_key, bucket) in snapshot.into_buckets() {
+            restored.merge(project_key, bucket).unwrap();
+        }
+
+        let mut buckets: Vec<_> = restored
+            .into_partitions()
+            .flatten()
+            .map(|(_, bucket)| bucket)
+            .collect();
+        buckets.sort_by_key(|b| b.metadata.extracted_from_indexed);
+
+        let expected = |extracted_from_indexed| Bucket {
+            timestamp,
+            width: 10,
+            ..bucket(now, extracted_from_indexed)
+        };
+        assert_eq!(buckets, vec![expected(false), expected(true)]);
+    }
+
+    #[test]
+    fn test_snapshot_expired() {
+        let snapshot = Snapshot {
+            created: UnixTimestamp::from_secs(100),
+            aggregator: "default".to_owned(),
+            buckets: Vec::new(),
+        };
+
+        assert!(!snapshot.is_expired(UnixTimestamp::from_secs(150), 50));
+        assert!(snapshot.is_expired(UnixTimestamp::from_secs(151), 50));
+        assert!(!snapshot.is_expired(UnixTimestamp::from_secs(50), 50));
+    }
+}
@@ -7,11 +7,12 @@ use relay_protocol::FiniteF64;
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 
-use crate::ParseMetricError;
 use crate::protocol::{
     self, CounterType, DistributionType, GaugeType, MetricName, MetricResourceIdentifier,
     MetricType, SetType, hash_set_value,
 };
+use crate::sketch::SketchData;
+use crate::{DistributionSketch, ParseMetricError};
 
 const VALUE_SEPARATOR: char = ':';
 
@@ -256,6 +257,36 @@ pub enum BucketValue {
     ///  - `count` adds the count of the newly added gauge (defaulting to `1`)
     #[serde(rename = "g")]
     Gauge(GaugeValue),
+
+    /// A distribution summarized in a quantile sketch ([`MetricType::Distribution`]).
+    ///
+    /// Aggregators can be configured to convert large [distributions](Self::Distribution) into
+    /// sketches to bound their memory, see [`AggregatorConfig::distribution_sketch_thresholds`].
+    /// The count, sum, minimum and maximum of all values are kept exactly, quantiles can be
+    /// estimated with a relative accuracy, see [`DistributionSketch`].
+    ///
+    /// # Statsd Format
+    ///
+    /// Sketches are declared as `"ds"`. The value consists of the count, sum, minimum, maximum and
+    /// the number of zero values, followed by bins of positive values as `p<index>=<count>` and bins
+    /// of negative values as `n<index>=<count>`:
+    ///
+    /// ```text
+    /// endpoint.response_time@millisecond:3:60:10:30:0:p116=1:p150=1:p171=1|ds
+    /// ```
+    ///
+    /// # Serialization
+    ///
+    /// This variant serializes to a structure with named fields, see [`DistributionSketch`].
+    ///
+    /// # Aggregation
+    ///
+    /// Sketches merge by adding up their bins. Merging a distribution and a sketch adds all values
+    /// of the distribution to the sketch.
+    ///
+    /// [`AggregatorConfig::distribution_sketch_thresholds`]: crate::aggregator::AggregatorConfig::distribution_sketch_thresholds
+    #[serde(rename = "ds")]
+    DistributionSketch(Box<DistributionSketch>),
 }
 
 impl BucketValue {
@@ -296,16 +327,20 @@ impl BucketValue {
             Self::Distribution(_) => MetricType::Distribution,
             Self::Set(_) => MetricType::Set,
             Self::Gauge(_) => MetricType::Gauge,
+            Self::DistributionSketch(_) => MetricType::Distribution,
         }
     }
 
     /// Returns the number of raw data points in this value.
+    ///
+    /// For distribution sketches, these are the exact aggregates and the bins of the sketch.
     pub fn len(&self) -> usize {
         match self {
             BucketValue::Counter(_) => 1,
             BucketValue::Distribution(distribution) => distribution.len(),
             BucketValue::Set(set) => set.len(),
             BucketValue::Gauge(_) => 5,
+            BucketValue::DistributionSketch(sketch) => 5 + sketch.num_bins(),
         }
     }
 
@@ -326,11 +361,25 @@ impl BucketValue {
             Self::Set(s) => mem::size_of::<SetType>() * s.len(),
             Self::Gauge(_) => 0,
             Self::Distribution(d) => d.len() * mem::size_of::<DistributionType>(),
+            Self::DistributionSketch(s) => mem::size_of::<DistributionSketch>() + s.bins_cost(),
         };
 
         mem::size_of::<Self>() + allocated_cost
     }
 
+    /// Converts a distribution with more than `max_values` values into a [`DistributionSketch`].
+    ///
+    /// Returns `true` if the value was converted. All other values remain unchanged.
+    pub fn sketch_distribution(&mut self, max_values: usize) -> bool {
+        match self {
+            Self::Distribution(values) if values.len() > max_values => {
+                *self = Self::DistributionSketch(Box::new(DistributionSketch::from_values(values)));
+                true
+            }
+            _ => false,
+        }
+    }
+
     /// Merges the given `bucket_value` into `self`.
     ///
     /// Returns `Ok(())` if the two bucket values can be merged. This is the case when both bucket
@@ -341,6 +390,14 @@ impl BucketValue {
             (Self::Distribution(slf), Self::Distribution(other)) => slf.extend_from_slice(&other),
             (Self::Set(slf), Self::Set(other)) => slf.extend(other),
             (Self::Gauge(slf), Self::Gauge(other)) => slf.merge(other),
+            (Self::DistributionSketch(slf), Self::DistributionSketch(other)) => slf.merge(&other),
+            (Self::DistributionSketch(slf), Self::Distribution(other)) => slf.extend(other),
+            (slf @ Self::Distribution(_), Self::DistributionSketch(mut other)) => {
+                if let Self::Distribution(values) = slf {
+                    other.extend(values.drain(..));
+                }
+                *slf = Self::DistributionSketch(other);
+            }
             (_, other) => return Err(other),
         }
 
@@ -366,6 +423,33 @@ fn parse_distribution(string: &str) -> Option<DistributionValue> {
     Some(dist)
 }
 
+/// Parses a distribution sketch from its aggregates and bins separated by colons.
+fn parse_distribution_sketch(string: &str) -> Option<DistributionSketch> {
+    let mut components = string.split(VALUE_SEPARATOR);
+
+    let mut data = SketchData {
+        count: components.next()?.parse().ok()?,
+        sum: components.next()?.parse().ok()?,
+        min: components.next()?.parse().ok()?,
+        max: components.next()?.parse().ok()?,
+        zeros: components.next()?.parse().ok()?,
+        positive: Vec::new(),
+        negative: Vec::new(),
+    };
+
+    for component in components {
+        let (index, count) = component.get(1..)?.split_once('=')?;
+        let bin = (index.parse().ok()?, count.parse().ok()?);
+        match component.chars().next()? {
+            'p' => data.positive.push(bin),
+            'n' => data.negative.push(bin),
+            _ => return None,
+        }
+    }
+
+    data.try_into().ok()
+}
+
 /// Parses a set of hashed numeric values.
 fn parse_set(string: &str) -> Option<SetValue> {
     let mut set = SetValue::default();
@@ -628,16 +712,24 @@ impl Bucket {
         let mut components = string.split('|');
 
         let (mri_str, values_str) = components.next()?.split_once(':')?;
-        let ty = components.next().and_then(|s| s.parse().ok())?;
-
-        let mri = MetricResourceIdentifier::parse_with_type(mri_str, ty).ok()?;
-        let value = match ty {
-            MetricType::Counter => BucketValue::Counter(parse_counter(values_str)?),
-            MetricType::Distribution => BucketValue::Distribution(parse_distribution(values_str)?),
-            MetricType::Set => BucketValue::Set(parse_set(values_str)?),
-            MetricType::Gauge => BucketValue::Gauge(parse_gauge(values_str)?),
+        let ty_str = components.next()?;
+
+        let value = match ty_str {
+            "ds" => {
+                BucketValue::DistributionSketch(Box::new(parse_distribution_sketch(values_str)?))
+            }
+            _ => match ty_str.parse().ok()? {
+                MetricType::Counter => BucketValue::Counter(parse_counter(values_str)?),
+                MetricType::Distribution => {
+                    BucketValue::Distribution(parse_distribution(values_str)?)
+                }
+                MetricType::Set => BucketValue::Set(parse_set(values_str)?),
+                MetricType::Gauge => BucketValue::Gauge(parse_gauge(values_str)?),
+            },
         };
 
+        let mri = MetricResourceIdentifier::parse_with_type(mri_str, value.ty()).ok()?;
+
         let mut bucket = Bucket {
             timestamp,
             width: 0,
@@ -897,6 +989,64 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bucket_value_merge_distribution_sketch() {
+        let sketch = |values: &[_]| {
+            BucketValue::DistributionSketch(Box::new(DistributionSketch::from_values(values)))
+        };
+
+        let mut value = BucketValue::Distribution(dist![1, 2]);
+        value.merge(sketch(&dist![3, 4])).unwrap();
+        assert_eq!(value, sketch(&dist![1, 2, 3, 4]));
+
+        value.merge(BucketValue::Distribution(dist![5])).unwrap();
+        assert_eq!(value, sketch(&dist![1, 2, 3, 4, 5]));
+
+        assert!(value.merge(BucketValue::counter(1.into())).is_err());
+    }
+
+    #[test]
+    fn test_bucket_value_sketch_distribution() {
+        let mut value = BucketValue::Distribution(dist![1, 2, 3]);
+        assert!(!value.sketch_distribution(3));
+        assert!(value.sketch_distribution(2));
+        assert_eq!(
+            value,
+            BucketValue::DistributionSketch(Box::new(DistributionSketch::from_values(&dist![
+                1, 2, 3
+            ])))
+        );
+        assert_eq!(value.ty(), MetricType::Distribution);
+        assert!(!value.sketch_distribution(0));
+    }
+
+    #[test]
+    fn test_parse_distribution_sketch() {
+        let s = "endpoint.response_time@millisecond:3:60:10:30:0:p116=1:p150=1:p171=1|ds|T4712";
+        let timestamp = UnixTimestamp::from_secs(4711);
+        let metric = Bucket::parse(s.as_bytes(), timestamp).unwrap();
+
+        assert_eq!(&*metric.name, "d:custom/endpoint.response_time@millisecond");
+        assert_eq!(metric.timestamp, UnixTimestamp::from_secs(4712));
+        assert_eq!(
+            metric.value,
+            BucketValue::DistributionSketch(Box::new(DistributionSketch::from_values(&dist![
+                10, 20, 30
+            ])))
+        );
+
+        let json = serde_json::to_string(&metric).unwrap();
+        assert!(json.contains(r#""type":"ds""#), "{json}");
+        assert_eq!(serde_json::from_str::<Bucket>(&json).unwrap(), metric);
+
+        // More binned values than the count.
+        assert!(Bucket::parse(b"foo:1:60:10:30:0:p116=2|ds", timestamp).is_err());
+        // Invalid bin.
+        assert!(Bucket::parse(b"foo:3:60:10:30:0:x116=1|ds", timestamp).is_err());
+        // Missing aggregates.
+        assert!(Bucket::parse(b"foo:3:60:10|ds", timestamp).is_err());
+    }
+
     #[test]
     fn test_parse_garbage() {
         let s = "x23-408j17z4232@#34d\nc3456y7^😎";
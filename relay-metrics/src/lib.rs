@@ -73,11 +73,13 @@ pub mod cogs;
 
 mod bucket;
 mod protocol;
+mod sketch;
 mod statsd;
 mod utils;
 mod view;
 
 pub use bucket::*;
 pub use protocol::*;
+pub use sketch::*;
 pub use utils::ByNamespace;
 pub use view::*;
@@ -0,0 +1,421 @@
+use std::fmt;
+
+use itertools::{EitherOrBoth, Itertools};
+use serde::{Deserialize, Serialize};
+
+use crate::DistributionType;
+
+/// Relative accuracy of quantiles estimated from a [`DistributionSketch`].
+///
+/// The accuracy is fixed, so that sketches created by different Relays can always be merged.
+pub const SKETCH_RELATIVE_ACCURACY: f64 = 0.01;
+
+/// Base of the logarithmic bins in a [`DistributionSketch`].
+const GAMMA: f64 = (1.0 + SKETCH_RELATIVE_ACCURACY) / (1.0 - SKETCH_RELATIVE_ACCURACY);
+
+/// Absolute values smaller than this are counted as zero.
+const MIN_INDEXABLE_VALUE: f64 = 1e-9;
+
+/// The maximum amount of bins for positive and negative values each.
+///
+/// When a sketch exceeds this amount of bins, the bins of the values closest to zero are
+/// collapsed. With the configured accuracy, this covers values from `1e-9` to `1e9` without any
+/// collapsing.
+const MAX_BINS: usize = 2048;
+
+/// A logarithmic bin, identified by its index, and the amount of values it contains.
+type Bin = (i32, u32);
+
+/// A mergeable quantile sketch of a distribution.
+///
+/// Values are counted in logarithmically sized bins, which allows quantiles to be estimated with
+/// a relative accuracy of [`SKETCH_RELATIVE_ACCURACY`] (DDSketch). The count, sum, minimum and
+/// maximum of the values are tracked exactly. The memory required by a sketch only depends on
+/// the range of contained values and is bounded, regardless of how many values are added.
+///
+/// # Serialization
+///
+/// Sketches serialize to a structure with the exact aggregates and lists of `[index, count]`
+/// pairs for bins of positive and negative values:
+///
+/// ```json
+/// {
+///   "count": 3,
+///   "sum": 60.0,
+///   "min": 10.0,
+///   "max": 30.0,
+///   "positive": [[116, 1], [150, 1], [171, 1]]
+/// }
+/// ```
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "SketchData")]
+pub struct DistributionSketch {
+    count: u64,
+    sum: DistributionType,
+    min: DistributionType,
+    max: DistributionType,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    zeros: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    positive: Vec<Bin>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    negative: Vec<Bin>,
+}
+
+impl DistributionSketch {
+    /// Creates a sketch from a list of values.
+    pub fn from_values(values: &[DistributionType]) -> Self {
+        let mut sketch = Self {
+            count: 0,
+            sum: DistributionType::ZERO,
+            min: DistributionType::MAX,
+            max: DistributionType::MIN,
+            zeros: 0,
+            positive: Vec::new(),
+            negative: Vec::new(),
+        };
+
+        sketch.extend(values.iter().copied());
+        sketch
+    }
+
+    /// Returns the exact number of values in the sketch.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the exact sum of all values in the sketch.
+    pub fn sum(&self) -> DistributionType {
+        self.sum
+    }
+
+    /// Returns the smallest value in the sketch or `None` if the sketch is empty.
+    pub fn min(&self) -> Option<DistributionType> {
+        (self.count > 0).then_some(self.min)
+    }
+
+    /// Returns the largest value in the sketch or `None` if the sketch is empty.
+    pub fn max(&self) -> Option<DistributionType> {
+        (self.count > 0).then_some(self.max)
+    }
+
+    /// Returns the number of bins used by the sketch.
+    pub fn num_bins(&self) -> usize {
+        self.positive.len() + self.negative.len()
+    }
+
+    /// Estimates the number of bytes needed to store the bins of the sketch.
+    pub fn bins_cost(&self) -> usize {
+        self.num_bins() * std::mem::size_of::<Bin>()
+    }
+
+    /// Adds values to the sketch.
+    pub fn extend(&mut self, values: impl IntoIterator<Item = DistributionType>) {
+        let mut positive = Vec::new();
+        let mut negative = Vec::new();
+
+        for value in values {
+            self.count += 1;
+            self.sum = self.sum.saturating_add(value);
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+
+            let v = value.to_f64();
+            if v >= MIN_INDEXABLE_VALUE {
+                positive.push(index(v));
+            } else if v <= -MIN_INDEXABLE_VALUE {
+                negative.push(index(-v));
+            } else {
+                self.zeros += 1;
+            }
+        }
+
+        merge_bins(&mut self.positive, &bins_from_indices(positive));
+        merge_bins(&mut self.negative, &bins_from_indices(negative));
+    }
+
+    /// Merges another sketch into this sketch.
+    pub fn merge(&mut self, other: &Self) {
+        if other.count == 0 {
+            return;
+        }
+
+        self.count = self.count.saturating_add(other.count);
+        self.sum = self.sum.saturating_add(other.sum);
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.zeros = self.zeros.saturating_add(other.zeros);
+        merge_bins(&mut self.positive, &other.positive);
+        merge_bins(&mut self.negative, &other.negative);
+    }
+
+    /// Estimates the value at quantile `q`, which must be between `0` and `1`.
+    ///
+    /// Returns `None` if the sketch is empty or `q` is out of range.
+    pub fn quantile(&self, q: f64) -> Option<DistributionType> {
+        if self.count == 0 || !(0.0..=1.0).contains(&q) {
+            return None;
+        }
+
+        // Values in ascending order: negative values with the largest magnitude first, then
+        // zeros, then positive values.
+        let negative = self.negative.iter().rev().map(|&(i, n)| (-bin_value(i), n));
+        let zeros = std::iter::once((0.0, self.zeros.try_into().unwrap_or(u32::MAX)));
+        let positive = self.positive.iter().map(|&(i, n)| (bin_value(i), n));
+        let bins = negative.chain(zeros).chain(positive);
+
+        let total: u64 = bins.clone().map(|(_, n)| u64::from(n)).sum();
+        let rank = (q * total.saturating_sub(1) as f64) as u64;
+
+        let mut seen = 0;
+        for (value, n) in bins {
+            seen += u64::from(n);
+            if seen > rank {
+                let value = DistributionType::new(value)?;
+                return Some(value.max(self.min).min(self.max));
+            }
+        }
+
+        Some(self.max)
+    }
+}
+
+impl fmt::Debug for DistributionSketch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DistributionSketch")
+            .field("count", &self.count)
+            .field("sum", &self.sum)
+            .field("min", &self.min)
+            .field("max", &self.max)
+            .field("bins", &self.num_bins())
+            .finish()
+    }
+}
+
+/// Serialized representation of a [`DistributionSketch`], which is validated and normalized.
+#[derive(Deserialize)]
+pub(crate) struct SketchData {
+    pub count: u64,
+    pub sum: DistributionType,
+    pub min: DistributionType,
+    pub max: DistributionType,
+    #[serde(default)]
+    pub zeros: u64,
+    #[serde(default)]
+    pub positive: Vec<Bin>,
+    #[serde(default)]
+    pub negative: Vec<Bin>,
+}
+
+impl TryFrom<SketchData> for DistributionSketch {
+    type Error = InvalidSketch;
+
+    fn try_from(data: SketchData) -> Result<Self, Self::Error> {
+        if data.count > 0 && data.min > data.max {
+            return Err(InvalidSketch);
+        }
+
+        let binned = data
+            .positive
+            .iter()
+            .chain(&data.negative)
+            .map(|&(_, n)| u64::from(n))
+            .sum::<u64>();
+        if binned.saturating_add(data.zeros) > data.count {
+            return Err(InvalidSketch);
+        }
+
+        Ok(Self {
+            count: data.count,
+            sum: data.sum,
+            min: data.min,
+            max: data.max,
+            zeros: data.zeros,
+            positive: normalize_bins(data.positive),
+            negative: normalize_bins(data.negative),
+        })
+    }
+}
+
+/// An error returned when a [`DistributionSketch`] cannot be deserialized.
+#[derive(Debug)]
+pub struct InvalidSketch;
+
+impl fmt::Display for InvalidSketch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid distribution sketch")
+    }
+}
+
+impl std::error::Error for InvalidSketch {}
+
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+/// Returns the index of the bin containing the positive value `value`.
+fn index(value: f64) -> i32 {
+    (value.ln() / GAMMA.ln()).ceil() as i32
+}
+
+/// Returns the value representing all values in the bin with index `index`.
+fn bin_value(index: i32) -> f64 {
+    2.0 * GAMMA.powi(index) / (1.0 + GAMMA)
+}
+
+/// Creates sorted bins from a list of bin indices.
+fn bins_from_indices(mut indices: Vec<i32>) -> Vec<Bin> {
+    indices.sort_unstable();
+    indices
+        .into_iter()
+        .dedup_with_count()
+        .map(|(n, index)| (index, u32::try_from(n).unwrap_or(u32::MAX)))
+        .collect()
+}
+
+/// Sorts bins, merges bins with the same index, and removes empty bins.
+fn normalize_bins(mut bins: Vec<Bin>) -> Vec<Bin> {
+    bins.sort_unstable_by_key(|&(index, _)| index);
+    bins.dedup_by(|(index, n), (prev_index, prev_n)| {
+        let duplicate = index == prev_index;
+        if duplicate {
+            *prev_n = prev_n.saturating_add(*n);
+        }
+        duplicate
+    });
+    bins.retain(|&(_, n)| n > 0);
+    collapse(&mut bins);
+    bins
+}
+
+/// Merges sorted `other` bins into sorted `bins`.
+fn merge_bins(bins: &mut Vec<Bin>, other: &[Bin]) {
+    if other.is_empty() {
+        return;
+    }
+
+    *bins = bins
+        .iter()
+        .merge_join_by(other, |a, b| a.0.cmp(&b.0))
+        .map(|entry| match entry {
+            EitherOrBoth::Left(&bin) | EitherOrBoth::Right(&bin) => bin,
+            EitherOrBoth::Both(&(index, a), &(_, b)) => (index, a.saturating_add(b)),
+        })
+        .collect();
+
+    collapse(bins);
+}
+
+/// Collapses the bins closest to zero until there are at most [`MAX_BINS`] bins.
+fn collapse(bins: &mut Vec<Bin>) {
+    let Some(excess) = bins.len().checked_sub(MAX_BINS) else {
+        return;
+    };
+
+    let n = bins[..=excess]
+        .iter()
+        .fold(0u32, |sum, &(_, n)| sum.saturating_add(n));
+    bins.drain(..excess);
+    bins[0].1 = n;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(values: impl IntoIterator<Item = f64>) -> Vec<DistributionType> {
+        values
+            .into_iter()
+            .map(|v| DistributionType::new(v).unwrap())
+            .collect()
+    }
+
+    fn assert_accurate(actual: DistributionType, expected: f64) {
+        let error = (actual.to_f64() - expected).abs() / expected.abs();
+        assert!(
+            error <= SKETCH_RELATIVE_ACCURACY,
+            "{actual} is not within accuracy of {expected}"
+        );
+    }
+
+    #[test]
+    fn test_exact_aggregates() {
+        let sketch = DistributionSketch::from_values(&values([3.0, -1.0, 0.0, 10.5]));
+
+        assert_eq!(sketch.count(), 4);
+        assert_eq!(sketch.sum(), 12.5);
+        assert_eq!(sketch.min(), DistributionType::new(-1.0));
+        assert_eq!(sketch.max(), DistributionType::new(10.5));
+        assert_eq!(sketch.num_bins(), 3);
+
+        let empty = DistributionSketch::from_values(&[]);
+        assert_eq!(empty.min(), None);
+        assert_eq!(empty.quantile(0.5), None);
+    }
+
+    #[test]
+    fn test_quantiles() {
+        let sketch = DistributionSketch::from_values(&values((1..=1000).map(f64::from)));
+
+        assert_eq!(sketch.quantile(0.0), DistributionType::new(1.0));
+        assert_accurate(sketch.quantile(0.5).unwrap(), 500.0);
+        assert_accurate(sketch.quantile(0.99).unwrap(), 990.0);
+        assert_eq!(sketch.quantile(1.0), DistributionType::new(1000.0));
+        assert_eq!(sketch.quantile(1.5), None);
+
+        let sketch = DistributionSketch::from_values(&values((-100..=100).map(f64::from)));
+        assert_accurate(sketch.quantile(0.25).unwrap(), -50.0);
+        assert_eq!(sketch.quantile(0.5), DistributionType::new(0.0));
+        assert_accurate(sketch.quantile(0.75).unwrap(), 50.0);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut sketch = DistributionSketch::from_values(&values((1..=500).map(f64::from)));
+        sketch.merge(&DistributionSketch::from_values(&values(
+            (501..=1000).map(f64::from),
+        )));
+
+        let expected = DistributionSketch::from_values(&values((1..=1000).map(f64::from)));
+        assert_eq!(sketch, expected);
+    }
+
+    #[test]
+    fn test_bounded_bins() {
+        let sketch = DistributionSketch::from_values(&values(
+            (-200..2600).map(|e| 1.03f64.powi(e)).chain([f64::MAX]),
+        ));
+
+        assert_eq!(sketch.num_bins(), MAX_BINS);
+        assert_eq!(sketch.count(), 2801);
+        assert_eq!(sketch.max(), DistributionType::new(f64::MAX));
+        assert_accurate(sketch.quantile(0.99).unwrap(), 1.03f64.powi(2572));
+    }
+
+    #[test]
+    fn test_serde() {
+        let sketch = DistributionSketch::from_values(&values([10.0, 20.0, 30.0]));
+        let json = serde_json::to_string(&sketch).unwrap();
+        assert_eq!(
+            json,
+            r#"{"count":3,"sum":60.0,"min":10.0,"max":30.0,"positive":[[116,1],[150,1],[171,1]]}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<DistributionSketch>(&json).unwrap(),
+            sketch
+        );
+
+        // Bins are normalized.
+        let unsorted = r#"{"count":3,"sum":60.0,"min":10.0,"max":30.0,"positive":[[171,1],[116,1],[150,0],[150,1]]}"#;
+        assert_eq!(
+            serde_json::from_str::<DistributionSketch>(unsorted).unwrap(),
+            sketch
+        );
+
+        // More binned values than counted.
+        let invalid = r#"{"count":1,"sum":60.0,"min":10.0,"max":30.0,"positive":[[116,2]]}"#;
+        assert!(serde_json::from_str::<DistributionSketch>(invalid).is_err());
+        let invalid = r#"{"count":1,"sum":1.0,"min":2.0,"max":1.0}"#;
+        assert!(serde_json::from_str::<DistributionSketch>(invalid).is_err());
+    }
+}
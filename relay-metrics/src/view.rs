@@ -3,7 +3,8 @@ use serde::Serialize;
 use serde::ser::{SerializeMap, SerializeSeq};
 
 use crate::{
-    BucketMetadata, CounterType, DistributionType, GaugeValue, MetricName, SetType, SetValue,
+    BucketMetadata, CounterType, DistributionSketch, DistributionType, GaugeValue, MetricName,
+    SetType, SetValue,
 };
 use relay_base_schema::metrics::MetricType;
 use std::collections::BTreeMap;
@@ -428,17 +429,13 @@ impl<'a> BucketView<'a> {
             BucketValue::Distribution(d) => BucketViewValue::Distribution(&d[self.range.clone()]),
             BucketValue::Set(s) => BucketViewValue::Set(SetView::new(s, self.range.clone())),
             BucketValue::Gauge(g) => BucketViewValue::Gauge(*g),
+            BucketValue::DistributionSketch(s) => BucketViewValue::DistributionSketch(s),
         }
     }
 
     /// Type of the value of the bucket view.
     pub fn ty(&self) -> MetricType {
-        match &self.inner.value {
-            BucketValue::Counter(_) => MetricType::Counter,
-            BucketValue::Distribution(_) => MetricType::Distribution,
-            BucketValue::Set(_) => MetricType::Set,
-            BucketValue::Gauge(_) => MetricType::Gauge,
-        }
+        self.inner.value.ty()
     }
 
     /// Name of the bucket.
@@ -501,7 +498,7 @@ impl<'a> BucketView<'a> {
     ///
     /// Returns `None` when:
     /// - the passed range is not contained in the current view.
-    /// - trying to split a counter or gauge bucket.
+    /// - trying to split a counter, gauge or distribution sketch bucket.
     pub fn select(mut self, range: Range<usize>) -> Option<Self> {
         if range.start < self.range.start || range.end > self.range.end {
             return None;
@@ -660,6 +657,11 @@ pub enum BucketViewValue<'a> {
     /// See: [`BucketValue::Gauge`].
     #[serde(rename = "g")]
     Gauge(GaugeValue),
+    /// A distribution sketch.
+    ///
+    /// See: [`BucketValue::DistributionSketch`].
+    #[serde(rename = "ds")]
+    DistributionSketch(&'a DistributionSketch),
 }
 
 impl<'a> From<&'a BucketValue> for BucketViewValue<'a> {
@@ -669,6 +671,7 @@ impl<'a> From<&'a BucketValue> for BucketViewValue<'a> {
             BucketValue::Distribution(d) => BucketViewValue::Distribution(d),
             BucketValue::Set(s) => BucketViewValue::Set(SetView::new(s, 0..s.len())),
             BucketValue::Gauge(g) => BucketViewValue::Gauge(*g),
+            BucketValue::DistributionSketch(s) => BucketViewValue::DistributionSketch(s),
         }
     }
 }
@@ -0,0 +1,256 @@
+//! Validation and normalization of crontab schedules.
+
+use std::fmt::Write;
+
+/// Error returned by [`normalize`].
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum CrontabError {
+    /// The macro is not one of the supported `@`-macros.
+    #[error("unknown macro `{0}`")]
+    UnknownMacro(String),
+
+    /// The expression does not consist of exactly five fields.
+    #[error("expected 5 fields, got {0}")]
+    FieldCount(usize),
+
+    /// A field could not be parsed or contains values out of range.
+    #[error("invalid {0} field")]
+    InvalidField(&'static str),
+}
+
+/// A single field of a crontab expression.
+struct Field {
+    name: &'static str,
+    min: u32,
+    max: u32,
+    aliases: &'static [&'static str],
+    /// Allows `L` for the last day of the month.
+    last: bool,
+    /// Allows `day#n` for the n-th weekday of the month.
+    nth: bool,
+}
+
+const FIELDS: [Field; 5] = [
+    Field {
+        name: "minute",
+        min: 0,
+        max: 59,
+        aliases: &[],
+        last: false,
+        nth: false,
+    },
+    Field {
+        name: "hour",
+        min: 0,
+        max: 23,
+        aliases: &[],
+        last: false,
+        nth: false,
+    },
+    Field {
+        name: "day of month",
+        min: 1,
+        max: 31,
+        aliases: &[],
+        last: true,
+        nth: false,
+    },
+    Field {
+        name: "month",
+        min: 1,
+        max: 12,
+        aliases: &[
+            "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+        ],
+        last: false,
+        nth: false,
+    },
+    Field {
+        name: "day of week",
+        min: 0,
+        max: 7,
+        aliases: &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"],
+        last: false,
+        nth: true,
+    },
+];
+
+/// Expands a `@`-macro into its equivalent expression.
+fn expand_macro(value: &str) -> Result<&'static str, CrontabError> {
+    Ok(match value.to_ascii_lowercase().as_str() {
+        "@yearly" | "@annually" => "0 0 1 1 *",
+        "@monthly" => "0 0 1 * *",
+        "@weekly" => "0 0 * * 0",
+        "@daily" | "@midnight" => "0 0 * * *",
+        "@hourly" => "0 * * * *",
+        _ => return Err(CrontabError::UnknownMacro(value.to_owned())),
+    })
+}
+
+/// Validates a crontab expression and returns it in canonical form.
+///
+/// The canonical form expands macros such as `@daily`, separates fields by a single space, replaces
+/// month and weekday names with their numeric values and uses `0` for Sunday outside of ranges.
+pub fn normalize(value: &str) -> Result<String, CrontabError> {
+    let value = value.trim();
+    let value = match value.starts_with('@') {
+        true => expand_macro(value)?,
+        false => value,
+    };
+
+    let parts = value.split_whitespace().collect::<Vec<_>>();
+    if parts.len() != FIELDS.len() {
+        return Err(CrontabError::FieldCount(parts.len()));
+    }
+
+    let mut normalized = String::with_capacity(value.len());
+    for (part, field) in parts.into_iter().zip(&FIELDS) {
+        if !normalized.is_empty() {
+            normalized.push(' ');
+        }
+        normalize_field(part, field, &mut normalized)
+            .ok_or(CrontabError::InvalidField(field.name))?;
+    }
+
+    Ok(normalized)
+}
+
+fn normalize_field(part: &str, field: &Field, out: &mut String) -> Option<()> {
+    for (index, item) in part.split(',').enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        normalize_item(item, field, out)?;
+    }
+
+    Some(())
+}
+
+fn normalize_item(item: &str, field: &Field, out: &mut String) -> Option<()> {
+    if field.last && item.eq_ignore_ascii_case("L") {
+        out.push('L');
+        return Some(());
+    }
+
+    if field.nth
+        && let Some((day, nth)) = item.split_once('#')
+    {
+        let day = canonical_value(parse_value(day, field)?, field);
+        let nth = nth.parse::<u32>().ok().filter(|n| (1..=5).contains(n))?;
+        write!(out, "{day}#{nth}").ok()?;
+        return Some(());
+    }
+
+    let (range, step) = match item.split_once('/') {
+        Some((range, step)) => {
+            let step = step.parse::<u32>().ok().filter(|s| *s > 0)?;
+            (range, Some(step))
+        }
+        None => (item, None),
+    };
+
+    if range == "*" {
+        out.push('*');
+    } else if let Some((start, end)) = range.split_once('-') {
+        let start = parse_value(start, field)?;
+        let end = parse_value(end, field)?;
+        if start > end {
+            return None;
+        }
+        write!(out, "{start}-{end}").ok()?;
+    } else {
+        write!(
+            out,
+            "{}",
+            canonical_value(parse_value(range, field)?, field)
+        )
+        .ok()?;
+    }
+
+    if let Some(step) = step {
+        if step > field.max {
+            return None;
+        }
+        write!(out, "/{step}").ok()?;
+    }
+
+    Some(())
+}
+
+fn parse_value(value: &str, field: &Field) -> Option<u32> {
+    let number = match value.parse::<u32>() {
+        Ok(number) => number,
+        Err(_) => {
+            let index = field
+                .aliases
+                .iter()
+                .position(|alias| alias.eq_ignore_ascii_case(value))?;
+            field.min + index as u32
+        }
+    };
+
+    (field.min..=field.max).contains(&number).then_some(number)
+}
+
+/// Returns the canonical form of a single value.
+///
+/// Sunday may be written as `0` or `7`, the canonical form is `0`. This does not apply to ranges,
+/// where `7` is required to include Sunday at the end of the week, such as in `5-7`.
+fn canonical_value(number: u32, field: &Field) -> u32 {
+    match (field.nth, number) {
+        (true, 7) => 0,
+        _ => number,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use similar_asserts::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize_valid() {
+        for (input, expected) in [
+            ("0 * * * *", "0 * * * *"),
+            ("  */15   9-17 * * MON-fri ", "*/15 9-17 * * 1-5"),
+            ("0 0 L * *", "0 0 L * *"),
+            ("30 4 1,15 jan,Jul 7", "30 4 1,15 1,7 0"),
+            ("0 12 * * 5#2", "0 12 * * 5#2"),
+            ("0 12 * * 7#1", "0 12 * * 0#1"),
+            ("0 9 * * 1-7", "0 9 * * 1-7"),
+            ("0 9 * * 5-7", "0 9 * * 5-7"),
+            ("0 9 * * fri-7/2", "0 9 * * 5-7/2"),
+            ("5/10 0-23/2 * * *", "5/10 0-23/2 * * *"),
+            ("@daily", "0 0 * * *"),
+            ("@Weekly", "0 0 * * 0"),
+            ("@annually", "0 0 1 1 *"),
+        ] {
+            assert_eq!(normalize(input).as_deref(), Ok(expected), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_normalize_invalid() {
+        for (input, expected) in [
+            ("", CrontabError::FieldCount(0)),
+            ("* * * *", CrontabError::FieldCount(4)),
+            ("0 0 * * * *", CrontabError::FieldCount(6)),
+            ("@often", CrontabError::UnknownMacro("@often".to_owned())),
+            ("60 * * * *", CrontabError::InvalidField("minute")),
+            ("* 24 * * *", CrontabError::InvalidField("hour")),
+            ("* * 0 * *", CrontabError::InvalidField("day of month")),
+            ("* * * 13 *", CrontabError::InvalidField("month")),
+            ("* * * * 8", CrontabError::InvalidField("day of week")),
+            ("*/0 * * * *", CrontabError::InvalidField("minute")),
+            ("* 5-1 * * *", CrontabError::InvalidField("hour")),
+            ("* * * * 7-1", CrontabError::InvalidField("day of week")),
+            ("* * * * L", CrontabError::InvalidField("day of week")),
+            ("* * * * 1#6", CrontabError::InvalidField("day of week")),
+            ("* * * foo *", CrontabError::InvalidField("month")),
+            ("1,,2 * * * *", CrontabError::InvalidField("minute")),
+        ] {
+            assert_eq!(normalize(input), Err(expected), "{input}");
+        }
+    }
+}
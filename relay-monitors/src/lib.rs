@@ -16,6 +16,8 @@
 )]
 #![warn(missing_docs)]
 
+use std::fmt;
+use std::str::FromStr;
 use std::sync::OnceLock;
 
 use relay_base_schema::project::ProjectId;
@@ -23,12 +25,22 @@ use relay_event_schema::protocol::{EventId, TraceId};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+pub use self::crontab::CrontabError;
+
+mod crontab;
+
 /// Maximum length of monitor slugs.
 const SLUG_LENGTH: usize = 50;
 
 /// Maximum length of environment names.
 const ENVIRONMENT_LENGTH: usize = 64;
 
+/// Maximum check-in margin and runtime in minutes (28 days).
+const MAX_TIMEOUT: u64 = 40_320;
+
+/// Maximum number of consecutive check-ins for the failure and recovery thresholds.
+const MAX_THRESHOLD: u64 = 720;
+
 /// Error returned from [`process_check_in`].
 #[derive(Debug, thiserror::Error)]
 pub enum ProcessCheckInError {
@@ -43,6 +55,30 @@ pub enum ProcessCheckInError {
     /// Environment name was invalid.
     #[error("the environment is invalid")]
     InvalidEnvironment,
+
+    /// The monitor config for the upsert was invalid.
+    #[error("the monitor config is invalid: {0}")]
+    InvalidMonitorConfig(#[from] MonitorConfigError),
+}
+
+/// Error returned when validating the [`MonitorConfig`] of a check-in.
+#[derive(Debug, thiserror::Error)]
+pub enum MonitorConfigError {
+    /// The crontab schedule could not be parsed.
+    #[error("invalid crontab schedule: {0}")]
+    InvalidCrontab(#[from] CrontabError),
+
+    /// The interval schedule has a zero value or an unknown unit.
+    #[error("invalid interval schedule")]
+    InvalidInterval,
+
+    /// The timezone is not a tz database name.
+    #[error("unknown timezone")]
+    InvalidTimezone,
+
+    /// A margin, runtime or threshold is out of its allowed bounds.
+    #[error("{0} is out of bounds")]
+    OutOfBounds(&'static str),
 }
 
 /// Describes the status of the incoming CheckIn.
@@ -67,11 +103,10 @@ pub enum CheckInStatus {
 #[serde(tag = "type")]
 enum Schedule {
     Crontab { value: String },
-    Interval { value: u64, unit: IntervalName },
+    Interval { value: u64, unit: String },
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
-#[serde(rename_all = "snake_case")]
+#[derive(Clone, Copy, Debug, PartialEq)]
 enum IntervalName {
     Year,
     Month,
@@ -81,6 +116,43 @@ enum IntervalName {
     Minute,
 }
 
+impl IntervalName {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Year => "year",
+            Self::Month => "month",
+            Self::Week => "week",
+            Self::Day => "day",
+            Self::Hour => "hour",
+            Self::Minute => "minute",
+        }
+    }
+}
+
+impl FromStr for IntervalName {
+    type Err = MonitorConfigError;
+
+    /// Parses the unit case insensitively, in singular or plural form.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        Ok(match s.strip_suffix('s').unwrap_or(&s) {
+            "year" => Self::Year,
+            "month" => Self::Month,
+            "week" => Self::Week,
+            "day" => Self::Day,
+            "hour" => Self::Hour,
+            "minute" => Self::Minute,
+            _ => return Err(MonitorConfigError::InvalidInterval),
+        })
+    }
+}
+
+impl fmt::Display for IntervalName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// The monitor configuration payload for upserting monitors during check-in
 #[derive(Debug, Deserialize, Serialize)]
 pub struct MonitorConfig {
@@ -117,6 +189,63 @@ pub struct MonitorConfig {
     owner: Option<String>,
 }
 
+impl MonitorConfig {
+    /// Validates the monitor config and normalizes it into its canonical form.
+    ///
+    /// Crontab schedules are normalized according to [`crontab::normalize`], interval units and
+    /// timezones are normalized to their canonical spelling.
+    fn normalize(&mut self) -> Result<(), MonitorConfigError> {
+        match &mut self.schedule {
+            Schedule::Crontab { value } => *value = crontab::normalize(value)?,
+            Schedule::Interval { value, unit } => {
+                if *value == 0 {
+                    return Err(MonitorConfigError::InvalidInterval);
+                }
+                *unit = unit.parse::<IntervalName>()?.to_string();
+            }
+        }
+
+        if let Some(timezone) = &mut self.timezone {
+            *timezone = normalize_timezone(timezone).ok_or(MonitorConfigError::InvalidTimezone)?;
+        }
+
+        check_bounds("checkin_margin", self.checkin_margin, MAX_TIMEOUT)?;
+        check_bounds("max_runtime", self.max_runtime, MAX_TIMEOUT)?;
+        check_bounds(
+            "failure_issue_threshold",
+            self.failure_issue_threshold,
+            MAX_THRESHOLD,
+        )?;
+        check_bounds("recovery_threshold", self.recovery_threshold, MAX_THRESHOLD)?;
+
+        Ok(())
+    }
+}
+
+/// Returns the canonical spelling of a tz database name, matched case insensitively.
+fn normalize_timezone(timezone: &str) -> Option<String> {
+    let timezone = timezone.trim();
+    let canonical = match timezone.parse::<chrono_tz::Tz>() {
+        Ok(tz) => tz,
+        Err(_) => *chrono_tz::TZ_VARIANTS
+            .iter()
+            .find(|tz| tz.name().eq_ignore_ascii_case(timezone))?,
+    };
+
+    Some(canonical.name().to_owned())
+}
+
+fn check_bounds(
+    name: &'static str,
+    value: Option<u64>,
+    max: u64,
+) -> Result<(), MonitorConfigError> {
+    match value {
+        Some(value) if !(1..=max).contains(&value) => Err(MonitorConfigError::OutOfBounds(name)),
+        _ => Ok(()),
+    }
+}
+
 /// The trace context sent with a check-in.
 #[derive(Debug, Deserialize, Serialize)]
 pub struct CheckInTrace {
@@ -174,6 +303,11 @@ pub struct ProcessedCheckInResult {
 
     /// The JSON payload of the processed check-in.
     pub payload: Vec<u8>,
+
+    /// The error if the monitor config of the check-in was invalid.
+    ///
+    /// Invalid monitor configs are removed from the check-in, the check-in itself is still valid.
+    pub monitor_config_error: Option<ProcessCheckInError>,
 }
 
 /// Normalizes a monitor check-in payload.
@@ -202,6 +336,15 @@ pub fn process_check_in(
         return Err(ProcessCheckInError::InvalidEnvironment);
     }
 
+    // An invalid upsert must not discard the check-in, only the monitor config is dropped.
+    let monitor_config_error = match &mut check_in.monitor_config {
+        Some(config) => config.normalize().err(),
+        None => None,
+    };
+    if monitor_config_error.is_some() {
+        check_in.monitor_config = None;
+    }
+
     static NAMESPACE: OnceLock<Uuid> = OnceLock::new();
     let namespace = NAMESPACE
         .get_or_init(|| Uuid::new_v5(&Uuid::NAMESPACE_URL, b"https://sentry.io/crons/#did"));
@@ -217,6 +360,7 @@ pub fn process_check_in(
     Ok(ProcessedCheckInResult {
         routing_hint,
         payload: serde_json::to_vec(&check_in)?,
+        monitor_config_error: monitor_config_error.map(Into::into),
     })
 }
 
@@ -380,4 +524,87 @@ mod tests {
             Err(ProcessCheckInError::InvalidEnvironment)
         ));
     }
+
+    #[test]
+    fn process_normalize_monitor_config() {
+        let json = r#"{
+          "check_in_id": "a460c25ff2554577b920fcfacae4e5eb",
+          "monitor_slug": "my-monitor",
+          "status": "ok",
+          "monitor_config": {
+            "schedule": {"type": "crontab", "value": "@hourly"},
+            "timezone": "europe/vienna",
+            "checkin_margin": 5
+          }
+        }"#;
+
+        let result = process_check_in(json.as_bytes(), ProjectId::new(1)).unwrap();
+        assert!(result.monitor_config_error.is_none());
+        assert_eq!(
+            String::from_utf8(result.payload).unwrap(),
+            r#"{"check_in_id":"a460c25ff2554577b920fcfacae4e5eb","monitor_slug":"my-monitor","status":"ok","monitor_config":{"schedule":{"type":"crontab","value":"0 * * * *"},"checkin_margin":5,"timezone":"Europe/Vienna"}}"#
+        );
+    }
+
+    #[test]
+    fn process_normalize_interval() {
+        let json = r#"{
+          "check_in_id": "a460c25ff2554577b920fcfacae4e5eb",
+          "monitor_slug": "my-monitor",
+          "status": "ok",
+          "monitor_config": {"schedule": {"type": "interval", "value": 5, "unit": "Days"}}
+        }"#;
+
+        let result = process_check_in(json.as_bytes(), ProjectId::new(1)).unwrap();
+        assert!(result.monitor_config_error.is_none());
+        assert_eq!(
+            String::from_utf8(result.payload).unwrap(),
+            r#"{"check_in_id":"a460c25ff2554577b920fcfacae4e5eb","monitor_slug":"my-monitor","status":"ok","monitor_config":{"schedule":{"type":"interval","value":5,"unit":"day"}}}"#
+        );
+    }
+
+    #[test]
+    fn process_invalid_monitor_config() {
+        for monitor_config in [
+            r#"{"schedule": {"type": "crontab", "value": "0 * * *"}}"#,
+            r#"{"schedule": {"type": "crontab", "value": "@sometimes"}}"#,
+            r#"{"schedule": {"type": "interval", "value": 0, "unit": "day"}}"#,
+            r#"{"schedule": {"type": "interval", "value": 1, "unit": "fortnight"}}"#,
+            r#"{"schedule": {"type": "crontab", "value": "0 * * * *"}, "timezone": "America/Los_Angles"}"#,
+            r#"{"schedule": {"type": "crontab", "value": "0 * * * *"}, "checkin_margin": 0}"#,
+            r#"{"schedule": {"type": "crontab", "value": "0 * * * *"}, "max_runtime": 40321}"#,
+            r#"{"schedule": {"type": "crontab", "value": "0 * * * *"}, "recovery_threshold": 721}"#,
+        ] {
+            let json = format!(
+                r#"{{"check_in_id":"a460c25ff2554577b920fcfacae4e5eb","monitor_slug":"my-monitor","status":"ok","monitor_config":{monitor_config}}}"#
+            );
+
+            let result = process_check_in(json.as_bytes(), ProjectId::new(1)).unwrap();
+            assert!(
+                matches!(
+                    result.monitor_config_error,
+                    Some(ProcessCheckInError::InvalidMonitorConfig(_))
+                ),
+                "{monitor_config}"
+            );
+            assert_eq!(
+                String::from_utf8(result.payload).unwrap(),
+                r#"{"check_in_id":"a460c25ff2554577b920fcfacae4e5eb","monitor_slug":"my-monitor","status":"ok"}"#
+            );
+        }
+    }
+
+    #[test]
+    fn normalize_timezone_names() {
+        assert_eq!(normalize_timezone("UTC").as_deref(), Some("UTC"));
+        assert_eq!(
+            normalize_timezone("America/Los_Angeles").as_deref(),
+            Some("America/Los_Angeles")
+        );
+        assert_eq!(
+            normalize_timezone("us/pacific").as_deref(),
+            Some("US/Pacific")
+        );
+        assert_eq!(normalize_timezone("Mars/Olympus_Mons"), None);
+    }
 }
@@ -0,0 +1,317 @@
+//! Transforms documents of the Elasticsearch `_bulk` API to Sentry Logs.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use relay_conventions::attributes::SENTRY__ORIGIN;
+use relay_event_schema::protocol::{Attributes, OurLog, OurLogLevel, SpanId, Timestamp, TraceId};
+use relay_protocol::{Annotated, Meta, Remark, RemarkType};
+use serde_json::{Map, Value};
+
+/// Field containing the time of the log, as used by Beats, Logstash and ECS.
+const TIMESTAMP_FIELD: &str = "@timestamp";
+/// Field containing the log message.
+const MESSAGE_FIELD: &str = "message";
+/// Fields containing the log level, in order of precedence.
+const LEVEL_FIELDS: [&str; 2] = ["level", "log.level"];
+/// ECS field containing the trace id.
+const TRACE_ID_FIELD: &str = "trace.id";
+/// ECS field containing the span id.
+const SPAN_ID_FIELD: &str = "span.id";
+
+/// Maps the textual log level of a document to Sentry log level.
+///
+/// Levels are matched case-insensitively and include common syslog and logging library variants.
+fn map_level_to_sentry(level: &str) -> OurLogLevel {
+    match level.to_ascii_lowercase().as_str() {
+        "trace" | "verbose" => OurLogLevel::Trace,
+        "debug" => OurLogLevel::Debug,
+        "info" | "information" | "notice" => OurLogLevel::Info,
+        "warn" | "warning" => OurLogLevel::Warn,
+        "error" | "err" => OurLogLevel::Error,
+        "fatal" | "critical" | "crit" | "alert" | "emerg" | "emergency" | "panic" => {
+            OurLogLevel::Fatal
+        }
+        _ => OurLogLevel::Info,
+    }
+}
+
+/// Parses a date in one of the default Elasticsearch date formats.
+///
+/// Supports RFC 3339 dates, dates without timezone which are assumed to be UTC, and milliseconds
+/// since the epoch.
+fn parse_timestamp(value: &Value) -> Option<DateTime<Utc>> {
+    match value {
+        Value::Number(number) => Utc.timestamp_millis_opt(number.as_i64()?).single(),
+        Value::String(s) => {
+            if let Ok(millis) = s.parse::<i64>() {
+                return Utc.timestamp_millis_opt(millis).single();
+            }
+
+            DateTime::parse_from_rfc3339(s)
+                .map(|dt| dt.to_utc())
+                .or_else(|_| NaiveDateTime::from_str(s).map(|dt| dt.and_utc()))
+                .ok()
+        }
+        _ => None,
+    }
+}
+
+/// Flattens nested objects into dotted keys.
+///
+/// Arrays are kept as values and `null` values are dropped.
+fn flatten(prefix: Option<&str>, object: Map<String, Value>, out: &mut BTreeMap<String, Value>) {
+    for (key, value) in object {
+        let key = match prefix {
+            Some(prefix) => format!("{prefix}.{key}"),
+            None => key,
+        };
+
+        match value {
+            Value::Null => {}
+            Value::Object(object) => flatten(Some(&key), object, out),
+            value => {
+                out.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Inserts a scalar field as attribute, arrays are stored as JSON strings.
+fn insert_attribute(attributes: &mut Attributes, key: String, value: Value) {
+    match value {
+        Value::Bool(b) => attributes.insert(key, b),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => attributes.insert(key, i),
+            None => {
+                if let Some(f) = n.as_f64() {
+                    attributes.insert(key, f)
+                }
+            }
+        },
+        Value::String(s) => attributes.insert(key, s),
+        Value::Array(_) => attributes.insert(key, value.to_string()),
+        Value::Null | Value::Object(_) => {}
+    }
+}
+
+/// Returns the value of a field as string, serializing non-string values as JSON.
+fn into_string(value: Value) -> String {
+    match value {
+        Value::String(s) => s,
+        value => value.to_string(),
+    }
+}
+
+/// Transforms a document of the Elasticsearch `_bulk` API to a Sentry log.
+///
+/// The `@timestamp`, `message` and `level` (or `log.level`) fields are mapped to the log's
+/// timestamp, body and level. ECS `trace.id` and `span.id` fields are used as trace and span
+/// ids. All remaining fields are flattened into attributes. Documents without a valid timestamp
+/// are assigned `received_at`.
+pub fn elasticsearch_document_to_sentry_log(
+    mut document: Map<String, Value>,
+    received_at: DateTime<Utc>,
+) -> OurLog {
+    let timestamp = match document.remove(TIMESTAMP_FIELD) {
+        Some(value) => match parse_timestamp(&value) {
+            Some(timestamp) => Annotated::new(Timestamp(timestamp)),
+            None => {
+                let mut meta = Meta::default();
+                meta.add_remark(Remark::new(RemarkType::Substituted, "timestamp.invalid"));
+                Annotated(Some(Timestamp(received_at)), meta)
+            }
+        },
+        None => {
+            let mut meta = Meta::default();
+            meta.add_remark(Remark::new(RemarkType::Substituted, "timestamp.missing"));
+            Annotated(Some(Timestamp(received_at)), meta)
+        }
+    };
+
+    let body = document
+        .remove(MESSAGE_FIELD)
+        .map(into_string)
+        .unwrap_or_default();
+
+    let mut fields = BTreeMap::new();
+    flatten(None, document, &mut fields);
+
+    let level = LEVEL_FIELDS
+        .iter()
+        .find_map(|field| match fields.get(*field) {
+            Some(Value::String(level)) => Some((*field, map_level_to_sentry(level))),
+            _ => None,
+        })
+        .map(|(field, level)| {
+            fields.remove(field);
+            level
+        })
+        .unwrap_or(OurLogLevel::Info);
+
+    let trace_id = fields.remove(TRACE_ID_FIELD).map(into_string);
+    let trace_id = TraceId::try_from_str_or_random(trace_id.as_deref().unwrap_or_default());
+
+    let span_id = match fields.remove(SPAN_ID_FIELD).map(into_string) {
+        Some(s) if !s.is_empty() => {
+            SpanId::from_str(&s).map_or_else(|err| Annotated::from_error(err, None), Annotated::new)
+        }
+        _ => Annotated::empty(),
+    };
+
+    let mut attributes = Attributes::default();
+    for (key, value) in fields {
+        insert_attribute(&mut attributes, key, value);
+    }
+    // Inserted last, so that the origin cannot be overwritten by the document.
+    attributes.insert(SENTRY__ORIGIN, "auto.log_drain.elasticsearch");
+
+    OurLog {
+        timestamp,
+        trace_id,
+        span_id,
+        level: Annotated::new(level),
+        body: Annotated::new(body),
+        attributes: Annotated::new(attributes),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use relay_protocol::SerializableAnnotated;
+
+    use super::*;
+
+    fn received_at() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 2, 12, 0, 0).unwrap()
+    }
+
+    fn document(json: &str) -> Map<String, Value> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_elasticsearch_document_to_sentry_log() {
+        let document = document(
+            r#"{
+                "@timestamp": "2025-01-02T11:59:00.123Z",
+                "message": "GET /api/users 500",
+                "log": {"level": "ERROR", "logger": "http"},
+                "trace": {"id": "1b02cd14bb8642fd092bc23f54c7ffcd"},
+                "span": {"id": "f24e8631bd11faa7"},
+                "http": {"status_code": 500, "duration": 0.25},
+                "kubernetes": {"pod": {"name": "api-1"}, "labels": null},
+                "tags": ["web", "prod"],
+                "cached": false
+            }"#,
+        );
+
+        let our_log = Annotated::new(elasticsearch_document_to_sentry_log(
+            document,
+            received_at(),
+        ));
+
+        insta::assert_json_snapshot!(SerializableAnnotated(&our_log), @r#"
+        {
+          "timestamp": 1735819140.123,
+          "trace_id": "1b02cd14bb8642fd092bc23f54c7ffcd",
+          "span_id": "f24e8631bd11faa7",
+          "level": "error",
+          "body": "GET /api/users 500",
+          "attributes": {
+            "cached": {
+              "type": "boolean",
+              "value": false
+            },
+            "http.duration": {
+              "type": "double",
+              "value": 0.25
+            },
+            "http.status_code": {
+              "type": "integer",
+              "value": 500
+            },
+            "kubernetes.pod.name": {
+              "type": "string",
+              "value": "api-1"
+            },
+            "log.logger": {
+              "type": "string",
+              "value": "http"
+            },
+            "sentry.origin": {
+              "type": "string",
+              "value": "auto.log_drain.elasticsearch"
+            },
+            "tags": {
+              "type": "string",
+              "value": "[\"web\",\"prod\"]"
+            }
+          }
+        }
+        "#);
+    }
+
+    #[test]
+    fn test_level_precedence() {
+        let document = document(r#"{"level": "warning", "log.level": "debug"}"#);
+        let our_log = elasticsearch_document_to_sentry_log(document, received_at());
+
+        assert_eq!(our_log.level.value(), Some(&OurLogLevel::Warn));
+        let attributes = our_log.attributes.value().unwrap();
+        assert!(!attributes.contains_key("level"));
+        assert!(attributes.contains_key("log.level"));
+    }
+
+    #[test]
+    fn test_origin_not_overwritten() {
+        for json in [
+            r#"{"sentry.origin": "custom"}"#,
+            r#"{"sentry": {"origin": "custom"}}"#,
+        ] {
+            let our_log = elasticsearch_document_to_sentry_log(document(json), received_at());
+            let attributes = our_log.attributes.value().unwrap();
+            assert_eq!(
+                attributes
+                    .get_value(SENTRY__ORIGIN)
+                    .and_then(|v| v.as_str()),
+                Some("auto.log_drain.elasticsearch"),
+                "{json}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_timestamp_formats() {
+        let expected = Utc.with_ymd_and_hms(2025, 1, 2, 11, 0, 0).unwrap();
+
+        for timestamp in [
+            r#""2025-01-02T11:00:00Z""#,
+            r#""2025-01-02T12:00:00+01:00""#,
+            r#""2025-01-02T11:00:00""#,
+            "1735815600000",
+            r#""1735815600000""#,
+        ] {
+            let document = document(&format!(r#"{{"@timestamp": {timestamp}}}"#));
+            let our_log = elasticsearch_document_to_sentry_log(document, received_at());
+            assert_eq!(
+                our_log.timestamp.value(),
+                Some(&Timestamp(expected)),
+                "{timestamp}"
+            );
+            assert!(our_log.timestamp.meta().is_empty(), "{timestamp}");
+        }
+    }
+
+    #[test]
+    fn test_invalid_timestamp() {
+        let document = document(r#"{"@timestamp": "yesterday", "message": {"a": 1}}"#);
+        let our_log = elasticsearch_document_to_sentry_log(document, received_at());
+
+        assert_eq!(our_log.timestamp.value(), Some(&Timestamp(received_at())));
+        assert!(!our_log.timestamp.meta().is_empty());
+        assert_eq!(our_log.body.as_str(), Some(r#"{"a":1}"#));
+    }
+}
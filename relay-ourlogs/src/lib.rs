@@ -6,12 +6,19 @@
     html_favicon_url = "https://raw.githubusercontent.com/getsentry/relay/master/artwork/relay-icon.png"
 )]
 
+mod elasticsearch_to_sentry;
 mod otel_to_sentry;
 mod size;
+mod syslog_to_sentry;
 mod vercel_to_sentry;
 
+pub use self::elasticsearch_to_sentry::elasticsearch_document_to_sentry_log;
 pub use self::otel_to_sentry::otel_to_sentry_log;
 pub use self::size::calculate_size;
+pub use self::syslog_to_sentry::{
+    OctetCountedFrames, StructuredDataElement, SyslogError, SyslogMessage, parse_syslog,
+    split_octet_counted, syslog_to_sentry_log,
+};
 pub use self::vercel_to_sentry::{VercelLog, vercel_log_to_sentry_log};
 
 pub use opentelemetry_proto::tonic::logs::v1 as otel_logs;
@@ -0,0 +1,751 @@
+//! Transforms syslog messages to Sentry Logs.
+//!
+//! Supports messages in the [RFC 5424](https://datatracker.ietf.org/doc/html/rfc5424) and the
+//! legacy BSD [RFC 3164](https://datatracker.ietf.org/doc/html/rfc3164) format, as well as
+//! octet-counted framing used by [logplex](https://devcenter.heroku.com/articles/log-drains#https-drains)
+//! HTTPS log drains.
+
+use std::borrow::Cow;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, Utc};
+use relay_conventions::attributes::SENTRY__ORIGIN;
+use relay_event_schema::protocol::{Attributes, OurLog, OurLogLevel, Timestamp, TraceId};
+use relay_protocol::{Annotated, Meta, Remark, RemarkType};
+
+/// Attribute containing the name of the syslog facility, for example `local0`.
+const SYSLOG__FACILITY: &str = "syslog.facility";
+/// Attribute containing the name of the syslog severity, for example `err`.
+const SYSLOG__SEVERITY: &str = "syslog.severity";
+/// Attribute containing the hostname of the machine that sent the message.
+const SYSLOG__HOSTNAME: &str = "syslog.hostname";
+/// Attribute containing the application that sent the message.
+const SYSLOG__APP_NAME: &str = "syslog.app_name";
+/// Attribute containing the process identifier of the sender.
+const SYSLOG__PROC_ID: &str = "syslog.proc_id";
+/// Attribute containing the type of the message.
+const SYSLOG__MSG_ID: &str = "syslog.msg_id";
+/// Prefix for attributes of structured data parameters.
+///
+/// Parameters are stored as `syslog.structured_data.<sd-id>.<param-name>`.
+const SYSLOG__STRUCTURED_DATA: &str = "syslog.structured_data";
+
+/// Names of syslog facilities, indexed by their numerical code.
+const FACILITIES: [&str; 24] = [
+    "kern",
+    "user",
+    "mail",
+    "daemon",
+    "auth",
+    "syslog",
+    "lpr",
+    "news",
+    "uucp",
+    "cron",
+    "authpriv",
+    "ftp",
+    "ntp",
+    "security",
+    "console",
+    "solaris-cron",
+    "local0",
+    "local1",
+    "local2",
+    "local3",
+    "local4",
+    "local5",
+    "local6",
+    "local7",
+];
+
+/// Names of syslog severities, indexed by their numerical code.
+const SEVERITIES: [&str; 8] = [
+    "emerg", "alert", "crit", "err", "warning", "notice", "info", "debug",
+];
+
+/// An error returned when parsing syslog messages or their framing.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum SyslogError {
+    /// The message does not start with a valid `<PRI>` part.
+    #[error("invalid or missing priority")]
+    InvalidPriority,
+    /// An octet-counted frame has an invalid length prefix or is truncated.
+    #[error("invalid octet-counted frame")]
+    InvalidFrame,
+}
+
+/// A structured data element of a RFC 5424 syslog message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructuredDataElement<'a> {
+    /// The identifier of the element, for example `origin` or `exampleSDID@32473`.
+    pub id: &'a str,
+    /// All parameters of the element in order of appearance.
+    pub params: Vec<(&'a str, Cow<'a, str>)>,
+}
+
+/// A syslog message parsed from RFC 5424 or RFC 3164 format.
+///
+/// Fields holding the nil value (`-`) are `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyslogMessage<'a> {
+    /// Numerical code of the facility, between `0` and `23`.
+    pub facility: u8,
+    /// Numerical code of the severity, between `0` (emergency) and `7` (debug).
+    pub severity: u8,
+    /// The time the message was created, if known.
+    pub timestamp: Option<DateTime<Utc>>,
+    /// The machine that sent the message.
+    pub hostname: Option<&'a str>,
+    /// The application or device that sent the message.
+    pub app_name: Option<&'a str>,
+    /// The process identifier of the sender.
+    pub proc_id: Option<&'a str>,
+    /// The type of the message. Only available for RFC 5424 messages.
+    pub msg_id: Option<&'a str>,
+    /// Structured data elements. Only available for RFC 5424 messages.
+    pub structured_data: Vec<StructuredDataElement<'a>>,
+    /// The free-form message.
+    pub message: &'a str,
+}
+
+/// Parses a single syslog message in RFC 5424 or RFC 3164 format.
+///
+/// The format is detected from the version following the priority. Parsing is lenient where
+/// common senders deviate from the RFCs, for instance logplex omits the structured data of
+/// RFC 5424 messages entirely. RFC 3164 timestamps do not contain a year, which is inferred from
+/// `received_at`.
+pub fn parse_syslog(
+    input: &str,
+    received_at: DateTime<Utc>,
+) -> Result<SyslogMessage<'_>, SyslogError> {
+    let input = input.trim_end_matches(['\r', '\n']);
+
+    let (priority, rest) = input
+        .strip_prefix('<')
+        .and_then(|s| s.split_once('>'))
+        .ok_or(SyslogError::InvalidPriority)?;
+
+    // The priority has at most three digits and must not contain leading zeros.
+    let priority = match priority.parse::<u8>() {
+        Ok(value) if value < 192 && (value == 0 || !priority.starts_with('0')) => value,
+        _ => return Err(SyslogError::InvalidPriority),
+    };
+
+    let mut message = SyslogMessage {
+        facility: priority / 8,
+        severity: priority % 8,
+        timestamp: None,
+        hostname: None,
+        app_name: None,
+        proc_id: None,
+        msg_id: None,
+        structured_data: Vec::new(),
+        message: "",
+    };
+
+    match rest.strip_prefix("1 ") {
+        Some(rest) => parse_rfc5424(rest, &mut message),
+        None => parse_rfc3164(rest, received_at, &mut message),
+    }
+
+    Ok(message)
+}
+
+/// Parses the header, structured data and message following `<PRI>1 `.
+fn parse_rfc5424<'a>(mut rest: &'a str, message: &mut SyslogMessage<'a>) {
+    message.timestamp = next_field(&mut rest)
+        .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+        .map(|ts| ts.to_utc());
+    message.hostname = next_field(&mut rest);
+    message.app_name = next_field(&mut rest);
+    message.proc_id = next_field(&mut rest);
+    message.msg_id = next_field(&mut rest);
+
+    // Senders like logplex omit the structured data, in which case the remainder is the message.
+    // This also applies to messages which start with brackets but no valid structured data.
+    if let Some(after) = rest.strip_prefix('-') {
+        rest = after;
+    } else if let Some((structured_data, after)) = parse_structured_data(rest) {
+        message.structured_data = structured_data;
+        rest = after;
+    }
+
+    let rest = rest.strip_prefix(' ').unwrap_or(rest);
+    message.message = rest.strip_prefix('\u{feff}').unwrap_or(rest);
+}
+
+/// Parses all structured data elements at the start of the input.
+///
+/// Returns `None` if the input does not start with valid structured data.
+fn parse_structured_data(mut input: &str) -> Option<(Vec<StructuredDataElement<'_>>, &str)> {
+    let mut elements = Vec::new();
+
+    while let Some(after) = input.strip_prefix('[') {
+        let (element, after) = parse_sd_element(after)?;
+        elements.push(element);
+        input = after;
+    }
+
+    match elements.is_empty() {
+        true => None,
+        false => Some((elements, input)),
+    }
+}
+
+/// Parses a single structured data element after its opening `[`.
+///
+/// Returns the element and the remaining input after the closing `]`.
+fn parse_sd_element(input: &str) -> Option<(StructuredDataElement<'_>, &str)> {
+    let (id, mut rest) = input.split_at(input.find([' ', ']'])?);
+    if id.is_empty() || id.contains(['=', '"']) {
+        return None;
+    }
+
+    let mut params = Vec::new();
+    while let Some(after) = rest.strip_prefix(' ') {
+        let (name, after) = after.split_once("=\"")?;
+        if name.is_empty() || name.contains([' ', ']', '"']) {
+            return None;
+        }
+        let (value, after) = parse_param_value(after)?;
+        params.push((name, value));
+        rest = after;
+    }
+
+    let rest = rest.strip_prefix(']')?;
+
+    // Messages commonly start with bracketed text such as `[INFO]`, which is indistinguishable
+    // from an element without parameters. Only accept those for registered or enterprise IDs.
+    if params.is_empty() && !id.contains('@') && !matches!(id, "timeQuality" | "origin" | "meta") {
+        return None;
+    }
+
+    Some((StructuredDataElement { id, params }, rest))
+}
+
+/// Parses a parameter value after its opening quote, resolving escaped characters.
+///
+/// Returns the value and the remaining input after the closing quote.
+fn parse_param_value(input: &str) -> Option<(Cow<'_, str>, &str)> {
+    let mut value = Cow::Borrowed("");
+    let mut start = 0;
+    let mut chars = input.char_indices();
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => {
+                let value = match value {
+                    Cow::Borrowed(_) => Cow::Borrowed(&input[..index]),
+                    Cow::Owned(mut value) => {
+                        value.push_str(&input[start..index]);
+                        Cow::Owned(value)
+                    }
+                };
+                return Some((value, &input[index + 1..]));
+            }
+            '\\' => {
+                if let Some((next_index, next @ ('"' | '\\' | ']'))) = chars.clone().next() {
+                    let owned = value.to_mut();
+                    owned.push_str(&input[start..index]);
+                    owned.push(next);
+                    start = next_index + next.len_utf8();
+                    chars.next();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Parses the timestamp, hostname, tag and message following `<PRI>`.
+///
+/// Messages without a valid timestamp are kept in their entirety as message.
+fn parse_rfc3164<'a>(rest: &'a str, received_at: DateTime<Utc>, message: &mut SyslogMessage<'a>) {
+    let Some(timestamp) = rest
+        .get(..15)
+        .and_then(|ts| parse_bsd_timestamp(ts, received_at))
+    else {
+        message.message = rest;
+        return;
+    };
+
+    message.timestamp = Some(timestamp);
+
+    let mut rest = rest[15..].trim_start_matches(' ');
+    message.hostname = next_field(&mut rest);
+
+    // The tag is terminated by the first non-alphanumeric character, usually `[pid]:` or `:`.
+    if let Some((tag, msg)) = rest.split_once(": ")
+        && !tag.is_empty()
+        && !tag.contains(' ')
+    {
+        let (app_name, proc_id) = match tag.strip_suffix(']').and_then(|t| t.split_once('[')) {
+            Some((app_name, proc_id)) => (app_name, Some(proc_id)),
+            None => (tag, None),
+        };
+
+        message.app_name = Some(app_name).filter(|s| !s.is_empty());
+        message.proc_id = proc_id.filter(|s| !s.is_empty());
+        rest = msg;
+    }
+
+    message.message = rest;
+}
+
+/// Parses a `Mmm dd hh:mm:ss` timestamp in the year of `received_at`.
+///
+/// Timestamps more than a day ahead of `received_at` are assumed to be from the previous year.
+fn parse_bsd_timestamp(timestamp: &str, received_at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let parse = |year: i32| {
+        NaiveDateTime::parse_from_str(&format!("{year} {timestamp}"), "%Y %b %e %H:%M:%S")
+            .ok()
+            .map(|dt| dt.and_utc())
+    };
+
+    let year = received_at.year();
+    match parse(year) {
+        Some(dt) if dt > received_at + Duration::days(1) => parse(year - 1),
+        Some(dt) => Some(dt),
+        // February 29th does not exist in every year.
+        None => parse(year - 1),
+    }
+}
+
+/// Returns the next space-separated header field, or `None` for the nil value.
+fn next_field<'a>(input: &mut &'a str) -> Option<&'a str> {
+    let (field, rest) = input.split_once(' ').unwrap_or((input, ""));
+    *input = rest;
+    Some(field).filter(|f| !f.is_empty() && *f != "-")
+}
+
+/// Splits a payload with octet-counted framing into individual syslog messages.
+///
+/// Each message is prefixed with its length in bytes and a space, as described in
+/// [RFC 6587](https://datatracker.ietf.org/doc/html/rfc6587#section-3.4.1). Whitespace between
+/// frames is ignored. After an invalid frame, the iterator yields an error and stops.
+pub fn split_octet_counted(payload: &[u8]) -> OctetCountedFrames<'_> {
+    OctetCountedFrames { payload }
+}
+
+/// Iterator returned by [`split_octet_counted`].
+#[derive(Debug)]
+pub struct OctetCountedFrames<'a> {
+    payload: &'a [u8],
+}
+
+impl<'a> Iterator for OctetCountedFrames<'a> {
+    type Item = Result<&'a [u8], SyslogError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let payload = self.payload.trim_ascii_start();
+        if payload.is_empty() {
+            self.payload = payload;
+            return None;
+        }
+
+        let frame = payload.iter().position(|&b| b == b' ').and_then(|pos| {
+            let len: usize = std::str::from_utf8(&payload[..pos]).ok()?.parse().ok()?;
+            let start = pos.checked_add(1)?;
+            let end = start.checked_add(len)?;
+            Some((payload.get(start..end)?, &payload[end..]))
+        });
+
+        match frame {
+            Some((frame, rest)) => {
+                self.payload = rest;
+                Some(Ok(frame))
+            }
+            None => {
+                self.payload = &[];
+                Some(Err(SyslogError::InvalidFrame))
+            }
+        }
+    }
+}
+
+/// Maps a syslog severity to Sentry log level.
+fn map_syslog_severity_to_sentry(severity: u8) -> OurLogLevel {
+    match severity {
+        0..=2 => OurLogLevel::Fatal,
+        3 => OurLogLevel::Error,
+        4 => OurLogLevel::Warn,
+        5 | 6 => OurLogLevel::Info,
+        _ => OurLogLevel::Debug,
+    }
+}
+
+/// Transforms a syslog message to a Sentry log.
+///
+/// Messages without a timestamp are assigned `received_at`.
+pub fn syslog_to_sentry_log(message: SyslogMessage<'_>, received_at: DateTime<Utc>) -> OurLog {
+    let SyslogMessage {
+        facility,
+        severity,
+        timestamp,
+        hostname,
+        app_name,
+        proc_id,
+        msg_id,
+        structured_data,
+        message,
+    } = message;
+
+    let mut attributes = Attributes::default();
+
+    attributes.insert(SENTRY__ORIGIN, "auto.log_drain.syslog");
+    if let Some(facility) = FACILITIES.get(usize::from(facility)) {
+        attributes.insert(SYSLOG__FACILITY, *facility);
+    }
+    if let Some(severity) = SEVERITIES.get(usize::from(severity)) {
+        attributes.insert(SYSLOG__SEVERITY, *severity);
+    }
+    if let Some(hostname) = hostname {
+        attributes.insert(SYSLOG__HOSTNAME, hostname);
+    }
+    if let Some(app_name) = app_name {
+        attributes.insert(SYSLOG__APP_NAME, app_name);
+    }
+    if let Some(proc_id) = proc_id {
+        attributes.insert(SYSLOG__PROC_ID, proc_id);
+    }
+    if let Some(msg_id) = msg_id {
+        attributes.insert(SYSLOG__MSG_ID, msg_id);
+    }
+
+    for StructuredDataElement { id, params } in structured_data {
+        for (name, value) in params {
+            attributes.insert(
+                format!("{SYSLOG__STRUCTURED_DATA}.{id}.{name}"),
+                value.into_owned(),
+            );
+        }
+    }
+
+    let timestamp = match timestamp {
+        Some(timestamp) => Annotated::new(Timestamp(timestamp)),
+        None => {
+            let mut meta = Meta::default();
+            meta.add_remark(Remark::new(RemarkType::Substituted, "timestamp.missing"));
+            Annotated(Some(Timestamp(received_at)), meta)
+        }
+    };
+
+    OurLog {
+        timestamp,
+        trace_id: TraceId::try_from_str_or_random(""),
+        level: Annotated::new(map_syslog_severity_to_sentry(severity)),
+        body: Annotated::new(message.to_owned()),
+        attributes: Annotated::new(attributes),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use relay_protocol::SerializableAnnotated;
+
+    use super::*;
+
+    fn received_at() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 2, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_rfc5424() {
+        let input = r#"<165>1 2003-10-11T22:14:15.003Z mymachine.example.com evntslog - ID47 [exampleSDID@32473 iut="3" eventSource="Application" eventID="1011"][examplePriority@32473 class="high"] An application event log entry..."#;
+
+        insta::assert_debug_snapshot!(parse_syslog(input, received_at()).unwrap(), @r#"
+        SyslogMessage {
+            facility: 20,
+            severity: 5,
+            timestamp: Some(
+                2003-10-11T22:14:15.003Z,
+            ),
+            hostname: Some(
+                "mymachine.example.com",
+            ),
+            app_name: Some(
+                "evntslog",
+            ),
+            proc_id: None,
+            msg_id: Some(
+                "ID47",
+            ),
+            structured_data: [
+                StructuredDataElement {
+                    id: "exampleSDID@32473",
+                    params: [
+                        (
+                            "iut",
+                            "3",
+                        ),
+                        (
+                            "eventSource",
+                            "Application",
+                        ),
+                        (
+                            "eventID",
+                            "1011",
+                        ),
+                    ],
+                },
+                StructuredDataElement {
+                    id: "examplePriority@32473",
+                    params: [
+                        (
+                            "class",
+                            "high",
+                        ),
+                    ],
+                },
+            ],
+            message: "An application event log entry...",
+        }
+        "#);
+    }
+
+    #[test]
+    fn test_parse_rfc5424_nil_values() {
+        let input = "<34>1 - - - - - -\n";
+        let message = parse_syslog(input, received_at()).unwrap();
+
+        assert_eq!(
+            message,
+            SyslogMessage {
+                facility: 4,
+                severity: 2,
+                timestamp: None,
+                hostname: None,
+                app_name: None,
+                proc_id: None,
+                msg_id: None,
+                structured_data: vec![],
+                message: "",
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_rfc5424_escaped_params() {
+        let input = r#"<14>1 - - - - - [meta a="x\"y\\z\]" b="\n"] msg"#;
+        let message = parse_syslog(input, received_at()).unwrap();
+
+        assert_eq!(
+            message.structured_data[0].params,
+            vec![("a", r#"x"y\z]"#.into()), ("b", r"\n".into())]
+        );
+        assert_eq!(message.message, "msg");
+    }
+
+    #[test]
+    fn test_parse_rfc5424_invalid_structured_data() {
+        let input = r#"<14>1 - - - - - [meta a="unterminated] msg"#;
+        let message = parse_syslog(input, received_at()).unwrap();
+
+        assert!(message.structured_data.is_empty());
+        assert_eq!(message.message, r#"[meta a="unterminated] msg"#);
+
+        let input = "<14>1 - - - - - [INFO] Server started";
+        let message = parse_syslog(input, received_at()).unwrap();
+
+        assert!(message.structured_data.is_empty());
+        assert_eq!(message.message, "[INFO] Server started");
+
+        let input = "<14>1 - - - - - [example@32473] Server started";
+        let message = parse_syslog(input, received_at()).unwrap();
+
+        assert_eq!(
+            message.structured_data,
+            vec![StructuredDataElement {
+                id: "example@32473",
+                params: vec![],
+            }]
+        );
+        assert_eq!(message.message, "Server started");
+    }
+
+    #[test]
+    fn test_parse_logplex() {
+        // Logplex omits the structured data.
+        let input =
+            "<40>1 2012-11-30T06:45:29+00:00 host app web.3 - State changed from starting to up\n";
+        let message = parse_syslog(input, received_at()).unwrap();
+
+        assert_eq!(message.hostname, Some("host"));
+        assert_eq!(message.app_name, Some("app"));
+        assert_eq!(message.proc_id, Some("web.3"));
+        assert_eq!(message.msg_id, None);
+        assert!(message.structured_data.is_empty());
+        assert_eq!(message.message, "State changed from starting to up");
+    }
+
+    #[test]
+    fn test_parse_rfc3164() {
+        let input =
+            "<34>Oct 11 22:14:15 mymachine su[123]: 'su root' failed for lonvick on /dev/pts/8";
+        let message = parse_syslog(input, received_at()).unwrap();
+
+        assert_eq!(
+            message,
+            SyslogMessage {
+                facility: 4,
+                severity: 2,
+                timestamp: Some(Utc.with_ymd_and_hms(2024, 10, 11, 22, 14, 15).unwrap()),
+                hostname: Some("mymachine"),
+                app_name: Some("su"),
+                proc_id: Some("123"),
+                msg_id: None,
+                structured_data: vec![],
+                message: "'su root' failed for lonvick on /dev/pts/8",
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_rfc3164_without_tag() {
+        let input = "<13>Jan  2 11:00:00 host just a message";
+        let message = parse_syslog(input, received_at()).unwrap();
+
+        assert_eq!(
+            message.timestamp,
+            Some(Utc.with_ymd_and_hms(2025, 1, 2, 11, 0, 0).unwrap())
+        );
+        assert_eq!(message.hostname, Some("host"));
+        assert_eq!(message.app_name, None);
+        assert_eq!(message.message, "just a message");
+    }
+
+    #[test]
+    fn test_parse_rfc3164_without_timestamp() {
+        let input = "<13>some message";
+        let message = parse_syslog(input, received_at()).unwrap();
+
+        assert_eq!(message.timestamp, None);
+        assert_eq!(message.hostname, None);
+        assert_eq!(message.message, "some message");
+    }
+
+    #[test]
+    fn test_parse_invalid_priority() {
+        for input in [
+            "no priority",
+            "<>1 - - - - - -",
+            "<192>msg",
+            "<013>msg",
+            "<1",
+        ] {
+            assert_eq!(
+                parse_syslog(input, received_at()),
+                Err(SyslogError::InvalidPriority),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_split_octet_counted() {
+        let payload = b"13 <14>1 - - - \n12 <14>Hello\xc3\xa4\n\n5 <14>a";
+        let frames: Vec<_> = split_octet_counted(payload).collect();
+
+        assert_eq!(
+            frames,
+            vec![
+                Ok(&b"<14>1 - - - \n"[..]),
+                Ok(&b"<14>Hello\xc3\xa4\n"[..]),
+                Ok(&b"<14>a"[..]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_octet_counted_invalid() {
+        let frames: Vec<_> = split_octet_counted(b"5 <14>a 100 <14>b").collect();
+        assert_eq!(
+            frames,
+            vec![Ok(&b"<14>a"[..]), Err(SyslogError::InvalidFrame)]
+        );
+
+        let frames: Vec<_> = split_octet_counted(b"<14>a").collect();
+        assert_eq!(frames, vec![Err(SyslogError::InvalidFrame)]);
+
+        let payload = format!("{} <14>a", usize::MAX);
+        let frames: Vec<_> = split_octet_counted(payload.as_bytes()).collect();
+        assert_eq!(frames, vec![Err(SyslogError::InvalidFrame)]);
+    }
+
+    #[test]
+    fn test_syslog_to_sentry_log() {
+        let input = r#"<131>1 2025-01-02T11:59:00.5Z web-1 api 4242 request [origin ip="10.0.0.1"] Request failed"#;
+        let message = parse_syslog(input, received_at()).unwrap();
+        let our_log = Annotated::new(syslog_to_sentry_log(message, received_at()));
+
+        insta::assert_json_snapshot!(SerializableAnnotated(&our_log), {
+            ".trace_id" => "[trace-id]",
+        }, @r#"
+        {
+          "timestamp": 1735819140.5,
+          "trace_id": "[trace-id]",
+          "level": "error",
+          "body": "Request failed",
+          "attributes": {
+            "sentry.origin": {
+              "type": "string",
+              "value": "auto.log_drain.syslog"
+            },
+            "syslog.app_name": {
+              "type": "string",
+              "value": "api"
+            },
+            "syslog.facility": {
+              "type": "string",
+              "value": "local0"
+            },
+            "syslog.hostname": {
+              "type": "string",
+              "value": "web-1"
+            },
+            "syslog.msg_id": {
+              "type": "string",
+              "value": "request"
+            },
+            "syslog.proc_id": {
+              "type": "string",
+              "value": "4242"
+            },
+            "syslog.severity": {
+              "type": "string",
+              "value": "err"
+            },
+            "syslog.structured_data.origin.ip": {
+              "type": "string",
+              "value": "10.0.0.1"
+            }
+          },
+          "_meta": {
+            "trace_id": {
+              "": {
+                "rem": [
+                  [
+                    "trace_id.missing",
+                    "s"
+                  ]
+                ]
+              }
+            }
+          }
+        }
+        "#);
+    }
+
+    #[test]
+    fn test_syslog_to_sentry_log_missing_timestamp() {
+        let message = parse_syslog("<15>debug output", received_at()).unwrap();
+        let our_log = syslog_to_sentry_log(message, received_at());
+
+        assert_eq!(our_log.timestamp.value(), Some(&Timestamp(received_at())));
+        assert!(!our_log.timestamp.meta().is_empty());
+        assert_eq!(our_log.level.value(), Some(&OurLogLevel::Debug));
+    }
+}
@@ -1,7 +1,7 @@
 use criterion::measurement::WallTime;
 use criterion::{BenchmarkGroup, Criterion, criterion_group, criterion_main};
 
-use relay_pattern::Pattern;
+use relay_pattern::{Pattern, Patterns};
 
 fn bench(group: &mut BenchmarkGroup<'_, WallTime>, haystack: &str, needle: &str) {
     group.bench_function("case_sensitive", |b| {
@@ -84,6 +84,45 @@ fn complex_match(c: &mut Criterion) {
     group.finish();
 }
 
+fn many_patterns(c: &mut Criterion) {
+    let mut group = c.benchmark_group("many_patterns");
+
+    const HAYSTACK: &str = "foobarwithacrazylongprefixandanditactuallymatches";
+
+    let needles: Vec<_> = (0..250)
+        .flat_map(|i| {
+            [
+                format!("release-{i}"),
+                format!("release-{i}*"),
+                format!("*-{i}.min.js"),
+                format!("*exception {i}*"),
+                format!("*[0-9]-{i}?*/{{foo,bar}}"),
+            ]
+        })
+        .chain(["*crazy*prefix*matches".to_owned()])
+        .collect();
+
+    for (name, case_insensitive) in [("case_sensitive", false), ("case_insensitive", true)] {
+        let mut builder = Patterns::builder()
+            .case_insensitive(case_insensitive)
+            .patterns();
+        for needle in &needles {
+            builder.add(needle).unwrap();
+        }
+        let patterns = builder.build();
+
+        group.bench_function(name, |b| b.iter(|| assert!(patterns.is_match(HAYSTACK))));
+        group.bench_function(format!("{name}_first_match"), |b| {
+            b.iter(|| assert_eq!(patterns.first_match(HAYSTACK), Some(needles.len() - 1)))
+        });
+        group.bench_function(format!("{name}_no_match"), |b| {
+            b.iter(|| assert!(!patterns.is_match("nothing to see here")))
+        });
+    }
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     literal_match,
@@ -92,5 +131,6 @@ criterion_group!(
     contains_match,
     wildcard_match,
     complex_match,
+    many_patterns,
 );
 criterion_main!(benches);
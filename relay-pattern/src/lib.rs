@@ -41,6 +41,9 @@ use std::borrow::Borrow;
 use std::fmt;
 use std::num::NonZeroUsize;
 
+use self::multi::{Find, MultiMatcher};
+
+mod multi;
 mod typed;
 mod wildmatch;
 
@@ -178,10 +181,12 @@ impl<'de> serde::Deserialize<'de> for Pattern {
 }
 
 /// A collection of [`Pattern`]s sharing the same configuration.
+///
+/// All patterns are compiled into a single matcher, which checks all patterns together instead of
+/// evaluating them one after another.
 #[derive(Debug, Clone)]
 pub struct Patterns {
-    strategies: Vec<MatchStrategy>,
-    options: Options,
+    matcher: MultiMatcher,
 }
 
 impl Patterns {
@@ -196,8 +201,7 @@ impl Patterns {
     /// ```
     pub fn empty() -> Self {
         Self {
-            strategies: Vec::new(),
-            options: Options::default(),
+            matcher: MultiMatcher::default(),
         }
     }
 
@@ -210,16 +214,39 @@ impl Patterns {
 
     /// Returns `true` if any of the contained patterns matches the passed string.
     pub fn is_match(&self, haystack: &str) -> bool {
-        self.strategies
-            .iter()
-            .any(|s| s.is_match(haystack, self.options))
+        self.matcher.find(haystack, Find::Any).is_some()
+    }
+
+    /// Returns the index of the first pattern matching the passed string.
+    ///
+    /// Patterns are indexed in the order they were added to the builder.
+    ///
+    /// ```
+    /// # use relay_pattern::Patterns;
+    /// let mut builder = Patterns::builder().patterns();
+    /// builder.add("*bar").unwrap();
+    /// builder.add("foo*").unwrap();
+    /// builder.add("*").unwrap();
+    /// let patterns = builder.build();
+    ///
+    /// assert_eq!(patterns.first_match("foobar"), Some(0));
+    /// assert_eq!(patterns.first_match("foobaz"), Some(1));
+    /// assert_eq!(patterns.first_match("baz"), Some(2));
+    /// ```
+    pub fn first_match(&self, haystack: &str) -> Option<usize> {
+        self.matcher.find(haystack, Find::First)
+    }
+
+    /// Returns the amount of contained patterns.
+    pub fn len(&self) -> usize {
+        self.matcher.len()
     }
 
     /// Returns `true` if this instance contains no patterns.
     ///
     /// An empty [`Patterns`] never matches any input.
     pub fn is_empty(&self) -> bool {
-        self.strategies.is_empty()
+        self.len() == 0
     }
 }
 
@@ -349,8 +376,7 @@ impl PatternsBuilderConfigured {
     /// Builds a [`Patterns`] from the contained patterns.
     pub fn build(self) -> Patterns {
         Patterns {
-            strategies: self.strategies,
-            options: self.options,
+            matcher: MultiMatcher::new(self.strategies, self.options),
         }
     }
 
@@ -359,8 +385,7 @@ impl PatternsBuilderConfigured {
     /// The builder can still be used afterwards, it keeps the configuration.
     pub fn take(&mut self) -> Patterns {
         Patterns {
-            strategies: std::mem::take(&mut self.strategies),
-            options: self.options,
+            matcher: MultiMatcher::new(std::mem::take(&mut self.strategies), self.options),
         }
     }
 }
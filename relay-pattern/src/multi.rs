@@ -0,0 +1,586 @@
+//! Simultaneous matching of many patterns.
+//!
+//! Instead of evaluating every pattern of a [`Patterns`](crate::Patterns) instance one after
+//! another, the patterns are grouped by their [`MatchStrategy`] and compiled into shared
+//! structures:
+//!
+//! - Literal and prefix patterns are stored in a byte trie, which is walked once from the start
+//!   of the haystack.
+//! - Suffix patterns are stored reversed in a second byte trie, which is walked once from the end
+//!   of the haystack.
+//! - Contains patterns are compiled into a single Aho-Corasick automaton.
+//! - Complex patterns are still evaluated with [`wildmatch`], but a pattern is only evaluated if
+//!   the longest literal it requires is found in the haystack. All required literals are searched
+//!   at once with another Aho-Corasick automaton.
+
+use std::borrow::Cow;
+
+use aho_corasick::AhoCorasick;
+
+use crate::{MatchStrategy, Options, Token, Tokens, wildmatch};
+
+/// How many matches to look for in [`MultiMatcher::find`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Find {
+    /// Returns the index of any matching pattern.
+    Any,
+    /// Returns the index of the first matching pattern, in the order the patterns were added.
+    First,
+}
+
+/// A compiled set of [`MatchStrategy`]s, which are matched together.
+#[derive(Clone, Debug, Default)]
+pub struct MultiMatcher {
+    options: Options,
+    /// The number of contained patterns.
+    len: usize,
+    /// Index of the first pattern which always matches.
+    always: Option<usize>,
+    /// Literal and prefix patterns.
+    prefixes: ByteTrie,
+    /// Suffix patterns, the literals are inserted in reverse.
+    suffixes: ByteTrie,
+    /// Contains patterns.
+    contains: Option<LiteralSet>,
+    /// Patterns which need to be matched with [`wildmatch`], ordered by their index.
+    wildmatch: Vec<Wildmatch>,
+    /// Required literals of [`Self::wildmatch`] patterns.
+    ///
+    /// The set's indices refer to positions in [`Self::wildmatch`].
+    prefilter: Option<LiteralSet>,
+}
+
+impl MultiMatcher {
+    /// Compiles strategies into a matcher.
+    ///
+    /// Indices returned by [`Self::find`] are positions in `strategies`.
+    pub fn new(strategies: Vec<MatchStrategy>, options: Options) -> Self {
+        let mut matcher = Self {
+            options,
+            len: strategies.len(),
+            ..Default::default()
+        };
+
+        let mut contains = Vec::new();
+        let mut required = Vec::new();
+
+        for (index, strategy) in strategies.into_iter().enumerate() {
+            match strategy {
+                MatchStrategy::Literal(literal) => {
+                    matcher
+                        .prefixes
+                        .insert_exact(literal.as_case_converted_bytes(), index);
+                }
+                MatchStrategy::Prefix(prefix) => {
+                    matcher
+                        .prefixes
+                        .insert_prefix(prefix.as_case_converted_bytes().iter().copied(), index);
+                }
+                MatchStrategy::Suffix(suffix) => {
+                    let reversed = suffix.as_case_converted_bytes().iter().rev().copied();
+                    matcher.suffixes.insert_prefix(reversed, index);
+                }
+                MatchStrategy::Contains(literal) => contains.push((literal.0, index)),
+                MatchStrategy::Static(true) => {
+                    matcher.always.get_or_insert(index);
+                }
+                MatchStrategy::Static(false) => {}
+                MatchStrategy::Wildmatch(tokens) => {
+                    let literal = required_literal(&tokens, options).map(str::to_owned);
+                    let filtered = literal.is_some();
+                    if let Some(literal) = literal {
+                        required.push((literal, matcher.wildmatch.len()));
+                    }
+
+                    matcher.wildmatch.push(Wildmatch {
+                        index,
+                        tokens,
+                        filtered,
+                    });
+                }
+            }
+        }
+
+        matcher.contains = LiteralSet::new(contains, options);
+        matcher.prefilter = LiteralSet::new(required, options);
+        matcher
+    }
+
+    /// Returns the number of contained patterns.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the index of a pattern matching `haystack`.
+    ///
+    /// Depending on `mode`, this returns either any or the first matching pattern.
+    pub fn find(&self, haystack: &str, mode: Find) -> Option<usize> {
+        let mut best = self.always;
+
+        macro_rules! found {
+            ($index:expr) => {
+                if let Some(index) = $index {
+                    if mode == Find::Any {
+                        return Some(index);
+                    }
+                    best = Some(best.map_or(index, |best| best.min(index)));
+                }
+            };
+        }
+
+        found!(best);
+
+        let haystacks = Haystacks::new(haystack, self.options);
+        let case_insensitive = self.options.case_insensitive;
+        let bytes = || {
+            haystacks
+                .chars
+                .bytes()
+                .map(move |b| match case_insensitive {
+                    true => b.to_ascii_lowercase(),
+                    false => b,
+                })
+        };
+
+        if !self.prefixes.is_empty() {
+            found!(self.prefixes.find(bytes()));
+        }
+        if !self.suffixes.is_empty() {
+            found!(self.suffixes.find(bytes().rev()));
+        }
+        if let Some(contains) = &self.contains {
+            found!(contains.find(haystacks.str(), mode));
+        }
+
+        let candidates = self
+            .wildmatch
+            .iter()
+            .enumerate()
+            .take_while(|(_, w)| best.is_none_or(|best| w.index < best));
+
+        // Only search for required literals if there is a candidate which needs them.
+        let mut required = None;
+
+        for (position, w) in candidates {
+            if w.filtered {
+                let required = required.get_or_insert_with(|| match &self.prefilter {
+                    Some(prefilter) => prefilter.find_all(haystacks.str()),
+                    None => Vec::new(),
+                });
+
+                if !required.get(position).copied().unwrap_or(false) {
+                    continue;
+                }
+            }
+
+            if wildmatch::is_match(haystack, &w.tokens, self.options) {
+                return Some(w.index);
+            }
+        }
+
+        best
+    }
+}
+
+/// A pattern which is matched with [`wildmatch`].
+#[derive(Clone, Debug)]
+struct Wildmatch {
+    /// The index of the pattern.
+    index: usize,
+    tokens: Tokens,
+    /// Whether the pattern has a required literal in the prefilter.
+    filtered: bool,
+}
+
+/// Returns the longest literal which must be contained in every match of `tokens`.
+///
+/// Only top level literals are considered, literals in alternates and optional tokens are not
+/// required.
+fn required_literal(tokens: &Tokens, options: Options) -> Option<&str> {
+    tokens
+        .as_slice()
+        .iter()
+        .filter_map(|token| match token {
+            Token::Literal(literal) => Some(literal.as_case_converted_str()),
+            _ => None,
+        })
+        // Lowercasing the haystack maps a sigma depending on its surrounding characters, while
+        // `wildmatch` lowercases only the remaining haystack. The lowercase sigma variants may
+        // therefore not be found in the lowercase haystack, even though the pattern matches.
+        .filter(|literal| !options.case_insensitive || !literal.contains(['σ', 'ς']))
+        .max_by_key(|literal| literal.len())
+}
+
+/// Case converted variants of a haystack.
+///
+/// ASCII characters are not converted, matching is ASCII case insensitive instead. This avoids
+/// allocating a converted copy of ASCII haystacks.
+struct Haystacks<'a> {
+    /// The haystack with every non-ASCII character converted individually.
+    ///
+    /// Matches the case conversion of literal, prefix and suffix strategies.
+    chars: Cow<'a, str>,
+    /// The haystack converted with [`str::to_lowercase`], if it differs from [`Self::chars`].
+    sigma: Option<String>,
+}
+
+impl<'a> Haystacks<'a> {
+    fn new(haystack: &'a str, options: Options) -> Self {
+        if !options.case_insensitive || haystack.is_ascii() {
+            return Self {
+                chars: Cow::Borrowed(haystack),
+                sigma: None,
+            };
+        }
+
+        Self {
+            chars: haystack.chars().flat_map(char::to_lowercase).collect(),
+            // The only difference between both conversions is the final sigma.
+            sigma: haystack.contains('Σ').then(|| haystack.to_lowercase()),
+        }
+    }
+
+    /// Returns the haystack converted like the entire string.
+    ///
+    /// Matches the case conversion of contains strategies and [`wildmatch`].
+    fn str(&self) -> &str {
+        self.sigma.as_deref().unwrap_or(&self.chars)
+    }
+}
+
+/// A set of literals searched with a single automaton.
+#[derive(Clone, Debug)]
+struct LiteralSet {
+    searcher: Searcher,
+    /// Maps the automaton's pattern IDs to indices.
+    indices: Vec<usize>,
+}
+
+/// Searches the literals of a [`LiteralSet`].
+#[derive(Clone, Debug)]
+enum Searcher {
+    /// All literals are searched at once.
+    Automaton(AhoCorasick),
+    /// Literals are searched one after another.
+    ///
+    /// Used if the automaton cannot be built, for example, if it exceeds its size limits.
+    Literals {
+        literals: Vec<String>,
+        case_insensitive: bool,
+    },
+}
+
+impl LiteralSet {
+    /// Creates a new set from literals and their indices.
+    ///
+    /// Literals must be ordered by their index. Returns `None` if there are no literals.
+    fn new(literals: Vec<(String, usize)>, options: Options) -> Option<Self> {
+        if literals.is_empty() {
+            return None;
+        }
+
+        let (literals, indices): (Vec<_>, Vec<_>) = literals.into_iter().unzip();
+
+        let searcher = match AhoCorasick::builder()
+            .ascii_case_insensitive(options.case_insensitive)
+            .build(&literals)
+        {
+            Ok(automaton) => Searcher::Automaton(automaton),
+            Err(_) => Searcher::Literals {
+                literals,
+                case_insensitive: options.case_insensitive,
+            },
+        };
+
+        Some(Self { searcher, indices })
+    }
+
+    /// Returns the index of a literal contained in `haystack`.
+    fn find(&self, haystack: &str, mode: Find) -> Option<usize> {
+        let id = match (&self.searcher, mode) {
+            (Searcher::Automaton(automaton), Find::Any) => {
+                automaton.find(haystack)?.pattern().as_usize()
+            }
+            (Searcher::Automaton(automaton), Find::First) => automaton
+                .find_overlapping_iter(haystack)
+                .map(|m| m.pattern().as_usize())
+                .min()?,
+            // Literals are ordered by their index, the first match is also the first literal.
+            (
+                Searcher::Literals {
+                    literals,
+                    case_insensitive,
+                },
+                _,
+            ) => literals
+                .iter()
+                .position(|literal| contains(haystack, literal, *case_insensitive))?,
+        };
+
+        Some(self.indices[id])
+    }
+
+    /// Returns a lookup table which contains `true` for every index contained in `haystack`.
+    fn find_all(&self, haystack: &str) -> Vec<bool> {
+        let len = self.indices.last().map_or(0, |last| last + 1);
+        let mut found = vec![false; len];
+        match &self.searcher {
+            Searcher::Automaton(automaton) => {
+                for m in automaton.find_overlapping_iter(haystack) {
+                    found[self.indices[m.pattern()]] = true;
+                }
+            }
+            Searcher::Literals {
+                literals,
+                case_insensitive,
+            } => {
+                for (literal, &index) in literals.iter().zip(&self.indices) {
+                    found[index] = contains(haystack, literal, *case_insensitive);
+                }
+            }
+        }
+        found
+    }
+}
+
+/// Returns `true` if `haystack` contains `needle`, optionally ignoring ASCII case.
+fn contains(haystack: &str, needle: &str, case_insensitive: bool) -> bool {
+    if !case_insensitive {
+        return haystack.contains(needle);
+    }
+
+    needle.is_empty()
+        || haystack
+            .as_bytes()
+            .windows(needle.len())
+            .any(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+/// A trie of byte strings, which finds all strings which are a prefix of a haystack.
+#[derive(Clone, Debug, Default)]
+struct ByteTrie {
+    nodes: Vec<TrieNode>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct TrieNode {
+    /// Child nodes, sorted by their byte.
+    children: Vec<(u8, usize)>,
+    /// Index of the first string which ends in this node and must match the entire haystack.
+    exact: Option<usize>,
+    /// Index of the first string which ends in this node and only needs to match a prefix.
+    prefix: Option<usize>,
+}
+
+impl TrieNode {
+    fn child(&self, byte: u8) -> Option<usize> {
+        self.children
+            .binary_search_by_key(&byte, |(b, _)| *b)
+            .ok()
+            .map(|i| self.children[i].1)
+    }
+}
+
+impl ByteTrie {
+    fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Inserts a string which must match the entire haystack.
+    fn insert_exact(&mut self, bytes: &[u8], index: usize) {
+        let node = self.insert(bytes.iter().copied());
+        self.nodes[node].exact.get_or_insert(index);
+    }
+
+    /// Inserts a string which needs to match a prefix of the haystack.
+    fn insert_prefix(&mut self, bytes: impl IntoIterator<Item = u8>, index: usize) {
+        let node = self.insert(bytes);
+        self.nodes[node].prefix.get_or_insert(index);
+    }
+
+    fn insert(&mut self, bytes: impl IntoIterator<Item = u8>) -> usize {
+        if self.nodes.is_empty() {
+            self.nodes.push(TrieNode::default());
+        }
+
+        let mut node = 0;
+        for byte in bytes {
+            node = match self.nodes[node]
+                .children
+                .binary_search_by_key(&byte, |(b, _)| *b)
+            {
+                Ok(i) => self.nodes[node].children[i].1,
+                Err(i) => {
+                    let child = self.nodes.len();
+                    self.nodes.push(TrieNode::default());
+                    self.nodes[node].children.insert(i, (byte, child));
+                    child
+                }
+            };
+        }
+
+        node
+    }
+
+    /// Returns the smallest index of all strings matching `bytes`.
+    fn find(&self, bytes: impl Iterator<Item = u8>) -> Option<usize> {
+        let mut node = self.nodes.first()?;
+        let mut best = node.prefix;
+
+        for byte in bytes {
+            match node.child(byte) {
+                Some(child) => node = &self.nodes[child],
+                None => return best,
+            }
+            best = min(best, node.prefix);
+        }
+
+        min(best, node.exact)
+    }
+}
+
+fn min(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    a.into_iter().chain(b).min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Pattern, Patterns};
+
+    fn find(patterns: &[&str], haystack: &str, case_insensitive: bool) -> Option<usize> {
+        let mut builder = Patterns::builder()
+            .case_insensitive(case_insensitive)
+            .patterns();
+        for pattern in patterns {
+            builder.add(pattern).unwrap();
+        }
+        builder.build().first_match(haystack)
+    }
+
+    #[test]
+    fn test_trie() {
+        let mut trie = ByteTrie::default();
+        trie.insert_prefix(*b"foo", 3);
+        trie.insert_exact(b"foobar", 1);
+        trie.insert_prefix(*b"foob", 2);
+        trie.insert_exact(b"fo", 0);
+
+        assert_eq!(trie.find(b"foobar".iter().copied()), Some(1));
+        assert_eq!(trie.find(b"foobarbaz".iter().copied()), Some(2));
+        assert_eq!(trie.find(b"fooz".iter().copied()), Some(3));
+        assert_eq!(trie.find(b"fo".iter().copied()), Some(0));
+        assert_eq!(trie.find(b"f".iter().copied()), None);
+        assert_eq!(ByteTrie::default().find(b"f".iter().copied()), None);
+    }
+
+    #[test]
+    fn test_first_match_across_strategies() {
+        let patterns = ["*baz", "foo*", "*o*", "f?o*", "foo", "*"];
+
+        assert_eq!(find(&patterns, "foo", false), Some(1));
+        assert_eq!(find(&patterns, "barbaz", false), Some(0));
+        assert_eq!(find(&patterns, "bob", false), Some(2));
+        assert_eq!(find(&patterns, "fxo", false), Some(2));
+        assert_eq!(find(&patterns, "fxa", false), Some(5));
+        assert_eq!(find(&patterns[..5], "fxa", false), None);
+    }
+
+    #[test]
+    fn test_prefilter() {
+        let patterns = ["a*[0-9]bc*d", "*needle?", "x{y,z}*"];
+
+        assert_eq!(find(&patterns, "a1bcd", false), Some(0));
+        assert_eq!(find(&patterns, "a1bxd", false), None);
+        assert_eq!(find(&patterns, "haystack_needle!", false), Some(1));
+        assert_eq!(find(&patterns, "haystack_needle", false), None);
+        assert_eq!(find(&patterns, "xzz", false), Some(2));
+        assert_eq!(find(&patterns, "HAYSTACK_NEEDLE!", true), Some(1));
+        assert_eq!(find(&patterns, "HAYSTACK_NEEDLE!", false), None);
+    }
+
+    #[test]
+    fn test_same_as_individual_patterns() {
+        let patterns = [
+            "foo",
+            "foo*",
+            "*foo",
+            "*foo*",
+            "f?o",
+            "*",
+            "",
+            "F[o]O*",
+            "{foo,bar}*baz",
+            "*ba?",
+            "fOo",
+            "*Σ",
+            "*ΣΑ*",
+            "[!f]*",
+            "foo{,bar}",
+        ];
+        let haystacks = [
+            "", "foo", "FOO", "foobar", "barfoo", "xfoox", "foobaz", "barbaz", "ΑΣ", "ΣΑΣ", "fo",
+        ];
+
+        for case_insensitive in [false, true] {
+            for haystack in haystacks {
+                let expected = patterns.iter().position(|pattern| {
+                    Pattern::builder(pattern)
+                        .case_insensitive(case_insensitive)
+                        .build()
+                        .unwrap()
+                        .is_match(haystack)
+                });
+
+                assert_eq!(
+                    find(&patterns, haystack, case_insensitive),
+                    expected,
+                    "{haystack} {case_insensitive}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_literal_fallback() {
+        let options = Options {
+            case_insensitive: true,
+        };
+        let literals = vec![("bar".to_owned(), 1), ("foo".to_owned(), 3)];
+
+        let mut set = LiteralSet::new(literals, options).unwrap();
+        let Searcher::Automaton(_) = set.searcher else {
+            panic!("expected automaton");
+        };
+
+        set.searcher = Searcher::Literals {
+            literals: vec!["bar".to_owned(), "foo".to_owned()],
+            case_insensitive: true,
+        };
+
+        assert_eq!(set.find("xFOOxBAR", Find::First), Some(1));
+        assert_eq!(set.find("xFOOx", Find::Any), Some(3));
+        assert_eq!(set.find("xbaz", Find::Any), None);
+        assert_eq!(set.find_all("FOO"), vec![false, false, false, true]);
+    }
+
+    #[test]
+    fn test_sigma() {
+        // A final sigma is lowercased to `ς` in the entire haystack, but to `σ` in the
+        // remaining haystack during wildmatch.
+        for (pattern, haystack) in [("?σ*", "ΑΣ"), ("*ς", "ΑΣ"), ("Α[σ]", "ΑΣ"), ("*σ?", "ΣΑ")]
+        {
+            let expected = Pattern::builder(pattern)
+                .case_insensitive(true)
+                .build()
+                .unwrap()
+                .is_match(haystack);
+
+            assert_eq!(
+                find(&[pattern], haystack, true).is_some(),
+                expected,
+                "{pattern} {haystack}"
+            );
+        }
+    }
+}
@@ -11,6 +11,7 @@ use crate::{PiiConfig, PiiConfigError, Redaction, RuleSpec, RuleType, SelectorSp
 #[derive(Debug, Clone)]
 pub struct CompiledPiiConfig {
     pub(super) applications: Vec<(SelectorSpec, BTreeSet<RuleRef>)>,
+    pub(super) json_fields: Option<SelectorSpec>,
 }
 
 impl CompiledPiiConfig {
@@ -26,7 +27,10 @@ impl CompiledPiiConfig {
             applications.push((selector.clone(), rule_set));
         }
 
-        CompiledPiiConfig { applications }
+        CompiledPiiConfig {
+            applications,
+            json_fields: config.json_fields.clone(),
+        }
     }
 
     /// Force compilation of all regex patterns in this config.
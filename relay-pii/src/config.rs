@@ -239,6 +239,23 @@ pub struct PiiConfig {
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub applications: BTreeMap<SelectorSpec, Vec<String>>,
 
+    /// Selector for string values containing JSON documents.
+    ///
+    /// Matching strings are parsed and all applications are applied to the values of the inner
+    /// document, which are addressed by their path below the string. Rules selected for the string
+    /// itself apply to all strings within the document. Strings which are not JSON objects or
+    /// arrays, or which exceed size and depth limits, are scrubbed as plain text.
+    ///
+    /// Scrubbed documents are serialized compactly with object keys in sorted order, so the string
+    /// does not retain the formatting and key order of the original document. Documents which do
+    /// not require scrubbing are left unchanged.
+    #[serde(
+        default,
+        rename = "jsonFields",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub json_fields: Option<SelectorSpec>,
+
     /// PII config derived from datascrubbing settings.
     ///
     /// Cached because the conversion process is expensive.
@@ -254,10 +271,14 @@ impl PartialEq for PiiConfig {
             rules,
             vars,
             applications,
+            json_fields,
             compiled: _compiled,
         } = &self;
 
-        rules == &other.rules && vars == &other.vars && applications == &other.applications
+        rules == &other.rules
+            && vars == &other.vars
+            && applications == &other.applications
+            && json_fields == &other.json_fields
     }
 }
 
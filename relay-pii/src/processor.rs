@@ -19,6 +19,17 @@ use crate::redactions::Redaction;
 use crate::regexes::{self, ANYTHING_REGEX, PatternType, ReplaceBehavior};
 use crate::utils;
 
+/// Maximum size in bytes of a string which is parsed as JSON document for scrubbing.
+const MAX_JSON_FIELD_SIZE: usize = 256 * 1024;
+
+/// Maximum nesting depth of a JSON document in a string which is scrubbed as JSON.
+const MAX_JSON_FIELD_DEPTH: usize = 32;
+
+/// Attributes of values within JSON documents in strings.
+///
+/// All values in the document may contain PII and can be matched by wildcard selectors.
+const JSON_FIELD_ATTRS: FieldAttrs = FieldAttrs::new().pii(Pii::True);
+
 /// Controls how scrubbing rules are applied to attributes.
 #[derive(Debug, Clone, Copy)]
 pub enum AttributeMode {
@@ -35,6 +46,12 @@ pub struct PiiProcessor<'a> {
     /// Controls how rules are applied to attributes.
     attribute_mode: AttributeMode,
     compiled_config: &'a CompiledPiiConfig,
+    /// Rules selected for the string containing the JSON document that is being scrubbed.
+    ///
+    /// These apply to all strings in the document in addition to the rules selected for them.
+    json_field_rules: Vec<&'a RuleRef>,
+    /// Whether a JSON document in a string is being scrubbed.
+    in_json_field: bool,
 }
 
 impl<'a> PiiProcessor<'a> {
@@ -45,6 +62,8 @@ impl<'a> PiiProcessor<'a> {
         PiiProcessor {
             compiled_config,
             attribute_mode: AttributeMode::Object,
+            json_field_rules: Vec::new(),
+            in_json_field: false,
         }
     }
 
@@ -75,8 +94,95 @@ impl<'a> PiiProcessor<'a> {
             }
         }
 
+        if let Some(value) = value {
+            for rule in &self.json_field_rules {
+                // Skip rules which have already been applied through their own selector.
+                let applied = self
+                    .compiled_config
+                    .applications
+                    .iter()
+                    .any(|(selector, rules)| {
+                        rules.contains(*rule) && selector.matches_path(&state.path())
+                    });
+
+                if !applied {
+                    apply_rule_to_value(meta, rule, state.path().key(), Some(&mut *value))?;
+                }
+            }
+        }
+
         Ok(())
     }
+
+    /// Scrubs a string containing a JSON document if it matches the configured JSON fields.
+    ///
+    /// All rules are applied to the values within the document, along with the rules selected for
+    /// the string itself, which apply to every string in the document. The document is serialized
+    /// back into the string if anything was scrubbed. Returns `None` if the string is not selected
+    /// or cannot be scrubbed as JSON, in which case it must be scrubbed as text.
+    fn process_json_string(
+        &mut self,
+        value: &mut String,
+        meta: &mut Meta,
+        state: &ProcessingState<'_>,
+    ) -> Option<ProcessingResult> {
+        let config = self.compiled_config;
+        let selector = config.json_fields.as_ref()?;
+        if state.pii() == Pii::False
+            || value.len() > MAX_JSON_FIELD_SIZE
+            || !value.trim_start().starts_with(['{', '['])
+            || !selector.matches_path(&state.path())
+        {
+            return None;
+        }
+
+        let document = serde_json::from_str::<Value>(value).ok()?;
+        if json_depth(&document) > MAX_JSON_FIELD_DEPTH {
+            return None;
+        }
+
+        // The document replaces the string in the path, the state does not add a path item and
+        // keeps the string's value type, so that no rules are applied to the document itself.
+        let json_state = state.enter_nothing(Some(Cow::Borrowed(&JSON_FIELD_ATTRS)));
+        let field_rules = config
+            .applications
+            .iter()
+            .filter(|(selector, _)| selector.matches_path(&state.path()))
+            .flat_map(|(_, rules)| rules)
+            .collect();
+        let outer_rules = mem::replace(&mut self.json_field_rules, field_rules);
+
+        let mut document = Annotated::new(document);
+        let in_json_field = mem::replace(&mut self.in_json_field, true);
+        let result = process_value(&mut document, self, &json_state);
+        self.in_json_field = in_json_field;
+        self.json_field_rules = outer_rules;
+        if let Err(err) = result {
+            return Some(Err(err));
+        }
+
+        let mut remarks = Vec::new();
+        collect_json_remarks(&document, &mut remarks);
+        if remarks.is_empty() {
+            return Some(Ok(()));
+        }
+
+        let original_length = value.chars().count();
+        match document.payload_to_json() {
+            Ok(json) => *value = json,
+            Err(_) => return None,
+        }
+
+        if meta.original_length().is_none() {
+            meta.set_original_length(Some(original_length));
+        }
+
+        for remark in remarks {
+            meta.add_remark(remark);
+        }
+
+        Some(Ok(()))
+    }
 }
 
 impl Processor for PiiProcessor<'_> {
@@ -123,6 +229,34 @@ impl Processor for PiiProcessor<'_> {
         self.apply_all_rules(meta, state, None)
     }
 
+    fn process_value(
+        &mut self,
+        value: &mut Value,
+        _meta: &mut Meta,
+        state: &ProcessingState<'_>,
+    ) -> ProcessingResult {
+        if !self.in_json_field {
+            return Ok(());
+        }
+
+        // Numbers in JSON documents can hold identifiers, such as credit card numbers. If a rule
+        // matches the number as text, it is converted into a string and scrubbed as such.
+        let number = match value {
+            Value::I64(number) => number.to_string(),
+            Value::U64(number) => number.to_string(),
+            Value::F64(number) => number.to_string(),
+            _ => return Ok(()),
+        };
+
+        let mut scrubbed = number.clone();
+        let result = self.apply_all_rules(&mut Meta::default(), state, Some(&mut scrubbed));
+        if result.is_err() || scrubbed != number {
+            *value = Value::String(number);
+        }
+
+        Ok(())
+    }
+
     fn process_array<T>(
         &mut self,
         array: &mut Array<T>,
@@ -175,6 +309,10 @@ impl Processor for PiiProcessor<'_> {
             return Ok(());
         }
 
+        if let Some(result) = self.process_json_string(value, meta, state) {
+            return result;
+        }
+
         // same as before_process. duplicated here because we can only check for "true",
         // "false" etc in process_string.
         self.apply_all_rules(meta, state, Some(value))
@@ -434,6 +572,47 @@ fn scrub_graphql_data(keys: &BTreeSet<&str>, data: &mut BTreeMap<String, Annotat
     }
 }
 
+/// Returns the nesting depth of a JSON value.
+fn json_depth(value: &Value) -> usize {
+    let children = match value {
+        Value::Array(items) => items
+            .iter()
+            .filter_map(Annotated::value)
+            .map(json_depth)
+            .max(),
+        Value::Object(items) => items
+            .values()
+            .filter_map(Annotated::value)
+            .map(json_depth)
+            .max(),
+        _ => return 0,
+    };
+
+    children.unwrap_or(0) + 1
+}
+
+/// Collects the remarks of all values in a scrubbed JSON document.
+///
+/// The ranges of the remarks refer to the scrubbed values within the document and are dropped.
+fn collect_json_remarks(value: &Annotated<Value>, remarks: &mut Vec<Remark>) {
+    for remark in value.meta().iter_remarks() {
+        let remark = Remark::new(remark.ty(), remark.rule_id());
+        if !remarks.contains(&remark) {
+            remarks.push(remark);
+        }
+    }
+
+    match value.value() {
+        Some(Value::Array(items)) => items
+            .iter()
+            .for_each(|item| collect_json_remarks(item, remarks)),
+        Some(Value::Object(items)) => items
+            .values()
+            .for_each(|item| collect_json_remarks(item, remarks)),
+        _ => {}
+    }
+}
+
 fn apply_rule_to_value(
     meta: &mut Meta,
     rule: &RuleRef,
@@ -2265,4 +2444,160 @@ mod tests {
         )
         "###);
     }
+
+    fn event_with_extra(value: &str) -> Annotated<Event> {
+        Annotated::new(Event {
+            extra: {
+                let mut map = Object::new();
+                map.insert(
+                    "payload".to_owned(),
+                    Annotated::new(ExtraValue(Value::String(value.to_owned()))),
+                );
+                Annotated::new(map)
+            },
+            ..Default::default()
+        })
+    }
+
+    fn json_fields_config() -> PiiConfig {
+        serde_json::from_value(json!({
+            "applications": {
+                "$string": ["@email:replace"],
+                "**": ["@password:filter"]
+            },
+            "jsonFields": "extra.payload"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_scrub_json_field() {
+        let mut event = event_with_extra(
+            r#"{"user": {"email": "jane@example.com", "password": "hunter2"}, "count": 2}"#,
+        );
+
+        let config = json_fields_config();
+        let mut processor = PiiProcessor::new(config.compiled());
+        process_value(&mut event, &mut processor, ProcessingState::root()).unwrap();
+        assert_annotated_snapshot!(event, @r#"
+        {
+          "extra": {
+            "payload": "{\"count\":2,\"user\":{\"email\":\"[email]\",\"password\":\"[Filtered]\"}}"
+          },
+          "_meta": {
+            "extra": {
+              "payload": {
+                "": {
+                  "rem": [
+                    [
+                      "@email:replace",
+                      "s"
+                    ],
+                    [
+                      "@password:filter",
+                      "s"
+                    ]
+                  ],
+                  "len": 74
+                }
+              }
+            }
+          }
+        }
+        "#);
+    }
+
+    #[test]
+    fn test_scrub_json_field_unchanged() {
+        let payload = r#"{"user": {"name": "jane"}}"#;
+        let mut event = event_with_extra(payload);
+
+        let config = json_fields_config();
+        let mut processor = PiiProcessor::new(config.compiled());
+        process_value(&mut event, &mut processor, ProcessingState::root()).unwrap();
+
+        let extra = get_value!(event.extra!);
+        assert_eq!(
+            extra.get("payload").and_then(|v| v.value()),
+            Some(&ExtraValue(Value::String(payload.to_owned())))
+        );
+    }
+
+    #[test]
+    fn test_scrub_json_field_invalid() {
+        let mut event = event_with_extra(r#"{"email": "jane@example.com""#);
+
+        let config = json_fields_config();
+        let mut processor = PiiProcessor::new(config.compiled());
+        process_value(&mut event, &mut processor, ProcessingState::root()).unwrap();
+        assert_annotated_snapshot!(event, @r#"
+        {
+          "extra": {
+            "payload": "{\"email\": \"[email]\""
+          },
+          "_meta": {
+            "extra": {
+              "payload": {
+                "": {
+                  "rem": [
+                    [
+                      "@email:replace",
+                      "s",
+                      11,
+                      18
+                    ]
+                  ],
+                  "len": 28
+                }
+              }
+            }
+          }
+        }
+        "#);
+    }
+
+    #[test]
+    fn test_scrub_json_field_numbers() {
+        let mut event = event_with_extra(r#"{"card": 4571234567890111, "count": 2, "ratio": 0.5}"#);
+
+        let config = serde_json::from_value::<PiiConfig>(json!({
+            "applications": {
+                "$string": ["@creditcard:replace"]
+            },
+            "jsonFields": "extra.payload"
+        }))
+        .unwrap();
+        let mut processor = PiiProcessor::new(config.compiled());
+        process_value(&mut event, &mut processor, ProcessingState::root()).unwrap();
+
+        // Only the matching number is turned into a string, other numbers are kept as they are.
+        let extra = get_value!(event.extra!);
+        assert_eq!(
+            extra.get("payload").and_then(|v| v.value()),
+            Some(&ExtraValue(Value::String(
+                r#"{"card":"[creditcard]","count":2,"ratio":0.5}"#.to_owned()
+            )))
+        );
+    }
+
+    #[test]
+    fn test_scrub_json_field_too_deep() {
+        let payload = format!(
+            r#"{}{{"password": "hunter2"}}{}"#,
+            "[".repeat(MAX_JSON_FIELD_DEPTH),
+            "]".repeat(MAX_JSON_FIELD_DEPTH)
+        );
+        let mut event = event_with_extra(&payload);
+
+        let config = json_fields_config();
+        let mut processor = PiiProcessor::new(config.compiled());
+        process_value(&mut event, &mut processor, ProcessingState::root()).unwrap();
+
+        // The document is scrubbed as text, which filters the entire string.
+        let extra = get_value!(event.extra!);
+        assert_eq!(
+            extra.get("payload").and_then(|v| v.value()),
+            Some(&ExtraValue(Value::String("[Filtered]".to_owned())))
+        );
+    }
 }
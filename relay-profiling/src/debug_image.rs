@@ -59,6 +59,23 @@ pub fn get_proguard_image(uuid: &str) -> Result<DebugImage, UuidError> {
     })
 }
 
+/// Converts a raw ELF build ID into a Sentry [`DebugId`].
+///
+/// The first 16 bytes of the build ID are interpreted as a little-endian UUID.
+/// If the build ID is shorter than 16 bytes it is zero-padded on the right.
+pub fn build_id_to_debug_id(raw: &[u8]) -> Option<DebugId> {
+    if raw.is_empty() {
+        return None;
+    }
+
+    let mut buf = [0u8; 16];
+    let len = raw.len().min(16);
+    buf[..len].copy_from_slice(&raw[..len]);
+
+    let uuid = uuid::Uuid::from_bytes_le(buf);
+    Some(DebugId::from(uuid))
+}
+
 #[cfg(test)]
 mod tests {
     use relay_event_schema::protocol::{
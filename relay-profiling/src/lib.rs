@@ -60,6 +60,7 @@ mod extract_from_transaction;
 mod measurements;
 mod outcomes;
 mod perfetto;
+mod pprof;
 mod profile_chunk;
 mod sample;
 mod transaction_metadata;
@@ -68,6 +69,7 @@ mod utils;
 
 pub use self::android::chunk::Chunk as AndroidProfileChunk;
 pub use self::perfetto::Chunk as PerfettoProfileChunk;
+pub use self::pprof::{Chunk as PprofProfileChunk, Format as PprofFormat};
 pub use self::profile_chunk::{AndroidOrV2ProfileChunk, AnyProfileChunk, ProfileChunk};
 pub use self::sample::v2::ProfileChunk as V2ProfileChunk;
 
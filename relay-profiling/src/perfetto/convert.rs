@@ -11,10 +11,10 @@ use hashbrown::{HashMap, HashSet};
 use prost::Message;
 use prost::encoding::{self, WireType};
 
-use relay_event_schema::protocol::{Addr, DebugId};
+use relay_event_schema::protocol::Addr;
 use relay_protocol::FiniteF64;
 
-use crate::debug_image::{DebugImage, ImageType};
+use crate::debug_image::{DebugImage, ImageType, build_id_to_debug_id};
 use crate::error::ProfileError;
 use crate::sample::v2::{ProfileData, Sample};
 use crate::sample::{Frame, ThreadMetadata};
@@ -625,23 +625,6 @@ fn is_java_mapping(path: &str) -> bool {
     JVM_EXTENSIONS.iter().any(|ext| path.ends_with(ext))
 }
 
-/// Converts a raw ELF build ID into a Sentry [`DebugId`].
-///
-/// The first 16 bytes of the build ID are interpreted as a little-endian UUID.
-/// If the build ID is shorter than 16 bytes it is zero-padded on the right.
-fn build_id_to_debug_id(raw: &[u8]) -> Option<DebugId> {
-    if raw.is_empty() {
-        return None;
-    }
-
-    let mut buf = [0u8; 16];
-    let len = raw.len().min(16);
-    buf[..len].copy_from_slice(&raw[..len]);
-
-    let uuid = uuid::Uuid::from_bytes_le(buf);
-    Some(DebugId::from(uuid))
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
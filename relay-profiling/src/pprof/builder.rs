@@ -0,0 +1,349 @@
+use std::collections::BTreeMap;
+
+use hashbrown::hash_map::Entry;
+use hashbrown::{HashMap, HashSet};
+use relay_event_schema::protocol::Addr;
+use relay_protocol::FiniteF64;
+
+use crate::debug_image::{DebugImage, ImageType, build_id_to_debug_id};
+use crate::error::ProfileError;
+use crate::sample::v2::{ProfileData, Sample};
+use crate::sample::{Frame, ThreadMetadata};
+
+/// Maximum number of samples we emit for a profile before bailing out.
+///
+/// Weighted samples are expanded into one sample per count, this also bounds the memory used by
+/// adversarial weights.
+const MAX_SAMPLES: usize = 100_000;
+
+/// Upper bound on the number of frames in a stack.
+const MAX_STACK_DEPTH: usize = 1000;
+
+/// Upper bound on the number of unique frames in a profile.
+const MAX_UNIQUE_FRAMES: usize = 1_000_000;
+
+/// Interval between samples if the profile does not state its duration.
+const DEFAULT_SAMPLE_INTERVAL_NS: u64 = 10_000_000;
+
+/// The thread samples are attributed to if they do not carry a thread label.
+pub const DEFAULT_THREAD_ID: &str = "0";
+
+/// Label or attribute keys containing the thread identifier of a sample.
+pub const THREAD_ID_KEYS: &[&str] = &["thread.id", "thread_id", "tid"];
+
+/// Label or attribute keys containing the thread name of a sample.
+pub const THREAD_NAME_KEYS: &[&str] = &["thread.name", "thread_name"];
+
+/// Deduplication key for frames.
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct FrameKey {
+    function: Option<String>,
+    abs_path: Option<String>,
+    lineno: Option<u32>,
+    colno: Option<u32>,
+    package: Option<String>,
+    instruction_addr: Option<u64>,
+}
+
+impl From<&Frame> for FrameKey {
+    fn from(frame: &Frame) -> Self {
+        Self {
+            function: frame.function.clone(),
+            abs_path: frame.abs_path.clone(),
+            lineno: frame.lineno,
+            colno: frame.colno,
+            package: frame.package.clone(),
+            instruction_addr: frame.instruction_addr.map(|addr| addr.0),
+        }
+    }
+}
+
+/// A sample aggregated over the duration of a profile.
+pub struct WeightedSample {
+    /// Index of the stack returned by [`ProfileBuilder::add_stack`].
+    pub stack_id: usize,
+    /// Identifier of the thread the sample was captured on.
+    pub thread_id: String,
+    /// The number of samples this sample represents.
+    pub count: u64,
+}
+
+/// Builds Sample v2 [`ProfileData`] from pprof-like profiles.
+///
+/// Deduplicates frames and stacks and collects debug images and thread metadata.
+#[derive(Debug, Default)]
+pub struct ProfileBuilder {
+    frame_index: HashMap<FrameKey, usize>,
+    frames: Vec<Frame>,
+    stack_index: HashMap<Vec<usize>, usize>,
+    stacks: Vec<Vec<usize>>,
+    samples: Vec<Sample>,
+    thread_metadata: BTreeMap<String, ThreadMetadata>,
+    debug_images: Vec<DebugImage>,
+    seen_images: HashSet<(String, u64)>,
+}
+
+impl ProfileBuilder {
+    /// Adds a frame and returns its index.
+    pub fn add_frame(&mut self, frame: Frame) -> Result<usize, ProfileError> {
+        match self.frame_index.entry(FrameKey::from(&frame)) {
+            Entry::Occupied(entry) => Ok(*entry.get()),
+            Entry::Vacant(entry) => {
+                let index = self.frames.len();
+                if index >= MAX_UNIQUE_FRAMES {
+                    return Err(ProfileError::ExceedSizeLimit);
+                }
+                self.frames.push(frame);
+                entry.insert(index);
+                Ok(index)
+            }
+        }
+    }
+
+    /// Adds a leaf-first stack of frame indices and returns its index.
+    pub fn add_stack(&mut self, frames: Vec<usize>) -> Result<usize, ProfileError> {
+        if frames.len() > MAX_STACK_DEPTH {
+            return Err(ProfileError::ExceedSizeLimit);
+        }
+
+        if let Some(&index) = self.stack_index.get(&frames) {
+            return Ok(index);
+        }
+
+        let index = self.stacks.len();
+        self.stack_index.insert(frames.clone(), index);
+        self.stacks.push(frames);
+        Ok(index)
+    }
+
+    /// Adds a native debug image for a mapping, unless it has been added before.
+    pub fn add_debug_image(
+        &mut self,
+        code_file: &str,
+        build_id: &[u8],
+        memory_start: u64,
+        memory_limit: u64,
+    ) {
+        let Some(debug_id) = build_id_to_debug_id(build_id) else {
+            return;
+        };
+
+        if !self
+            .seen_images
+            .insert((code_file.to_owned(), memory_start))
+        {
+            return;
+        }
+
+        self.debug_images.push(DebugImage {
+            code_file: Some(code_file.into()),
+            debug_id: Some(debug_id),
+            image_type: ImageType::Symbolic,
+            image_addr: Some(Addr(memory_start)),
+            image_vmaddr: None,
+            image_size: memory_limit.saturating_sub(memory_start),
+            uuid: None,
+        });
+    }
+
+    /// Sets the name of a thread.
+    pub fn set_thread_name(&mut self, thread_id: &str, name: &str) {
+        if self.thread_metadata.contains_key(thread_id) {
+            return;
+        }
+
+        self.thread_metadata.insert(
+            thread_id.to_owned(),
+            ThreadMetadata {
+                name: Some(name.to_owned()),
+                priority: None,
+            },
+        );
+    }
+
+    /// Adds a sample captured at the given unix timestamp in nanoseconds.
+    pub fn add_sample(
+        &mut self,
+        timestamp_ns: u64,
+        stack_id: usize,
+        thread_id: &str,
+    ) -> Result<(), ProfileError> {
+        if self.samples.len() >= MAX_SAMPLES {
+            return Err(ProfileError::ExceedSizeLimit);
+        }
+
+        // Convert to seconds with millisecond precision.
+        let timestamp = (timestamp_ns / 1_000_000) as f64 / 1_000.0;
+        let Some(timestamp) = FiniteF64::new(timestamp) else {
+            return Ok(());
+        };
+
+        self.samples.push(Sample {
+            timestamp,
+            stack_id,
+            thread_id: thread_id.to_owned(),
+        });
+
+        Ok(())
+    }
+
+    /// Expands aggregated samples into individual samples.
+    ///
+    /// Aggregated profiles do not record when a sample was captured. The expanded samples are
+    /// spread evenly over the duration of the profile, starting at `start_ns`.
+    pub fn add_weighted_samples(
+        &mut self,
+        samples: Vec<WeightedSample>,
+        start_ns: u64,
+        duration_ns: u64,
+    ) -> Result<(), ProfileError> {
+        let total = samples
+            .iter()
+            .fold(0u64, |total, sample| total.saturating_add(sample.count));
+
+        if total == 0 {
+            return Ok(());
+        }
+
+        if total > (MAX_SAMPLES - self.samples.len()) as u64 {
+            return Err(ProfileError::ExceedSizeLimit);
+        }
+
+        if start_ns == 0 {
+            return Err(ProfileError::InvalidSampledProfile);
+        }
+
+        let interval_ns = match duration_ns {
+            0 => DEFAULT_SAMPLE_INTERVAL_NS,
+            duration_ns => duration_ns / total,
+        };
+
+        let mut timestamp_ns = start_ns;
+        for sample in samples {
+            for _ in 0..sample.count {
+                self.add_sample(timestamp_ns, sample.stack_id, &sample.thread_id)?;
+                timestamp_ns = timestamp_ns.saturating_add(interval_ns);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the converted profile and its debug images.
+    pub fn finish(self) -> Result<(ProfileData, Vec<DebugImage>), ProfileError> {
+        if self.samples.is_empty() {
+            return Err(ProfileError::NotEnoughSamples);
+        }
+
+        Ok((
+            ProfileData {
+                samples: self.samples,
+                stacks: self.stacks,
+                frames: self.frames,
+                thread_metadata: self.thread_metadata,
+            },
+            self.debug_images,
+        ))
+    }
+}
+
+/// Returns the number of samples a sample value represents.
+///
+/// Values in the `count` unit are taken as-is. Values in the unit of the sampling period, for
+/// example CPU time in nanoseconds, are divided by the period. All other values count as a single
+/// sample.
+pub fn sample_count(value: i64, unit: &str, period: i64, period_unit: Option<&str>) -> u64 {
+    let Ok(value) = u64::try_from(value) else {
+        return 0;
+    };
+
+    if value == 0 || unit == "count" {
+        return value;
+    }
+
+    match u64::try_from(period) {
+        Ok(period) if period > 0 && period_unit == Some(unit) => value.div_ceil(period),
+        _ => 1,
+    }
+}
+
+/// Decodes a hex encoded build id, as written by the Go runtime and `perf`.
+pub fn decode_build_id(build_id: &str) -> Vec<u8> {
+    data_encoding::HEXLOWER_PERMISSIVE
+        .decode(build_id.as_bytes())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_count() {
+        assert_eq!(sample_count(3, "count", 0, None), 3);
+        assert_eq!(
+            sample_count(30_000_000, "nanoseconds", 10_000_000, Some("nanoseconds")),
+            3
+        );
+        assert_eq!(
+            sample_count(25_000_000, "nanoseconds", 10_000_000, Some("nanoseconds")),
+            3
+        );
+        assert_eq!(
+            sample_count(4096, "bytes", 10_000_000, Some("nanoseconds")),
+            1
+        );
+        assert_eq!(sample_count(0, "count", 0, None), 0);
+        assert_eq!(sample_count(-1, "count", 0, None), 0);
+    }
+
+    #[test]
+    fn test_weighted_samples_are_spread() {
+        let mut builder = ProfileBuilder::default();
+        let frame = builder.add_frame(Frame::default()).unwrap();
+        let stack_id = builder.add_stack(vec![frame]).unwrap();
+
+        builder
+            .add_weighted_samples(
+                vec![WeightedSample {
+                    stack_id,
+                    thread_id: DEFAULT_THREAD_ID.to_owned(),
+                    count: 4,
+                }],
+                1_700_000_000_000_000_000,
+                1_000_000_000,
+            )
+            .unwrap();
+
+        let (profile, _) = builder.finish().unwrap();
+        let timestamps: Vec<_> = profile
+            .samples
+            .iter()
+            .map(|sample| sample.timestamp.to_f64())
+            .collect();
+        assert_eq!(
+            timestamps,
+            [
+                1_700_000_000.0,
+                1_700_000_000.25,
+                1_700_000_000.5,
+                1_700_000_000.75
+            ]
+        );
+    }
+
+    #[test]
+    fn test_weighted_samples_limit() {
+        let mut builder = ProfileBuilder::default();
+        let result = builder.add_weighted_samples(
+            vec![WeightedSample {
+                stack_id: 0,
+                thread_id: DEFAULT_THREAD_ID.to_owned(),
+                count: MAX_SAMPLES as u64 + 1,
+            }],
+            1_700_000_000_000_000_000,
+            0,
+        );
+        assert!(matches!(result, Err(ProfileError::ExceedSizeLimit)));
+    }
+}
@@ -0,0 +1,364 @@
+//! Conversion of pprof profiles into the Sample v2 format.
+
+use hashbrown::HashMap;
+use relay_event_schema::protocol::Addr;
+
+use crate::debug_image::DebugImage;
+use crate::error::ProfileError;
+use crate::pprof::builder::{
+    DEFAULT_THREAD_ID, ProfileBuilder, THREAD_ID_KEYS, THREAD_NAME_KEYS, WeightedSample,
+    decode_build_id, sample_count,
+};
+use crate::pprof::proto;
+use crate::sample::Frame;
+use crate::sample::v2::ProfileData;
+
+/// Converts a decoded pprof profile into Sample v2 profile data and debug images.
+///
+/// Locations are resolved into frames, with one frame per line of inlined functions. Mappings
+/// with a build id are turned into debug images, so that address-only frames can be symbolicated.
+/// Sample values are converted into sample counts, and thread labels into thread metadata.
+pub fn convert(profile: &proto::Profile) -> Result<(ProfileData, Vec<DebugImage>), ProfileError> {
+    if profile.time_nanos <= 0 {
+        return Err(ProfileError::InvalidSampledProfile);
+    }
+
+    let strings = &profile.string_table;
+    let string = |index: i64| -> &str {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| strings.get(index))
+            .map_or("", String::as_str)
+    };
+
+    let value_index = sample_value_index(profile)?;
+    let value_type = profile.sample_type[value_index];
+    let value_unit = string(value_type.unit);
+    let period_unit = profile
+        .period_type
+        .map(|period_type| string(period_type.unit));
+
+    let functions: HashMap<u64, &proto::Function> =
+        profile.function.iter().map(|f| (f.id, f)).collect();
+    let mappings: HashMap<u64, &proto::Mapping> =
+        profile.mapping.iter().map(|m| (m.id, m)).collect();
+
+    let mut builder = ProfileBuilder::default();
+
+    // Resolved frames per location id, innermost frame first.
+    let mut locations: HashMap<u64, Vec<usize>> = HashMap::new();
+    for location in &profile.location {
+        let mapping = mappings.get(&location.mapping_id);
+        let package = mapping
+            .map(|mapping| string(mapping.filename))
+            .filter(|filename| !filename.is_empty());
+
+        let mut frames = Vec::with_capacity(location.line.len().max(1));
+
+        // Lines are ordered from the innermost inlined function to the caller.
+        for line in &location.line {
+            let Some(function) = functions.get(&line.function_id) else {
+                return Err(ProfileError::MalformedStacks);
+            };
+
+            let name = match string(function.name) {
+                "" => string(function.system_name),
+                name => name,
+            };
+
+            let frame = Frame {
+                function: (!name.is_empty()).then(|| name.to_owned()),
+                abs_path: Some(string(function.filename))
+                    .filter(|filename| !filename.is_empty())
+                    .map(str::to_owned),
+                lineno: u32::try_from(line.line).ok().filter(|&line| line > 0),
+                colno: u32::try_from(line.column).ok().filter(|&column| column > 0),
+                package: package.map(str::to_owned),
+                ..Default::default()
+            };
+
+            frames.push(builder.add_frame(frame)?);
+        }
+
+        if location.line.is_empty() {
+            let frame = Frame {
+                instruction_addr: Some(Addr(location.address)),
+                package: package.map(str::to_owned),
+                platform: Some("native".to_owned()),
+                ..Default::default()
+            };
+
+            frames.push(builder.add_frame(frame)?);
+
+            if let Some(mapping) = mapping {
+                let build_id = decode_build_id(string(mapping.build_id));
+                builder.add_debug_image(
+                    string(mapping.filename),
+                    &build_id,
+                    mapping.memory_start,
+                    mapping.memory_limit,
+                );
+            }
+        }
+
+        locations.insert(location.id, frames);
+    }
+
+    let mut samples = Vec::with_capacity(profile.sample.len());
+    for sample in &profile.sample {
+        let Some(&value) = sample.value.get(value_index) else {
+            return Err(ProfileError::MalformedSamples);
+        };
+
+        let count = sample_count(value, value_unit, profile.period, period_unit);
+        if count == 0 {
+            continue;
+        }
+
+        // Location ids are ordered from the leaf to the root.
+        let mut frames = Vec::new();
+        for location_id in &sample.location_id {
+            let Some(location) = locations.get(location_id) else {
+                return Err(ProfileError::MalformedStacks);
+            };
+            frames.extend_from_slice(location);
+        }
+        let stack_id = builder.add_stack(frames)?;
+
+        let mut thread_id = None;
+        let mut thread_name = None;
+        for label in &sample.label {
+            let key = string(label.key);
+            if THREAD_ID_KEYS.contains(&key) {
+                thread_id = match string(label.str) {
+                    "" => Some(label.num.to_string()),
+                    id => Some(id.to_owned()),
+                };
+            } else if THREAD_NAME_KEYS.contains(&key) {
+                thread_name = Some(string(label.str)).filter(|name| !name.is_empty());
+            }
+        }
+
+        let thread_id = thread_id.unwrap_or_else(|| DEFAULT_THREAD_ID.to_owned());
+        if let Some(thread_name) = thread_name {
+            builder.set_thread_name(&thread_id, thread_name);
+        }
+
+        samples.push(WeightedSample {
+            stack_id,
+            thread_id,
+            count,
+        });
+    }
+
+    builder.add_weighted_samples(
+        samples,
+        profile.time_nanos as u64,
+        profile.duration_nanos.max(0) as u64,
+    )?;
+
+    builder.finish()
+}
+
+/// Returns the index of the sample value to convert.
+///
+/// This is the default sample type if the profile declares one, otherwise the last sample type
+/// as recommended by the pprof specification.
+fn sample_value_index(profile: &proto::Profile) -> Result<usize, ProfileError> {
+    let default_index = profile
+        .sample_type
+        .iter()
+        .position(|value_type| value_type.r#type == profile.default_sample_type);
+
+    match default_index {
+        Some(index) if profile.default_sample_type != 0 => Ok(index),
+        _ => profile
+            .sample_type
+            .len()
+            .checked_sub(1)
+            .ok_or(ProfileError::InvalidSampledProfile),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(strings: &[&str]) -> Vec<String> {
+        strings.iter().map(|s| (*s).to_owned()).collect()
+    }
+
+    fn go_cpu_profile() -> proto::Profile {
+        proto::Profile {
+            sample_type: vec![
+                proto::ValueType { r#type: 1, unit: 2 },
+                proto::ValueType { r#type: 3, unit: 4 },
+            ],
+            sample: vec![
+                proto::Sample {
+                    location_id: vec![2, 1],
+                    value: vec![2, 20_000_000],
+                    label: vec![proto::Label {
+                        key: 10,
+                        str: 11,
+                        ..Default::default()
+                    }],
+                },
+                proto::Sample {
+                    location_id: vec![3],
+                    value: vec![1, 10_000_000],
+                    label: vec![],
+                },
+            ],
+            mapping: vec![proto::Mapping {
+                id: 1,
+                memory_start: 0x1000,
+                memory_limit: 0x5000,
+                file_offset: 0,
+                filename: 5,
+                build_id: 12,
+            }],
+            location: vec![
+                proto::Location {
+                    id: 1,
+                    mapping_id: 1,
+                    address: 0x1100,
+                    line: vec![proto::Line {
+                        function_id: 1,
+                        line: 12,
+                        column: 0,
+                    }],
+                },
+                proto::Location {
+                    id: 2,
+                    mapping_id: 1,
+                    address: 0x1200,
+                    line: vec![
+                        proto::Line {
+                            function_id: 3,
+                            line: 7,
+                            column: 0,
+                        },
+                        proto::Line {
+                            function_id: 2,
+                            line: 30,
+                            column: 0,
+                        },
+                    ],
+                },
+                proto::Location {
+                    id: 3,
+                    mapping_id: 1,
+                    address: 0x2000,
+                    line: vec![],
+                },
+            ],
+            function: vec![
+                proto::Function {
+                    id: 1,
+                    name: 6,
+                    system_name: 6,
+                    filename: 9,
+                    start_line: 10,
+                },
+                proto::Function {
+                    id: 2,
+                    name: 7,
+                    system_name: 7,
+                    filename: 9,
+                    start_line: 25,
+                },
+                proto::Function {
+                    id: 3,
+                    name: 8,
+                    system_name: 8,
+                    filename: 9,
+                    start_line: 5,
+                },
+            ],
+            string_table: strings(&[
+                "",
+                "samples",
+                "count",
+                "cpu",
+                "nanoseconds",
+                "/usr/bin/server",
+                "main.main",
+                "main.work",
+                "main.inlined",
+                "/src/main.go",
+                "thread_name",
+                "worker",
+                "b5381a457906d279073822a5ceb24c4bfef94ddb",
+            ]),
+            time_nanos: 1_700_000_000_000_000_000,
+            duration_nanos: 1_000_000_000,
+            period_type: Some(proto::ValueType { r#type: 3, unit: 4 }),
+            period: 10_000_000,
+            default_sample_type: 0,
+        }
+    }
+
+    #[test]
+    fn test_convert_go_cpu_profile() {
+        let (profile, debug_images) = convert(&go_cpu_profile()).unwrap();
+
+        // Two samples of 10ms for the first stack, one for the second.
+        assert_eq!(profile.samples.len(), 3);
+        assert_eq!(profile.stacks.len(), 2);
+
+        let functions: Vec<_> = profile.stacks[0]
+            .iter()
+            .map(|&frame| profile.frames[frame].function.as_deref())
+            .collect();
+        assert_eq!(
+            functions,
+            [Some("main.inlined"), Some("main.work"), Some("main.main")]
+        );
+
+        let frame = &profile.frames[profile.stacks[0][0]];
+        assert_eq!(frame.abs_path.as_deref(), Some("/src/main.go"));
+        assert_eq!(frame.lineno, Some(7));
+        assert_eq!(frame.package.as_deref(), Some("/usr/bin/server"));
+
+        let native = &profile.frames[profile.stacks[1][0]];
+        assert_eq!(native.instruction_addr, Some(Addr(0x2000)));
+        assert_eq!(native.platform.as_deref(), Some("native"));
+
+        assert_eq!(profile.thread_metadata["0"].name.as_deref(), Some("worker"));
+        assert!(profile.samples.iter().all(|sample| sample.thread_id == "0"));
+
+        assert_eq!(debug_images.len(), 1);
+        assert_eq!(debug_images[0].image_size, 0x4000);
+    }
+
+    #[test]
+    fn test_convert_missing_time() {
+        let mut profile = go_cpu_profile();
+        profile.time_nanos = 0;
+        assert!(matches!(
+            convert(&profile),
+            Err(ProfileError::InvalidSampledProfile)
+        ));
+    }
+
+    #[test]
+    fn test_convert_unknown_location() {
+        let mut profile = go_cpu_profile();
+        profile.sample[0].location_id.push(42);
+        assert!(matches!(
+            convert(&profile),
+            Err(ProfileError::MalformedStacks)
+        ));
+    }
+
+    #[test]
+    fn test_convert_no_samples() {
+        let mut profile = go_cpu_profile();
+        profile.sample.clear();
+        assert!(matches!(
+            convert(&profile),
+            Err(ProfileError::NotEnoughSamples)
+        ));
+    }
+}
@@ -0,0 +1,225 @@
+use std::io::Read;
+
+use bytes::Bytes;
+use flate2::read::GzDecoder;
+use opentelemetry_proto::tonic::profiles::v1development::ProfilesData;
+use prost::Message;
+
+use crate::sample::v2;
+use crate::{ProfileError, V2ProfileChunk};
+
+mod builder;
+mod convert;
+mod otlp;
+#[allow(dead_code)]
+mod proto;
+
+/// Maximum size of a decompressed profile.
+const MAX_DECOMPRESSED_SIZE: u64 = 50 * 1024 * 1024;
+
+/// The encoding of a [`Chunk`]'s raw profile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// A pprof `Profile` message, as emitted by Go and many OpenTelemetry profilers.
+    Pprof,
+    /// An OTLP `ProfilesData` message.
+    Otlp,
+}
+
+/// A parsed pprof or OTLP profiling chunk.
+#[derive(Debug)]
+pub struct Chunk {
+    inner: v2::ProfileChunk,
+    raw: Bytes,
+    format: Format,
+}
+
+impl Chunk {
+    /// Parses a [`Chunk`] from the required [`v2::ProfileChunk`] and a raw profile.
+    ///
+    /// Like Perfetto profiles, pprof and OTLP profiles require an associated [`v2::ProfileChunk`]
+    /// for additional metadata. The resulting [`Chunk`] contains all metadata from the
+    /// [`v2::ProfileChunk`] and samples from the `raw` profile, which may be gzip compressed.
+    ///
+    /// Note: if the parsed `sample` already contains profiling information, the frames in the
+    /// raw profile are not extracted again.
+    pub fn parse(sample: &[u8], raw: Bytes, format: Format) -> Result<Self, ProfileError> {
+        let mut inner: v2::ProfileChunk = {
+            let deserializer = &mut serde_json::Deserializer::from_slice(sample);
+            serde_path_to_error::deserialize(deserializer).map_err(ProfileError::InvalidJson)?
+        };
+
+        if inner.profile.is_empty() {
+            let payload = decompress(&raw)?;
+            let (profile_data, debug_images) = match format {
+                Format::Pprof => {
+                    let profile = proto::Profile::decode(payload.as_slice())
+                        .map_err(|_| ProfileError::InvalidSampledProfile)?;
+                    convert::convert(&profile)?
+                }
+                Format::Otlp => {
+                    let data = ProfilesData::decode(payload.as_slice())
+                        .map_err(|_| ProfileError::InvalidSampledProfile)?;
+                    otlp::convert(&data)?
+                }
+            };
+            inner.profile = profile_data;
+            inner.metadata.debug_meta.images = debug_images;
+        }
+
+        Ok(Self { inner, raw, format })
+    }
+
+    /// Returns the raw profile this [`Chunk`] was parsed from.
+    pub fn raw(&self) -> &Bytes {
+        &self.raw
+    }
+
+    /// Returns the encoding of the raw profile.
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Returns the combined metadata and profile as a [`V2ProfileChunk`].
+    pub fn as_v2(&self) -> &V2ProfileChunk {
+        &self.inner
+    }
+}
+
+impl crate::profile_chunk::ProfileChunk for Chunk {
+    fn platform(&self) -> &str {
+        &self.inner.metadata.platform
+    }
+
+    fn normalize(&mut self) -> Result<(), ProfileError> {
+        self.inner.normalize()
+    }
+}
+
+impl relay_filter::Filterable for Chunk {
+    fn release(&self) -> Option<&str> {
+        self.inner.metadata.release.as_deref()
+    }
+}
+
+impl relay_protocol::Getter for Chunk {
+    fn get_value(&self, path: &str) -> Option<relay_protocol::Val<'_>> {
+        self.inner.get_value(path)
+    }
+}
+
+/// Decompresses a gzip compressed profile.
+///
+/// `pprof` files are gzip compressed by default, uncompressed payloads are returned as-is.
+fn decompress(payload: &[u8]) -> Result<Vec<u8>, ProfileError> {
+    if !payload.starts_with(&[0x1f, 0x8b]) {
+        return Ok(payload.to_vec());
+    }
+
+    let mut decompressed = Vec::new();
+    GzDecoder::new(payload)
+        .take(MAX_DECOMPRESSED_SIZE + 1)
+        .read_to_end(&mut decompressed)
+        .map_err(|_| ProfileError::InvalidSampledProfile)?;
+
+    if decompressed.len() as u64 > MAX_DECOMPRESSED_SIZE {
+        return Err(ProfileError::ExceedSizeLimit);
+    }
+
+    Ok(decompressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+
+    use super::*;
+
+    fn metadata() -> Vec<u8> {
+        serde_json::to_vec(&serde_json::json!({
+            "version": "2",
+            "chunk_id": "0432a0a4c25f4697bf9f0a2fcbe6a814",
+            "profiler_id": "4d229f1d3807421ba62a5f8bc295d836",
+            "platform": "go",
+            "client_sdk": {"name": "sentry.go", "version": "1.0"},
+        }))
+        .unwrap()
+    }
+
+    fn pprof() -> Vec<u8> {
+        let strings = ["", "cpu", "nanoseconds", "main.main", "/src/main.go"];
+        proto::Profile {
+            sample_type: vec![proto::ValueType { r#type: 1, unit: 2 }],
+            sample: vec![proto::Sample {
+                location_id: vec![1],
+                value: vec![20_000_000],
+                label: vec![],
+            }],
+            location: vec![proto::Location {
+                id: 1,
+                line: vec![proto::Line {
+                    function_id: 1,
+                    line: 5,
+                    column: 0,
+                }],
+                ..Default::default()
+            }],
+            function: vec![proto::Function {
+                id: 1,
+                name: 3,
+                filename: 4,
+                ..Default::default()
+            }],
+            string_table: strings.map(str::to_owned).to_vec(),
+            time_nanos: 1_700_000_000_000_000_000,
+            duration_nanos: 1_000_000_000,
+            period_type: Some(proto::ValueType { r#type: 1, unit: 2 }),
+            period: 10_000_000,
+            ..Default::default()
+        }
+        .encode_to_vec()
+    }
+
+    #[test]
+    fn test_parse_pprof() {
+        let chunk = Chunk::parse(&metadata(), Bytes::from(pprof()), Format::Pprof).unwrap();
+
+        assert_eq!(chunk.format(), Format::Pprof);
+        assert_eq!(chunk.inner.metadata.platform, "go");
+        assert_eq!(chunk.inner.profile.samples.len(), 2);
+        assert_eq!(
+            chunk.inner.profile.frames[0].function.as_deref(),
+            Some("main.main")
+        );
+    }
+
+    #[test]
+    fn test_parse_pprof_gzip() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&pprof()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let chunk = Chunk::parse(&metadata(), Bytes::from(compressed), Format::Pprof).unwrap();
+        assert_eq!(chunk.inner.profile.samples.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_pprof_invalid() {
+        let result = Chunk::parse(
+            &metadata(),
+            Bytes::from_static(b"\xff\xff\xff"),
+            Format::Pprof,
+        );
+        assert!(matches!(result, Err(ProfileError::InvalidSampledProfile)));
+    }
+
+    #[test]
+    fn test_parse_otlp_without_dictionary() {
+        let raw = ProfilesData::default().encode_to_vec();
+        let result = Chunk::parse(&metadata(), Bytes::from(raw), Format::Otlp);
+        assert!(matches!(result, Err(ProfileError::InvalidSampledProfile)));
+    }
+}
@@ -0,0 +1,429 @@
+//! Conversion of OTLP profiles into the Sample v2 format.
+//!
+//! OTLP profiles are derived from pprof, but share all tables across profiles in a
+//! [`ProfilesDictionary`] and reference stacks instead of location lists.
+
+use hashbrown::HashMap;
+use opentelemetry_proto::tonic::common::v1::any_value::Value;
+use opentelemetry_proto::tonic::profiles::v1development::{
+    KeyValueAndUnit, Profile, ProfilesData, ProfilesDictionary,
+};
+use relay_event_schema::protocol::Addr;
+
+use crate::debug_image::DebugImage;
+use crate::error::ProfileError;
+use crate::pprof::builder::{
+    DEFAULT_THREAD_ID, ProfileBuilder, THREAD_ID_KEYS, THREAD_NAME_KEYS, WeightedSample,
+    decode_build_id, sample_count,
+};
+use crate::sample::Frame;
+use crate::sample::v2::ProfileData;
+
+/// Mapping attribute containing the GNU build id of an executable.
+const BUILD_ID_KEY: &str = "process.executable.build_id.gnu";
+
+/// Converts OTLP profiles into Sample v2 profile data and debug images.
+///
+/// Samples of all contained profiles are merged into a single profile.
+pub fn convert(data: &ProfilesData) -> Result<(ProfileData, Vec<DebugImage>), ProfileError> {
+    let Some(dictionary) = &data.dictionary else {
+        return Err(ProfileError::InvalidSampledProfile);
+    };
+
+    let mut converter = Converter {
+        dictionary,
+        builder: ProfileBuilder::default(),
+        locations: HashMap::new(),
+    };
+
+    let profiles = data
+        .resource_profiles
+        .iter()
+        .flat_map(|resource| &resource.scope_profiles)
+        .flat_map(|scope| &scope.profiles);
+
+    for profile in profiles {
+        converter.convert_profile(profile)?;
+    }
+
+    converter.builder.finish()
+}
+
+struct Converter<'a> {
+    dictionary: &'a ProfilesDictionary,
+    builder: ProfileBuilder,
+    /// Resolved frames per location index, innermost frame first.
+    locations: HashMap<i32, Vec<usize>>,
+}
+
+impl<'a> Converter<'a> {
+    fn string(&self, index: i32) -> &'a str {
+        let strings = &self.dictionary.string_table;
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| strings.get(index))
+            .map_or("", String::as_str)
+    }
+
+    fn attribute(&self, index: i32) -> Option<(&'a str, &'a Value)> {
+        let attributes = &self.dictionary.attribute_table;
+        let attribute: &KeyValueAndUnit = attributes.get(usize::try_from(index).ok()?)?;
+        let value = attribute.value.as_ref()?.value.as_ref()?;
+        Some((self.string(attribute.key_strindex), value))
+    }
+
+    fn convert_profile(&mut self, profile: &Profile) -> Result<(), ProfileError> {
+        let sample_type = profile.sample_type.unwrap_or_default();
+        let value_unit = self.string(sample_type.unit_strindex);
+        let period_unit = profile
+            .period_type
+            .map(|period_type| self.string(period_type.unit_strindex));
+
+        let mut weighted = Vec::new();
+        for sample in &profile.sample {
+            let stack = usize::try_from(sample.stack_index)
+                .ok()
+                .and_then(|index| self.dictionary.stack_table.get(index))
+                .ok_or(ProfileError::MalformedStacks)?;
+
+            // Location indices are ordered from the leaf to the root.
+            let mut frames = Vec::new();
+            for &location_index in &stack.location_indices {
+                frames.extend_from_slice(self.resolve_location(location_index)?);
+            }
+            let stack_id = self.builder.add_stack(frames)?;
+
+            let mut thread_id = None;
+            let mut thread_name = None;
+            for &index in &sample.attribute_indices {
+                let Some((key, value)) = self.attribute(index) else {
+                    continue;
+                };
+
+                if THREAD_ID_KEYS.contains(&key) {
+                    thread_id = match value {
+                        Value::StringValue(id) => Some(id.clone()),
+                        Value::IntValue(id) => Some(id.to_string()),
+                        _ => None,
+                    };
+                } else if THREAD_NAME_KEYS.contains(&key)
+                    && let Value::StringValue(name) = value
+                {
+                    thread_name = Some(name.as_str());
+                }
+            }
+
+            let thread_id = thread_id.unwrap_or_else(|| DEFAULT_THREAD_ID.to_owned());
+            if let Some(thread_name) = thread_name {
+                self.builder.set_thread_name(&thread_id, thread_name);
+            }
+
+            if !sample.timestamps_unix_nano.is_empty() {
+                for &timestamp in &sample.timestamps_unix_nano {
+                    self.builder.add_sample(timestamp, stack_id, &thread_id)?;
+                }
+                continue;
+            }
+
+            let value = sample.values.first().copied().unwrap_or_default();
+            let count = sample_count(value, value_unit, profile.period, period_unit);
+            if count > 0 {
+                weighted.push(WeightedSample {
+                    stack_id,
+                    thread_id,
+                    count,
+                });
+            }
+        }
+
+        self.builder
+            .add_weighted_samples(weighted, profile.time_unix_nano, profile.duration_nano)
+    }
+
+    fn resolve_location(&mut self, index: i32) -> Result<&[usize], ProfileError> {
+        if !self.locations.contains_key(&index) {
+            let frames = self.convert_location(index)?;
+            self.locations.insert(index, frames);
+        }
+
+        Ok(&self.locations[&index])
+    }
+
+    fn convert_location(&mut self, index: i32) -> Result<Vec<usize>, ProfileError> {
+        let dictionary = self.dictionary;
+        let location = usize::try_from(index)
+            .ok()
+            .and_then(|index| dictionary.location_table.get(index))
+            .ok_or(ProfileError::MalformedStacks)?;
+
+        let mapping = usize::try_from(location.mapping_index)
+            .ok()
+            .and_then(|index| dictionary.mapping_table.get(index));
+        let package = mapping
+            .map(|mapping| self.string(mapping.filename_strindex))
+            .filter(|filename| !filename.is_empty());
+
+        let mut frames = Vec::with_capacity(location.line.len().max(1));
+
+        // Lines are ordered from the innermost inlined function to the caller.
+        for line in &location.line {
+            let function = usize::try_from(line.function_index)
+                .ok()
+                .and_then(|index| dictionary.function_table.get(index))
+                .ok_or(ProfileError::MalformedStacks)?;
+
+            let name = match self.string(function.name_strindex) {
+                "" => self.string(function.system_name_strindex),
+                name => name,
+            };
+
+            let frame = Frame {
+                function: (!name.is_empty()).then(|| name.to_owned()),
+                abs_path: Some(self.string(function.filename_strindex))
+                    .filter(|filename| !filename.is_empty())
+                    .map(str::to_owned),
+                lineno: u32::try_from(line.line).ok().filter(|&line| line > 0),
+                colno: u32::try_from(line.column).ok().filter(|&column| column > 0),
+                package: package.map(str::to_owned),
+                ..Default::default()
+            };
+
+            frames.push(self.builder.add_frame(frame)?);
+        }
+
+        if location.line.is_empty() {
+            let frame = Frame {
+                instruction_addr: Some(Addr(location.address)),
+                package: package.map(str::to_owned),
+                platform: Some("native".to_owned()),
+                ..Default::default()
+            };
+
+            frames.push(self.builder.add_frame(frame)?);
+
+            if let (Some(mapping), Some(package)) = (mapping, package) {
+                let build_id = mapping
+                    .attribute_indices
+                    .iter()
+                    .filter_map(|&index| self.attribute(index))
+                    .find_map(|(key, value)| match value {
+                        Value::StringValue(build_id) if key == BUILD_ID_KEY => Some(build_id),
+                        _ => None,
+                    });
+
+                if let Some(build_id) = build_id {
+                    self.builder.add_debug_image(
+                        package,
+                        &decode_build_id(build_id),
+                        mapping.memory_start,
+                        mapping.memory_limit,
+                    );
+                }
+            }
+        }
+
+        Ok(frames)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry_proto::tonic::common::v1::AnyValue;
+    use opentelemetry_proto::tonic::profiles::v1development::{
+        Function, Line, Location, Mapping, ResourceProfiles, Sample, ScopeProfiles, Stack,
+        ValueType,
+    };
+
+    use super::*;
+
+    fn attribute(key_strindex: i32, value: Value) -> KeyValueAndUnit {
+        KeyValueAndUnit {
+            key_strindex,
+            value: Some(AnyValue { value: Some(value) }),
+            unit_strindex: 0,
+        }
+    }
+
+    fn profiles_data(samples: Vec<Sample>) -> ProfilesData {
+        let string_table = [
+            "",
+            "cpu",
+            "nanoseconds",
+            "/usr/lib/libapp.so",
+            "app::run",
+            "app::main",
+            "/src/main.rs",
+            "thread.id",
+            "thread.name",
+            "main",
+            BUILD_ID_KEY,
+        ]
+        .map(str::to_owned)
+        .to_vec();
+
+        let dictionary = ProfilesDictionary {
+            mapping_table: vec![
+                Mapping::default(),
+                Mapping {
+                    memory_start: 0x1000,
+                    memory_limit: 0x3000,
+                    file_offset: 0,
+                    filename_strindex: 3,
+                    attribute_indices: vec![3],
+                },
+            ],
+            location_table: vec![
+                Location::default(),
+                Location {
+                    mapping_index: 1,
+                    address: 0x1100,
+                    line: vec![Line {
+                        function_index: 1,
+                        line: 10,
+                        column: 5,
+                    }],
+                    attribute_indices: vec![],
+                },
+                Location {
+                    mapping_index: 1,
+                    address: 0x1200,
+                    line: vec![Line {
+                        function_index: 2,
+                        line: 3,
+                        column: 0,
+                    }],
+                    attribute_indices: vec![],
+                },
+                Location {
+                    mapping_index: 1,
+                    address: 0x2200,
+                    line: vec![],
+                    attribute_indices: vec![],
+                },
+            ],
+            function_table: vec![
+                Function::default(),
+                Function {
+                    name_strindex: 4,
+                    system_name_strindex: 0,
+                    filename_strindex: 6,
+                    start_line: 8,
+                },
+                Function {
+                    name_strindex: 5,
+                    system_name_strindex: 0,
+                    filename_strindex: 6,
+                    start_line: 1,
+                },
+            ],
+            link_table: vec![],
+            string_table,
+            attribute_table: vec![
+                KeyValueAndUnit::default(),
+                attribute(7, Value::IntValue(42)),
+                attribute(8, Value::StringValue("main".to_owned())),
+                attribute(
+                    10,
+                    Value::StringValue("b5381a457906d279073822a5ceb24c4bfef94ddb".to_owned()),
+                ),
+            ],
+            stack_table: vec![
+                Stack::default(),
+                Stack {
+                    location_indices: vec![1, 2],
+                },
+                Stack {
+                    location_indices: vec![3, 2],
+                },
+            ],
+        };
+
+        let profile = Profile {
+            sample_type: Some(ValueType {
+                type_strindex: 1,
+                unit_strindex: 2,
+                aggregation_temporality: 0,
+            }),
+            sample: samples,
+            time_unix_nano: 1_700_000_000_000_000_000,
+            duration_nano: 1_000_000_000,
+            period_type: Some(ValueType {
+                type_strindex: 1,
+                unit_strindex: 2,
+                aggregation_temporality: 0,
+            }),
+            period: 10_000_000,
+            ..Default::default()
+        };
+
+        ProfilesData {
+            resource_profiles: vec![ResourceProfiles {
+                scope_profiles: vec![ScopeProfiles {
+                    profiles: vec![profile],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            dictionary: Some(dictionary),
+        }
+    }
+
+    #[test]
+    fn test_convert_timestamped_samples() {
+        let data = profiles_data(vec![Sample {
+            stack_index: 1,
+            values: vec![],
+            attribute_indices: vec![1, 2],
+            link_index: 0,
+            timestamps_unix_nano: vec![1_700_000_000_010_000_000, 1_700_000_000_020_000_000],
+        }]);
+
+        let (profile, debug_images) = convert(&data).unwrap();
+
+        assert_eq!(profile.samples.len(), 2);
+        assert_eq!(profile.samples[1].timestamp.to_f64(), 1_700_000_000.02);
+        assert_eq!(profile.samples[0].thread_id, "42");
+        assert_eq!(profile.thread_metadata["42"].name.as_deref(), Some("main"));
+
+        let functions: Vec<_> = profile.stacks[0]
+            .iter()
+            .map(|&frame| profile.frames[frame].function.as_deref())
+            .collect();
+        assert_eq!(functions, [Some("app::run"), Some("app::main")]);
+        assert!(debug_images.is_empty());
+    }
+
+    #[test]
+    fn test_convert_aggregated_samples() {
+        let data = profiles_data(vec![Sample {
+            stack_index: 2,
+            values: vec![30_000_000],
+            attribute_indices: vec![],
+            link_index: 0,
+            timestamps_unix_nano: vec![],
+        }]);
+
+        let (profile, debug_images) = convert(&data).unwrap();
+
+        assert_eq!(profile.samples.len(), 3);
+        assert!(profile.samples.iter().all(|sample| sample.thread_id == "0"));
+
+        let native = &profile.frames[profile.stacks[0][0]];
+        assert_eq!(native.instruction_addr, Some(Addr(0x2200)));
+        assert_eq!(native.package.as_deref(), Some("/usr/lib/libapp.so"));
+
+        assert_eq!(debug_images.len(), 1);
+        assert_eq!(debug_images[0].image_size, 0x2000);
+    }
+
+    #[test]
+    fn test_convert_invalid_stack() {
+        let data = profiles_data(vec![Sample {
+            stack_index: 7,
+            values: vec![1],
+            ..Default::default()
+        }]);
+
+        assert!(matches!(convert(&data), Err(ProfileError::MalformedStacks)));
+    }
+}
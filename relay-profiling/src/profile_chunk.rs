@@ -1,7 +1,8 @@
 use serde::Deserialize;
 
 use crate::{
-    AndroidProfileChunk, PerfettoProfileChunk, ProfileError, ProfileType, V2ProfileChunk, sample,
+    AndroidProfileChunk, PerfettoProfileChunk, PprofProfileChunk, ProfileError, ProfileType,
+    V2ProfileChunk, sample,
 };
 
 /// Minimum interface all profile chunk types must implement.
@@ -25,6 +26,7 @@ pub trait ProfileChunk {
 pub enum AnyProfileChunk {
     Android(Box<AndroidProfileChunk>),
     Perfetto(Box<PerfettoProfileChunk>),
+    Pprof(Box<PprofProfileChunk>),
     V2(Box<V2ProfileChunk>),
 }
 
@@ -46,6 +48,12 @@ impl From<Box<PerfettoProfileChunk>> for AnyProfileChunk {
     }
 }
 
+impl From<Box<PprofProfileChunk>> for AnyProfileChunk {
+    fn from(chunk: Box<PprofProfileChunk>) -> Self {
+        Self::Pprof(chunk)
+    }
+}
+
 impl From<AndroidOrV2ProfileChunk> for AnyProfileChunk {
     fn from(chunk: AndroidOrV2ProfileChunk) -> Self {
         match chunk {
@@ -60,6 +68,7 @@ impl ProfileChunk for AnyProfileChunk {
         match self {
             AnyProfileChunk::Android(chunk) => chunk.platform(),
             AnyProfileChunk::Perfetto(chunk) => chunk.platform(),
+            AnyProfileChunk::Pprof(chunk) => chunk.platform(),
             AnyProfileChunk::V2(chunk) => chunk.platform(),
         }
     }
@@ -68,6 +77,7 @@ impl ProfileChunk for AnyProfileChunk {
         match self {
             AnyProfileChunk::Android(chunk) => chunk.normalize(),
             AnyProfileChunk::Perfetto(chunk) => chunk.normalize(),
+            AnyProfileChunk::Pprof(chunk) => chunk.normalize(),
             AnyProfileChunk::V2(chunk) => chunk.normalize(),
         }
     }
@@ -78,6 +88,7 @@ impl relay_protocol::Getter for AnyProfileChunk {
         match self {
             AnyProfileChunk::Android(chunk) => chunk.get_value(path),
             AnyProfileChunk::Perfetto(chunk) => chunk.get_value(path),
+            AnyProfileChunk::Pprof(chunk) => chunk.get_value(path),
             AnyProfileChunk::V2(chunk) => chunk.get_value(path),
         }
     }
@@ -88,6 +99,7 @@ impl relay_filter::Filterable for AnyProfileChunk {
         match self {
             AnyProfileChunk::Android(chunk) => chunk.release(),
             AnyProfileChunk::Perfetto(chunk) => chunk.release(),
+            AnyProfileChunk::Pprof(chunk) => chunk.release(),
             AnyProfileChunk::V2(chunk) => chunk.release(),
         }
     }
@@ -8,11 +8,13 @@ use chrono::{DateTime, Utc};
 use rand::Rng;
 use rand::distr::StandardUniform;
 use rand_pcg::Pcg32;
+use relay_event_schema::protocol::Event;
 use relay_protocol::Getter;
 use serde::Serialize;
 use uuid::Uuid;
 
-use crate::config::{RuleId, SamplingRule, SamplingValue};
+use crate::config::{RuleId, RuleType, SamplingRule, SamplingValue};
+use crate::{DynamicSamplingContext, SamplingConfig};
 
 /// Generates a pseudo random number by seeding the generator with the given id.
 ///
@@ -255,17 +257,80 @@ impl fmt::Display for MatchedRuleIds {
     }
 }
 
+/// Computes the sampling decision for an event and its trace.
+///
+/// Transaction rules of the project's `sampling_config` are matched against the event, followed
+/// by its project rules and the trace rules of the `root_sampling_config` of the trace root,
+/// which are matched against the dynamic sampling context. Returns `None` if no rules match.
+///
+/// Configs with unsupported rules never match, unless `processing_enabled` is set. Processing
+/// Relays match the supported rules of such configs instead.
+pub fn compute_sampling_decision(
+    processing_enabled: bool,
+    sampling_config: Option<&SamplingConfig>,
+    event: Option<&Event>,
+    root_sampling_config: Option<&SamplingConfig>,
+    dsc: Option<&DynamicSamplingContext>,
+) -> Option<SamplingMatch> {
+    if (sampling_config.is_none() || event.is_none())
+        && (root_sampling_config.is_none() || dsc.is_none())
+    {
+        return None;
+    }
+
+    if sampling_config.is_some_and(|config| config.unsupported())
+        || root_sampling_config.is_some_and(|config| config.unsupported())
+    {
+        if processing_enabled {
+            relay_log::error!("found unsupported rules even as processing relay");
+        } else {
+            return None;
+        }
+    }
+
+    let mut evaluator = SamplingEvaluator::new(Utc::now());
+
+    if let (Some(event), Some(sampling_state)) = (event, sampling_config)
+        && let Some(seed) = event.id.value().map(|id| id.0)
+    {
+        let rules = sampling_state.filter_rules(RuleType::Transaction);
+        evaluator = match evaluator.match_rules(seed, event, rules) {
+            ControlFlow::Continue(evaluator) => evaluator,
+            ControlFlow::Break(sampling_match) => return Some(sampling_match),
+        }
+    };
+
+    if let (Some(dsc), Some(sampling_state)) = (dsc, sampling_config) {
+        let rules = sampling_state.filter_rules(RuleType::Project);
+        evaluator = match evaluator.match_rules(*dsc.trace_id, dsc, rules) {
+            ControlFlow::Continue(evaluator) => evaluator,
+            ControlFlow::Break(sampling_match) => return Some(sampling_match),
+        }
+    };
+
+    if let (Some(dsc), Some(sampling_state)) = (dsc, root_sampling_config) {
+        let rules = sampling_state.filter_rules(RuleType::Trace);
+        return match evaluator.match_rules(*dsc.trace_id, dsc, rules) {
+            ControlFlow::Continue(_) => None,
+            ControlFlow::Break(sampling_match) => Some(sampling_match),
+        };
+    }
+
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::TimeZone;
+    use relay_base_schema::events::EventType;
     use relay_base_schema::project::ProjectId;
-    use relay_protocol::RuleCondition;
+    use relay_event_schema::protocol::{EventId, LenientString};
+    use relay_protocol::{Annotated, RuleCondition};
     use similar_asserts::assert_eq;
     use std::str::FromStr;
     use uuid::Uuid;
 
-    use crate::DynamicSamplingContext;
-    use crate::config::{DecayingFunction, RuleType, TimeRange};
+    use crate::config::{DecayingFunction, TimeRange};
     use crate::dsc::TraceUserContext;
 
     use super::*;
@@ -633,4 +698,81 @@ mod tests {
         rule.sampling_value = SamplingValue::MinimumSampleRate { value: 1.0 };
         assert_eq!(eval.try_compute_sample_rate(&rule), None);
     }
+
+    fn mocked_event(transaction: &str, release: &str) -> Event {
+        Event {
+            id: Annotated::new(EventId::new()),
+            ty: Annotated::new(EventType::Transaction),
+            transaction: Annotated::new(transaction.to_owned()),
+            release: Annotated::new(LenientString(release.to_owned())),
+            ..Event::default()
+        }
+    }
+
+    fn sampling_config(rule_type: RuleType, sample_rate: f64) -> SamplingConfig {
+        SamplingConfig {
+            rules: vec![SamplingRule {
+                condition: RuleCondition::all(),
+                sampling_value: SamplingValue::SampleRate { value: sample_rate },
+                ty: rule_type,
+                id: RuleId(1),
+                time_range: TimeRange::default(),
+                decaying_fn: DecayingFunction::Constant,
+            }],
+            ..SamplingConfig::new()
+        }
+    }
+
+    #[test]
+    fn test_compute_sampling_decision_keeps_or_drops() {
+        let event = mocked_event("testing", "1.0");
+
+        for (sample_rate, should_keep) in [(0.0, false), (1.0, true)] {
+            let config = sampling_config(RuleType::Transaction, sample_rate);
+            let sampling_match =
+                compute_sampling_decision(false, Some(&config), Some(&event), None, None).unwrap();
+            assert_eq!(sampling_match.decision().is_keep(), should_keep);
+        }
+    }
+
+    #[test]
+    fn test_compute_sampling_decision_matching() {
+        let dsc = mocked_dsc_with_getter_values(vec![]);
+
+        for rule_type in [RuleType::Transaction, RuleType::Project] {
+            let event = mocked_event("foo", "bar");
+            let config = sampling_config(rule_type, 1.0);
+
+            let result =
+                compute_sampling_decision(false, Some(&config), Some(&event), None, Some(&dsc));
+            assert!(result.is_some());
+        }
+    }
+
+    #[test]
+    fn test_compute_sampling_decision_unsupported_rule() {
+        let event = mocked_event("foo", "bar");
+        let mut config = sampling_config(RuleType::Transaction, 1.0);
+        config.rules.push(SamplingRule {
+            ty: RuleType::Unsupported,
+            ..config.rules[0].clone()
+        });
+
+        // Unsupported rule should result in no match if processing is not enabled.
+        let result = compute_sampling_decision(false, Some(&config), Some(&event), None, None);
+        assert!(result.is_none());
+
+        // Match if processing is enabled.
+        let result = compute_sampling_decision(true, Some(&config), Some(&event), None, None);
+        assert!(result.is_some());
+    }
+
+    #[test]
+    fn test_compute_sampling_decision_trace() {
+        let dsc = mocked_dsc_with_getter_values(vec![]);
+        let config = sampling_config(RuleType::Trace, 0.2);
+
+        let result = compute_sampling_decision(false, None, None, Some(&config), Some(&dsc));
+        assert_eq!(result.unwrap().sample_rate(), 0.2);
+    }
 }
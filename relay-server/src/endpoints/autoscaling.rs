@@ -36,14 +36,35 @@ fn to_prometheus_string(data: &AutoscalingData) -> String {
     append_data_row(&mut result, "spool_total_size", data.total_size, &[]);
     for utilization in &data.services_metrics {
         let service_name = extract_service_name(utilization.name);
+        let instance_id = format!("{}", utilization.instance_id);
+        let tags = [
+            ("relay_service", service_name),
+            ("instance_id", &instance_id),
+        ];
+
         append_data_row(
             &mut result,
             "service_utilization",
             utilization.utilization,
-            &[
-                ("relay_service", service_name),
-                ("instance_id", &format!("{}", utilization.instance_id)),
-            ],
+            &tags,
+        );
+        append_data_row(
+            &mut result,
+            "service_queue_size",
+            utilization.queue_size,
+            &tags,
+        );
+        append_data_row(
+            &mut result,
+            "service_queue_wait_ms",
+            utilization.queue_wait_ms,
+            &tags,
+        );
+        append_data_row(
+            &mut result,
+            "service_dropped_messages",
+            utilization.dropped_messages,
+            &tags,
         );
     }
 
@@ -148,16 +169,25 @@ mod test {
                     name: "test",
                     instance_id: 0,
                     utilization: 10,
+                    queue_size: 0,
+                    queue_wait_ms: 0,
+                    dropped_messages: 0,
                 },
                 ServiceUtilization {
                     name: "test",
                     instance_id: 1,
                     utilization: 30,
+                    queue_size: 5,
+                    queue_wait_ms: 12,
+                    dropped_messages: 0,
                 },
                 ServiceUtilization {
                     name: "envelope",
                     instance_id: 1,
                     utilization: 50,
+                    queue_size: 100,
+                    queue_wait_ms: 250,
+                    dropped_messages: 7,
                 },
             ],
             worker_pool_utilization: 61,
@@ -171,8 +201,17 @@ relay_up 1
 relay_spool_item_count 10
 relay_spool_total_size 30
 relay_service_utilization{relay_service="test", instance_id="0"} 10
+relay_service_queue_size{relay_service="test", instance_id="0"} 0
+relay_service_queue_wait_ms{relay_service="test", instance_id="0"} 0
+relay_service_dropped_messages{relay_service="test", instance_id="0"} 0
 relay_service_utilization{relay_service="test", instance_id="1"} 30
+relay_service_queue_size{relay_service="test", instance_id="1"} 5
+relay_service_queue_wait_ms{relay_service="test", instance_id="1"} 12
+relay_service_dropped_messages{relay_service="test", instance_id="1"} 0
 relay_service_utilization{relay_service="envelope", instance_id="1"} 50
+relay_service_queue_size{relay_service="envelope", instance_id="1"} 100
+relay_service_queue_wait_ms{relay_service="envelope", instance_id="1"} 250
+relay_service_dropped_messages{relay_service="envelope", instance_id="1"} 7
 relay_worker_pool_utilization 61
 relay_runtime_utilization 41
 "#
@@ -1,21 +1,36 @@
 //! Handles envelope store requests.
 
 use std::convert::Infallible;
+use std::io;
 
+use axum::body::Body;
 use axum::extract::rejection::BytesRejection;
 use axum::extract::{DefaultBodyLimit, FromRequest, Request};
 use axum::response::IntoResponse;
 use axum::routing::{MethodRouter, post};
 use axum::{Json, RequestExt};
 use bytes::Bytes;
+use futures::{Stream, StreamExt};
 use relay_config::Config;
+use relay_dynamic_config::Feature;
 use relay_event_schema::protocol::EventId;
+use relay_quotas::{DataCategory, RateLimits};
+use relay_system::Addr;
 use serde::Serialize;
+use tokio::sync::mpsc;
 
+use crate::constants::NNSWITCH_DYING_MESSAGE_FILENAME;
 use crate::endpoints::common::{self, BadStoreRequest};
-use crate::envelope::Envelope;
+use crate::envelope::{
+    AttachmentType, ContentType, Envelope, EnvelopeError, EnvelopeReader, Item, ItemType, Items,
+};
 use crate::extractors::{BadEventMeta, PartialMeta, RequestMeta};
+use crate::managed::Managed;
 use crate::service::ServiceState;
+use crate::services::outcome::Outcome;
+use crate::services::projects::project::ProjectState;
+use crate::services::upload::{ProjectContext, Upload};
+use crate::utils::{find_error_source, is_length_limit_error};
 
 /// Aggregate rejection thrown when extracting [`EnvelopeParams`].
 #[derive(Debug)]
@@ -51,28 +66,69 @@ impl IntoResponse for BadEnvelopeParams {
     }
 }
 
+/// The body of an envelope request.
+#[derive(Debug)]
+enum EnvelopeBody {
+    /// The request body read into memory.
+    Bytes(Bytes),
+    /// The unread request body.
+    ///
+    /// Only used if large attachments may be uploaded while the envelope is being parsed.
+    Stream(Body),
+}
+
 #[derive(Debug)]
 struct EnvelopeParams {
     meta: RequestMeta,
-    body: Bytes,
+    body: EnvelopeBody,
 }
 
 impl EnvelopeParams {
-    fn extract_envelope(self) -> Result<Box<Envelope>, BadStoreRequest> {
+    /// Parses the envelope from the request body.
+    ///
+    /// Also returns rate limits of items that were dropped while parsing the envelope.
+    async fn extract_envelope(
+        self,
+        state: &ServiceState,
+    ) -> Result<(Box<Envelope>, RateLimits), BadStoreRequest> {
         let Self { meta, body } = self;
 
-        if body.is_empty() {
-            return Err(BadStoreRequest::EmptyBody);
-        }
+        let (envelope, rate_limits) = match body {
+            EnvelopeBody::Bytes(body) => (parse_envelope(body, meta)?, RateLimits::new()),
+            EnvelopeBody::Stream(body) => match upload_context(state, &meta) {
+                Some(upload_context) => {
+                    let stream = body.into_data_stream().map(|r| r.map_err(io::Error::other));
+                    read_envelope(state, stream, meta, upload_context).await?
+                }
+                None => {
+                    let body = axum::body::to_bytes(body, state.config().max_envelope_size())
+                        .await
+                        .map_err(|error| {
+                            match find_error_source(&error, is_length_limit_error) {
+                                Some(_) => BadStoreRequest::RequestTooLarge,
+                                None => BadStoreRequest::InvalidBody(io::Error::other(error)),
+                            }
+                        })?;
+                    (parse_envelope(body, meta)?, RateLimits::new())
+                }
+            },
+        };
 
-        let envelope = Envelope::parse_request(body, meta)?;
         if envelope.is_internal() {
             return Err(BadStoreRequest::InternalEnvelope);
         }
-        Ok(envelope)
+        Ok((envelope, rate_limits))
     }
 }
 
+fn parse_envelope(body: Bytes, meta: RequestMeta) -> Result<Box<Envelope>, BadStoreRequest> {
+    if body.is_empty() {
+        return Err(BadStoreRequest::EmptyBody);
+    }
+
+    Ok(Envelope::parse_request(body, meta)?)
+}
+
 impl FromRequest<ServiceState> for EnvelopeParams {
     type Rejection = BadEnvelopeParams;
 
@@ -83,10 +139,12 @@ impl FromRequest<ServiceState> for EnvelopeParams {
         let result = request.extract_parts_with_state(state).await;
 
         if !matches!(result, Err(BadEventMeta::MissingAuth)) {
-            return Ok(Self {
-                meta: result?,
-                body: request.extract().await?,
-            });
+            let meta = result?;
+            let body = match state.config().upload().envelope_attachment_threshold {
+                Some(_) => EnvelopeBody::Stream(request.into_body()),
+                None => EnvelopeBody::Bytes(request.extract().await?),
+            };
+            return Ok(Self { meta, body });
         }
 
         let partial_meta: PartialMeta = request.extract_parts_with_state(state).await?;
@@ -101,11 +159,285 @@ impl FromRequest<ServiceState> for EnvelopeParams {
 
         Ok(Self {
             meta: partial_meta.copy_to(request_meta),
-            body,
+            body: EnvelopeBody::Bytes(body),
         })
     }
 }
 
+/// What to do with an attachment read from the request body.
+#[derive(Debug)]
+enum UploadDecision {
+    /// Read the attachment into memory and put it into the envelope as-is.
+    Inline,
+    /// Upload the attachment and put a placeholder into the envelope.
+    Upload,
+    /// Drop the attachment without reading it into memory or uploading it.
+    Drop(RateLimits),
+}
+
+struct UploadContext<'a> {
+    upload: &'a Addr<Upload>,
+    project: ProjectContext,
+    /// Minimum size of attachments to upload.
+    threshold: usize,
+    /// Whether minidumps may be uploaded in addition to regular attachments.
+    upload_minidumps: bool,
+    /// Cached rate limits of the attachment category.
+    rate_limits: RateLimits,
+}
+
+impl UploadContext<'_> {
+    fn upload_decision(&self, item: &Item, length: usize) -> UploadDecision {
+        if length == 0 || length < self.threshold || item.ty() != &ItemType::Attachment {
+            return UploadDecision::Inline;
+        }
+
+        // Placeholders and trace attachments must be processed by Relay, as well as Nintendo
+        // Switch dying messages which are only recognized by their payload.
+        if matches!(
+            item.content_type(),
+            Some(ContentType::AttachmentRef | ContentType::TraceAttachment)
+        ) || item.filename() == Some(NNSWITCH_DYING_MESSAGE_FILENAME)
+        {
+            return UploadDecision::Inline;
+        }
+
+        match item.attachment_type() {
+            None | Some(AttachmentType::Attachment) => (),
+            Some(AttachmentType::Minidump) if self.upload_minidumps => (),
+            Some(_) => return UploadDecision::Inline,
+        }
+
+        if self.rate_limits.is_limited() {
+            UploadDecision::Drop(self.rate_limits.clone())
+        } else {
+            UploadDecision::Upload
+        }
+    }
+}
+
+/// Creates an [`UploadContext`] if large attachments of this request can be uploaded.
+///
+/// Requires the `endpoint_fetch_config_enabled` option and the `EnvelopeAttachmentUploads`
+/// feature to be enabled. The feature is checked on the cached project config without waiting for
+/// it, envelopes of projects which are not cached yet are read entirely.
+fn upload_context<'a>(state: &'a ServiceState, meta: &RequestMeta) -> Option<UploadContext<'a>> {
+    let threshold = state
+        .config()
+        .upload()
+        .envelope_attachment_threshold
+        .as_ref()?;
+
+    if !state
+        .global_config_handle()
+        .current()
+        .unwrap_or_default()
+        .options
+        .endpoint_fetch_config_enabled
+    {
+        return None;
+    }
+
+    let project = state.project_cache_handle().get(meta.public_key());
+
+    let project_config = match project.state() {
+        ProjectState::Enabled(info) => info.clone(),
+        // Leave disabled and pending projects to the regular envelope checks.
+        ProjectState::Dummy | ProjectState::Disabled | ProjectState::Pending => return None,
+    };
+
+    if !project_config.has_feature(Feature::EnvelopeAttachmentUploads) {
+        return None;
+    }
+
+    let scoping = project_config.scoping(meta.public_key())?;
+
+    let rate_limits = project.rate_limits().current_limits().check_with_quotas(
+        project_config.get_quotas(),
+        scoping.item(DataCategory::Attachment),
+    );
+
+    Some(UploadContext {
+        upload: state.upload(),
+        project: ProjectContext {
+            scoping,
+            upstream: project_config.upstream.clone(),
+        },
+        threshold: threshold.as_bytes(),
+        upload_minidumps: project_config.has_feature(Feature::MinidumpUploads),
+        rate_limits,
+    })
+}
+
+/// Reads an envelope incrementally from the request body.
+///
+/// Large attachments are uploaded while the request body is being read and replaced by
+/// placeholders. Attachments exceeding the upload size limit fail the request before they are
+/// read, and attachments that are rate limited are dropped without uploading them.
+async fn read_envelope<S>(
+    state: &ServiceState,
+    stream: S,
+    meta: RequestMeta,
+    upload_context: UploadContext<'_>,
+) -> Result<(Box<Envelope>, RateLimits), BadStoreRequest>
+where
+    S: Stream<Item = io::Result<Bytes>> + Unpin,
+{
+    let config = state.config();
+    let mut reader =
+        EnvelopeReader::new(stream, config.max_envelope_size(), config.max_upload_size());
+
+    let headers = match reader.read_headers(meta.clone()).await {
+        Err(EnvelopeError::MissingHeader) => return Err(BadStoreRequest::EmptyBody),
+        result => result.map_err(envelope_error)?,
+    };
+
+    let mut items = Items::new();
+    let mut rate_limits = RateLimits::new();
+    let mut upload_size = 0;
+
+    while let Some((mut item, length)) = reader.read_item_headers().await.map_err(envelope_error)? {
+        let Some(length) = length else {
+            reader
+                .read_payload(&mut item, None)
+                .await
+                .map_err(envelope_error)?;
+            items.push(item);
+            continue;
+        };
+
+        let decision = upload_context.upload_decision(&item, length);
+        if !matches!(decision, UploadDecision::Inline) {
+            // Check the size limit before reading anything. Dropped attachments count towards
+            // the limit, too, since their payload still has to be read from the request body.
+            upload_size += length;
+            if upload_size > config.max_upload_size() {
+                let attachment_type = item.attachment_type().unwrap_or_default();
+                return Err(BadStoreRequest::ItemTooLarge(attachment_type.into()));
+            }
+        }
+
+        match decision {
+            UploadDecision::Inline => {
+                reader
+                    .read_payload(&mut item, Some(length))
+                    .await
+                    .map_err(envelope_error)?;
+                items.push(item);
+            }
+            UploadDecision::Drop(limits) => {
+                let mut remaining = length;
+                while reader
+                    .read_chunk(&mut remaining)
+                    .await
+                    .map_err(envelope_error)?
+                    .is_some()
+                {}
+
+                let mut dropped = Managed::with_meta_from_request_meta(
+                    &meta,
+                    state.outcome_aggregator(),
+                    [
+                        (DataCategory::Attachment, length),
+                        (DataCategory::AttachmentItem, 1),
+                    ],
+                );
+                dropped.scope(upload_context.project.scoping);
+                let _ = dropped.reject_err(Outcome::RateLimited(
+                    limits.longest().and_then(|l| l.reason_code.clone()),
+                ));
+                rate_limits.merge(limits);
+            }
+            UploadDecision::Upload => {
+                let is_minidump = item.attachment_type() == Some(AttachmentType::Minidump);
+                if let Some(item) =
+                    upload_attachment(state, &mut reader, &meta, &upload_context, item, length)
+                        .await?
+                {
+                    items.push(item);
+                } else if is_minidump {
+                    // A failed minidump upload should cause the entire request to be rejected.
+                    return Err(BadStoreRequest::ObjectstoreUploadFailed);
+                }
+            }
+        }
+    }
+
+    Ok((Envelope::from_request_parts(headers, items), rate_limits))
+}
+
+/// Streams the payload of an attachment from the request body to the upload service.
+///
+/// Returns the attachment placeholder, or `None` if the upload failed. The payload is read in
+/// its entirety in either case, so that the next item can be read.
+async fn upload_attachment<S>(
+    state: &ServiceState,
+    reader: &mut EnvelopeReader<S>,
+    meta: &RequestMeta,
+    upload_context: &UploadContext<'_>,
+    item: Item,
+    length: usize,
+) -> Result<Option<Item>, BadStoreRequest>
+where
+    S: Stream<Item = io::Result<Bytes>> + Unpin,
+{
+    let content_type = item.raw_content_type().map(str::to_owned);
+    let mut item = Managed::with_meta_from_request_meta(meta, state.outcome_aggregator(), item);
+    item.scope(upload_context.project.scoping);
+
+    let (tx, rx) = mpsc::channel(1);
+    let stream = futures::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|chunk| (chunk, rx))
+    });
+
+    let upload = common::upload_to_objectstore(
+        stream,
+        content_type,
+        item,
+        state.config(),
+        upload_context.project.clone(),
+        upload_context.upload,
+        "envelope",
+    );
+
+    let forward = async move {
+        let mut remaining = length;
+        loop {
+            match reader.read_chunk(&mut remaining).await {
+                // Keep reading if the upload has failed, the upload reports the error.
+                Ok(Some(chunk)) => {
+                    let _ = tx.send(Ok(chunk)).await;
+                }
+                Ok(None) => return Ok(()),
+                Err(error) => {
+                    let _ = tx
+                        .send(Err(io::Error::other("incomplete attachment")))
+                        .await;
+                    return Err(error);
+                }
+            }
+        }
+    };
+
+    let (uploaded, forwarded) = tokio::join!(upload, forward);
+    forwarded.map_err(envelope_error)?;
+
+    Ok(uploaded.ok().map(|item| item.accept(|item| item)))
+}
+
+fn envelope_error(error: EnvelopeError) -> BadStoreRequest {
+    match error {
+        EnvelopeError::TooLarge => BadStoreRequest::RequestTooLarge,
+        EnvelopeError::ReadFailed(error) => {
+            match find_error_source(&error, is_length_limit_error) {
+                Some(_) => BadStoreRequest::RequestTooLarge,
+                None => BadStoreRequest::InvalidBody(error),
+            }
+        }
+        error => BadStoreRequest::InvalidEnvelope(error),
+    }
+}
+
 #[derive(Serialize)]
 struct StoreResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -117,10 +449,10 @@ async fn handle(
     state: ServiceState,
     params: EnvelopeParams,
 ) -> axum::response::Result<impl IntoResponse> {
-    let envelope = params.extract_envelope()?;
-    let id = common::handle_envelope(&state, envelope)
-        .await?
-        .check_rate_limits()?;
+    let (envelope, rate_limits) = params.extract_envelope(&state).await?;
+    let mut handled = common::handle_envelope(&state, envelope).await?;
+    handled.rate_limits.merge(rate_limits);
+    let id = handled.check_rate_limits()?;
     Ok(Json(StoreResponse { id }))
 }
 
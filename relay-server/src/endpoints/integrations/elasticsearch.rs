@@ -0,0 +1,460 @@
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+use axum::extract::{DefaultBodyLimit, Path};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{MethodRouter, get, post};
+use relay_config::Config;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::endpoints::common;
+use crate::extractors::IntegrationBuilder;
+use crate::integrations::LogsIntegration;
+use crate::service::ServiceState;
+
+/// Header with which Elasticsearch identifies itself, checked by some clients.
+const PRODUCT_HEADER: (&str, &str) = ("X-Elastic-Product", "Elasticsearch");
+
+/// The Elasticsearch version reported to clients.
+///
+/// Log shippers check the version to select the request format. All supported shippers send
+/// compatible `_bulk` requests for this version.
+const VERSION: &str = "8.11.0";
+
+/// The index reported for documents when neither the request path nor the action specify one.
+const DEFAULT_INDEX: &str = "sentry";
+
+/// All routes configured for the Elasticsearch integration.
+///
+/// The integration currently supports the following endpoints:
+///  - Cluster information, used by log shippers to detect the version
+///  - Bulk API, restricted to `index` and `create` actions
+pub fn routes(config: &Config) -> axum::Router<ServiceState> {
+    axum::Router::new()
+        .route("/", info::route())
+        .route("/_bulk", bulk::route(config))
+        .route("/_bulk/", bulk::route(config))
+        .route("/{index}/_bulk", bulk::route(config))
+        .route("/{index}/_bulk/", bulk::route(config))
+}
+
+mod info {
+    use super::*;
+
+    #[derive(Debug, Serialize)]
+    struct VersionInfo {
+        number: &'static str,
+        build_flavor: &'static str,
+    }
+
+    #[derive(Debug, Serialize)]
+    struct InfoResponse {
+        name: &'static str,
+        cluster_name: &'static str,
+        version: VersionInfo,
+        tagline: &'static str,
+    }
+
+    async fn handle() -> impl IntoResponse {
+        let response = InfoResponse {
+            name: "relay",
+            cluster_name: "sentry",
+            version: VersionInfo {
+                number: VERSION,
+                build_flavor: "default",
+            },
+            tagline: "You Know, for Search",
+        };
+
+        ([PRODUCT_HEADER], axum::Json(response))
+    }
+
+    pub fn route() -> MethodRouter<ServiceState> {
+        get(handle)
+    }
+}
+
+mod bulk {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct BulkPath {
+        index: Option<String>,
+    }
+
+    async fn handle(
+        Path(path): Path<BulkPath>,
+        state: ServiceState,
+        builder: IntegrationBuilder,
+    ) -> axum::response::Result<Response> {
+        let start = Instant::now();
+        let default_index = path.index.as_deref().unwrap_or(DEFAULT_INDEX);
+
+        let mut request = match parse_bulk(builder.payload(), default_index) {
+            Ok(request) => request,
+            Err(error) => return Ok(error.into_response()),
+        };
+
+        if !request.documents.is_empty() {
+            let envelope = builder
+                .with_payload(std::mem::take(&mut request.documents))
+                .with_type(LogsIntegration::ElasticsearchBulk)
+                .build();
+
+            let handled = common::handle_envelope(&state, envelope).await?;
+
+            // Rate limits apply to the entire request, all documents have been dropped.
+            if handled.rate_limits.is_limited() {
+                for item in &mut request.items {
+                    if let ItemStatus::Created = item.status {
+                        item.status = ItemStatus::RateLimited;
+                    }
+                }
+            }
+        }
+
+        let response = BulkResponse::new(request.items, start.elapsed().as_millis() as u64);
+        Ok(([PRODUCT_HEADER], axum::Json(response)).into_response())
+    }
+
+    pub fn route(config: &Config) -> MethodRouter<ServiceState> {
+        post(handle).route_layer(DefaultBodyLimit::max(config.max_logs_integration_size()))
+    }
+}
+
+/// An action of the `_bulk` API.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Action {
+    Index(ActionMeta),
+    Create(ActionMeta),
+    Update(ActionMeta),
+    Delete(ActionMeta),
+}
+
+/// Metadata of a `_bulk` action.
+#[derive(Debug, Default, Deserialize)]
+struct ActionMeta {
+    #[serde(rename = "_index")]
+    index: Option<String>,
+    #[serde(rename = "_id")]
+    id: Option<String>,
+}
+
+/// The result of a single action in a `_bulk` request.
+#[derive(Debug, PartialEq)]
+enum ItemStatus {
+    /// The document was accepted.
+    Created,
+    /// The document is not a valid JSON object.
+    Invalid(String),
+    /// The action is neither `index` nor `create`.
+    Unsupported,
+    /// The document was dropped due to rate limits.
+    RateLimited,
+}
+
+/// A single action of a `_bulk` request.
+#[derive(Debug)]
+struct BulkItem {
+    action: &'static str,
+    index: String,
+    id: String,
+    status: ItemStatus,
+}
+
+/// A parsed `_bulk` request.
+#[derive(Debug)]
+struct BulkRequest {
+    /// All actions in order of the request.
+    items: Vec<BulkItem>,
+    /// All valid documents as newline delimited JSON.
+    documents: Vec<u8>,
+}
+
+/// An error which rejects an entire `_bulk` request.
+#[derive(Debug, thiserror::Error)]
+enum BulkError {
+    #[error("malformed action/metadata line [{line}]: {source}")]
+    InvalidAction {
+        line: usize,
+        source: serde_json::Error,
+    },
+    #[error("the bulk request must be terminated by a newline [{line}]")]
+    MissingDocument { line: usize },
+}
+
+impl IntoResponse for BulkError {
+    fn into_response(self) -> Response {
+        let body = serde_json::json!({
+            "error": {
+                "type": "illegal_argument_exception",
+                "reason": self.to_string(),
+            },
+            "status": 400,
+        });
+
+        (StatusCode::BAD_REQUEST, [PRODUCT_HEADER], axum::Json(body)).into_response()
+    }
+}
+
+/// Parses a `_bulk` request consisting of newline delimited action and document lines.
+///
+/// Documents of `index` and `create` actions which are valid JSON objects are collected into
+/// [`BulkRequest::documents`], all other actions are reported as failed items.
+fn parse_bulk(payload: &[u8], default_index: &str) -> Result<BulkRequest, BulkError> {
+    let mut lines = payload
+        .split(|&b| b == b'\n')
+        .map(<[u8]>::trim_ascii)
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty());
+
+    let mut request = BulkRequest {
+        items: Vec::new(),
+        documents: Vec::new(),
+    };
+
+    while let Some((line, action)) = lines.next() {
+        let action = serde_json::from_slice::<Action>(action).map_err(|source| {
+            BulkError::InvalidAction {
+                line: line + 1,
+                source,
+            }
+        })?;
+
+        let (name, meta) = match action {
+            Action::Index(meta) => ("index", meta),
+            Action::Create(meta) => ("create", meta),
+            Action::Update(meta) => ("update", meta),
+            Action::Delete(meta) => ("delete", meta),
+        };
+
+        let status = match name {
+            // Deletes are the only actions without a document.
+            "delete" => ItemStatus::Unsupported,
+            _ => {
+                let (_, document) = lines
+                    .next()
+                    .ok_or(BulkError::MissingDocument { line: line + 1 })?;
+
+                match serde_json::from_slice::<Map<String, Value>>(document) {
+                    Ok(_) if name == "update" => ItemStatus::Unsupported,
+                    Ok(_) => {
+                        request.documents.extend_from_slice(document);
+                        request.documents.push(b'\n');
+                        ItemStatus::Created
+                    }
+                    Err(error) => ItemStatus::Invalid(error.to_string()),
+                }
+            }
+        };
+
+        request.items.push(BulkItem {
+            action: name,
+            index: meta.index.unwrap_or_else(|| default_index.to_owned()),
+            id: meta
+                .id
+                .unwrap_or_else(|| Uuid::new_v4().as_simple().to_string()),
+            status,
+        });
+    }
+
+    Ok(request)
+}
+
+/// The response body of the `_bulk` API.
+#[derive(Debug, Serialize)]
+struct BulkResponse {
+    took: u64,
+    errors: bool,
+    items: Vec<BTreeMap<&'static str, BulkItemResponse>>,
+}
+
+impl BulkResponse {
+    fn new(items: Vec<BulkItem>, took: u64) -> Self {
+        let errors = items.iter().any(|i| i.status != ItemStatus::Created);
+        let items = items
+            .into_iter()
+            .map(|item| BTreeMap::from([(item.action, BulkItemResponse::from(item))]))
+            .collect();
+
+        Self {
+            took,
+            errors,
+            items,
+        }
+    }
+}
+
+/// The result of a single action in the `_bulk` response.
+#[derive(Debug, Serialize)]
+struct BulkItemResponse {
+    #[serde(rename = "_index")]
+    index: String,
+    #[serde(rename = "_id")]
+    id: String,
+    status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ErrorCause>,
+}
+
+/// The cause of a failed action in the `_bulk` response.
+#[derive(Debug, Serialize)]
+struct ErrorCause {
+    #[serde(rename = "type")]
+    ty: &'static str,
+    reason: String,
+}
+
+impl From<BulkItem> for BulkItemResponse {
+    fn from(item: BulkItem) -> Self {
+        let (status, ty, reason) = match item.status {
+            ItemStatus::Created => (StatusCode::CREATED, None, String::new()),
+            ItemStatus::Invalid(reason) => (
+                StatusCode::BAD_REQUEST,
+                Some("document_parsing_exception"),
+                reason,
+            ),
+            ItemStatus::Unsupported => (
+                StatusCode::BAD_REQUEST,
+                Some("action_request_validation_exception"),
+                format!("{} actions are not supported", item.action),
+            ),
+            ItemStatus::RateLimited => (
+                StatusCode::TOO_MANY_REQUESTS,
+                Some("es_rejected_execution_exception"),
+                "rate limited".to_owned(),
+            ),
+        };
+
+        Self {
+            index: item.index,
+            id: item.id,
+            status: status.as_u16(),
+            result: ty.is_none().then_some("created"),
+            error: ty.map(|ty| ErrorCause { ty, reason }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bulk() {
+        let payload = br#"{"index":{"_index":"logs","_id":"1"}}
+{"message":"first","level":"info"}
+{"create":{}}
+{"message":"second"}
+
+{"delete":{"_id":"2"}}
+{"update":{"_id":"3"}}
+{"doc":{"message":"updated"}}
+{"index":{"_id":"4"}}
+not json
+{"index":{"_id":"5"}}
+["not","an","object"]
+"#;
+
+        let request = parse_bulk(payload, "default").unwrap();
+
+        assert_eq!(
+            request.documents,
+            b"{\"message\":\"first\",\"level\":\"info\"}\n{\"message\":\"second\"}\n"
+        );
+
+        let items: Vec<_> = request
+            .items
+            .iter()
+            .map(|i| (i.action, i.index.as_str(), &i.status))
+            .collect();
+        assert!(matches!(
+            items.as_slice(),
+            [
+                ("index", "logs", ItemStatus::Created),
+                ("create", "default", ItemStatus::Created),
+                ("delete", "default", ItemStatus::Unsupported),
+                ("update", "default", ItemStatus::Unsupported),
+                ("index", "default", ItemStatus::Invalid(_)),
+                ("index", "default", ItemStatus::Invalid(_)),
+            ]
+        ));
+        assert_eq!(request.items[0].id, "1");
+        assert_eq!(request.items[1].id.len(), 32);
+    }
+
+    #[test]
+    fn test_parse_bulk_invalid() {
+        let error = parse_bulk(b"{\"index\":{}}\n", "default").unwrap_err();
+        assert!(matches!(error, BulkError::MissingDocument { line: 1 }));
+
+        let error = parse_bulk(b"{\"message\":\"no action\"}\n{}\n", "default").unwrap_err();
+        assert!(matches!(error, BulkError::InvalidAction { line: 1, .. }));
+    }
+
+    #[test]
+    fn test_bulk_response() {
+        let item = |status| BulkItem {
+            action: "create",
+            index: "logs".to_owned(),
+            id: "1".to_owned(),
+            status,
+        };
+
+        let response = BulkResponse::new(vec![item(ItemStatus::Created)], 3);
+        insta::assert_json_snapshot!(response, @r#"
+        {
+          "took": 3,
+          "errors": false,
+          "items": [
+            {
+              "create": {
+                "_index": "logs",
+                "_id": "1",
+                "status": 201,
+                "result": "created"
+              }
+            }
+          ]
+        }
+        "#);
+
+        let response = BulkResponse::new(
+            vec![item(ItemStatus::Created), item(ItemStatus::RateLimited)],
+            3,
+        );
+        insta::assert_json_snapshot!(response, @r#"
+        {
+          "took": 3,
+          "errors": true,
+          "items": [
+            {
+              "create": {
+                "_index": "logs",
+                "_id": "1",
+                "status": 201,
+                "result": "created"
+              }
+            },
+            {
+              "create": {
+                "_index": "logs",
+                "_id": "1",
+                "status": 429,
+                "error": {
+                  "type": "es_rejected_execution_exception",
+                  "reason": "rate limited"
+                }
+              }
+            }
+          ]
+        }
+        "#);
+    }
+}
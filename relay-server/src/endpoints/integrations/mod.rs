@@ -1,2 +1,5 @@
+pub mod elasticsearch;
 pub mod otlp;
+pub mod syslog;
 pub mod vercel;
+pub mod zipkin;
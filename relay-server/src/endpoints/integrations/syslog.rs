@@ -0,0 +1,56 @@
+use axum::extract::DefaultBodyLimit;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::routing::{MethodRouter, post};
+use relay_config::Config;
+
+use crate::endpoints::common;
+use crate::envelope::ContentType;
+use crate::extractors::{IntegrationBuilder, RawContentType};
+use crate::integrations::{LogsIntegration, SyslogFormat};
+use crate::service::ServiceState;
+
+/// Content type of logplex HTTPS drains, for example sent by Heroku.
+const LOGPLEX_CONTENT_TYPE: &str = "application/logplex-1";
+
+/// All routes configured for the syslog integration.
+///
+/// The integration currently supports the following endpoints:
+///  - Syslog Log Drain
+pub fn routes(config: &Config) -> axum::Router<ServiceState> {
+    axum::Router::new()
+        .route("/logs", logs::route(config))
+        .route("/logs/", logs::route(config))
+}
+
+mod logs {
+    use super::*;
+
+    async fn handle(
+        content_type: RawContentType,
+        state: ServiceState,
+        builder: IntegrationBuilder,
+    ) -> axum::response::Result<impl IntoResponse> {
+        let format = match content_type.as_ref() {
+            ct if ct.eq_ignore_ascii_case(LOGPLEX_CONTENT_TYPE) => SyslogFormat::Logplex,
+            ct => match ct.parse::<ContentType>() {
+                Ok(ContentType::Text) => SyslogFormat::Text,
+                _ => return Ok(StatusCode::UNSUPPORTED_MEDIA_TYPE),
+            },
+        };
+
+        let envelope = builder
+            .with_type(LogsIntegration::Syslog { format })
+            .build();
+
+        common::handle_envelope(&state, envelope)
+            .await?
+            .check_rate_limits()?;
+
+        Ok(StatusCode::ACCEPTED)
+    }
+
+    pub fn route(config: &Config) -> MethodRouter<ServiceState> {
+        post(handle).route_layer(DefaultBodyLimit::max(config.max_logs_integration_size()))
+    }
+}
@@ -0,0 +1,52 @@
+use axum::extract::DefaultBodyLimit;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::routing::{MethodRouter, post};
+use relay_config::Config;
+
+use crate::endpoints::common;
+use crate::envelope::ContentType;
+use crate::extractors::{IntegrationBuilder, RawContentType};
+use crate::integrations::{SpansIntegration, ZipkinFormat};
+use crate::service::ServiceState;
+
+/// All routes configured for the Zipkin integration.
+///
+/// The integration currently supports the following endpoints:
+///  - V2 Spans
+pub fn routes(config: &Config) -> axum::Router<ServiceState> {
+    axum::Router::new()
+        .route("/api/v2/spans", spans::route(config))
+        .route("/api/v2/spans/", spans::route(config))
+}
+
+mod spans {
+    use super::*;
+
+    async fn handle(
+        content_type: RawContentType,
+        state: ServiceState,
+        builder: IntegrationBuilder,
+    ) -> axum::response::Result<impl IntoResponse> {
+        let format = match content_type.as_ref().parse::<ContentType>() {
+            Ok(ContentType::Json) => ZipkinFormat::Json,
+            Ok(ContentType::Protobuf) => ZipkinFormat::Protobuf,
+            _ => return Ok(StatusCode::UNSUPPORTED_MEDIA_TYPE),
+        };
+
+        let envelope = builder
+            .with_type(SpansIntegration::ZipkinV2 { format })
+            .build();
+
+        common::handle_envelope(&state, envelope)
+            .await?
+            .check_rate_limits()?;
+
+        // Zipkin collectors acknowledge accepted spans with `202 Accepted`.
+        Ok(StatusCode::ACCEPTED)
+    }
+
+    pub fn route(config: &Config) -> MethodRouter<ServiceState> {
+        post(handle).route_layer(DefaultBodyLimit::max(config.max_spans_integration_size()))
+    }
+}
@@ -15,6 +15,7 @@ mod integrations;
 mod minidump;
 mod monitor;
 mod nel;
+mod outcomes;
 #[cfg(sentry)]
 mod playstation;
 mod project_configs;
@@ -56,6 +57,14 @@ pub fn internal_routes(_: &Config) -> Router<ServiceState>{
         .route("/api/relay/{*not_found}", any(statics::not_found))
 }
 
+/// Relay's internal routes on a dedicated internal listener.
+///
+/// In addition to [`internal_routes`], this includes routes which are not authenticated and must
+/// never be exposed on the public listener.
+pub fn dedicated_internal_routes(config: &Config) -> Router<ServiceState> {
+    internal_routes(config).route("/api/relay/outcomes/", get(outcomes::handle))
+}
+
 /// Relay's public routes.
 ///
 /// Routes which are public API and must be exposed.
@@ -114,6 +123,9 @@ fn public_routes_raw(config: &Config) -> Router<ServiceState> {
     let integration_routes = Router::new()
         .nest("/api/{project_id}/integration/otlp", integrations::otlp::routes(config))
         .nest("/api/{project_id}/integration/vercel", integrations::vercel::routes(config))
+        .nest("/api/{project_id}/integration/syslog", integrations::syslog::routes(config))
+        .nest("/api/{project_id}/integration/elasticsearch", integrations::elasticsearch::routes(config))
+        .nest("/api/{project_id}/integration/zipkin", integrations::zipkin::routes(config))
         .route_layer(middlewares::cors());
 
     // NOTE: If you add a new (non-experimental) route here, please also list it in
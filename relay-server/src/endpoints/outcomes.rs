@@ -0,0 +1,27 @@
+//! Internal endpoint to query the local outcome ledger.
+
+use axum::extract::Query;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+
+use crate::service::ServiceState;
+use crate::services::outcome::{LedgerEntry, LedgerQuery};
+
+#[derive(Debug, Serialize)]
+struct OutcomesResponse {
+    outcomes: Vec<LedgerEntry>,
+}
+
+/// Returns outcomes recorded in the local outcome ledger.
+///
+/// Supports filtering by `project_key` and a time range with `start` and `end` as RFC 3339
+/// timestamps.
+pub async fn handle(state: ServiceState, Query(query): Query<LedgerQuery>) -> Response {
+    let Some(ledger) = state.outcome_ledger() else {
+        return (StatusCode::NOT_FOUND, "Outcome ledger not enabled").into_response();
+    };
+
+    let outcomes = ledger.query(&query);
+    axum::Json(OutcomesResponse { outcomes }).into_response()
+}
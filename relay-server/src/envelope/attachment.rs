@@ -52,6 +52,9 @@ pub enum AttachmentType {
 
     /// An application UI view hierarchy (json payload).
     ViewHierarchy,
+
+    /// A HotSpot JVM fatal error log (`hs_err_pid<N>.log`, text data).
+    HsErrLog,
 }
 
 impl fmt::Display for AttachmentType {
@@ -67,6 +70,7 @@ impl fmt::Display for AttachmentType {
             AttachmentType::UnrealContext => write!(f, "unreal.context"),
             AttachmentType::UnrealLogs => write!(f, "unreal.logs"),
             AttachmentType::ViewHierarchy => write!(f, "event.view_hierarchy"),
+            AttachmentType::HsErrLog => write!(f, "jvm.hs_err_log"),
         }
     }
 }
@@ -99,6 +103,7 @@ impl std::str::FromStr for AttachmentType {
             "event.view_hierarchy" => AttachmentType::ViewHierarchy,
             "unreal.context" => AttachmentType::UnrealContext,
             "unreal.logs" => AttachmentType::UnrealLogs,
+            "jvm.hs_err_log" => AttachmentType::HsErrLog,
             _ => return Err(UnknownAttachmentType),
         })
     }
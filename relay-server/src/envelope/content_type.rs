@@ -40,6 +40,10 @@ pub enum ContentType {
     AttachmentRef,
     /// `application/x-perfetto-trace`
     PerfettoTrace,
+    /// `application/x-pprof`
+    Pprof,
+    /// `application/x-otlp-profiles+protobuf`
+    OtlpProfiles,
     /// All integration content types.
     Integration(Integration),
 }
@@ -63,6 +67,8 @@ impl ContentType {
             Self::TraceAttachment => "application/vnd.sentry.trace-attachment",
             Self::AttachmentRef => "application/vnd.sentry.attachment-ref+json",
             Self::PerfettoTrace => "application/x-perfetto-trace",
+            Self::Pprof => "application/x-pprof",
+            Self::OtlpProfiles => "application/x-otlp-profiles+protobuf",
             Self::Integration(integration) => integration.as_content_type(),
         }
     }
@@ -114,6 +120,10 @@ impl ContentType {
             Some(Self::AttachmentRef)
         } else if ct.eq_ignore_ascii_case(Self::PerfettoTrace.as_str()) {
             Some(Self::PerfettoTrace)
+        } else if ct.eq_ignore_ascii_case(Self::Pprof.as_str()) {
+            Some(Self::Pprof)
+        } else if ct.eq_ignore_ascii_case(Self::OtlpProfiles.as_str()) {
+            Some(Self::OtlpProfiles)
         } else {
             Integration::from_content_type(ct).map(Self::Integration)
         }
@@ -180,12 +180,16 @@ impl Item {
                 Some(Integration::Logs(
                     LogsIntegration::Nel
                     | LogsIntegration::OtelV1 { .. }
-                    | LogsIntegration::VercelDrainLog { .. },
+                    | LogsIntegration::VercelDrainLog { .. }
+                    | LogsIntegration::Syslog { .. }
+                    | LogsIntegration::ElasticsearchBulk,
                 )) => smallvec![
                     (DataCategory::LogByte, self.len().max(1)),
                     (DataCategory::LogItem, item_count),
                 ],
-                Some(Integration::Spans(SpansIntegration::OtelV1 { .. })) => {
+                Some(Integration::Spans(
+                    SpansIntegration::OtelV1 { .. } | SpansIntegration::ZipkinV2 { .. },
+                )) => {
                     smallvec![
                         (DataCategory::Span, item_count),
                         (DataCategory::SpanIndexed, item_count),
@@ -656,7 +660,8 @@ impl Item {
                         | AttachmentType::EventPayload
                         | AttachmentType::Prosperodump
                         | AttachmentType::Breadcrumbs
-                        | AttachmentType::NintendoSwitchDyingMessage,
+                        | AttachmentType::NintendoSwitchDyingMessage
+                        | AttachmentType::HsErrLog,
                     ) => true,
                     Some(
                         AttachmentType::Attachment
@@ -1150,7 +1155,10 @@ impl ItemHeaders {
             .and_then(|v| T::deserialize(&v.0).ok())
     }
 
-    fn try_get<'a, T>(&'a self, key: ItemHeaderKey) -> Result<Option<T>, serde_json::Error>
+    pub(super) fn try_get<'a, T>(
+        &'a self,
+        key: ItemHeaderKey,
+    ) -> Result<Option<T>, serde_json::Error>
     where
         T: Deserialize<'a>,
     {
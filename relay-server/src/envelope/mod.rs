@@ -56,12 +56,14 @@ mod container;
 mod content_type;
 mod item;
 mod meta;
+mod reader;
 
 pub use self::attachment::*;
 pub use self::container::*;
 pub use self::content_type::*;
 pub use self::item::*;
 pub use self::meta::*;
+pub use self::reader::*;
 
 #[derive(Debug, thiserror::Error)]
 pub enum EnvelopeError {
@@ -81,6 +83,10 @@ pub enum EnvelopeError {
     HeaderIoFailed(#[source] serde_json::Error),
     #[error("failed to write payload")]
     PayloadIoFailed(#[source] io::Error),
+    #[error("failed to read envelope")]
+    ReadFailed(#[source] io::Error),
+    #[error("envelope exceeds the maximum size")]
+    TooLarge,
 }
 
 #[derive(Clone, Deserialize, Serialize)]
@@ -337,15 +343,23 @@ impl Envelope {
         request_meta: RequestMeta,
     ) -> Result<Box<Self>, EnvelopeError> {
         let (partial_headers, offset) = Self::parse_headers::<PartialMeta>(&bytes)?;
-        let mut headers = partial_headers.complete(request_meta)?;
+        let headers = partial_headers.complete(request_meta)?;
+        let items = Self::parse_items(&bytes, offset)?;
+
+        Ok(Self::from_request_parts(headers, items))
+    }
 
+    /// Creates an envelope from headers and items parsed from a web request.
+    ///
+    /// If no event id is provided explicitly but one of the items requires it, an event id is
+    /// created on the fly. See also [`EnvelopeReader`].
+    pub fn from_request_parts(mut headers: EnvelopeHeaders, items: Items) -> Box<Self> {
         // Event-related envelopes *must* contain an event id.
-        let items = Self::parse_items(&bytes, offset)?;
         if items.iter().any(Item::requires_event) {
             headers.event_id.get_or_insert_with(EventId::new);
         }
 
-        Ok(Box::new(Envelope { headers, items }))
+        Box::new(Envelope { headers, items })
     }
 
     /// Move the envelope's items into an envelope with the same headers.
@@ -0,0 +1,401 @@
+use std::io;
+
+use bytes::{Buf, Bytes, BytesMut};
+use futures::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+
+use crate::envelope::{EnvelopeError, EnvelopeHeaders, Item, ItemHeaderKey, ItemHeaders};
+use crate::extractors::{PartialMeta, RequestMeta};
+
+/// Parses an envelope incrementally from a stream of bytes.
+///
+/// In contrast to [`Envelope::parse_request`](super::Envelope::parse_request), the reader does not
+/// require the entire envelope in memory. The headers of each item are read first, which allows
+/// the caller to decide whether the payload is read into memory with [`Self::read_payload`] or
+/// passed on chunk by chunk with [`Self::read_chunk`].
+///
+/// Headers and payloads read into memory count towards the maximum size of the reader. Payloads
+/// read in chunks count towards a separate maximum, since they are never held in memory entirely.
+pub struct EnvelopeReader<S> {
+    stream: S,
+    buffer: BytesMut,
+    eof: bool,
+    remaining_size: usize,
+    remaining_chunked_size: usize,
+}
+
+impl<S> EnvelopeReader<S>
+where
+    S: Stream<Item = io::Result<Bytes>> + Unpin,
+{
+    /// Creates a reader that holds at most `max_size` bytes of the envelope in memory.
+    ///
+    /// Payloads read with [`Self::read_chunk`] may add up to at most `max_chunked_size` bytes.
+    pub fn new(stream: S, max_size: usize, max_chunked_size: usize) -> Self {
+        Self {
+            stream,
+            buffer: BytesMut::new(),
+            eof: false,
+            remaining_size: max_size,
+            remaining_chunked_size: max_chunked_size,
+        }
+    }
+
+    /// Reads the envelope headers and validates them against the request.
+    ///
+    /// This must be called once before reading any items.
+    pub async fn read_headers(
+        &mut self,
+        request_meta: RequestMeta,
+    ) -> Result<EnvelopeHeaders, EnvelopeError> {
+        let headers: EnvelopeHeaders<PartialMeta> = self
+            .read_json(EnvelopeError::InvalidHeader)
+            .await?
+            .ok_or(EnvelopeError::MissingHeader)?;
+
+        headers.complete(request_meta)
+    }
+
+    /// Reads the headers of the next item.
+    ///
+    /// Returns the item without payload and the value of its `length` header, or `None` at the end
+    /// of the envelope. The payload must be read with [`Self::read_payload`] or
+    /// [`Self::read_chunk`] before reading the next item.
+    pub async fn read_item_headers(
+        &mut self,
+    ) -> Result<Option<(Item, Option<usize>)>, EnvelopeError> {
+        while self.buffer.is_empty() {
+            if !self.fill().await? {
+                return Ok(None);
+            }
+        }
+
+        let headers: ItemHeaders = self
+            .read_json(EnvelopeError::InvalidItemHeader)
+            .await?
+            .ok_or(EnvelopeError::UnexpectedEof)?;
+
+        let length = headers
+            .try_get::<usize>(ItemHeaderKey::Length)
+            .map_err(EnvelopeError::InvalidItemHeader)?;
+
+        let item = Item {
+            headers,
+            payload: Bytes::new(),
+        };
+
+        Ok(Some((item, length)))
+    }
+
+    /// Reads the payload of an item into memory.
+    ///
+    /// Items without a `length` header are read until the next newline.
+    pub async fn read_payload(
+        &mut self,
+        item: &mut Item,
+        length: Option<usize>,
+    ) -> Result<(), EnvelopeError> {
+        let payload = match length {
+            Some(length) => {
+                // Check the declared length before reading, so oversized items fail fast.
+                if length > self.remaining_size {
+                    return Err(EnvelopeError::TooLarge);
+                }
+                while self.buffer.len() < length {
+                    if !self.fill().await? {
+                        return Err(EnvelopeError::UnexpectedEof);
+                    }
+                }
+                self.take(length)?
+            }
+            None => loop {
+                if let Some(end) = self.buffer.iter().position(|b| *b == b'\n') {
+                    break self.take(end)?;
+                }
+                if self.buffer.len() > self.remaining_size {
+                    return Err(EnvelopeError::TooLarge);
+                }
+                if !self.fill().await? {
+                    break self.take(self.buffer.len())?;
+                }
+            },
+        };
+
+        self.finish_payload().await?;
+        item.payload = payload;
+        Ok(())
+    }
+
+    /// Reads the next chunk of a payload without holding it in memory.
+    ///
+    /// `remaining` is the number of payload bytes left to read and is decremented by the size of
+    /// the returned chunk. Returns `None` once the entire payload has been read. This must not be
+    /// called again for the same item after it returned `None`.
+    ///
+    /// Fails before reading anything if the rest of the payload exceeds the maximum size of chunked
+    /// payloads.
+    pub async fn read_chunk(
+        &mut self,
+        remaining: &mut usize,
+    ) -> Result<Option<Bytes>, EnvelopeError> {
+        if *remaining == 0 {
+            self.finish_payload().await?;
+            return Ok(None);
+        }
+
+        if *remaining > self.remaining_chunked_size {
+            return Err(EnvelopeError::TooLarge);
+        }
+
+        while self.buffer.is_empty() {
+            if !self.fill().await? {
+                return Err(EnvelopeError::UnexpectedEof);
+            }
+        }
+
+        let len = std::cmp::min(self.buffer.len(), *remaining);
+        *remaining -= len;
+        self.remaining_chunked_size -= len;
+        Ok(Some(self.buffer.split_to(len).freeze()))
+    }
+
+    /// Reads a JSON value terminated by a newline or the end of the envelope.
+    async fn read_json<T>(
+        &mut self,
+        map_err: fn(serde_json::Error) -> EnvelopeError,
+    ) -> Result<Option<T>, EnvelopeError>
+    where
+        T: DeserializeOwned,
+    {
+        loop {
+            let mut stream = serde_json::Deserializer::from_slice(&self.buffer).into_iter();
+            match stream.next() {
+                // The value is only complete once its terminating newline has been received.
+                Some(Ok(value)) if stream.byte_offset() < self.buffer.len() || self.eof => {
+                    let end = stream.byte_offset();
+                    super::require_termination(&self.buffer, end)?;
+                    self.consume(std::cmp::min(end + 1, self.buffer.len()))?;
+                    return Ok(Some(value));
+                }
+                None if self.eof => return Ok(None),
+                Some(Err(error)) if !error.is_eof() || self.eof => return Err(map_err(error)),
+                _ => (),
+            }
+
+            if self.buffer.len() > self.remaining_size {
+                return Err(EnvelopeError::TooLarge);
+            }
+            self.fill().await?;
+        }
+    }
+
+    /// Consumes the newline terminating a payload, if present.
+    async fn finish_payload(&mut self) -> Result<(), EnvelopeError> {
+        while self.buffer.is_empty() {
+            if !self.fill().await? {
+                return Ok(());
+            }
+        }
+
+        super::require_termination(&self.buffer, 0)?;
+        self.consume(1)
+    }
+
+    /// Removes `len` bytes from the front of the buffer and returns them.
+    fn take(&mut self, len: usize) -> Result<Bytes, EnvelopeError> {
+        self.remaining_size = self
+            .remaining_size
+            .checked_sub(len)
+            .ok_or(EnvelopeError::TooLarge)?;
+        Ok(self.buffer.split_to(len).freeze())
+    }
+
+    /// Discards `len` bytes from the front of the buffer.
+    fn consume(&mut self, len: usize) -> Result<(), EnvelopeError> {
+        self.remaining_size = self
+            .remaining_size
+            .checked_sub(len)
+            .ok_or(EnvelopeError::TooLarge)?;
+        self.buffer.advance(len);
+        Ok(())
+    }
+
+    /// Appends the next chunk of the stream to the buffer.
+    ///
+    /// Returns `false` if the stream has ended.
+    async fn fill(&mut self) -> Result<bool, EnvelopeError> {
+        if self.eof {
+            return Ok(false);
+        }
+
+        match self.stream.next().await {
+            Some(Ok(chunk)) => {
+                self.buffer.extend_from_slice(&chunk);
+                Ok(true)
+            }
+            Some(Err(error)) => Err(EnvelopeError::ReadFailed(error)),
+            None => {
+                self.eof = true;
+                Ok(false)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream;
+
+    use super::*;
+    use crate::envelope::{ContentType, Envelope, ItemType, Items};
+
+    fn request_meta() -> RequestMeta {
+        let dsn = "https://e12d836b15bb49d7bbf99e64295d995b:@sentry.io/42"
+            .parse()
+            .unwrap();
+
+        RequestMeta::new(dsn)
+    }
+
+    /// Creates a reader that receives the input in chunks of `chunk_size` bytes.
+    fn reader(
+        input: &'static [u8],
+        chunk_size: usize,
+        max_size: usize,
+        max_chunked_size: usize,
+    ) -> EnvelopeReader<impl Stream<Item = io::Result<Bytes>> + Unpin> {
+        let chunks = input
+            .chunks(chunk_size)
+            .map(|chunk| Ok(Bytes::from_static(chunk)))
+            .collect::<Vec<_>>();
+        EnvelopeReader::new(stream::iter(chunks), max_size, max_chunked_size)
+    }
+
+    async fn read_envelope<S>(mut reader: EnvelopeReader<S>) -> Result<Box<Envelope>, EnvelopeError>
+    where
+        S: Stream<Item = io::Result<Bytes>> + Unpin,
+    {
+        let headers = reader.read_headers(request_meta()).await?;
+        let mut items = Items::new();
+        while let Some((mut item, length)) = reader.read_item_headers().await? {
+            reader.read_payload(&mut item, length).await?;
+            items.push(item);
+        }
+        Ok(Envelope::from_request_parts(headers, items))
+    }
+
+    const ENVELOPE: &[u8] = b"\
+        {\"event_id\":\"9ec79c33ec9942ab8353589fcb2e04dc\",\"dsn\":\"https://e12d836b15bb49d7bbf99e64295d995b:@sentry.io/42\"}\n\
+        {\"type\":\"attachment\",\"length\":10,\"content_type\":\"text/plain\",\"filename\":\"hello.txt\"}\n\
+        \xef\xbb\xbfHello\r\n\n\
+        {\"type\":\"event\",\"content_type\":\"application/json\",\"filename\":\"application.log\"}\n\
+        {\"message\":\"hello world\",\"level\":\"error\"}\n\
+    ";
+
+    #[tokio::test]
+    async fn test_read_envelope_chunked() {
+        for chunk_size in [1, 7, 64, ENVELOPE.len()] {
+            let envelope = read_envelope(reader(ENVELOPE, chunk_size, usize::MAX, usize::MAX))
+                .await
+                .unwrap();
+            let expected =
+                Envelope::parse_request(Bytes::from_static(ENVELOPE), request_meta()).unwrap();
+
+            assert_eq!(envelope.event_id(), expected.event_id());
+            assert_eq!(envelope.len(), 2);
+            for (item, expected) in envelope.items().zip(expected.items()) {
+                assert_eq!(item.ty(), expected.ty());
+                assert_eq!(item.payload(), expected.payload());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_chunks() {
+        let mut reader = reader(ENVELOPE, 4, usize::MAX, usize::MAX);
+        reader.read_headers(request_meta()).await.unwrap();
+
+        let (item, length) = reader.read_item_headers().await.unwrap().unwrap();
+        assert_eq!(item.ty(), &ItemType::Attachment);
+        assert_eq!(item.content_type(), Some(ContentType::Text));
+
+        let mut remaining = length.unwrap();
+        let mut payload = Vec::new();
+        while let Some(chunk) = reader.read_chunk(&mut remaining).await.unwrap() {
+            assert!(chunk.len() <= 4);
+            payload.extend_from_slice(&chunk);
+        }
+        assert_eq!(payload, b"\xef\xbb\xbfHello\r\n");
+
+        // The following item is read as usual.
+        let (mut item, length) = reader.read_item_headers().await.unwrap().unwrap();
+        reader.read_payload(&mut item, length).await.unwrap();
+        assert_eq!(item.ty(), &ItemType::Event);
+        assert!(reader.read_item_headers().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_read_chunks_not_limited() {
+        // Only the headers of the envelope and the event item fit into the size limit.
+        let mut reader = reader(ENVELOPE, 16, 290, usize::MAX);
+        reader.read_headers(request_meta()).await.unwrap();
+
+        let (_, length) = reader.read_item_headers().await.unwrap().unwrap();
+        let mut remaining = length.unwrap();
+        while reader.read_chunk(&mut remaining).await.unwrap().is_some() {}
+
+        let (mut item, length) = reader.read_item_headers().await.unwrap().unwrap();
+        assert!(matches!(
+            reader.read_payload(&mut item, length).await,
+            Err(EnvelopeError::TooLarge)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_read_chunks_too_large() {
+        // The attachment has a length of 10 bytes.
+        let mut reader = reader(ENVELOPE, 4, usize::MAX, 9);
+        reader.read_headers(request_meta()).await.unwrap();
+
+        let (_, length) = reader.read_item_headers().await.unwrap().unwrap();
+        let mut remaining = length.unwrap();
+        assert!(matches!(
+            reader.read_chunk(&mut remaining).await,
+            Err(EnvelopeError::TooLarge)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_read_payload_too_large() {
+        let mut reader = reader(ENVELOPE, 64, 200, usize::MAX);
+        reader.read_headers(request_meta()).await.unwrap();
+
+        let (mut item, length) = reader.read_item_headers().await.unwrap().unwrap();
+        assert!(matches!(
+            reader.read_payload(&mut item, length).await,
+            Err(EnvelopeError::TooLarge)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_read_payload_eof() {
+        let input = b"{}\n{\"type\":\"attachment\",\"length\":10}\nHello";
+        let mut reader = reader(input, 3, usize::MAX, usize::MAX);
+        reader.read_headers(request_meta()).await.unwrap();
+
+        let (mut item, length) = reader.read_item_headers().await.unwrap().unwrap();
+        assert!(matches!(
+            reader.read_payload(&mut item, length).await,
+            Err(EnvelopeError::UnexpectedEof)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_read_headers_invalid() {
+        let mut reader = reader(b"{\"event_id\":42}\n", 4, usize::MAX, usize::MAX);
+        assert!(matches!(
+            reader.read_headers(request_meta()).await,
+            Err(EnvelopeError::InvalidHeader(_))
+        ));
+    }
+}
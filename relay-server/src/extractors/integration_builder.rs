@@ -27,6 +27,20 @@ pub struct IntegrationBuilder<T = ()> {
 }
 
 impl IntegrationBuilder<()> {
+    /// Returns the payload of the request.
+    pub fn payload(&self) -> &Bytes {
+        &self.payload
+    }
+
+    /// Replaces the payload of the request.
+    ///
+    /// This can be used for integrations which need to validate or transform the payload before
+    /// it is queued for processing.
+    pub fn with_payload(mut self, payload: impl Into<Bytes>) -> Self {
+        self.payload = payload.into();
+        self
+    }
+
     /// Configures the [`Integration`] type.
     ///
     /// Setting the type is required.
@@ -12,7 +12,7 @@ use axum::http::request::Parts;
 use axum::response::{IntoResponse, Response};
 use chrono::{DateTime, Utc};
 use data_encoding::BASE64;
-use relay_auth::{RelayId, Signature};
+use relay_auth::{IngestToken, IngestTokenError, RelayId, Signature};
 use relay_base_schema::organization::OrganizationId;
 use relay_base_schema::project::{ParseProjectKeyError, ProjectId, ProjectKey};
 use relay_common::{Auth, Dsn, ParseAuthError, ParseDsnError, Scheme};
@@ -53,6 +53,9 @@ pub enum BadEventMeta {
 
     #[error("bad x-sentry-relay-signature header")]
     SignatureError(SignatureError),
+
+    #[error("bad x-sentry-ingest-token header")]
+    BadIngestToken(#[source] IngestTokenError),
 }
 
 impl From<Infallible> for BadEventMeta {
@@ -67,7 +70,8 @@ impl IntoResponse for BadEventMeta {
             Self::MissingAuth
             | Self::MultipleAuth
             | Self::BadAuth(_)
-            | Self::BadEnvelopeAuth(_) => StatusCode::UNAUTHORIZED,
+            | Self::BadEnvelopeAuth(_)
+            | Self::BadIngestToken(_) => StatusCode::UNAUTHORIZED,
             Self::UnsupportedProtocolVersion(_)
             | Self::BadProject(_)
             | Self::BadPublicKey(_)
@@ -250,6 +254,13 @@ pub struct RequestMeta<D = PartialDsn> {
     #[serde(skip)]
     signature: Option<Signature>,
 
+    /// The ingestion token sent with the request, if any.
+    ///
+    /// The token is parsed during extraction and verified against the project config. It is
+    /// retained in the envelope headers, so it can be verified again after spooling.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ingest_token: Option<IngestToken>,
+
     /// Whether the request is coming from an statically configured internal Relay.
     ///
     /// NOTE: This is internal-only and not exposed to Envelope headers.
@@ -353,6 +364,11 @@ impl<D> RequestMeta<D> {
     pub fn signature(&self) -> Option<&Signature> {
         self.signature.as_ref()
     }
+
+    /// Returns the unverified ingestion token sent with the request.
+    pub fn ingest_token(&self) -> Option<&IngestToken> {
+        self.ingest_token.as_ref()
+    }
 }
 
 impl RequestMeta {
@@ -370,6 +386,7 @@ impl RequestMeta {
             received_at: Utc::now(),
             client_hints: ClientHints::default(),
             signature: None,
+            ingest_token: None,
             request_trust: None,
         }
     }
@@ -448,6 +465,7 @@ where
             no_cache,
             received_at,
             signature,
+            ingest_token,
             request_trust,
         } = self;
 
@@ -482,6 +500,10 @@ where
         if let Some(signature) = signature {
             map.entry(&"signature", signature);
         }
+        if ingest_token.is_some() {
+            // The token is a bearer credential and must not end up in logs.
+            map.entry(&"ingest_token", &"[redacted]");
+        }
         if let Some(request_trust) = request_trust {
             map.entry(&"request_trust", request_trust);
         }
@@ -590,6 +612,9 @@ impl PartialMeta {
         if self.request_trust.is_some() {
             complete.request_trust = self.request_trust;
         }
+        if self.ingest_token.is_some() {
+            complete.ingest_token = self.ingest_token;
+        }
         complete.client_hints.copy_from(self.client_hints);
 
         if self.no_cache {
@@ -639,6 +664,18 @@ impl FromRequestParts<ServiceState> for PartialMeta {
             .await
             .map_err(BadEventMeta::SignatureError)?;
 
+        let ingest_token = parts
+            .headers
+            .get("x-sentry-ingest-token")
+            .map(|header| {
+                header
+                    .to_str()
+                    .map_err(|_| IngestTokenError::Malformed)?
+                    .parse::<IngestToken>()
+            })
+            .transpose()
+            .map_err(BadEventMeta::BadIngestToken)?;
+
         Ok(RequestMeta {
             dsn: None,
             version: default_version(),
@@ -657,6 +694,7 @@ impl FromRequestParts<ServiceState> for PartialMeta {
             received_at,
             client_hints: ua.client_hints,
             signature,
+            ingest_token,
             request_trust,
         })
     }
@@ -820,6 +858,7 @@ impl FromRequestParts<ServiceState> for RequestMeta {
             received_at: partial_meta.received_at,
             client_hints: partial_meta.client_hints,
             signature: partial_meta.signature,
+            ingest_token: partial_meta.ingest_token,
             request_trust: partial_meta.request_trust,
         })
     }
@@ -845,6 +884,7 @@ mod tests {
                 client_hints: ClientHints::default(),
                 request_trust: None,
                 signature: None,
+                ingest_token: None,
             }
         }
     }
@@ -891,6 +931,7 @@ mod tests {
             },
             request_trust: None,
             signature: None,
+            ingest_token: None,
         };
         deserialized.received_at = reqmeta.received_at;
         assert_eq!(deserialized, reqmeta);
@@ -910,6 +951,61 @@ mod tests {
         assert_eq!(serialized_with_signature, serialized_without_signature);
     }
 
+    fn ingest_token() -> IngestToken {
+        let (sk, _) = relay_auth::generate_key_pair();
+        let claims = relay_auth::IngestTokenClaims {
+            public_key: "e12d836b15bb49d7bbf99e64295d995b".to_owned(),
+            expires: relay_common::time::UnixTimestamp::now(),
+            categories: vec![],
+            user_id: None,
+            release: None,
+        };
+        IngestToken::sign(claims, &sk)
+    }
+
+    #[test]
+    fn test_ingest_token_serialized() {
+        let dsn: relay_common::Dsn = "https://e12d836b15bb49d7bbf99e64295d995b:@sentry.io/42"
+            .parse()
+            .unwrap();
+        let mut meta = RequestMeta::new(dsn);
+        meta.ingest_token = Some(ingest_token());
+
+        let serialized = serde_json::to_string(&meta).unwrap();
+        let deserialized: RequestMeta = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.ingest_token, meta.ingest_token);
+    }
+
+    #[test]
+    fn test_ingest_token_copied() {
+        let partial: PartialMeta = serde_json::from_value(serde_json::json!({
+            "ingest_token": ingest_token().to_string(),
+        }))
+        .unwrap();
+        let token = partial.ingest_token.clone();
+
+        let dsn = "https://e12d836b15bb49d7bbf99e64295d995b:@sentry.io/42"
+            .parse()
+            .unwrap();
+        let complete = partial.copy_to(RequestMeta::new(dsn));
+        assert!(token.is_some());
+        assert_eq!(complete.ingest_token, token);
+    }
+
+    #[test]
+    fn test_ingest_token_redacted() {
+        let dsn: relay_common::Dsn = "https://e12d836b15bb49d7bbf99e64295d995b:@sentry.io/42"
+            .parse()
+            .unwrap();
+        let mut meta = RequestMeta::new(dsn);
+        let token = ingest_token();
+        meta.ingest_token = Some(token.clone());
+
+        let debug = format!("{meta:?}");
+        assert!(debug.contains("\"ingest_token\": \"[redacted]\""));
+        assert!(!debug.contains(&token.to_string()));
+    }
+
     #[test]
     fn test_trusted_is_not_untrusted() {
         let x = RequestTrust::Trusted;
@@ -45,6 +45,11 @@ define_integrations!(
     "application/vnd.sentry.integration.otel.spans+protobuf" => Integration::Spans(SpansIntegration::OtelV1 { format: OtelFormat::Protobuf }),
     "application/vnd.sentry.integration.vercel.logs+json" => Integration::Logs(LogsIntegration::VercelDrainLog { format: VercelLogDrainFormat::Json }),
     "application/vnd.sentry.integration.vercel.logs+ndjson" => Integration::Logs(LogsIntegration::VercelDrainLog { format: VercelLogDrainFormat::NdJson }),
+    "application/vnd.sentry.integration.syslog.logs+logplex" => Integration::Logs(LogsIntegration::Syslog { format: SyslogFormat::Logplex }),
+    "application/vnd.sentry.integration.syslog.logs+text" => Integration::Logs(LogsIntegration::Syslog { format: SyslogFormat::Text }),
+    "application/vnd.sentry.integration.elasticsearch.logs+ndjson" => Integration::Logs(LogsIntegration::ElasticsearchBulk),
+    "application/vnd.sentry.integration.zipkin.spans+json" => Integration::Spans(SpansIntegration::ZipkinV2 { format: ZipkinFormat::Json }),
+    "application/vnd.sentry.integration.zipkin.spans+protobuf" => Integration::Spans(SpansIntegration::ZipkinV2 { format: ZipkinFormat::Protobuf }),
 );
 
 /// An exhaustive list of all integrations supported by Relay.
@@ -83,6 +88,15 @@ pub enum LogsIntegration {
     ///
     /// Supports the [`relay_ourlogs::VercelLog`] format.
     VercelDrainLog { format: VercelLogDrainFormat },
+    /// The syslog log drain integration, used by Heroku and other platforms.
+    ///
+    /// Supports RFC 5424 and RFC 3164 [`relay_ourlogs::SyslogMessage`]s.
+    Syslog { format: SyslogFormat },
+    /// The Elasticsearch `_bulk` API integration, used by log shippers.
+    ///
+    /// Supports newline delimited documents, validated and extracted from the bulk request by the
+    /// endpoint.
+    ElasticsearchBulk,
 }
 
 /// All span integrations supported by Relay.
@@ -92,6 +106,10 @@ pub enum SpansIntegration {
     ///
     /// Supports OTeL's [`TracesData`](opentelemetry_proto::tonic::trace::v1::TracesData).
     OtelV1 { format: OtelFormat },
+    /// The Zipkin v2 spans integration.
+    ///
+    /// Supports lists of [`relay_spans::ZipkinSpan`]s.
+    ZipkinV2 { format: ZipkinFormat },
 }
 
 /// An OTeL wire format.
@@ -103,6 +121,15 @@ pub enum OtelFormat {
     Json,
 }
 
+/// A Zipkin v2 wire format.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ZipkinFormat {
+    /// A JSON array of spans.
+    Json,
+    /// A proto3 `ListOfSpans` message.
+    Protobuf,
+}
+
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum VercelLogDrainFormat {
     // Vercel Log Drain data in a JSON array payload
@@ -110,3 +137,11 @@ pub enum VercelLogDrainFormat {
     // Vercel Log Drain data in a newline delimited JSON payload
     NdJson,
 }
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum SyslogFormat {
+    // Octet-counted syslog messages, as sent by logplex HTTPS drains
+    Logplex,
+    // Newline delimited syslog messages
+    Text,
+}
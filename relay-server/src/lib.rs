@@ -273,6 +273,12 @@ pub use self::envelope::Envelope; // pub for benchmarks
 pub use self::services::buffer::{
     EnvelopeStack, PolymorphicEnvelopeBuffer, SqliteEnvelopeStack, SqliteEnvelopeStore,
 }; // pub for benchmarks
+pub use self::services::buffer::{SpoolFilter, SpoolSummary, SqliteSpoolFile};
+#[cfg(feature = "processing")]
+pub use self::services::processor::offline::StoreReport;
+pub use self::services::processor::offline::{
+    OfflineProcessor, OutcomeReport, ProcessingReport, SamplingReport,
+};
 pub use self::utils::{MemoryChecker, MemoryStat}; // pub for benchmarks
 
 #[cfg(test)]
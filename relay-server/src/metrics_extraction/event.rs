@@ -3,9 +3,8 @@ use std::collections::BTreeMap;
 use relay_base_schema::project::ProjectId;
 use relay_common::time::UnixTimestamp;
 use relay_dynamic_config::CombinedMetricExtractionConfig;
-use relay_event_schema::protocol::{Event, Span};
+use relay_event_schema::protocol::Event;
 use relay_metrics::{Bucket, BucketMetadata, BucketValue};
-use relay_quotas::DataCategory;
 use relay_sampling::evaluation::SamplingDecision;
 
 use crate::metrics_extraction::ExtractedMetrics;
@@ -13,35 +12,6 @@ use crate::metrics_extraction::generic::{self, Extractable};
 use crate::processing::transactions::extraction::extract_segment_span;
 use crate::statsd::RelayTimers;
 
-impl Extractable for Event {
-    fn category(&self) -> DataCategory {
-        // Obtain the event's data category, but treat default events as error events for the
-        // purpose of metric tagging.
-        match DataCategory::from(self.ty.value().copied().unwrap_or_default()) {
-            DataCategory::Default => DataCategory::Error,
-            category => category,
-        }
-    }
-
-    fn timestamp(&self) -> Option<UnixTimestamp> {
-        self.timestamp
-            .value()
-            .and_then(|ts| UnixTimestamp::from_datetime(ts.0))
-    }
-}
-
-impl Extractable for Span {
-    fn category(&self) -> DataCategory {
-        DataCategory::Span
-    }
-
-    fn timestamp(&self) -> Option<UnixTimestamp> {
-        self.timestamp
-            .value()
-            .and_then(|ts| UnixTimestamp::from_datetime(ts.0))
-    }
-}
-
 /// Configuration for [`extract_metrics`].
 #[derive(Debug, Copy, Clone)]
 pub struct ExtractMetricsConfig<'a> {
@@ -15,6 +15,7 @@ pub fn cors() -> CorsLayer {
         .allow_methods(Method::POST)
         .allow_headers([
             HeaderName::from_static("x-sentry-auth"),
+            HeaderName::from_static("x-sentry-ingest-token"),
             HeaderName::from_static("x-requested-with"),
             HeaderName::from_static("x-forwarded-for"),
             HeaderName::from_static("origin"),
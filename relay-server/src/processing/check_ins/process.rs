@@ -20,6 +20,13 @@ pub fn normalize(check_ins: &mut Managed<SerializedCheckIns>) {
                     )
                 })?;
 
+            if let Some(err) = &result.monitor_config_error {
+                relay_log::debug!(
+                    error = err as &dyn std::error::Error,
+                    "dropped invalid monitor config from check-in"
+                );
+            }
+
             check_in.set_routing_hint(result.routing_hint);
             check_in.set_payload(ContentType::Json, result.payload);
 
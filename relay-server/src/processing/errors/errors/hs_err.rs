@@ -0,0 +1,82 @@
+use relay_quotas::{DataCategory, RateLimits};
+
+use crate::envelope::{AttachmentType, Item, ItemType};
+use crate::managed::{Counted, Quantities, RecordKeeper};
+use crate::processing::ForwardContext;
+use crate::processing::errors::errors::{Context, Expansion, SentryError, utils};
+use crate::processing::errors::{Error, Result};
+
+/// A HotSpot JVM fatal error log.
+///
+/// In processing mode, the log is converted into the event and forwarded as a regular attachment.
+#[derive(Debug)]
+pub struct HsErr(pub Item);
+
+impl SentryError for HsErr {
+    fn event_category(&self) -> DataCategory {
+        DataCategory::Error
+    }
+
+    fn try_expand(items: &mut Vec<Item>, ctx: Context<'_>) -> Result<Option<Expansion<Self>>> {
+        #[cfg_attr(not(feature = "processing"), expect(unused_mut))]
+        let Some(mut hs_err_log) = utils::take_item_by(items, |item| {
+            item.attachment_type() == Some(AttachmentType::HsErrLog)
+        }) else {
+            return Ok(None);
+        };
+
+        let mut metrics = Default::default();
+        #[cfg_attr(not(feature = "processing"), expect(unused_mut))]
+        let mut event = utils::take_event_from_crash_items(items, &mut metrics, ctx)?;
+
+        utils::if_processing!(ctx, {
+            crate::utils::process_hs_err_log(
+                event.get_or_insert_with(Default::default),
+                &mut hs_err_log,
+            )?;
+        });
+
+        Ok(Some(Expansion {
+            event: Box::new(event),
+            attachments: utils::take_items_of_type(items, ItemType::Attachment),
+            user_reports: utils::take_items_of_type(items, ItemType::UserReport),
+            error: Self(hs_err_log),
+            metrics,
+            fully_normalized: false,
+        }))
+    }
+
+    fn apply_rate_limit(
+        &mut self,
+        _category: DataCategory,
+        limits: RateLimits,
+        records: &mut RecordKeeper<'_>,
+    ) -> Result<()> {
+        if !self.0.rate_limited() {
+            self.0.set_rate_limited(true);
+            records.reject_err(Error::RateLimited(limits), &self.0);
+        }
+
+        Ok(())
+    }
+
+    fn serialize_into(self, items: &mut Vec<Item>, _ctx: ForwardContext<'_>) -> Result<()> {
+        items.push(self.0);
+        Ok(())
+    }
+
+    fn minidump_mut(&mut self) -> Option<&mut Item> {
+        None
+    }
+}
+
+impl Counted for HsErr {
+    fn quantities(&self) -> Quantities {
+        // A rate limited log no longer counts as an attachment, but it is still passed along to
+        // have its data extracted into an error when processing.
+        match self.0.rate_limited() {
+            true => Default::default(),
+            false => self.0.quantities(),
+        }
+    }
+}
@@ -10,6 +10,7 @@ use crate::statsd::RelayCounters;
 
 mod apple_crash_report;
 mod generic;
+mod hs_err;
 mod minidump;
 mod nswitch;
 mod playstation;
@@ -21,6 +22,7 @@ mod utils;
 
 pub use self::apple_crash_report::*;
 pub use self::generic::*;
+pub use self::hs_err::*;
 pub use self::minidump::*;
 pub use self::nswitch::*;
 pub use self::playstation::*;
@@ -183,6 +185,7 @@ gen_error_kind![
     Unreal,
     Minidump,
     AppleCrashReport,
+    HsErr,
     Playstation,
     Security,
     RawSecurity,
@@ -1,12 +1,14 @@
-use crate::managed::Managed;
-use crate::processing::errors::{ExpandedError, Result};
+use crate::managed::{Managed, Rejected};
+use crate::processing::errors::{Error, ExpandedError, Result};
 use crate::processing::{self, Context};
 use crate::services::processor::ProcessingError;
 
 /// Runs inbound filters on the [`ExpandedError`].
-pub fn filter(error: &Managed<ExpandedError>, ctx: Context<'_>) -> Result<()> {
-    let _ = processing::utils::event::filter(&error.headers, &error.event, ctx)
-        .map_err(ProcessingError::EventFiltered)?;
+pub fn filter(error: &mut Managed<ExpandedError>, ctx: Context<'_>) -> Result<(), Rejected<Error>> {
+    error.try_modify(|error, _| {
+        let _ = processing::utils::event::filter(&error.headers, &mut error.event, ctx)
+            .map_err(ProcessingError::EventFiltered)?;
 
-    Ok(())
+        Ok::<_, Error>(())
+    })
 }
@@ -2,9 +2,7 @@ use std::sync::Arc;
 
 use crate::Envelope;
 use crate::envelope::{ContentType, EnvelopeHeaders, Item, ItemType};
-use crate::managed::{
-    Counted, Managed, ManagedEnvelope, ManagedResult as _, OutcomeError, Quantities, Rejected,
-};
+use crate::managed::{Counted, Managed, ManagedEnvelope, OutcomeError, Quantities, Rejected};
 use crate::processing::errors::errors::SentryError as _;
 use crate::processing::utils::attachments;
 use crate::processing::utils::event::EventFullyNormalized;
@@ -127,7 +125,7 @@ impl processing::Processor for ErrorsProcessor {
         process::finalize(&mut error, ctx)?;
         process::normalize(&mut error, &self.geoip_lookup, ctx)?;
 
-        filter::filter(&error, ctx).reject(&error)?;
+        filter::filter(&mut error, ctx)?;
 
         dynamic_sampling::apply(&mut error, ctx);
 
@@ -1,4 +1,4 @@
-use relay_dynamic_config::{CombinedMetricExtractionConfig, ErrorBoundary};
+use relay_dynamic_config::ErrorBoundary;
 use relay_quotas::DataCategory;
 use relay_sampling::evaluation::SamplingDecision;
 
@@ -71,7 +71,7 @@ fn split_indexed_and_total(
 ) {
     let scoping = spans.scoping();
     let transaction_from_dsc = spans.headers.dsc().and_then(|dsc| dsc.transaction.clone());
-    let mec = metrics_extraction_config(ctx);
+    let mec = processing::utils::metrics::extraction_config(ctx);
 
     spans.split_once(|spans, r| {
         r.lenient(DataCategory::MetricBucket);
@@ -96,12 +96,3 @@ fn split_indexed_and_total(
         (spans.into_indexed(), metrics.into_inner())
     })
 }
-
-fn metrics_extraction_config(ctx: Context<'_>) -> Option<CombinedMetricExtractionConfig<'_>> {
-    let local = match ctx.project_info.config.metric_extraction {
-        ErrorBoundary::Ok(ref config) if config.is_enabled() => config,
-        _ => return None,
-    };
-    let global = ctx.global_config.metric_extraction.as_ref().ok()?;
-    Some(CombinedMetricExtractionConfig::new(global, local))
-}
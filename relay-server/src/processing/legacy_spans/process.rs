@@ -4,6 +4,7 @@ use relay_event_schema::protocol::Span;
 use relay_metrics::MetricNamespace;
 use relay_pii::PiiProcessor;
 use relay_protocol::Annotated;
+use relay_quotas::DataCategory;
 
 use crate::managed::Managed;
 use crate::processing::legacy_spans::{
@@ -115,24 +116,32 @@ pub fn filter(spans: &mut Managed<ExpandedLegacySpans>, ctx: Context<'_>) {
 
     spans.retain(
         |spans| &mut spans.spans,
-        |span, _| {
-            let Some(span) = span.value() else {
+        |span, _| -> Result<(), Error> {
+            let Some(span) = span.value_mut() else {
                 return Ok(());
             };
 
-            relay_filter::should_filter(
-                span,
+            let result = relay_filter::should_filter(
+                &*span,
                 client_ip,
                 filter_settings,
                 ctx.global_config.filters(),
-            )
-            .map_err(|filter| {
-                relay_log::trace!(
-                    "filtering span {:?} that matched an inbound filter",
-                    span.span_id
-                );
-                Error::Filtered(filter)
-            })
+            );
+
+            let reported =
+                processing::utils::filter::apply(result, DataCategory::Span).map_err(|filter| {
+                    relay_log::trace!(
+                        "filtering span {:?} that matched an inbound filter",
+                        span.span_id
+                    );
+                    Error::Filtered(filter)
+                })?;
+
+            if let Some(key) = reported {
+                processing::utils::filter::attach_span_tag(&mut span.tags, &key);
+            }
+
+            Ok(())
         },
     );
 }
@@ -1,11 +1,12 @@
 use relay_dynamic_config::Feature;
 use relay_event_schema::protocol::OurLog;
 use relay_protocol::Annotated;
+use relay_quotas::DataCategory;
 
 use crate::extractors::RequestMeta;
 use crate::managed::Managed;
-use crate::processing::Context;
 use crate::processing::logs::{Error, ExpandedLogs, Result};
+use crate::processing::{Context, utils};
 
 /// Filters logs sent for a project which does not allow logs ingestion.
 pub fn feature_flag(ctx: Context<'_>) -> Result<()> {
@@ -23,16 +24,23 @@ pub fn filter(logs: &mut Managed<ExpandedLogs>, ctx: Context<'_>) {
     );
 }
 
-fn filter_log(log: &Annotated<OurLog>, meta: &RequestMeta, ctx: Context<'_>) -> Result<()> {
-    let Some(log) = log.value() else {
+fn filter_log(log: &mut Annotated<OurLog>, meta: &RequestMeta, ctx: Context<'_>) -> Result<()> {
+    let Some(log) = log.value_mut() else {
         return Ok(());
     };
 
-    relay_filter::should_filter(
-        log,
+    let result = relay_filter::should_filter(
+        &*log,
         meta.client_addr(),
         &ctx.project_info.config.filter_settings,
         ctx.global_config.filters(),
-    )
-    .map_err(Error::Filtered)
+    );
+
+    if let Some(key) =
+        utils::filter::apply(result, DataCategory::LogItem).map_err(Error::Filtered)?
+    {
+        utils::filter::attach_attribute(&mut log.attributes, &key);
+    }
+
+    Ok(())
 }
@@ -0,0 +1,39 @@
+use relay_event_schema::protocol::OurLog;
+use serde_json::{Map, Value};
+
+use crate::envelope::EnvelopeHeaders;
+use crate::processing::logs::{Error, Result, Settings};
+use crate::services::outcome::DiscardReason;
+
+/// Expands Elasticsearch bulk documents into the [`OurLog`] format.
+///
+/// The payload contains newline delimited documents, which have already been extracted from the
+/// bulk request by the endpoint.
+pub fn expand<F>(payload: &[u8], headers: &EnvelopeHeaders, mut produce: F) -> Result<Settings>
+where
+    F: FnMut(OurLog),
+{
+    let received_at = headers.meta().received_at();
+    let mut count: i32 = 0;
+
+    for line in payload.split(|&b| b == b'\n') {
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Ok(document) = serde_json::from_slice::<Map<String, Value>>(line) {
+            count += 1;
+            produce(relay_ourlogs::elasticsearch_document_to_sentry_log(
+                document,
+                received_at,
+            ));
+        }
+    }
+
+    if count == 0 {
+        relay_log::debug!("Failed to parse any logs from elasticsearch bulk payload");
+        return Err(Error::Invalid(DiscardReason::InvalidJson));
+    }
+
+    Ok(Settings::default())
+}
@@ -6,8 +6,10 @@ use crate::integrations::{Integration, LogsIntegration};
 use crate::managed::RecordKeeper;
 use crate::processing::logs::Settings;
 
+mod elasticsearch;
 mod nel;
 mod otel;
+mod syslog;
 mod vercel;
 
 /// Expands a log [`Integration`] into a list of logs.
@@ -48,6 +50,8 @@ pub fn expand(
         LogsIntegration::Nel => nel::expand(&payload, headers, produce),
         LogsIntegration::OtelV1 { format } => otel::expand(format, &payload, produce),
         LogsIntegration::VercelDrainLog { format } => vercel::expand(format, &payload, produce),
+        LogsIntegration::Syslog { format } => syslog::expand(format, &payload, headers, produce),
+        LogsIntegration::ElasticsearchBulk => elasticsearch::expand(&payload, headers, produce),
     };
     let settings = match settings {
         Ok(settings) => settings,
@@ -0,0 +1,65 @@
+use relay_event_schema::protocol::OurLog;
+
+use crate::envelope::EnvelopeHeaders;
+use crate::integrations::SyslogFormat;
+use crate::processing::logs::{Error, Result, Settings};
+use crate::services::outcome::DiscardReason;
+
+/// Expands syslog messages into the [`OurLog`] format.
+///
+/// Messages which cannot be parsed are skipped, the payload is only rejected if it does not
+/// contain a single valid message.
+pub fn expand<F>(
+    format: SyslogFormat,
+    payload: &[u8],
+    headers: &EnvelopeHeaders,
+    mut produce: F,
+) -> Result<Settings>
+where
+    F: FnMut(OurLog),
+{
+    let received_at = headers.meta().received_at();
+    let mut count: i32 = 0;
+
+    let mut expand_message = |message: &[u8]| {
+        let message = String::from_utf8_lossy(message);
+        match relay_ourlogs::parse_syslog(&message, received_at) {
+            Ok(message) => {
+                count += 1;
+                produce(relay_ourlogs::syslog_to_sentry_log(message, received_at));
+            }
+            Err(error) => relay_log::debug!(
+                error = &error as &dyn std::error::Error,
+                "Failed to parse syslog message"
+            ),
+        }
+    };
+
+    match format {
+        SyslogFormat::Logplex => {
+            for frame in relay_ourlogs::split_octet_counted(payload) {
+                match frame {
+                    Ok(frame) => expand_message(frame),
+                    Err(error) => relay_log::debug!(
+                        error = &error as &dyn std::error::Error,
+                        "Failed to parse logplex frame"
+                    ),
+                }
+            }
+        }
+        SyslogFormat::Text => {
+            for line in payload.split(|&b| b == b'\n') {
+                if !line.trim_ascii().is_empty() {
+                    expand_message(line);
+                }
+            }
+        }
+    }
+
+    if count == 0 {
+        relay_log::debug!("Failed to parse any logs from syslog payload");
+        return Err(Error::Invalid(DiscardReason::InvalidLog));
+    }
+
+    Ok(Settings::default())
+}
@@ -159,11 +159,15 @@ impl processing::Processor for LogsProcessor {
         process::normalize(&mut logs, ctx);
         filter::filter(&mut logs, ctx);
         process::scrub(&mut logs, ctx);
-        process::normalize_derived(&mut logs);
+        process::normalize_derived(&mut logs, ctx);
 
         let logs = self.limiter.enforce_quotas(logs, ctx).await?;
+        let metrics = process::extract_metrics(&logs, ctx);
 
-        Ok(Output::just(LogOutput(logs)))
+        Ok(Output {
+            main: Some(LogOutput(logs)),
+            metrics,
+        })
     }
 }
 
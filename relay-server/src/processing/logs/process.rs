@@ -8,9 +8,11 @@ use relay_quotas::DataCategory;
 use crate::envelope::{ContainerItems, EnvelopeHeaders, Item, ItemContainer};
 use crate::extractors::RequestTrust;
 use crate::managed::Rejected;
+use crate::metrics_extraction::ExtractedMetrics;
 use crate::processing::logs::{
     self, Error, ExpandedLogs, LogItems, Result, SerializedLogs, Settings,
 };
+use crate::processing::utils::normalize::Trimming;
 use crate::processing::{Context, Managed, utils};
 use crate::services::outcome::DiscardReason;
 
@@ -191,19 +193,55 @@ fn normalize_log(
 /// This is separate from [`normalize`] because it needs to run
 /// after PII scrubbing; PII might get leaked otherwise.
 ///
-/// In practice, for logs, it only performs schema validation.
-pub fn normalize_derived(logs: &mut Managed<ExpandedLogs>) {
+/// In practice, for logs, it applies attribute policies and performs schema validation.
+pub fn normalize_derived(logs: &mut Managed<ExpandedLogs>, ctx: Context<'_>) {
+    let trimming = utils::normalize::trimming(|t| t.log.as_ref(), ctx);
     logs.retain_with_context(
         |logs| (&mut logs.logs, &()),
         |log, _, _| {
-            normalize_log_derived(log).inspect_err(|err| {
+            normalize_log_derived(log, &trimming, ctx).inspect_err(|err| {
                 relay_log::debug!("failed to normalize log: {err}");
             })
         },
     );
 }
 
-fn normalize_log_derived(log: &mut Annotated<OurLog>) -> Result<()> {
+/// Extracts user defined metrics from the logs.
+///
+/// Metrics are only extracted in processing Relays, which see the final set of logs.
+pub fn extract_metrics(
+    logs: &Managed<ExpandedLogs>,
+    ctx: Context<'_>,
+) -> Option<Managed<ExtractedMetrics>> {
+    if !ctx.is_processing() {
+        return None;
+    }
+
+    let project_metrics =
+        utils::metrics::extract_custom(logs.logs.iter().filter_map(|log| log.value.value()), ctx);
+    if project_metrics.is_empty() {
+        return None;
+    }
+
+    Some(logs.wrap(ExtractedMetrics {
+        project_metrics,
+        sampling_metrics: Vec::new(),
+    }))
+}
+
+fn normalize_log_derived(
+    log: &mut Annotated<OurLog>,
+    trimming: &Trimming<'_>,
+    ctx: Context<'_>,
+) -> Result<()> {
+    if let Some(log) = log.value_mut() {
+        eap::apply_attribute_policies(
+            &mut log.attributes,
+            &trimming.policies,
+            &mut ctx.config.max_removed_attribute_key_size(),
+        );
+    }
+
     process_value(
         log,
         &mut SchemaProcessor::new()
@@ -1,12 +1,13 @@
 use relay_dynamic_config::Feature;
+use relay_quotas::DataCategory;
 
 use crate::envelope::ContentType;
 use crate::extractors::RequestMeta;
 use crate::managed::Managed;
-use crate::processing::Context;
 use crate::processing::profile_chunks::{
     Error, ExpandedProfileChunk, ExpandedProfileChunks, Result, SerializedProfileChunks,
 };
+use crate::processing::{Context, utils};
 
 /// Checks whether the profile ingestion feature flag is enabled for the current project.
 pub fn feature_flag(items: &mut Managed<SerializedProfileChunks>, ctx: Context<'_>) -> Result<()> {
@@ -29,6 +30,22 @@ pub fn feature_flag(items: &mut Managed<SerializedProfileChunks>, ctx: Context<'
         }
     }
 
+    if ctx.should_filter(Feature::ContinuousProfilingPprof) {
+        items.retain(
+            |items| &mut items.profile_chunks,
+            |pc, _| match pc.content_type() {
+                Some(ContentType::Pprof | ContentType::OtlpProfiles) => {
+                    Err(Error::FilterFeatureFlag)
+                }
+                _ => Ok(()),
+            },
+        );
+
+        if items.profile_chunks.is_empty() {
+            return Err(Error::FilterFeatureFlag);
+        }
+    }
+
     Ok(())
 }
 
@@ -50,11 +67,15 @@ fn filter_profile_chunk(
     meta: &RequestMeta,
     ctx: Context<'_>,
 ) -> Result<()> {
-    relay_filter::should_filter(
+    let result = relay_filter::should_filter(
         &profile_chunk.0,
         meta.client_addr(),
         &ctx.project_info.config.filter_settings,
         ctx.global_config.filters(),
-    )
-    .map_err(Error::Filtered)
+    );
+
+    // Profile chunks are forwarded unmodified, report-only matches are only counted.
+    utils::filter::apply(result, DataCategory::ProfileChunk).map_err(Error::Filtered)?;
+
+    Ok(())
 }
@@ -1,4 +1,6 @@
+use bytes::Bytes;
 use relay_profiling::AndroidOrV2ProfileChunk;
+use relay_profiling::PprofFormat;
 use relay_profiling::ProfileChunk as _;
 use relay_profiling::ProfileError;
 use relay_quotas::DataCategory;
@@ -60,6 +62,8 @@ fn expand_profile_chunk(
 ) -> Result<ExpandedProfileChunk> {
     let profile_chunk = match item.content_type() {
         Some(ContentType::PerfettoTrace) => expand_perfetto_profile_chunk(item),
+        Some(ContentType::Pprof) => expand_pprof_profile_chunk(item, PprofFormat::Pprof),
+        Some(ContentType::OtlpProfiles) => expand_pprof_profile_chunk(item, PprofFormat::Otlp),
         _ => expand_json_item(item, sdk, records),
     }?;
 
@@ -123,22 +127,32 @@ fn expand_json_item(
 }
 
 fn expand_perfetto_profile_chunk(item: &Item) -> Result<ExpandedProfileChunk> {
+    let (v2, perfetto) = split_compound_item(item)?;
+
+    let chunk = Box::new(relay_profiling::PerfettoProfileChunk::parse(&v2, perfetto)?);
+    Ok(ExpandedProfileChunk(chunk.into()))
+}
+
+fn expand_pprof_profile_chunk(item: &Item, format: PprofFormat) -> Result<ExpandedProfileChunk> {
+    let (v2, raw) = split_compound_item(item)?;
+
+    let chunk = Box::new(relay_profiling::PprofProfileChunk::parse(&v2, raw, format)?);
+    Ok(ExpandedProfileChunk(chunk.into()))
+}
+
+/// Splits a compound profile chunk item into the v2 metadata and the binary profile.
+fn split_compound_item(item: &Item) -> Result<(Bytes, Bytes)> {
     let meta_length =
         item.meta_length()
             .ok_or(relay_profiling::ProfileError::InvalidSampledProfile)? as usize;
 
-    let (v2, perfetto) = {
-        let mut v2 = item.payload();
-        // Split off panics on out of bounds -> validate the split off length.
-        //
-        // Both parts (v2 metadata and Perfetto profile) are required and must be length > 0.
-        if meta_length >= v2.len() || meta_length == 0 {
-            return Err(relay_profiling::ProfileError::InvalidSampledProfile.into());
-        }
-        let perfetto = v2.split_off(meta_length);
-        (v2, perfetto)
-    };
-
-    let chunk = Box::new(relay_profiling::PerfettoProfileChunk::parse(&v2, perfetto)?);
-    Ok(ExpandedProfileChunk(chunk.into()))
+    let mut v2 = item.payload();
+    // Split off panics on out of bounds -> validate the split off length.
+    //
+    // Both parts (v2 metadata and binary profile) are required and must be length > 0.
+    if meta_length >= v2.len() || meta_length == 0 {
+        return Err(relay_profiling::ProfileError::InvalidSampledProfile.into());
+    }
+    let raw = v2.split_off(meta_length);
+    Ok((v2, raw))
 }
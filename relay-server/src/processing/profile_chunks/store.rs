@@ -1,5 +1,5 @@
 use either::Either;
-use relay_profiling::AnyProfileChunk;
+use relay_profiling::{AnyProfileChunk, PprofFormat};
 
 use crate::envelope::ContentType;
 use crate::managed::Counted;
@@ -17,6 +17,7 @@ pub fn convert(
     let payload = match &pc.0 {
         AnyProfileChunk::Android(chunk) => chunk.serialize()?,
         AnyProfileChunk::Perfetto(chunk) => chunk.as_v2().serialize()?,
+        AnyProfileChunk::Pprof(chunk) => chunk.as_v2().serialize()?,
         AnyProfileChunk::V2(chunk) => chunk.serialize()?,
     };
 
@@ -29,6 +30,18 @@ pub fn convert(
             payload: chunk.perfetto().clone(),
             content_type: ContentType::PerfettoTrace,
         }),
+        AnyProfileChunk::Pprof(chunk) => Some(match chunk.format() {
+            PprofFormat::Pprof => RawProfile {
+                name: "profile.pprof".to_owned(),
+                payload: chunk.raw().clone(),
+                content_type: ContentType::Pprof,
+            },
+            PprofFormat::Otlp => RawProfile {
+                name: "profile.otlp".to_owned(),
+                payload: chunk.raw().clone(),
+                content_type: ContentType::OtlpProfiles,
+            },
+        }),
     };
 
     // Follows the pre-existing logic for validating the profiling sizes,
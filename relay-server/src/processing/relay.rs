@@ -27,6 +27,7 @@ use crate::processing::transactions::TransactionProcessor;
 use crate::processing::user_reports::UserReportsProcessor;
 use crate::processing::{Context, Output, Outputs, Processor, QuotaRateLimiter};
 use crate::services::outcome::{DiscardReason, Outcome, TrackOutcome};
+use crate::services::sessions::AggregateSessions;
 use crate::statsd::RelayTimers;
 
 /// Implementation of Relays processing pipeline.
@@ -61,6 +62,7 @@ impl RelayProcessor {
         quota_limiter: &Arc<QuotaRateLimiter>,
         geoip_lookup: &GeoIpLookup,
         outcome_aggregator: Addr<TrackOutcome>,
+        session_aggregator: Option<Addr<AggregateSessions>>,
     ) -> Self {
         // Just so everything fits in a single line.
         let ql = || Arc::clone(quota_limiter);
@@ -79,7 +81,7 @@ impl RelayProcessor {
             profile_chunks: ProfileChunksProcessor::new(ql()),
             profiles: ProfilesProcessor::new(ql()),
             replays: ReplaysProcessor::new(ql(), geoip_lookup.clone()),
-            sessions: SessionsProcessor::new(ql()),
+            sessions: SessionsProcessor::new(ql(), session_aggregator),
             spans: SpansProcessor::new(ql(), geoip_lookup.clone()),
             trace_attachments: TraceAttachmentsProcessor::new(ql()),
             trace_metrics: TraceMetricsProcessor::new(ql()),
@@ -1,9 +1,10 @@
 use relay_dynamic_config::Feature;
+use relay_quotas::DataCategory;
 use relay_statsd::metric;
 
 use crate::managed::{Managed, Rejected};
-use crate::processing::Context;
 use crate::processing::replays::{Error, ExpandedReplay, Result, SerializedReplays};
+use crate::processing::{Context, utils};
 use crate::statsd::RelayCounters;
 
 /// Maximum expected segment ID for a replay session, under normal operation.
@@ -26,7 +27,9 @@ pub fn feature_flag(
 }
 
 /// Applies inbound filters to a replay.
-pub fn filter(replay: &ExpandedReplay, ctx: Context<'_>) -> Result<()> {
+///
+/// If the replay matches a report-only filter, it is tagged with the name of the filter.
+pub fn filter(replay: &mut Managed<ExpandedReplay>, ctx: Context<'_>) -> Result<()> {
     let Some(event) = replay.payload.event() else {
         return Ok(());
     };
@@ -35,13 +38,13 @@ pub fn filter(replay: &ExpandedReplay, ctx: Context<'_>) -> Result<()> {
     let event_id = replay.headers.event_id();
     let event = event.value().ok_or(Error::NoEventContent)?;
 
-    relay_filter::should_filter(
+    let result = relay_filter::should_filter(
         event,
         client_addr,
         &ctx.project_info.config.filter_settings,
         ctx.global_config.filters(),
-    )
-    .map_err(Error::Filtered)?;
+    );
+    let reported = utils::filter::apply(result, DataCategory::Replay).map_err(Error::Filtered)?;
 
     // Log segments that exceed the hour limit so we can diagnose errant SDKs
     // or exotic customer implementations.
@@ -59,5 +62,17 @@ pub fn filter(replay: &ExpandedReplay, ctx: Context<'_>) -> Result<()> {
         );
     }
 
+    if let Some(key) = reported {
+        replay.modify(|replay, _| {
+            if let Some(event) = replay
+                .payload
+                .event_mut()
+                .and_then(|e| e.value_mut().as_mut())
+            {
+                utils::filter::attach_tag(&mut event.tags, &key);
+            }
+        });
+    }
+
     Ok(())
 }
@@ -204,7 +204,7 @@ impl processing::Processor for ReplaysProcessor {
 
         validate::validate(&replay).reject(&replay)?;
         process::normalize(&mut replay, &self.geoip_lookup, ctx);
-        filter::filter(&replay, ctx).reject(&replay)?;
+        filter::filter(&mut replay, ctx).reject(&replay)?;
 
         let mut replay = self.limiter.enforce_quotas(replay, ctx).await?;
 
@@ -1,10 +1,11 @@
 use relay_filter::Filterable;
 use relay_protocol::Getter;
+use relay_quotas::DataCategory;
 
 use crate::extractors::RequestMeta;
 use crate::managed::Managed;
-use crate::processing::Context;
 use crate::processing::sessions::{Error, ExpandedSessions, Result};
+use crate::processing::{Context, utils};
 
 /// Applies inbound filters to individual sessions.
 pub fn filter(sessions: &mut Managed<ExpandedSessions>, ctx: Context<'_>) {
@@ -23,11 +24,15 @@ fn filter_session<T>(session: &T, meta: &RequestMeta, ctx: Context<'_>) -> Resul
 where
     T: Filterable + Getter,
 {
-    relay_filter::should_filter(
+    let result = relay_filter::should_filter(
         session,
         meta.client_addr(),
         &ctx.project_info.config.filter_settings,
         ctx.global_config.filters(),
-    )
-    .map_err(Error::Filtered)
+    );
+
+    // Sessions have no tags or attributes, report-only matches are only counted.
+    utils::filter::apply(result, DataCategory::Session).map_err(Error::Filtered)?;
+
+    Ok(())
 }
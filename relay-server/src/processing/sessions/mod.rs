@@ -3,6 +3,7 @@ use std::sync::Arc;
 use relay_cogs::{AppFeature, FeatureWeights};
 use relay_event_schema::protocol::{SessionAggregates, SessionUpdate};
 use relay_quotas::{DataCategory, RateLimits};
+use relay_system::Addr;
 
 use crate::Envelope;
 use crate::envelope::{EnvelopeHeaders, Item, ItemType, Items};
@@ -10,6 +11,7 @@ use crate::managed::{Counted, Managed, ManagedEnvelope, OutcomeError, Quantities
 use crate::processing::sessions::process::Expansion;
 use crate::processing::{self, Context, CountRateLimited, Forward, Output, QuotaRateLimiter};
 use crate::services::outcome::Outcome;
+use crate::services::sessions::AggregateSessions;
 
 mod filter;
 mod process;
@@ -47,12 +49,57 @@ impl From<RateLimits> for Error {
 /// A processor for sessions, individual updates and aggregates.
 pub struct SessionsProcessor {
     limiter: Arc<QuotaRateLimiter>,
+    aggregator: Option<Addr<AggregateSessions>>,
 }
 
 impl SessionsProcessor {
     /// Creates a new [`Self`].
-    pub fn new(limiter: Arc<QuotaRateLimiter>) -> Self {
-        Self { limiter }
+    ///
+    /// Session updates are pre-aggregated if an `aggregator` is configured.
+    pub fn new(
+        limiter: Arc<QuotaRateLimiter>,
+        aggregator: Option<Addr<AggregateSessions>>,
+    ) -> Self {
+        Self {
+            limiter,
+            aggregator,
+        }
+    }
+
+    /// Sends session updates which can be aggregated to the aggregator.
+    ///
+    /// Returns the remaining sessions, or `None` if all sessions have been sent to the
+    /// aggregator. Pre-aggregated sessions are submitted as a new envelope and processed again.
+    fn aggregate(
+        &self,
+        sessions: Managed<SerializedSessions>,
+    ) -> Option<Managed<SerializedSessions>> {
+        let Some(aggregator) = &self.aggregator else {
+            return Some(sessions);
+        };
+
+        let client = sessions.headers.meta().client().map(str::to_owned);
+        let (sessions, aggregatable) = process::split_aggregatable(sessions);
+
+        if aggregatable.updates.is_empty() {
+            aggregatable.accept(|_| ());
+        } else if let Err(error) = aggregator.try_send(AggregateSessions {
+            sessions: aggregatable,
+            client,
+        }) {
+            // The dropped message rejects the contained sessions.
+            relay_log::debug!(
+                error = &error as &dyn std::error::Error,
+                "failed to send sessions to the aggregator"
+            );
+        }
+
+        if sessions.updates.is_empty() && sessions.aggregates.is_empty() {
+            sessions.accept(|_| ());
+            return None;
+        }
+
+        Some(sessions)
     }
 }
 
@@ -95,6 +142,10 @@ impl processing::Processor for SessionsProcessor {
         sessions: Managed<Self::Input>,
         ctx: Context<'_>,
     ) -> Result<Output<Self::Output>, Rejected<Self::Error>> {
+        let Some(sessions) = self.aggregate(sessions) else {
+            return Ok(Output::empty());
+        };
+
         let mut sessions = match process::expand(sessions, ctx) {
             Expansion::Continue(sessions) => sessions,
             Expansion::Forward(sessions) => return Ok(Output::just(SessionsOutput(sessions))),
@@ -169,6 +220,19 @@ impl Counted for SerializedSessions {
     }
 }
 
+/// Session updates split off for pre-aggregation.
+#[derive(Debug)]
+pub struct AggregatableSessions {
+    /// A list of parsed session updates, which can all be aggregated.
+    pub updates: Vec<SessionUpdate>,
+}
+
+impl Counted for AggregatableSessions {
+    fn quantities(&self) -> Quantities {
+        smallvec::smallvec![(DataCategory::Session, self.updates.len())]
+    }
+}
+
 #[derive(Debug)]
 pub struct ExpandedSessions {
     /// Original envelope headers.
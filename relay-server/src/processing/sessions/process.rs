@@ -10,8 +10,11 @@ use crate::managed::{Managed, RecordKeeper};
 use crate::metrics_extraction;
 use crate::metrics_extraction::ExtractedMetrics;
 use crate::processing::Context;
-use crate::processing::sessions::{Error, ExpandedSessions, Result, SerializedSessions};
+use crate::processing::sessions::{
+    AggregatableSessions, Error, ExpandedSessions, Result, SerializedSessions,
+};
 use crate::services::processor::MINIMUM_CLOCK_DRIFT;
+use crate::services::sessions::is_aggregatable;
 use crate::statsd::RelayTimers;
 
 /// Result of [`expand`].
@@ -64,6 +67,47 @@ pub fn expand(sessions: Managed<SerializedSessions>, ctx: Context<'_>) -> Expans
     Expansion::Continue(expanded)
 }
 
+/// Splits off all session updates which can be pre-aggregated.
+///
+/// Session updates which are invalid or cannot be aggregated remain in `sessions` and are
+/// processed as usual.
+pub fn split_aggregatable(
+    sessions: Managed<SerializedSessions>,
+) -> (Managed<SerializedSessions>, Managed<AggregatableSessions>) {
+    let clock_drift = ClockDriftProcessor::new(sessions.headers.sent_at(), sessions.received_at())
+        .at_least(MINIMUM_CLOCK_DRIFT);
+    let client_addr = sessions.headers.meta().client_addr();
+
+    sessions.split_once(|mut sessions, _| {
+        let mut updates = Vec::new();
+
+        sessions.updates.retain(|item| {
+            let Ok(mut update) = expand_session(item) else {
+                return true;
+            };
+            if !is_aggregatable(&update) {
+                return true;
+            }
+
+            // Aggregates are sent in a new envelope without the original headers and request
+            // meta, clock drift and the client address must be resolved here.
+            if clock_drift.is_drifted() {
+                clock_drift.process_datetime(&mut update.started);
+                clock_drift.process_datetime(&mut update.timestamp);
+            }
+            let attrs = &mut update.attributes;
+            if attrs.ip_address.as_ref().is_some_and(|ip| ip.is_auto()) {
+                attrs.ip_address = client_addr.map(Into::into);
+            }
+
+            updates.push(update);
+            false
+        });
+
+        (sessions, AggregatableSessions { updates })
+    })
+}
+
 fn expand_session(item: &Item) -> Result<SessionUpdate> {
     let payload = item.payload();
 
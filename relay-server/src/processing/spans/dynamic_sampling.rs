@@ -15,8 +15,8 @@ use relay_sampling::{DynamicSamplingContext, SamplingConfig};
 use crate::envelope::ClientName;
 use crate::managed::{Managed, Rejected};
 use crate::metrics_extraction::ExtractedMetrics;
-use crate::processing::Context;
 use crate::processing::spans::{Error, ExpandedSpan, ExpandedSpans, Indexed, Result};
+use crate::processing::{Context, utils};
 use crate::services::outcome::Outcome;
 use crate::services::projects::project::ProjectInfo;
 use crate::statsd::RelayCounters;
@@ -140,7 +140,7 @@ pub fn run(
     };
 
     // At this point the decision is to drop the spans.
-    let (spans, metrics) = split_indexed_and_total(spans, SamplingDecision::Drop);
+    let (spans, metrics) = split_indexed_and_total(spans, SamplingDecision::Drop, ctx);
 
     let outcome = Outcome::FilteredSampling(sampling_match.into_matched_rules().into());
     let _ = spans.reject_err(outcome);
@@ -155,8 +155,9 @@ pub fn run(
 pub fn reject_indexed_spans(
     spans: Managed<ExpandedSpans>,
     error: Error,
+    ctx: Context<'_>,
 ) -> Managed<ExtractedMetrics> {
-    let (indexed, total) = split_indexed_and_total(spans, SamplingDecision::Keep);
+    let (indexed, total) = split_indexed_and_total(spans, SamplingDecision::Keep, ctx);
     let _ = indexed.reject_err(error);
     total
 }
@@ -178,7 +179,7 @@ pub fn try_split_indexed_and_total(
         return Either::Left(spans);
     }
 
-    Either::Right(split_indexed_and_total(spans, SamplingDecision::Keep))
+    Either::Right(split_indexed_and_total(spans, SamplingDecision::Keep, ctx))
 }
 
 /// Splits spans into indexed spans and metrics representing the total counts.
@@ -188,12 +189,28 @@ pub fn try_split_indexed_and_total(
 fn split_indexed_and_total(
     spans: Managed<ExpandedSpans>,
     decision: SamplingDecision,
+    ctx: Context<'_>,
 ) -> SpansAndMetrics {
     let scoping = spans.scoping();
 
     spans.split_once(|spans, r| {
         r.lenient(DataCategory::MetricBucket);
-        let metrics = create_metrics(scoping, &spans.spans, spans.headers.dsc(), decision);
+        let mut metrics = create_metrics(scoping, &spans.spans, spans.headers.dsc(), decision);
+
+        // Spans dropped by dynamic sampling have not been normalized yet, user defined metrics
+        // are only extracted from normalized spans which are kept.
+        if decision.is_keep() {
+            let custom = utils::metrics::extract_custom(
+                spans.spans.iter().filter_map(|span| span.span.value()),
+                ctx,
+            );
+            metrics
+                .project_metrics
+                .extend(custom.into_iter().map(|mut bucket| {
+                    bucket.metadata.extracted_from_indexed = true;
+                    bucket
+                }));
+        }
 
         (spans.into_indexed(), metrics)
     })
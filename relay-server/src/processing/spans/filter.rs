@@ -1,11 +1,12 @@
 use relay_dynamic_config::Feature;
 use relay_event_schema::protocol::SpanV2;
 use relay_protocol::Annotated;
+use relay_quotas::DataCategory;
 
 use crate::extractors::RequestMeta;
 use crate::managed::Managed;
-use crate::processing::Context;
 use crate::processing::spans::{Error, ExpandedSpans, Result, SerializedSpans};
+use crate::processing::{Context, utils};
 
 // Filters span attachments for a project which does not allow for span attachment ingestion.
 pub fn feature_flag_attachment(
@@ -25,20 +26,25 @@ pub fn feature_flag_attachment(
 pub fn filter(spans: &mut Managed<ExpandedSpans>, ctx: Context<'_>) {
     spans.retain_with_context(
         |spans| (&mut spans.spans, spans.headers.meta()),
-        |span, meta, _| filter_span(&span.span, meta, ctx),
+        |span, meta, _| filter_span(&mut span.span, meta, ctx),
     );
 }
 
-fn filter_span(span: &Annotated<SpanV2>, meta: &RequestMeta, ctx: Context<'_>) -> Result<()> {
-    let Some(span) = span.value() else {
+fn filter_span(span: &mut Annotated<SpanV2>, meta: &RequestMeta, ctx: Context<'_>) -> Result<()> {
+    let Some(span) = span.value_mut() else {
         return Ok(());
     };
 
-    relay_filter::should_filter(
-        span,
+    let result = relay_filter::should_filter(
+        &*span,
         meta.client_addr(),
         &ctx.project_info.config.filter_settings,
         ctx.global_config.filters(),
-    )
-    .map_err(Error::Filtered)
+    );
+
+    if let Some(key) = utils::filter::apply(result, DataCategory::Span).map_err(Error::Filtered)? {
+        utils::filter::attach_attribute(&mut span.attributes, &key);
+    }
+
+    Ok(())
 }
@@ -4,9 +4,10 @@ use relay_quotas::DataCategory;
 use crate::envelope::{ContainerItems, Item, WithHeader};
 use crate::integrations::{Integration, SpansIntegration};
 use crate::managed::RecordKeeper;
-use crate::processing::spans::Settings;
+use crate::processing::spans::{Error, Settings};
 
 mod otel;
+mod zipkin;
 
 /// Expands a list of [`Integration`] items.
 ///
@@ -26,16 +27,25 @@ pub fn expand(
             }
         };
 
-        let produce = |span: SpanV2| {
+        let mut produce = |span: Result<SpanV2, Error>| {
             records.modify_by(DataCategory::Span, 1);
             records.modify_by(DataCategory::SpanIndexed, 1);
-            result.push(WithHeader::new(span.into()));
+            match span {
+                Ok(span) => result.push(WithHeader::new(span.into())),
+                Err(err) => drop(records.reject_err(
+                    err,
+                    [(DataCategory::Span, 1), (DataCategory::SpanIndexed, 1)],
+                )),
+            }
         };
 
         let payload = item.payload();
 
         let result = match integration {
-            SpansIntegration::OtelV1 { format } => otel::expand(format, &payload, produce),
+            SpansIntegration::OtelV1 { format } => {
+                otel::expand(format, &payload, |span| produce(Ok(span)))
+            }
+            SpansIntegration::ZipkinV2 { format } => zipkin::expand(format, &payload, produce),
         };
 
         match result {
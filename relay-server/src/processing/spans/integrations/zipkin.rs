@@ -0,0 +1,71 @@
+use prost::Message as _;
+use relay_event_schema::protocol::SpanV2;
+use relay_spans::{ZipkinSpan, zipkin_proto};
+use serde::Deserialize;
+
+use crate::integrations::ZipkinFormat;
+use crate::processing::spans::{Error, Result};
+use crate::services::outcome::DiscardReason;
+
+/// Expands Zipkin v2 spans into the [`SpanV2`] format.
+///
+/// Spans which cannot be parsed or converted are passed to `produce` as errors, without rejecting
+/// the other spans in the payload.
+pub fn expand<F>(format: ZipkinFormat, payload: &[u8], mut produce: F) -> Result<()>
+where
+    F: FnMut(Result<SpanV2>),
+{
+    for span in parse_spans(format, payload)? {
+        let span = span.and_then(|span| {
+            relay_spans::zipkin_to_sentry_span_v2(span).map_err(|e| {
+                relay_log::debug!(
+                    error = &e as &dyn std::error::Error,
+                    "Failed to convert zipkin span"
+                );
+                Error::Invalid(DiscardReason::InvalidSpan)
+            })
+        });
+        produce(span);
+    }
+
+    Ok(())
+}
+
+/// Parses the list of spans in the payload.
+///
+/// The list itself must be valid, while each of the JSON spans is parsed individually.
+fn parse_spans(format: ZipkinFormat, payload: &[u8]) -> Result<Vec<Result<ZipkinSpan>>, Error> {
+    match format {
+        ZipkinFormat::Json => {
+            let spans: Vec<serde_json::Value> = serde_json::from_slice(payload).map_err(|e| {
+                relay_log::debug!(
+                    error = &e as &dyn std::error::Error,
+                    "Failed to parse zipkin spans as JSON"
+                );
+                Error::Invalid(DiscardReason::InvalidJson)
+            })?;
+
+            Ok(spans
+                .into_iter()
+                .map(|span| {
+                    ZipkinSpan::deserialize(span).map_err(|e| {
+                        relay_log::debug!(
+                            error = &e as &dyn std::error::Error,
+                            "Failed to parse zipkin span as JSON"
+                        );
+                        Error::Invalid(DiscardReason::InvalidJson)
+                    })
+                })
+                .collect())
+        }
+        ZipkinFormat::Protobuf => zipkin_proto::ListOfSpans::decode(payload)
+            .map(|list| list.spans.into_iter().map(|span| Ok(span.into())).collect())
+            .map_err(|e| {
+                relay_log::debug!(
+                    error = &e as &dyn std::error::Error,
+                    "Failed to parse zipkin spans as protobuf"
+                );
+                Error::Invalid(DiscardReason::InvalidProtobuf)
+            }),
+    }
+}
@@ -590,7 +590,7 @@ impl RateLimited for Managed<ExpandedSpans<TotalAndIndexed>> {
     async fn enforce<R>(
         mut self,
         mut rate_limiter: R,
-        _: Context<'_>,
+        ctx: Context<'_>,
     ) -> Result<Self::Output, Rejected<Self::Error>>
     where
         R: processing::RateLimiter,
@@ -618,7 +618,7 @@ impl RateLimited for Managed<ExpandedSpans<TotalAndIndexed>> {
         if !limits.is_empty() {
             // If there is an indexed span quota reject all the spans and the associated attachments,
             // but keep the total counts.
-            let total = dynamic_sampling::reject_indexed_spans(self, limits.into());
+            let total = dynamic_sampling::reject_indexed_spans(self, limits.into(), ctx);
             return Ok(total.map(|total, _| Either::Right(total)));
         }
 
@@ -13,6 +13,7 @@ use crate::processing::spans::{
     self, Error, ExpandedAttachment, ExpandedSpan, ExpandedSpans, Result, SerializedSpans,
     Settings, SpanItems,
 };
+use crate::processing::utils::normalize::Trimming;
 use crate::processing::{Context, trace_attachments, utils};
 use crate::services::outcome::DiscardReason;
 
@@ -273,10 +274,11 @@ fn normalize_span(
 /// to also run for derived fields.
 pub fn normalize_derived(spans: &mut Managed<ExpandedSpans>, ctx: Context<'_>) {
     let settings = spans.settings;
+    let trimming = utils::normalize::trimming(|t| t.span.as_ref(), ctx);
     spans.retain_with_context(
         |spans| (&mut spans.spans, &()),
         |span, _, _| {
-            normalize_span_derived(&mut span.span, settings, ctx).inspect_err(|err| {
+            normalize_span_derived(&mut span.span, settings, &trimming, ctx).inspect_err(|err| {
                 relay_log::debug!("failed to normalize span: {err}");
             })
         },
@@ -286,8 +288,11 @@ pub fn normalize_derived(spans: &mut Managed<ExpandedSpans>, ctx: Context<'_>) {
 fn normalize_span_derived(
     span: &mut Annotated<SpanV2>,
     settings: Settings,
+    trimming: &Trimming<'_>,
     ctx: Context<'_>,
 ) -> Result<()> {
+    let mut removed_key_byte_budget = ctx.config.max_removed_attribute_key_size();
+
     if let Some(span) = span.value_mut() {
         // The order of operations shouldn't matter here—we should never _actually_
         // need to synthesize both the name and description. If the span was sent as
@@ -296,21 +301,22 @@ fn normalize_span_derived(
             eap::normalize_span_name(span);
         }
         eap::normalize_sentry_description(&mut span.attributes, &span.name);
+        eap::apply_attribute_policies(
+            &mut span.attributes,
+            &trimming.policies,
+            &mut removed_key_byte_budget,
+        );
     }
 
     // Set a max_bytes value on the root state if it's defined in the project config.
     // This causes the whole item to be trimmed down to the limit.
-    let max_bytes = ctx
-        .project_info
-        .config()
-        .trimming
-        .span
-        .map(|cfg| cfg.max_size as usize);
-    let trimming_root = ProcessingState::root_builder().max_bytes(max_bytes).build();
+    let trimming_root = ProcessingState::root_builder()
+        .max_bytes(trimming.max_size)
+        .build();
 
     process_value(
         span,
-        &mut eap::TrimmingProcessor::new(ctx.config.max_removed_attribute_key_size()),
+        &mut eap::TrimmingProcessor::new(removed_key_byte_budget).with_policies(&trimming.policies),
         &trimming_root,
     )?;
 
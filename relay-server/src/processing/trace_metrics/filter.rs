@@ -1,11 +1,12 @@
 use relay_dynamic_config::Feature;
 use relay_event_schema::protocol::TraceMetric;
 use relay_protocol::Annotated;
+use relay_quotas::DataCategory;
 
 use crate::extractors::RequestMeta;
 use crate::managed::Managed;
-use crate::processing::Context;
 use crate::processing::trace_metrics::{Error, ExpandedTraceMetrics, Result};
+use crate::processing::{Context, utils};
 
 pub fn feature_flag(ctx: Context<'_>) -> Result<()> {
     match ctx.should_filter(Feature::TraceMetricsIngestion) {
@@ -23,19 +24,26 @@ pub fn filter(metrics: &mut Managed<ExpandedTraceMetrics>, ctx: Context<'_>) {
 }
 
 fn filter_metric(
-    metric: &Annotated<TraceMetric>,
+    metric: &mut Annotated<TraceMetric>,
     meta: &RequestMeta,
     ctx: Context<'_>,
 ) -> Result<()> {
-    let Some(metric) = metric.value() else {
+    let Some(metric) = metric.value_mut() else {
         return Ok(());
     };
 
-    relay_filter::should_filter(
-        metric,
+    let result = relay_filter::should_filter(
+        &*metric,
         meta.client_addr(),
         &ctx.project_info.config.filter_settings,
         ctx.global_config.filters(),
-    )
-    .map_err(Error::Filtered)
+    );
+
+    if let Some(key) =
+        utils::filter::apply(result, DataCategory::TraceMetric).map_err(Error::Filtered)?
+    {
+        utils::filter::attach_attribute(&mut metric.attributes, &key);
+    }
+
+    Ok(())
 }
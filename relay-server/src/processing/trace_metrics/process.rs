@@ -10,6 +10,7 @@ use crate::extractors::RequestTrust;
 use crate::processing::Managed;
 use crate::processing::trace_metrics::{Error, Result, Settings, utils::calculate_size};
 use crate::processing::trace_metrics::{ExpandedTraceMetrics, SerializedTraceMetrics};
+use crate::processing::utils::normalize::Trimming;
 use crate::processing::{Context, utils};
 use crate::services::outcome::DiscardReason;
 
@@ -183,19 +184,32 @@ fn normalize_trace_metric(
 /// This is separate from [`normalize`] because it needs to run
 /// after PII scrubbing; PII might get leaked otherwise.
 ///
-/// In practice, for trace metrics, it only performs schema validation.
-pub fn normalize_derived(metrics: &mut Managed<ExpandedTraceMetrics>) {
+/// In practice, for trace metrics, it applies attribute policies and performs schema validation.
+pub fn normalize_derived(metrics: &mut Managed<ExpandedTraceMetrics>, ctx: Context<'_>) {
+    let trimming = utils::normalize::trimming(|t| t.trace_metric.as_ref(), ctx);
     metrics.retain_with_context(
         |metrics| (&mut metrics.metrics, &()),
         |metric, _, _| {
-            normalize_trace_metric_derived(metric).inspect_err(|err| {
+            normalize_trace_metric_derived(metric, &trimming, ctx).inspect_err(|err| {
                 relay_log::debug!("failed to normalize trace metric: {err}");
             })
         },
     );
 }
 
-fn normalize_trace_metric_derived(metric: &mut Annotated<TraceMetric>) -> Result<()> {
+fn normalize_trace_metric_derived(
+    metric: &mut Annotated<TraceMetric>,
+    trimming: &Trimming<'_>,
+    ctx: Context<'_>,
+) -> Result<()> {
+    if let Some(metric) = metric.value_mut() {
+        eap::apply_attribute_policies(
+            &mut metric.attributes,
+            &trimming.policies,
+            &mut ctx.config.max_removed_attribute_key_size(),
+        );
+    }
+
     process_value(
         metric,
         &mut SchemaProcessor::new()
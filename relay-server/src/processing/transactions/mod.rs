@@ -144,7 +144,7 @@ impl Processor for TransactionProcessor {
         let mut tx = process::normalize(tx, ctx, &self.geoip_lookup)?;
 
         relay_log::trace!("Filter transaction");
-        let filters_status = process::run_inbound_filters(&tx, ctx)?;
+        let filters_status = process::run_inbound_filters(&mut tx, ctx)?;
 
         relay_log::trace!("Processing profile");
         process::process_profile(&mut tx, ctx);
@@ -9,7 +9,7 @@ use relay_statsd::metric;
 use smallvec::smallvec;
 
 use crate::envelope::Item;
-use crate::managed::{Counted, Managed, ManagedResult, Quantities, RecordKeeper, Rejected};
+use crate::managed::{Counted, Managed, Quantities, RecordKeeper, Rejected};
 use crate::metrics_extraction::ExtractedMetrics;
 use crate::processing::spans::{Indexed, TotalAndIndexed};
 use crate::processing::transactions::extraction::{self, ExtractMetricsContext};
@@ -201,13 +201,17 @@ pub fn normalize(
 
 /// Rejects the entire unit of work if one of the project's filters matches.
 pub fn run_inbound_filters(
-    work: &Managed<Box<ExpandedTransaction>>,
+    work: &mut Managed<Box<ExpandedTransaction>>,
     ctx: Context<'_>,
 ) -> Result<FiltersStatus, Rejected<Error>> {
-    utils::event::filter(&work.headers, &work.event, ctx)
-        .map_err(ProcessingError::EventFiltered)
-        .map_err(Error::from)
-        .reject(work)
+    let mut status = FiltersStatus::Ok;
+    work.try_modify(|work, _| {
+        status = utils::event::filter(&work.headers, &mut work.event, ctx)
+            .map_err(ProcessingError::EventFiltered)?;
+        Ok::<_, Error>(())
+    })?;
+
+    Ok(status)
 }
 
 /// The result of dynamic sampling.
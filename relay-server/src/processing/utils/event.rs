@@ -343,12 +343,15 @@ pub enum FiltersStatus {
     Unsupported,
 }
 
+/// Applies inbound filters to the event.
+///
+/// If the event matches a report-only filter, it is tagged with the name of the filter.
 pub fn filter(
     headers: &EnvelopeHeaders,
-    event: &Annotated<Event>,
+    event: &mut Annotated<Event>,
     ctx: Context,
 ) -> Result<FiltersStatus, FilterStatKey> {
-    let event = match event.value() {
+    let event = match event.value_mut() {
         Some(event) => event,
         // Some events are created by processing relays (e.g. unreal), so they do not yet
         // exist at this point in non-processing relays.
@@ -358,15 +361,22 @@ pub fn filter(
     let client_ip = headers.meta().client_addr();
     let filter_settings = &ctx.project_info.config.filter_settings;
 
-    metric!(timer(RelayTimers::EventProcessingFiltering), {
+    let reported = metric!(timer(RelayTimers::EventProcessingFiltering), {
         relay_filter::should_filter(
-            event,
+            &*event,
             client_ip,
             filter_settings,
             ctx.global_config.filters(),
         )
     })?;
 
+    if let Some(key) = reported {
+        super::filter::attach_tag(&mut event.tags, &key);
+
+        let category = DataCategory::from(event.ty.value().copied().unwrap_or_default());
+        super::filter::report(&key, category);
+    }
+
     // Don't extract metrics if relay can't apply generic filters.  A filter
     // applied in another up-to-date relay in chain may need to drop the event,
     // and there should not be metrics from dropped events.
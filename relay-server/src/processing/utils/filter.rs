@@ -0,0 +1,65 @@
+//! Shared handling of inbound filter results.
+
+use relay_event_schema::protocol::{Attributes, JsonLenientString, Tags};
+use relay_filter::FilterStatKey;
+use relay_protocol::{Annotated, Object};
+use relay_quotas::DataCategory;
+use relay_statsd::metric;
+
+use crate::statsd::RelayCounters;
+
+/// Tag added to events which matched a report-only filter.
+const REPORT_ONLY_FILTER_TAG: &str = "report_only_filter";
+
+/// Attribute added to items which matched a report-only filter.
+const REPORT_ONLY_FILTER_ATTRIBUTE: &str = "sentry.report_only_filter";
+
+/// Applies the result of [`relay_filter::should_filter`] to an item of the given `category`.
+///
+/// Returns the filter reason if the item must be dropped. Matches of report-only filters keep the
+/// item, are reported, see [`report`], and returned so they can be attached to the item.
+pub fn apply(
+    result: Result<Option<FilterStatKey>, FilterStatKey>,
+    category: DataCategory,
+) -> Result<Option<FilterStatKey>, FilterStatKey> {
+    let reported = result?;
+    if let Some(key) = &reported {
+        report(key, category);
+    }
+
+    Ok(reported)
+}
+
+/// Reports an item of the given `category` which matched a report-only filter.
+pub fn report(key: &FilterStatKey, category: DataCategory) {
+    metric!(
+        counter(RelayCounters::ReportOnlyFilter) += 1,
+        filter = key.clone().name().as_ref(),
+        category = category.name(),
+    );
+}
+
+/// Attaches a matched report-only filter to the tags of an event.
+///
+/// An existing tag is replaced, so that the tag is not duplicated in chains of Relays.
+pub fn attach_tag(tags: &mut Annotated<Tags>, key: &FilterStatKey) {
+    tags.get_or_insert_with(Tags::default).0.insert(
+        REPORT_ONLY_FILTER_TAG.into(),
+        Annotated::new(key.to_string()),
+    );
+}
+
+/// Attaches a matched report-only filter to the tags of a span.
+pub fn attach_span_tag(tags: &mut Annotated<Object<JsonLenientString>>, key: &FilterStatKey) {
+    tags.get_or_insert_with(Default::default).insert(
+        REPORT_ONLY_FILTER_TAG.to_owned(),
+        Annotated::new(key.to_string().into()),
+    );
+}
+
+/// Attaches a matched report-only filter to the attributes of an item.
+pub fn attach_attribute(attributes: &mut Annotated<Attributes>, key: &FilterStatKey) {
+    attributes
+        .get_or_insert_with(Default::default)
+        .insert(REPORT_ONLY_FILTER_ATTRIBUTE, key.to_string());
+}
@@ -0,0 +1,99 @@
+//! Generic metric extraction for items of the logs and spans pipelines.
+
+use relay_dynamic_config::{CombinedMetricExtractionConfig, ErrorBoundary};
+use relay_metrics::{Bucket, MetricNamespace};
+
+use crate::metrics_extraction::generic::{self, Extractable};
+use crate::processing::Context;
+
+/// Returns the metric extraction config of the project combined with the global config.
+///
+/// Returns `None` if metric extraction is not enabled for the project.
+pub fn extraction_config(ctx: Context<'_>) -> Option<CombinedMetricExtractionConfig<'_>> {
+    let local = match ctx.project_info.config.metric_extraction {
+        ErrorBoundary::Ok(ref config) if config.is_enabled() => config,
+        _ => return None,
+    };
+    let global = ctx.global_config.metric_extraction.as_ref().ok()?;
+    Some(CombinedMetricExtractionConfig::new(global, local))
+}
+
+/// Extracts user defined metrics from all `items`.
+///
+/// Only buckets in the custom namespace are kept. Metrics in other namespaces are produced by
+/// dedicated extraction steps and must not be created from generic configs on these items.
+pub fn extract_custom<'a, T, I>(items: I, ctx: Context<'_>) -> Vec<Bucket>
+where
+    T: Extractable + 'a,
+    I: IntoIterator<Item = &'a T>,
+{
+    let Some(config) = extraction_config(ctx) else {
+        return Vec::new();
+    };
+
+    items
+        .into_iter()
+        .flat_map(|item| generic::extract_metrics(item, config))
+        .filter(|bucket| bucket.name.namespace() == MetricNamespace::Custom)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use relay_dynamic_config::{GlobalConfig, ProjectConfig};
+    use relay_event_schema::protocol::OurLog;
+    use relay_protocol::Annotated;
+
+    use crate::services::projects::project::ProjectInfo;
+
+    use super::*;
+
+    #[test]
+    fn test_extract_custom_only() {
+        let config: ProjectConfig = serde_json::from_value(serde_json::json!({
+            "metricExtraction": {
+                "version": 1,
+                "metrics": [
+                    {"category": "log_item", "mri": "c:custom/logs@none"},
+                    {"category": "log_item", "mri": "c:spans/logs@none"},
+                ]
+            }
+        }))
+        .unwrap();
+
+        let project_info = ProjectInfo {
+            config,
+            ..Default::default()
+        };
+        let global_config = GlobalConfig::default();
+        let ctx = Context {
+            project_info: &project_info,
+            global_config: &global_config,
+            ..Context::for_test()
+        };
+
+        let log = Annotated::<OurLog>::from_json(
+            r#"{
+                "timestamp": 1544719860.0,
+                "trace_id": "5b8efff798038103d269b633813fc60c",
+                "level": "info",
+                "body": "hello"
+            }"#,
+        )
+        .unwrap();
+
+        let metrics = extract_custom(log.value(), ctx);
+        let names: Vec<_> = metrics.iter().map(|b| b.name.as_ref()).collect();
+        assert_eq!(names, ["c:custom/logs@none"]);
+    }
+
+    #[test]
+    fn test_extract_disabled() {
+        let log = OurLog {
+            body: Annotated::new("hello".to_owned()),
+            ..Default::default()
+        };
+
+        assert!(extract_custom([&log], Context::for_test()).is_empty());
+    }
+}
@@ -2,6 +2,8 @@ pub mod attachments;
 pub mod dsc;
 pub mod dynamic_sampling;
 pub mod event;
+pub mod filter;
+pub mod metrics;
 pub mod normalize;
 #[cfg(feature = "processing")]
 pub mod store;
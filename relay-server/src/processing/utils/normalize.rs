@@ -1,7 +1,7 @@
 use std::time::Duration;
 
-use relay_dynamic_config::{RetentionConfig, RetentionsConfig};
-use relay_event_normalization::eap;
+use relay_dynamic_config::{RetentionConfig, RetentionsConfig, TrimmingConfig, TrimmingConfigs};
+use relay_event_normalization::eap::{self, AttributePolicy};
 
 use crate::envelope::EnvelopeHeaders;
 use crate::processing::Context;
@@ -29,6 +29,39 @@ where
     }
 }
 
+/// Trimming settings of an item type, combined from the project and global config.
+#[derive(Debug, Default)]
+pub struct Trimming<'a> {
+    /// The maximum size of an item in bytes.
+    ///
+    /// The size in the project config takes precedence over the global config.
+    pub max_size: Option<usize>,
+    /// Attribute policies in order of precedence, the project policy first.
+    pub policies: Vec<&'a AttributePolicy>,
+}
+
+/// Utility to create the [`Trimming`] settings of an item type.
+pub fn trimming<F>(f: F, ctx: Context<'_>) -> Trimming<'_>
+where
+    F: Fn(&TrimmingConfigs) -> Option<&TrimmingConfig>,
+{
+    let project = f(&ctx.project_info.config().trimming);
+    let global = f(&ctx.global_config.trimming);
+
+    Trimming {
+        max_size: project
+            .and_then(|config| config.max_size)
+            .or_else(|| global.and_then(|config| config.max_size))
+            .map(|max_size| max_size as usize),
+        policies: [project, global]
+            .into_iter()
+            .flatten()
+            .map(|config| &config.attributes)
+            .filter(|policy| !policy.is_empty())
+            .collect(),
+    }
+}
+
 fn retention_days_to_duration(days: u16) -> Duration {
     const DAYS_TO_SECONDS: u64 = 24 * 60 * 60;
     Duration::from_secs(days as u64 * DAYS_TO_SECONDS)
@@ -8,6 +8,7 @@ use crate::services::buffer::{
     ObservableEnvelopeBuffer, PartitionedEnvelopeBuffer, ProjectKeyPair,
 };
 use crate::services::cogs::{CogsService, CogsServiceRecorder};
+use crate::services::conventions::AttributeOverridesService;
 use crate::services::global_config::{
     GlobalConfigHandle, GlobalConfigManager, GlobalConfigService,
 };
@@ -18,8 +19,8 @@ use crate::services::objectstore::Objectstore;
 #[cfg(feature = "processing")]
 use crate::services::objectstore::ObjectstoreService;
 use crate::services::outcome::{
-    ClientReportOutcomeProducerService, NullOutcomeProducerService, OutcomeProducerService,
-    TrackOutcome,
+    ClientReportOutcomeProducerService, NullOutcomeProducerService, OutcomeLedger,
+    OutcomeLedgerService, OutcomeProducerService, TrackOutcome,
 };
 use crate::services::processor::{
     self, EnvelopeProcessor, EnvelopeProcessorService, EnvelopeProcessorServicePool,
@@ -28,11 +29,13 @@ use crate::services::projects::cache::{ProjectCacheHandle, ProjectCacheService};
 use crate::services::projects::source::ProjectSource;
 use crate::services::proxy_processor::{ProxyAddrs, ProxyProcessorService};
 use crate::services::relays::{RelayCache, RelayCacheService};
+use crate::services::sessions::SessionAggregatorService;
 use crate::services::stats::RelayStats;
 #[cfg(feature = "processing")]
 use crate::services::store::{StoreService, StoreServicePool};
 use crate::services::upload::{self, Upload};
 use crate::services::upstream::{UpstreamRelay, UpstreamRelayService};
+use crate::services::user_agent::UserAgentDefinitionsService;
 use crate::utils::{MemoryChecker, MemoryStat, ThreadKind};
 #[cfg(feature = "processing")]
 use anyhow::Context;
@@ -40,7 +43,7 @@ use anyhow::Result;
 use axum::extract::FromRequestParts;
 use axum::http::request::Parts;
 use relay_cogs::Cogs;
-use relay_config::{Config, EmitOutcomes, RelayMode};
+use relay_config::{Config, EmitOutcomes, RelayMode, ServiceBackpressure, ServiceQueue};
 #[cfg(feature = "processing")]
 use relay_config::{RedisConfigRef, RedisConfigsRef};
 #[cfg(feature = "processing")]
@@ -51,7 +54,10 @@ use relay_redis::redis::Script;
 use relay_redis::{RedisClients, RedisError, RedisScripts};
 #[cfg(feature = "processing")]
 use relay_system::ConcurrentService;
-use relay_system::{Addr, Service, ServiceSpawn, ServiceSpawnExt as _, channel};
+use relay_system::{
+    Addr, Backpressure, Receiver, Service, ServiceSpawn, ServiceSpawnExt as _, bounded_channel,
+    channel,
+};
 
 /// Indicates the type of failure of the server.
 #[derive(Debug, thiserror::Error)]
@@ -75,6 +81,7 @@ pub enum ServiceError {
 pub struct Registry {
     pub health_check: Addr<HealthCheck>,
     pub outcome_aggregator: Addr<TrackOutcome>,
+    pub outcome_ledger: Option<OutcomeLedger>,
     pub processor: Addr<EnvelopeProcessor>,
     pub relay_cache: Addr<RelayCache>,
     pub global_config: Addr<GlobalConfigManager>,
@@ -147,6 +154,23 @@ fn create_store_pool(config: &Config) -> Result<StoreServicePool> {
     Ok(pool)
 }
 
+/// Creates the inbound channel for a service, bounded if a queue is configured.
+fn service_channel<S: Service>(
+    queue: Option<&ServiceQueue>,
+) -> (Addr<S::Interface>, Receiver<S::Interface>) {
+    let Some(queue) = queue else {
+        return channel(S::name());
+    };
+
+    let backpressure = match queue.backpressure {
+        ServiceBackpressure::Wait => Backpressure::Wait,
+        ServiceBackpressure::Reject => Backpressure::Reject,
+        ServiceBackpressure::DropOldest => Backpressure::DropOldest,
+    };
+
+    bounded_channel(S::name(), queue.capacity, backpressure)
+}
+
 #[derive(Debug)]
 struct StateInner {
     config: Arc<Config>,
@@ -167,7 +191,11 @@ impl ServiceState {
         services: &dyn ServiceSpawn,
         config: Arc<Config>,
     ) -> Result<Self> {
-        let upstream_relay = services.start(UpstreamRelayService::new(config.clone()));
+        let upstream_service = UpstreamRelayService::new(config.clone());
+        let (upstream_relay, mut upstream_rx) =
+            service_channel::<UpstreamRelayService>(config.upstream_queue());
+        upstream_rx.set_loadshed(upstream_service.loadshed());
+        services.start_with(upstream_service, upstream_rx);
 
         #[cfg(feature = "processing")]
         let redis_clients = config
@@ -215,6 +243,16 @@ impl ServiceState {
             },
         };
 
+        // Record all outcomes locally before they leave Relay, if the ledger is enabled.
+        let outcome_ledger = config.outcome_ledger_retention().map(OutcomeLedger::new);
+        let outcome_aggregator = match &outcome_ledger {
+            Some(ledger) => services.start(OutcomeLedgerService::new(
+                ledger.clone(),
+                outcome_aggregator,
+            )),
+            None => outcome_aggregator,
+        };
+
         let (global_config, global_config_rx) =
             GlobalConfigService::new(config.clone(), upstream_relay.clone());
         let global_config_handle = global_config.handle();
@@ -223,6 +261,15 @@ impl ServiceState {
         // service fail if the service is not running.
         let global_config = services.start(global_config);
 
+        services.start(UserAgentDefinitionsService::new(
+            &config,
+            global_config_rx.clone(),
+        ));
+        services.start(AttributeOverridesService::new(
+            &config,
+            global_config_rx.clone(),
+        ));
+
         let project_source = ProjectSource::start_in(
             services,
             Arc::clone(&config),
@@ -248,7 +295,13 @@ impl ServiceState {
                     global_config_handle.clone(),
                     metric_outcomes.clone(),
                 )
-                .map(|s| services.start(s))
+                .map(|s| {
+                    let (store, mut store_rx) =
+                        service_channel::<StoreService>(config.store_queue());
+                    store_rx.set_loadshed(s.loadshed());
+                    services.start_with(s, store_rx);
+                    store
+                })
             })
             .transpose()?;
 
@@ -302,6 +355,10 @@ impl ServiceState {
                 let cogs = CogsService::new(&config);
                 let cogs = Cogs::new(CogsServiceRecorder::new(&config, services.start(cogs)));
 
+                let session_aggregator = config.aggregate_sessions().then(|| {
+                    services.start(SessionAggregatorService::new(&config, processor.clone()))
+                });
+
                 services.start_with(
                     EnvelopeProcessorService::new(
                         processor_pool.clone(),
@@ -319,6 +376,7 @@ impl ServiceState {
                             #[cfg(feature = "processing")]
                             store_forwarder: store,
                             aggregator: aggregator.clone(),
+                            session_aggregator,
                         },
                         metric_outcomes.clone(),
                     ),
@@ -371,6 +429,7 @@ impl ServiceState {
             processor,
             health_check,
             outcome_aggregator,
+            outcome_ledger,
             relay_cache,
             global_config,
             project_cache_handle,
@@ -450,6 +509,11 @@ impl ServiceState {
         &self.inner.registry.outcome_aggregator
     }
 
+    /// Returns the local [`OutcomeLedger`], if enabled.
+    pub fn outcome_ledger(&self) -> Option<&OutcomeLedger> {
+        self.inner.registry.outcome_ledger.as_ref()
+    }
+
     #[cfg(feature = "processing")]
     /// Returns the address of the [`Objectstore`] service.
     pub fn objectstore(&self) -> Option<&Addr<Objectstore>> {
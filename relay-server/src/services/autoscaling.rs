@@ -66,7 +66,10 @@ impl Service for AutoscalingMetricService {
                                 .map(|(id, metric)| ServiceUtilization {
                                     name: id.name(),
                                     instance_id: id.instance_id(),
-                                    utilization: metric.utilization
+                                    utilization: metric.utilization,
+                                    queue_size: metric.queue_size,
+                                    queue_wait_ms: metric.queue_wait.as_millis() as u64,
+                                    dropped_messages: metric.dropped_messages,
                                 }
                             )
                                 .collect();
@@ -163,6 +166,12 @@ pub struct ServiceUtilization {
     pub instance_id: u32,
     /// Utilization as percentage.
     pub utilization: u8,
+    /// Number of messages waiting in the inbound queue.
+    pub queue_size: u64,
+    /// Average time in milliseconds messages recently waited in the inbound queue.
+    pub queue_wait_ms: u64,
+    /// Total number of messages dropped from the inbound queue due to backpressure.
+    pub dropped_messages: u64,
 }
 
 impl ServiceUtilization {
@@ -171,6 +180,9 @@ impl ServiceUtilization {
             name,
             instance_id,
             utilization,
+            queue_size: 0,
+            queue_wait_ms: 0,
+            dropped_messages: 0,
         }
     }
 }
@@ -0,0 +1,366 @@
+//! Offline maintenance of spool files written by the [`SqliteEnvelopeStore`].
+//!
+//! All operations assume that no Relay is currently using the spool file. They are exposed
+//! through the `relay spool` command.
+//!
+//! [`SqliteEnvelopeStore`]: super::sqlite::SqliteEnvelopeStore
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use futures::stream::TryStreamExt;
+use relay_base_schema::project::ProjectKey;
+use sqlx::query::Query;
+use sqlx::sqlite::{SqliteArguments, SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+use sqlx::{Pool, Row, Sqlite};
+
+use super::sqlite::{
+    DatabaseEnvelope, SqliteEnvelopeStore, SqliteEnvelopeStoreError, extract_batch,
+    extract_project_key_pair,
+};
+use crate::services::buffer::common::ProjectKeyPair;
+
+/// Selects rows of a spool file.
+///
+/// Unset fields match all rows. Envelopes are stored in batches, the age of a batch is the time
+/// at which its newest envelope was received.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SpoolFilter {
+    /// Only select envelopes of this project.
+    pub own_key: Option<ProjectKey>,
+    /// Only select envelopes with this sampling project.
+    pub sampling_key: Option<ProjectKey>,
+    /// Only select envelopes received before this time.
+    pub received_before: Option<DateTime<Utc>>,
+}
+
+impl SpoolFilter {
+    /// Only selects envelopes that have been received more than `age` ago.
+    pub fn older_than(mut self, age: Duration) -> Self {
+        self.received_before = Some(Utc::now() - age);
+        self
+    }
+
+    /// Returns `true` if the filter selects all envelopes.
+    pub fn is_empty(&self) -> bool {
+        self.own_key.is_none() && self.sampling_key.is_none() && self.received_before.is_none()
+    }
+
+    /// Binds the filter to the numbered parameters `?1` to `?3` of a query.
+    fn bind<'a>(
+        &self,
+        query: Query<'a, Sqlite, SqliteArguments<'a>>,
+    ) -> Query<'a, Sqlite, SqliteArguments<'a>> {
+        query
+            .bind(self.own_key.map(|key| key.to_string()))
+            .bind(self.sampling_key.map(|key| key.to_string()))
+            .bind(self.received_before.map(|t| t.timestamp_millis()))
+    }
+}
+
+/// Statistics about the envelopes of a [`ProjectKeyPair`] in a spool file.
+#[derive(Clone, Debug)]
+pub struct SpoolSummary {
+    /// The project key pair of the envelopes.
+    pub project_key_pair: ProjectKeyPair,
+    /// The number of envelopes.
+    pub count: u64,
+    /// The compressed size of all envelopes in bytes.
+    pub size: u64,
+    /// The time at which the oldest batch was received.
+    pub oldest: DateTime<Utc>,
+    /// The time at which the newest batch was received.
+    pub newest: DateTime<Utc>,
+}
+
+/// A spool file of a stopped Relay.
+#[derive(Debug)]
+pub struct SqliteSpoolFile {
+    path: PathBuf,
+    db: Pool<Sqlite>,
+}
+
+impl SqliteSpoolFile {
+    /// Opens an existing spool file and migrates it to the current schema.
+    pub async fn open(path: &Path) -> Result<Self, SqliteEnvelopeStoreError> {
+        if !path.exists() {
+            return Err(SqliteEnvelopeStoreError::FileNotFound(path.to_owned()));
+        }
+
+        Self::create(path).await
+    }
+
+    /// Opens a spool file and creates it if it does not exist yet.
+    pub async fn create(path: &Path) -> Result<Self, SqliteEnvelopeStoreError> {
+        SqliteEnvelopeStore::setup(path).await?;
+
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .journal_mode(SqliteJournalMode::Wal);
+
+        // A single connection is required to attach other databases when importing.
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .map_err(SqliteEnvelopeStoreError::SqlxSetupFailed)?;
+
+        Ok(Self {
+            path: path.to_owned(),
+            db,
+        })
+    }
+
+    /// Returns the path of the spool file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns statistics for every project key pair in the spool file.
+    pub async fn summary(&self) -> Result<Vec<SpoolSummary>, SqliteEnvelopeStoreError> {
+        let rows = build_summary()
+            .fetch_all(&self.db)
+            .await
+            .map_err(SqliteEnvelopeStoreError::FetchError)?;
+
+        let mut summaries = Vec::with_capacity(rows.len());
+        for row in rows {
+            let Ok(project_key_pair) = extract_project_key_pair(&row) else {
+                continue;
+            };
+
+            let get = |column| -> Result<i64, _> {
+                row.try_get(column)
+                    .map_err(SqliteEnvelopeStoreError::FetchError)
+            };
+
+            summaries.push(SpoolSummary {
+                project_key_pair,
+                count: get("count")? as u64,
+                size: get("size")? as u64,
+                oldest: DateTime::from_timestamp_millis(get("oldest")?).unwrap_or_default(),
+                newest: DateTime::from_timestamp_millis(get("newest")?).unwrap_or_default(),
+            });
+        }
+
+        Ok(summaries)
+    }
+
+    /// Calls `f` for every envelope selected by the filter, without removing it.
+    pub async fn for_each<F>(
+        &self,
+        filter: &SpoolFilter,
+        mut f: F,
+    ) -> Result<(), SqliteEnvelopeStoreError>
+    where
+        F: FnMut(DatabaseEnvelope) -> Result<(), std::io::Error>,
+    {
+        let mut rows = filter.bind(build_select()).fetch(&self.db);
+
+        while let Some(row) = rows
+            .try_next()
+            .await
+            .map_err(SqliteEnvelopeStoreError::FetchError)?
+        {
+            let ProjectKeyPair {
+                own_key,
+                sampling_key,
+            } = extract_project_key_pair(&row)?;
+
+            for envelope in extract_batch(own_key, sampling_key, row)?.into_envelopes() {
+                f(envelope)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Deletes all envelopes selected by the filter and returns the number of deleted envelopes.
+    pub async fn delete(&self, filter: &SpoolFilter) -> Result<u64, SqliteEnvelopeStoreError> {
+        let rows = filter
+            .bind(build_delete())
+            .fetch_all(&self.db)
+            .await
+            .map_err(SqliteEnvelopeStoreError::WriteError)?;
+
+        let mut deleted = 0;
+        for row in rows {
+            let count: i64 = row
+                .try_get("count")
+                .map_err(SqliteEnvelopeStoreError::WriteError)?;
+            deleted += count as u64;
+        }
+
+        Ok(deleted)
+    }
+
+    /// Copies all envelopes from another spool file and returns the number of copied envelopes.
+    ///
+    /// The source file is migrated to the current schema, but otherwise left unchanged.
+    pub async fn import(&self, source: &Path) -> Result<u64, SqliteEnvelopeStoreError> {
+        // Migrate and checkpoint the source, so that all of its data is in the main file.
+        let source = Self::open(source).await?;
+        source.db.close().await;
+
+        let mut conn = self
+            .db
+            .acquire()
+            .await
+            .map_err(SqliteEnvelopeStoreError::WriteError)?;
+
+        sqlx::query("ATTACH DATABASE ? AS source;")
+            .bind(source.path.to_string_lossy().into_owned())
+            .execute(&mut *conn)
+            .await
+            .map_err(SqliteEnvelopeStoreError::WriteError)?;
+
+        let result = async {
+            let count: Option<i64> = sqlx::query("SELECT SUM(count) FROM source.envelopes;")
+                .fetch_one(&mut *conn)
+                .await
+                .and_then(|row| row.try_get(0))?;
+
+            sqlx::query(
+                "INSERT INTO main.envelopes (received_at, own_key, sampling_key, count, envelope)
+                 SELECT received_at, own_key, sampling_key, count, envelope FROM source.envelopes;",
+            )
+            .execute(&mut *conn)
+            .await?;
+
+            Ok::<_, sqlx::Error>(count.unwrap_or(0) as u64)
+        }
+        .await
+        .map_err(SqliteEnvelopeStoreError::WriteError);
+
+        sqlx::query("DETACH DATABASE source;")
+            .execute(&mut *conn)
+            .await
+            .map_err(SqliteEnvelopeStoreError::WriteError)?;
+
+        result
+    }
+
+    /// Rebuilds the database file to reclaim unused space and truncates the write-ahead log.
+    pub async fn vacuum(&self) -> Result<(), SqliteEnvelopeStoreError> {
+        sqlx::query("VACUUM;")
+            .execute(&self.db)
+            .await
+            .map_err(SqliteEnvelopeStoreError::WriteError)?;
+
+        sqlx::query("PRAGMA wal_checkpoint(TRUNCATE);")
+            .execute(&self.db)
+            .await
+            .map_err(SqliteEnvelopeStoreError::WriteError)?;
+
+        Ok(())
+    }
+
+    /// Closes the spool file and checkpoints the write-ahead log.
+    pub async fn close(self) {
+        self.db.close().await;
+    }
+}
+
+/// Returns the query to aggregate statistics for every project key pair.
+fn build_summary<'a>() -> Query<'a, Sqlite, SqliteArguments<'a>> {
+    sqlx::query(
+        "SELECT
+            own_key, sampling_key, SUM(count) AS count, SUM(LENGTH(envelope)) AS size,
+            MIN(received_at) AS oldest, MAX(received_at) AS newest
+         FROM envelopes
+         GROUP BY own_key, sampling_key
+         ORDER BY count DESC;",
+    )
+}
+
+/// Returns the query to select all rows matching a [`SpoolFilter`], oldest first.
+fn build_select<'a>() -> Query<'a, Sqlite, SqliteArguments<'a>> {
+    sqlx::query(
+        "SELECT received_at, own_key, sampling_key, envelope, count
+         FROM envelopes
+         WHERE (?1 IS NULL OR own_key = ?1)
+            AND (?2 IS NULL OR sampling_key = ?2)
+            AND (?3 IS NULL OR received_at < ?3)
+         ORDER BY received_at ASC;",
+    )
+}
+
+/// Returns the query to delete all rows matching a [`SpoolFilter`].
+fn build_delete<'a>() -> Query<'a, Sqlite, SqliteArguments<'a>> {
+    sqlx::query(
+        "DELETE FROM envelopes
+         WHERE (?1 IS NULL OR own_key = ?1)
+            AND (?2 IS NULL OR sampling_key = ?2)
+            AND (?3 IS NULL OR received_at < ?3)
+         RETURNING count;",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::services::buffer::testutils::utils::{mock_envelope, mock_envelopes};
+
+    async fn spool_file(envelopes: &[Box<crate::Envelope>]) -> SqliteSpoolFile {
+        let path = std::env::temp_dir().join(Uuid::new_v4().to_string());
+        let spool = SqliteSpoolFile::create(&path).await.unwrap();
+
+        let mut store = SqliteEnvelopeStore::new(0, spool.db.clone(), Default::default());
+        for envelope in envelopes {
+            let batch = vec![DatabaseEnvelope::try_from(envelope.as_ref()).unwrap()];
+            store.insert_batch(batch.try_into().unwrap()).await.unwrap();
+        }
+
+        spool
+    }
+
+    #[tokio::test]
+    async fn test_summary_and_export() {
+        let spool = spool_file(&mock_envelopes(3)).await;
+
+        let summary = spool.summary().await.unwrap();
+        assert_eq!(summary.len(), 1);
+        assert_eq!(summary[0].count, 3);
+        assert!(summary[0].size > 0);
+        assert!(summary[0].oldest <= summary[0].newest);
+
+        let mut exported = Vec::new();
+        spool
+            .for_each(&SpoolFilter::default(), |envelope| {
+                exported.push(envelope.into_payload()?);
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(exported.len(), 3);
+        assert!(crate::Envelope::parse_bytes(exported[0].to_vec().into()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_delete_by_age() {
+        let old = mock_envelope(Utc::now() - chrono::Duration::hours(2));
+        let new = mock_envelope(Utc::now());
+        let spool = spool_file(&[old, new]).await;
+
+        let filter = SpoolFilter::default().older_than(Duration::from_secs(3600));
+        assert_eq!(spool.delete(&filter).await.unwrap(), 1);
+        assert_eq!(spool.summary().await.unwrap()[0].count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_import() {
+        let source = spool_file(&mock_envelopes(2)).await;
+        let source_path = source.path().to_owned();
+        source.close().await;
+
+        let target = spool_file(&mock_envelopes(1)).await;
+        assert_eq!(target.import(&source_path).await.unwrap(), 2);
+        assert_eq!(target.summary().await.unwrap()[0].count, 3);
+
+        target.vacuum().await.unwrap();
+    }
+}
@@ -1 +1,2 @@
+pub mod maintenance;
 pub mod sqlite;
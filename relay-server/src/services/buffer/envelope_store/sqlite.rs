@@ -1,5 +1,5 @@
 use std::io::{ErrorKind, Read};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
@@ -52,6 +52,11 @@ impl DatabaseBatch {
     pub fn len(&self) -> usize {
         self.envelopes.len()
     }
+
+    /// Returns the envelopes contained in this batch.
+    pub fn into_envelopes(self) -> Vec<DatabaseEnvelope> {
+        self.envelopes
+    }
 }
 
 impl TryFrom<Vec<DatabaseEnvelope>> for DatabaseBatch {
@@ -100,23 +105,40 @@ impl DatabaseEnvelope {
     }
 }
 
+impl DatabaseEnvelope {
+    /// Returns the key of the project the envelope belongs to.
+    pub fn own_key(&self) -> ProjectKey {
+        self.own_key
+    }
+
+    /// Returns the key of the project used for dynamic sampling.
+    pub fn sampling_key(&self) -> ProjectKey {
+        self.sampling_key
+    }
+
+    /// Returns the serialized envelope, decompressing it if necessary.
+    pub fn into_payload(self) -> Result<Box<[u8]>, std::io::Error> {
+        let encoded_envelope = self.encoded_envelope;
+        if !encoded_envelope.starts_with(ZSTD_MAGIC_WORD) {
+            return Ok(encoded_envelope);
+        }
+
+        let decoded = relay_statsd::metric!(timer(RelayTimers::BufferEnvelopeDecompression), {
+            zstd::decode_all(&*encoded_envelope)
+        })?;
+
+        Ok(decoded.into_boxed_slice())
+    }
+}
+
 impl TryFrom<DatabaseEnvelope> for Box<Envelope> {
     type Error = InsertEnvelopeError;
 
     fn try_from(value: DatabaseEnvelope) -> Result<Self, Self::Error> {
         let received_at = value.received_at();
-        let DatabaseEnvelope {
-            received_at: _,
-            own_key,
-            sampling_key,
-            mut encoded_envelope,
-        } = value;
-
-        if encoded_envelope.starts_with(ZSTD_MAGIC_WORD) {
-            relay_statsd::metric!(timer(RelayTimers::BufferEnvelopeDecompression), {
-                encoded_envelope = zstd::decode_all(&*encoded_envelope)?.into_boxed_slice();
-            });
-        }
+        let own_key = value.own_key;
+        let sampling_key = value.sampling_key;
+        let encoded_envelope = value.into_payload()?;
 
         let mut envelope = Envelope::parse_bytes(Bytes::from(encoded_envelope))?;
         debug_assert_eq!(envelope.meta().public_key(), own_key);
@@ -194,6 +216,9 @@ pub enum SqliteEnvelopeStoreError {
 
     #[error("failed to get database file size: {0}")]
     FileSizeReadFailed(sqlx::Error),
+
+    #[error("spool file {0} does not exist")]
+    FileNotFound(PathBuf),
 }
 
 #[derive(Debug, Clone)]
@@ -373,7 +398,7 @@ impl SqliteEnvelopeStore {
     ///
     /// The directories and spool file will be created if they don't already
     /// exist.
-    async fn setup(path: &Path) -> Result<(), SqliteEnvelopeStoreError> {
+    pub(super) async fn setup(path: &Path) -> Result<(), SqliteEnvelopeStoreError> {
         Self::create_spool_directory(path).await?;
 
         let options = SqliteConnectOptions::new()
@@ -484,7 +509,7 @@ impl SqliteEnvelopeStore {
         let project_key_pairs = project_key_pairs
             .into_iter()
             // Collect only keys we can extract.
-            .filter_map(|project_key_pair| extract_project_key_pair(project_key_pair).ok())
+            .filter_map(|project_key_pair| extract_project_key_pair(&project_key_pair).ok())
             .collect();
 
         Ok(project_key_pairs)
@@ -553,7 +578,7 @@ fn unpack_envelopes(
 }
 
 /// Loads a [`DatabaseEnvelope`] from a database row.
-fn extract_batch(
+pub(super) fn extract_batch(
     own_key: ProjectKey,
     sampling_key: ProjectKey,
     row: SqliteRow,
@@ -591,7 +616,9 @@ fn extract_batch(
 }
 
 /// Deserializes a pair of [`ProjectKey`] from the database.
-fn extract_project_key_pair(row: SqliteRow) -> Result<ProjectKeyPair, SqliteEnvelopeStoreError> {
+pub(super) fn extract_project_key_pair(
+    row: &SqliteRow,
+) -> Result<ProjectKeyPair, SqliteEnvelopeStoreError> {
     let own_key = row
         .try_get("own_key")
         .map_err(SqliteEnvelopeStoreError::FetchError)
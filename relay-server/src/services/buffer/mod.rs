@@ -45,6 +45,8 @@ pub use envelope_stack::EnvelopeStack;
 // pub for benchmarks
 pub use envelope_store::sqlite::SqliteEnvelopeStore;
 
+pub use envelope_store::maintenance::{SpoolFilter, SpoolSummary, SqliteSpoolFile};
+
 use crate::services::projects::project::{ProjectInfo, ProjectState};
 pub use common::ProjectKeyPair;
 
@@ -0,0 +1,234 @@
+//! This module contains the service that keeps attribute overrides of `sentry-conventions` up to
+//! date.
+//!
+//! Attribute definitions are compiled into Relay. They can be overridden with definitions from a
+//! file configured in `conventions.attribute_overrides_path` and from the global config. Both are
+//! merged over the built-in definitions, where definitions from the global config take precedence
+//! over definitions from the file. Invalid overrides are discarded and the previous overrides
+//! remain active.
+
+use std::path::{Path, PathBuf};
+
+use relay_config::Config;
+use relay_conventions::{AttributeOverride, AttributeOverrides};
+use relay_dynamic_config::ErrorBoundary;
+use relay_statsd::metric;
+use relay_system::Service;
+use tokio::sync::watch;
+
+use crate::services::global_config;
+use crate::statsd::RelayCounters;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Service loading attribute overrides at runtime.
+#[derive(Debug)]
+pub struct AttributeOverridesService {
+    overrides_path: Option<PathBuf>,
+    global_config: watch::Receiver<global_config::Status>,
+}
+
+impl AttributeOverridesService {
+    /// Creates a new service, which loads overrides from the config and global config updates.
+    pub fn new(config: &Config, global_config: watch::Receiver<global_config::Status>) -> Self {
+        Self {
+            overrides_path: config.attribute_overrides_path().map(Into::into),
+            global_config,
+        }
+    }
+}
+
+impl Service for AttributeOverridesService {
+    type Interface = ();
+
+    async fn run(mut self, _rx: relay_system::Receiver<Self::Interface>) {
+        let file_overrides = match &self.overrides_path {
+            Some(path) => load_file(path).unwrap_or_else(|error| {
+                report("file", Err(error));
+                Vec::new()
+            }),
+            None => Vec::new(),
+        };
+
+        let mut state = OverridesState::new(file_overrides);
+
+        loop {
+            state.update(&self.global_config.borrow_and_update());
+
+            if self.global_config.changed().await.is_err() {
+                break;
+            }
+        }
+    }
+}
+
+/// Tracks the overrides from both sources and activates them on changes.
+#[derive(Debug)]
+struct OverridesState {
+    /// Overrides loaded from the file.
+    file: Vec<AttributeOverride>,
+    /// The last overrides received with the global config, even if invalid.
+    global: Option<Vec<AttributeOverride>>,
+}
+
+impl OverridesState {
+    /// Creates the state and activates the file overrides.
+    fn new(file: Vec<AttributeOverride>) -> Self {
+        if !file.is_empty() {
+            apply("file", &file, &[]);
+        }
+
+        Self { file, global: None }
+    }
+
+    /// Activates the overrides of a new global config status, if they changed.
+    ///
+    /// If the global config contains overrides which cannot be parsed, the previous overrides
+    /// remain active.
+    fn update(&mut self, status: &global_config::Status) {
+        let overrides = match status {
+            global_config::Status::Ready(config) => match &config.attribute_overrides {
+                ErrorBoundary::Ok(overrides) => overrides,
+                ErrorBoundary::Err(error) => {
+                    report("global_config", Err(error.to_string().into()));
+                    return;
+                }
+            },
+            global_config::Status::Pending => &Vec::new(),
+        };
+
+        if self.global.as_ref() == Some(overrides) {
+            return;
+        }
+
+        if !overrides.is_empty() || self.global.is_some() {
+            apply("global_config", &self.file, overrides);
+        }
+        self.global = Some(overrides.clone());
+    }
+}
+
+fn load_file(path: &Path) -> Result<Vec<AttributeOverride>, BoxError> {
+    let file = std::fs::File::open(path)?;
+    Ok(serde_json::from_reader(std::io::BufReader::new(file))?)
+}
+
+/// Merges the global overrides over the file overrides and activates them.
+fn apply(source: &'static str, file: &[AttributeOverride], global: &[AttributeOverride]) {
+    let merged: Vec<_> = file
+        .iter()
+        .filter(|attribute| !global.iter().any(|g| g.key == attribute.key))
+        .chain(global)
+        .cloned()
+        .collect();
+
+    let result = AttributeOverrides::new(&merged).map_err(BoxError::from);
+
+    if let Some(overrides) = report(source, result) {
+        if overrides.is_empty() {
+            relay_conventions::reset_attribute_overrides();
+        } else {
+            relay_conventions::set_attribute_overrides(overrides);
+        }
+    }
+}
+
+/// Reports the result of loading overrides.
+fn report(
+    source: &'static str,
+    result: Result<AttributeOverrides, BoxError>,
+) -> Option<AttributeOverrides> {
+    match result {
+        Ok(overrides) => {
+            relay_log::info!(
+                attributes = overrides.len(),
+                "loaded attribute overrides from {source}"
+            );
+            metric!(
+                counter(RelayCounters::AttributeOverridesLoaded) += 1,
+                source = source,
+                result = "success",
+            );
+            Some(overrides)
+        }
+        Err(error) => {
+            relay_log::error!(
+                error = error.as_ref() as &dyn std::error::Error,
+                "failed to load attribute overrides from {source}"
+            );
+            metric!(
+                counter(RelayCounters::AttributeOverridesLoaded) += 1,
+                source = source,
+                result = "invalid",
+            );
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use relay_conventions::{ApplyScrubbing, attribute_info};
+    use relay_dynamic_config::GlobalConfig;
+
+    use super::*;
+
+    fn overrides(json: &str) -> Vec<AttributeOverride> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn ready(json: &str) -> global_config::Status {
+        let config: GlobalConfig = serde_json::from_str(json).unwrap();
+        global_config::Status::Ready(Arc::new(config))
+    }
+
+    fn scrubbing(key: &str) -> Option<ApplyScrubbing> {
+        attribute_info(key).map(|info| info.apply_scrubbing)
+    }
+
+    #[test]
+    fn test_overrides_state() {
+        // All cases run in a single test, since overrides are global.
+        let mut state = OverridesState::new(overrides(
+            r#"[
+                {"key": "relay.test.file", "apply_scrubbing": {"key": "never"}},
+                {"key": "relay.test.both", "apply_scrubbing": {"key": "never"}}
+            ]"#,
+        ));
+        assert_eq!(scrubbing("relay.test.file"), Some(ApplyScrubbing::Never));
+        assert_eq!(scrubbing("relay.test.both"), Some(ApplyScrubbing::Never));
+
+        // The global config takes precedence over the file.
+        state.update(&ready(
+            r#"{"attributeOverrides": [
+                {"key": "relay.test.both", "apply_scrubbing": {"key": "manual"}},
+                {"key": "relay.test.global", "apply_scrubbing": {"key": "auto"}}
+            ]}"#,
+        ));
+        assert_eq!(scrubbing("relay.test.file"), Some(ApplyScrubbing::Never));
+        assert_eq!(scrubbing("relay.test.both"), Some(ApplyScrubbing::Manual));
+        assert_eq!(scrubbing("relay.test.global"), Some(ApplyScrubbing::Auto));
+
+        // Overrides which cannot be parsed keep the previous overrides.
+        state.update(&ready(r#"{"attributeOverrides": [{"key": 42}]}"#));
+        assert_eq!(scrubbing("relay.test.both"), Some(ApplyScrubbing::Manual));
+        assert_eq!(scrubbing("relay.test.global"), Some(ApplyScrubbing::Auto));
+
+        // Invalid overrides keep the previous overrides.
+        state.update(&ready(
+            r#"{"attributeOverrides": [{"key": "relay.test.a"}, {"key": "relay.test.a"}]}"#,
+        ));
+        assert_eq!(scrubbing("relay.test.global"), Some(ApplyScrubbing::Auto));
+        assert_eq!(scrubbing("relay.test.a"), None);
+
+        // Removing the overrides from the global config falls back to the file.
+        state.update(&ready("{}"));
+        assert_eq!(scrubbing("relay.test.file"), Some(ApplyScrubbing::Never));
+        assert_eq!(scrubbing("relay.test.both"), Some(ApplyScrubbing::Never));
+        assert_eq!(scrubbing("relay.test.global"), None);
+
+        relay_conventions::reset_attribute_overrides();
+    }
+}
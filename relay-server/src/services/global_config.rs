@@ -154,7 +154,8 @@ pub struct GlobalConfigHandle {
 
 impl GlobalConfigHandle {
     /// Creates a new global config handle with a fixed global config.
-    #[cfg(test)]
+    ///
+    /// The handle is not connected to a [`GlobalConfigService`] and never receives updates.
     pub fn fixed(config: GlobalConfig) -> Self {
         let (_, watch) = watch::channel(Status::Ready(Arc::new(config)));
         Self { watch }
@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -6,11 +7,15 @@ use std::time::{Duration, SystemTime};
 use hashbrown::HashMap;
 use hashbrown::hash_map::Entry;
 use relay_base_schema::project::ProjectKey;
+use relay_common::time::UnixTimestamp;
 use relay_config::AggregatorServiceConfig;
 use relay_metrics::Bucket;
-use relay_metrics::aggregator::{self, AggregateMetricsError, AggregatorConfig, Partition};
+use relay_metrics::aggregator::{
+    self, AggregateMetricsError, AggregatorConfig, Partition, Snapshot,
+};
 use relay_quotas::{RateLimits, Scoping};
 use relay_system::{Controller, FromMessage, Interface, NoResponse, Recipient, Service};
+use tokio::task::JoinHandle;
 use tokio::time::{Instant, Sleep};
 
 use crate::services::projects::cache::ProjectCacheHandle;
@@ -26,6 +31,9 @@ use crate::statsd::{RelayCounters, RelayTimers};
 /// Internally, the aggregator maintains a continuous flush cycle every 100ms. It guarantees that
 /// all elapsed buckets belonging to the same [`ProjectKey`] are flushed together.
 ///
+/// If [`AggregatorServiceConfig::snapshot_path`] is configured, unflushed buckets are persisted
+/// periodically and on shutdown, and restored when the service starts.
+///
 /// Receivers must implement a handler for the [`FlushBuckets`] message.
 #[derive(Debug)]
 pub enum Aggregator {
@@ -92,6 +100,11 @@ pub struct AggregatorService {
     config: AggregatorServiceConfig,
     can_accept_metrics: Arc<AtomicBool>,
     next_flush: Pin<Box<Sleep>>,
+    /// Path of the snapshot file, `None` if snapshots are disabled or after shutdown.
+    snapshot_path: Option<PathBuf>,
+    next_snapshot: Pin<Box<Sleep>>,
+    /// The snapshot currently being written, see [`Self::write_snapshot`].
+    snapshot_task: Option<JoinHandle<bool>>,
 }
 
 impl AggregatorService {
@@ -115,13 +128,18 @@ impl AggregatorService {
         project_cache: ProjectCacheHandle,
     ) -> Self {
         let aggregator = aggregator::Aggregator::named(name, &config.aggregator);
+        let snapshot_interval = Duration::from_secs(config.snapshot_interval.max(1));
+
         Self {
             receiver,
+            snapshot_path: config.snapshot_path.clone(),
             config,
             can_accept_metrics: Arc::new(AtomicBool::new(true)),
             aggregator,
             project_cache,
             next_flush: Box::pin(tokio::time::sleep(Duration::from_secs(0))),
+            next_snapshot: Box::pin(tokio::time::sleep(snapshot_interval)),
+            snapshot_task: None,
         }
     }
 
@@ -274,13 +292,155 @@ impl AggregatorService {
         }
     }
 
+    /// Merges the buckets of a previously written snapshot into the aggregator.
+    ///
+    /// Snapshots older than [`AggregatorConfig::max_secs_in_past`] are skipped. Individual buckets
+    /// are validated like merged buckets. Project states are checked once the buckets are flushed.
+    ///
+    /// The snapshot is read on a blocking thread and removed once it has been read, so that a crash
+    /// before the next snapshot does not restore the same buckets twice.
+    async fn restore_snapshot(&mut self) {
+        let Some(path) = self.snapshot_path.clone() else {
+            return;
+        };
+
+        // Restoring the buckets again after a restart would count them twice, so the snapshot is
+        // only restored if it can be removed.
+        let result = tokio::task::spawn_blocking(move || {
+            let snapshot = Snapshot::read(&path)?;
+            if snapshot.is_some() {
+                Snapshot::remove(&path)?;
+            }
+            Ok(snapshot)
+        })
+        .await
+        .unwrap_or_else(|error| Err(std::io::Error::other(error)));
+
+        let snapshot = match result {
+            Ok(Some(snapshot)) => snapshot,
+            Ok(None) => return,
+            Err(error) => {
+                relay_log::error!(
+                    tags.aggregator = self.aggregator.name(),
+                    error = &error as &dyn std::error::Error,
+                    "failed to restore metrics aggregator snapshot"
+                );
+                return;
+            }
+        };
+
+        let max_age = self.config.aggregator.max_secs_in_past;
+        if snapshot.is_expired(UnixTimestamp::now(), max_age) {
+            relay_log::warn!(
+                tags.aggregator = self.aggregator.name(),
+                "skipping expired metrics aggregator snapshot from {}",
+                snapshot.created
+            );
+            return;
+        }
+
+        let total = snapshot.len();
+        let mut restored = 0;
+        for (project_key, mut bucket) in snapshot.into_buckets() {
+            if !validate_bucket(&mut bucket, &self.config) {
+                continue;
+            }
+
+            match self.aggregator.merge(project_key, bucket) {
+                Ok(()) => restored += 1,
+                Err(error) => relay_log::debug!(
+                    tags.aggregator = self.aggregator.name(),
+                    tags.project_key = project_key.as_str(),
+                    bucket.error = &error as &dyn std::error::Error,
+                    "failed to restore metric bucket"
+                ),
+            }
+        }
+
+        relay_log::info!(
+            "Restored {restored} of {total} buckets into metrics aggregator {}",
+            self.aggregator.name()
+        );
+        relay_statsd::metric!(
+            counter(RelayCounters::BucketsRestored) += restored,
+            aggregator = self.aggregator.name(),
+        );
+    }
+
+    /// Writes a snapshot of all buckets to the configured snapshot path in the background.
+    ///
+    /// The snapshot is skipped if the previous snapshot is still being written.
+    fn write_snapshot(&mut self) {
+        if self
+            .snapshot_task
+            .as_ref()
+            .is_some_and(|task| !task.is_finished())
+        {
+            relay_log::debug!(
+                "Skipping snapshot for metrics aggregator {}, previous snapshot is pending",
+                self.aggregator.name()
+            );
+            return;
+        }
+
+        self.snapshot_task = self.spawn_snapshot();
+    }
+
+    /// Writes a snapshot of all buckets and waits for it to complete.
+    ///
+    /// Returns `true` if the snapshot was written successfully.
+    async fn persist_snapshot(&mut self) -> bool {
+        // Wait for the pending snapshot, it must not replace the latest snapshot.
+        if let Some(task) = self.snapshot_task.take() {
+            task.await.ok();
+        }
+
+        match self.spawn_snapshot() {
+            Some(task) => task.await.unwrap_or(false),
+            None => false,
+        }
+    }
+
+    /// Serializes all buckets into a snapshot and writes it on a blocking thread.
+    ///
+    /// Returns `None` if snapshots are disabled. The task resolves to `true` if the snapshot was
+    /// written successfully.
+    fn spawn_snapshot(&self) -> Option<JoinHandle<bool>> {
+        let path = self.snapshot_path.clone()?;
+        let name = self.aggregator.name().to_owned();
+
+        let result = self
+            .aggregator
+            .serialize_snapshot(UnixTimestamp::now())
+            .map_err(std::io::Error::from);
+
+        Some(tokio::task::spawn_blocking(move || {
+            match result.and_then(|data| Snapshot::write(&path, &data).map(|()| data.len())) {
+                Ok(size) => {
+                    relay_log::debug!(
+                        "Wrote snapshot of {size} bytes for metrics aggregator {name}"
+                    );
+                    true
+                }
+                Err(error) => {
+                    relay_log::error!(
+                        tags.aggregator = name.as_str(),
+                        error = &error as &dyn std::error::Error,
+                        "failed to write metrics aggregator snapshot"
+                    );
+                    false
+                }
+            }
+        }))
+    }
+
     fn handle_message(&mut self, message: Aggregator) {
         match message {
             Aggregator::MergeBuckets(msg) => self.handle_merge_buckets(msg),
         }
     }
 
-    fn handle_shutdown(&mut self) {
+    async fn handle_shutdown(&mut self) {
         relay_log::info!(
             "Shutting down metrics aggregator {}",
             self.aggregator.name()
@@ -293,18 +453,27 @@ impl AggregatorService {
                 bucket_interval: 1,
                 aggregator_size: 1,
                 initial_delay: 0,
-                ..self.config.aggregator
+                ..self.config.aggregator.clone()
             },
         );
 
+        // Persist the buckets instead of flushing them early. Buckets merged from now on are
+        // flushed by the new aggregator, snapshots must no longer overwrite the persisted buckets.
+        let persisted = self.persist_snapshot().await;
+        self.snapshot_path = None;
+
         let previous = std::mem::replace(&mut self.aggregator, aggregator);
 
-        let mut partitions = 0;
-        for partition in previous.into_partitions() {
-            self.flush_partition(partition);
-            partitions += 1;
+        if persisted {
+            relay_log::debug!("Persisted buckets instead of flushing them");
+        } else {
+            let mut partitions = 0;
+            for partition in previous.into_partitions() {
+                self.flush_partition(partition);
+                partitions += 1;
+            }
+            relay_log::debug!("Force flushed {partitions} partitions");
         }
-        relay_log::debug!("Force flushed {partitions} partitions");
 
         // Reset the next flush time, to the time of the new aggregator.
         self.next_flush
@@ -319,6 +488,8 @@ impl Service for AggregatorService {
     async fn run(mut self, mut rx: relay_system::Receiver<Self::Interface>) {
         let mut shutdown = Controller::shutdown_handle();
 
+        self.restore_snapshot().await;
+
         macro_rules! timed {
             ($task:expr, $body:expr) => {{
                 let task_name = $task;
@@ -341,8 +512,15 @@ impl Service for AggregatorService {
                         self.next_flush.as_mut().reset(Instant::now() + next);
                     }
                 ),
+                _ = &mut self.next_snapshot, if self.snapshot_path.is_some() => timed!(
+                    "snapshot", {
+                        self.write_snapshot();
+                        let interval = Duration::from_secs(self.config.snapshot_interval.max(1));
+                        self.next_snapshot.as_mut().reset(Instant::now() + interval);
+                    }
+                ),
                 Some(message) = rx.recv() => timed!(message.variant(), self.handle_message(message)),
-                _ = shutdown.notified() => timed!("shutdown", self.handle_shutdown()),
+                _ = shutdown.notified() => timed!("shutdown", self.handle_shutdown().await),
 
                 else => break,
             }
@@ -543,6 +721,67 @@ mod tests {
         assert_eq!(receiver.bucket_count(), 1);
     }
 
+    #[tokio::test]
+    async fn test_restore_snapshot() {
+        relay_test::setup();
+
+        let project_key = ProjectKey::parse("a94ae32be2584e0bbd7a4cbb95971fee").unwrap();
+        let project_cache = ProjectCacheHandle::for_test();
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = AggregatorServiceConfig {
+            snapshot_path: Some(dir.path().join("default.json")),
+            ..Default::default()
+        };
+
+        let mut bucket = some_bucket();
+        bucket.timestamp = UnixTimestamp::now();
+
+        let path = dir.path().join("default.json");
+
+        let mut previous = AggregatorService::new(config.clone(), None, project_cache.clone());
+        previous.handle_merge_buckets(MergeBuckets::new(project_key, vec![bucket.clone()]));
+        previous.write_snapshot();
+        previous.handle_shutdown().await;
+        // Buckets are persisted instead of flushed, no more snapshots after shutdown.
+        assert!(previous.aggregator.is_empty());
+        assert!(!previous.persist_snapshot().await);
+
+        let mut restored = AggregatorService::new(config.clone(), None, project_cache.clone());
+        restored.restore_snapshot().await;
+        let snapshot = restored
+            .aggregator
+            .serialize_snapshot(UnixTimestamp::now())
+            .unwrap();
+        let snapshot: Snapshot = serde_json::from_slice(&snapshot).unwrap();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot.buckets[0].bucket.value, bucket.value);
+
+        // The snapshot is removed after restoring, a second restore does not duplicate buckets.
+        assert!(!path.exists());
+        let mut duplicate = AggregatorService::new(config.clone(), None, project_cache.clone());
+        duplicate.restore_snapshot().await;
+        assert!(duplicate.aggregator.is_empty());
+
+        restored.handle_shutdown().await;
+
+        // Snapshots older than `max_secs_in_past` are skipped.
+        let expired = AggregatorServiceConfig {
+            aggregator: AggregatorConfig {
+                max_secs_in_past: 0,
+                ..Default::default()
+            },
+            ..config
+        };
+        let mut snapshot = Snapshot::read(&path).unwrap().unwrap();
+        snapshot.created = UnixTimestamp::from_secs(0);
+        Snapshot::write(&path, &serde_json::to_vec(&snapshot).unwrap()).unwrap();
+
+        let mut skipped = AggregatorService::new(expired, None, project_cache);
+        skipped.restore_snapshot().await;
+        assert!(skipped.aggregator.is_empty());
+    }
+
     fn test_config() -> AggregatorServiceConfig {
         AggregatorServiceConfig {
             max_name_length: 200,
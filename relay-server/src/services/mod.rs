@@ -30,6 +30,7 @@
 pub mod autoscaling;
 pub mod buffer;
 pub mod cogs;
+pub mod conventions;
 pub mod global_config;
 pub mod health_check;
 pub mod metrics;
@@ -41,8 +42,10 @@ pub mod projects;
 pub mod proxy_processor;
 pub mod relays;
 pub mod server;
+pub mod sessions;
 pub mod stats;
 #[cfg(feature = "processing")]
 pub mod store;
 pub mod upload;
 pub mod upstream;
+pub mod user_agent;
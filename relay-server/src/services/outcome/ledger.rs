@@ -0,0 +1,326 @@
+//! An in-memory ledger of recently emitted outcomes.
+//!
+//! Outcomes leave Relay either as outcomes, metrics or client reports. The ledger keeps a local,
+//! aggregated copy of them for a limited time, so operators can inspect what has been dropped and
+//! why directly on the Relay.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use relay_base_schema::project::ProjectKey;
+use relay_quotas::DataCategory;
+use relay_system::{Addr, Service};
+use serde::{Deserialize, Serialize};
+
+use crate::services::outcome::TrackOutcome;
+
+/// The width of a ledger bucket in seconds.
+const BUCKET_INTERVAL: i64 = 60;
+
+/// Identifies aggregated outcomes within a single bucket.
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct LedgerKey {
+    project_key: ProjectKey,
+    category: DataCategory,
+    outcome: &'static str,
+    reason: Option<String>,
+}
+
+/// Filters outcomes returned from [`OutcomeLedger::query`].
+#[derive(Debug, Default, Deserialize)]
+pub struct LedgerQuery {
+    /// Only returns outcomes of this project.
+    pub project_key: Option<ProjectKey>,
+    /// Only returns outcomes from buckets starting at or after this time.
+    pub start: Option<DateTime<Utc>>,
+    /// Only returns outcomes from buckets starting before this time.
+    pub end: Option<DateTime<Utc>>,
+}
+
+/// Aggregated outcomes of one project, category, outcome and reason within a minute.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct LedgerEntry {
+    /// Start of the minute in which the outcomes were recorded.
+    pub timestamp: DateTime<Utc>,
+    /// The public key of the project.
+    pub project_key: ProjectKey,
+    /// The data category of the outcomes.
+    pub category: DataCategory,
+    /// The name of the outcome, for example `rate_limited`.
+    pub outcome: &'static str,
+    /// The reason of the outcome, if any.
+    pub reason: Option<String>,
+    /// The total quantity of all outcomes.
+    pub quantity: u64,
+}
+
+/// Buckets of the ledger along with the time up to which they have been expired.
+#[derive(Debug, Default)]
+struct LedgerBuckets {
+    buckets: BTreeMap<i64, HashMap<LedgerKey, u64>>,
+    /// All buckets before this timestamp have been removed.
+    expired_before: i64,
+}
+
+/// A rolling, in-memory record of outcomes aggregated in minute buckets.
+///
+/// Cloning the ledger is cheap, all clones share the same records.
+#[derive(Clone, Debug)]
+pub struct OutcomeLedger {
+    buckets: Arc<Mutex<LedgerBuckets>>,
+    retention: i64,
+}
+
+impl OutcomeLedger {
+    /// Creates a new ledger, which keeps outcomes for the specified `retention`.
+    pub fn new(retention: Duration) -> Self {
+        Self {
+            buckets: Default::default(),
+            retention: i64::try_from(retention.as_secs()).unwrap_or(i64::MAX),
+        }
+    }
+
+    /// Records an outcome.
+    ///
+    /// Outcomes which are older than the retention are ignored.
+    pub fn record(&self, outcome: &TrackOutcome) {
+        self.record_at(outcome, Utc::now());
+    }
+
+    fn record_at(&self, outcome: &TrackOutcome, now: DateTime<Utc>) {
+        let oldest = bucket(now).saturating_sub(self.retention);
+        let timestamp = bucket(outcome.timestamp);
+
+        let mut ledger = self.buckets.lock().unwrap_or_else(PoisonError::into_inner);
+
+        // Expire all buckets which are no longer retained. The oldest retained bucket only changes
+        // once per bucket interval, so this does not run for every outcome.
+        if oldest > ledger.expired_before {
+            ledger.buckets = ledger.buckets.split_off(&oldest);
+            ledger.expired_before = oldest;
+        }
+
+        if timestamp < oldest {
+            return;
+        }
+
+        let key = LedgerKey {
+            project_key: outcome.scoping.project_key,
+            category: outcome.category,
+            outcome: outcome.outcome.name(),
+            reason: outcome.outcome.to_reason().map(Into::into),
+        };
+
+        *ledger
+            .buckets
+            .entry(timestamp)
+            .or_default()
+            .entry(key)
+            .or_default() += outcome.quantity;
+    }
+
+    /// Returns all recorded outcomes matching the query, ordered by time.
+    pub fn query(&self, query: &LedgerQuery) -> Vec<LedgerEntry> {
+        let start = query.start.map_or(i64::MIN, |start| start.timestamp());
+        let end = query.end.map_or(i64::MAX, |end| end.timestamp());
+        if start >= end {
+            return Vec::new();
+        }
+
+        let ledger = self.buckets.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let mut entries = Vec::new();
+        for (&timestamp, bucket) in ledger.buckets.range(start..end) {
+            let first = entries.len();
+
+            for (key, &quantity) in bucket {
+                if query
+                    .project_key
+                    .is_some_and(|project_key| project_key != key.project_key)
+                {
+                    continue;
+                }
+
+                entries.push(LedgerEntry {
+                    timestamp: DateTime::from_timestamp(timestamp, 0).unwrap_or_default(),
+                    project_key: key.project_key,
+                    category: key.category,
+                    outcome: key.outcome,
+                    reason: key.reason.clone(),
+                    quantity,
+                });
+            }
+
+            // Buckets are unordered, sort them for a stable output.
+            entries[first..].sort_by(|a, b| {
+                let a = (
+                    a.project_key.as_str(),
+                    a.category.name(),
+                    a.outcome,
+                    &a.reason,
+                );
+                let b = (
+                    b.project_key.as_str(),
+                    b.category.name(),
+                    b.outcome,
+                    &b.reason,
+                );
+                a.cmp(&b)
+            });
+        }
+
+        entries
+    }
+}
+
+/// Returns the start of the bucket containing `timestamp`.
+fn bucket(timestamp: DateTime<Utc>) -> i64 {
+    let timestamp = timestamp.timestamp();
+    timestamp - timestamp.rem_euclid(BUCKET_INTERVAL)
+}
+
+/// Service recording all outcomes in an [`OutcomeLedger`] before passing them on.
+#[derive(Debug)]
+pub struct OutcomeLedgerService {
+    ledger: OutcomeLedger,
+    outcomes: Addr<TrackOutcome>,
+}
+
+impl OutcomeLedgerService {
+    /// Creates a new service, recording outcomes in `ledger` and forwarding them to `outcomes`.
+    pub fn new(ledger: OutcomeLedger, outcomes: Addr<TrackOutcome>) -> Self {
+        Self { ledger, outcomes }
+    }
+}
+
+impl Service for OutcomeLedgerService {
+    type Interface = TrackOutcome;
+
+    async fn run(self, mut rx: relay_system::Receiver<Self::Interface>) {
+        while let Some(message) = rx.recv().await {
+            self.ledger.record(&message);
+            self.outcomes.send(message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use relay_base_schema::organization::OrganizationId;
+    use relay_base_schema::project::ProjectId;
+    use relay_quotas::{ReasonCode, Scoping};
+
+    use super::*;
+    use crate::services::outcome::{DiscardReason, Outcome};
+
+    fn project_key(key: &str) -> ProjectKey {
+        ProjectKey::parse(key).unwrap()
+    }
+
+    fn outcome(timestamp: &str, key: &str, outcome: Outcome, quantity: u64) -> TrackOutcome {
+        TrackOutcome {
+            timestamp: timestamp.parse().unwrap(),
+            scoping: Scoping {
+                organization_id: OrganizationId::new(1),
+                project_id: ProjectId::new(42),
+                project_key: project_key(key),
+                key_id: None,
+            },
+            outcome,
+            event_id: None,
+            remote_addr: None,
+            category: DataCategory::Error,
+            quantity,
+        }
+    }
+
+    const KEY_A: &str = "a94ae32be2584e0bbd7a4cbb95971fee";
+    const KEY_B: &str = "b94ae32be2584e0bbd7a4cbb95971fee";
+
+    #[test]
+    fn test_ledger_aggregates_by_minute() {
+        let ledger = OutcomeLedger::new(Duration::from_secs(3600));
+        let now = "2025-01-01T10:30:00Z".parse().unwrap();
+
+        let rate_limited = || Outcome::RateLimited(Some(ReasonCode::new("quota")));
+        for outcome in [
+            outcome("2025-01-01T10:00:05Z", KEY_A, rate_limited(), 1),
+            outcome("2025-01-01T10:00:55Z", KEY_A, rate_limited(), 2),
+            outcome("2025-01-01T10:01:00Z", KEY_A, rate_limited(), 1),
+            outcome(
+                "2025-01-01T10:00:30Z",
+                KEY_B,
+                Outcome::Invalid(DiscardReason::Payload),
+                5,
+            ),
+        ] {
+            ledger.record_at(&outcome, now);
+        }
+
+        let entries = ledger.query(&LedgerQuery::default());
+        insta::assert_json_snapshot!(entries, @r#"
+        [
+          {
+            "timestamp": "2025-01-01T10:00:00Z",
+            "project_key": "a94ae32be2584e0bbd7a4cbb95971fee",
+            "category": "error",
+            "outcome": "rate_limited",
+            "reason": "quota",
+            "quantity": 3
+          },
+          {
+            "timestamp": "2025-01-01T10:00:00Z",
+            "project_key": "b94ae32be2584e0bbd7a4cbb95971fee",
+            "category": "error",
+            "outcome": "invalid",
+            "reason": "payload",
+            "quantity": 5
+          },
+          {
+            "timestamp": "2025-01-01T10:01:00Z",
+            "project_key": "a94ae32be2584e0bbd7a4cbb95971fee",
+            "category": "error",
+            "outcome": "rate_limited",
+            "reason": "quota",
+            "quantity": 1
+          }
+        ]
+        "#);
+
+        let entries = ledger.query(&LedgerQuery {
+            project_key: Some(project_key(KEY_B)),
+            ..Default::default()
+        });
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].quantity, 5);
+
+        let entries = ledger.query(&LedgerQuery {
+            start: Some("2025-01-01T10:01:00Z".parse().unwrap()),
+            end: Some("2025-01-01T10:02:00Z".parse().unwrap()),
+            ..Default::default()
+        });
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].quantity, 1);
+    }
+
+    #[test]
+    fn test_ledger_retention() {
+        let ledger = OutcomeLedger::new(Duration::from_secs(600));
+
+        let invalid = || Outcome::Invalid(DiscardReason::Payload);
+        ledger.record_at(
+            &outcome("2025-01-01T10:00:00Z", KEY_A, invalid(), 1),
+            "2025-01-01T10:05:00Z".parse().unwrap(),
+        );
+        assert_eq!(ledger.query(&LedgerQuery::default()).len(), 1);
+
+        // Too old to be recorded, also expires the first bucket.
+        ledger.record_at(
+            &outcome("2025-01-01T10:01:00Z", KEY_A, invalid(), 1),
+            "2025-01-01T10:20:00Z".parse().unwrap(),
+        );
+        assert!(ledger.query(&LedgerQuery::default()).is_empty());
+    }
+}
@@ -9,11 +9,13 @@ use relay_quotas::ReasonCode;
 use relay_sampling::config::RuleId;
 use relay_sampling::evaluation::MatchedRuleIds;
 
+mod ledger;
 pub mod metric;
 mod service;
 
 use crate::envelope::{AttachmentType, ItemType};
 
+pub use self::ledger::*;
 pub use self::service::*;
 
 /// The numerical identifier of the outcome category (Accepted, Filtered, ...)
@@ -83,6 +85,18 @@ impl Outcome {
         }
     }
 
+    /// Returns a short, low-cardinality name of this outcome.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Outcome::Accepted => "accepted",
+            Outcome::Filtered(_) | Outcome::FilteredSampling(_) => "filtered",
+            Outcome::RateLimited(_) => "rate_limited",
+            Outcome::Invalid(_) => "invalid",
+            Outcome::Abuse => "abuse",
+            Outcome::ClientDiscard(_) => "client_discard",
+        }
+    }
+
     /// Returns the `reason` code field of this outcome.
     pub fn to_reason(&self) -> Option<Cow<'_, str>> {
         match self {
@@ -397,6 +411,12 @@ pub enum DiscardReason {
     /// (Relay) The signature from a trusted Relay was missing but required.
     MissingSignature,
 
+    /// (Relay) The ingestion token was invalid, expired or did not permit the submitted data.
+    InvalidIngestToken,
+
+    /// (Relay) The ingestion token was missing but required by the project.
+    MissingIngestToken,
+
     /// (Relay) The signature from a trusted Relay was missing but required.
     InvalidCheckIn,
 
@@ -435,6 +455,8 @@ impl DiscardReason {
             DiscardReason::ProcessUnreal => "process_unreal",
             DiscardReason::InvalidSignature => "invalid_signature",
             DiscardReason::MissingSignature => "missing_signature",
+            DiscardReason::InvalidIngestToken => "invalid_ingest_token",
+            DiscardReason::MissingIngestToken => "missing_ingest_token",
             DiscardReason::Payload => "payload",
             DiscardReason::EmptyBody => "empty_body",
             DiscardReason::InvalidBody => "invalid_body",
@@ -573,6 +595,7 @@ impl DiscardItemType {
             Self::Attachment(DiscardAttachmentType::UnrealContext) => "attachment:unreal_context",
             Self::Attachment(DiscardAttachmentType::UnrealLogs) => "attachment:unreal_logs",
             Self::Attachment(DiscardAttachmentType::ViewHierarchy) => "attachment:view_hierarchy",
+            Self::Attachment(DiscardAttachmentType::HsErrLog) => "attachment:hs_err_log",
             Self::FormData => "form_data",
             Self::RawSecurity => "raw_security",
             Self::UnrealReport => "unreal_report",
@@ -672,6 +695,8 @@ pub enum DiscardAttachmentType {
     UnrealLogs,
     /// An application UI view hierarchy (json payload).
     ViewHierarchy,
+    /// A HotSpot JVM fatal error log.
+    HsErrLog,
 }
 
 impl From<&AttachmentType> for DiscardAttachmentType {
@@ -687,6 +712,7 @@ impl From<&AttachmentType> for DiscardAttachmentType {
             AttachmentType::UnrealContext => Self::UnrealContext,
             AttachmentType::UnrealLogs => Self::UnrealLogs,
             AttachmentType::ViewHierarchy => Self::ViewHierarchy,
+            AttachmentType::HsErrLog => Self::HsErrLog,
         }
     }
 }
@@ -254,14 +254,7 @@ struct BucketKey {
 }
 
 fn send_outcome_metric(message: &TrackOutcome) {
-    let outcome_name = match message.outcome {
-        Outcome::Accepted => "accepted",
-        Outcome::Filtered(_) | Outcome::FilteredSampling(_) => "filtered",
-        Outcome::RateLimited(_) => "rate_limited",
-        Outcome::Invalid(_) => "invalid",
-        Outcome::Abuse => "abuse",
-        Outcome::ClientDiscard(_) => "client_discard",
-    };
+    let outcome_name = message.outcome.name();
 
     metric!(
         counter(RelayCounters::OutcomeQuantity) += message.quantity,
@@ -21,7 +21,7 @@ use relay_common::time::UnixTimestamp;
 use relay_config::{Config, EmitOutcomes, HttpEncoding, UpstreamDescriptor};
 use relay_event_normalization::{ClockDriftProcessor, GeoIpLookup};
 use relay_event_schema::processor::ProcessingAction;
-use relay_event_schema::protocol::ClientReport;
+use relay_event_schema::protocol::{ClientReport, SessionAggregates};
 use relay_filter::FilterStatKey;
 use relay_log::sentry::SentryFutureExt;
 use relay_metrics::{Bucket, BucketMetadata, BucketView, BucketsView, MetricNamespace};
@@ -46,6 +46,7 @@ use crate::services::metrics::{Aggregator, FlushBuckets, MergeBuckets, ProjectBu
 use crate::services::outcome::{self, DiscardItemType, DiscardReason, Outcome, TrackOutcome};
 use crate::services::projects::cache::ProjectCacheHandle;
 use crate::services::projects::project::{ProjectInfo, ProjectState};
+use crate::services::sessions::AggregateSessions;
 use crate::services::upstream::{
     SendRequest, Sign, SignatureType, UpstreamRelay, UpstreamRequest, UpstreamRequestError,
 };
@@ -66,6 +67,7 @@ use {
 };
 
 mod metrics;
+pub mod offline;
 
 /// The minimum clock drift for correction to apply.
 pub const MINIMUM_CLOCK_DRIFT: Duration = Duration::from_secs(55 * 60);
@@ -129,6 +131,10 @@ pub enum ProcessingError {
     #[cfg(feature = "processing")]
     #[error("invalid attachment reference")]
     InvalidAttachmentRef,
+
+    #[cfg(feature = "processing")]
+    #[error("invalid hs_err log")]
+    InvalidHsErrLog,
 }
 
 impl ProcessingError {
@@ -151,6 +157,8 @@ impl ProcessingError {
             Self::InvalidNintendoDyingMessage(_) => Some(Outcome::Invalid(DiscardReason::Payload)),
             #[cfg(all(sentry, feature = "processing"))]
             Self::InvalidPlaystationDump(_) => Some(Outcome::Invalid(DiscardReason::Payload)),
+            #[cfg(feature = "processing")]
+            Self::InvalidHsErrLog => Some(Outcome::Invalid(DiscardReason::Payload)),
             Self::InvalidUnrealReport(err) if err.kind() == Unreal4ErrorKind::BadCompression => {
                 Some(Outcome::Invalid(DiscardReason::InvalidCompression))
             }
@@ -433,6 +441,20 @@ pub struct SubmitClientReports {
     pub scoping: Scoping,
 }
 
+/// Processes pre-aggregated sessions.
+///
+/// The session aggregates are wrapped in a new envelope, which runs through the regular processing
+/// pipeline of the project.
+#[derive(Debug)]
+pub struct SubmitSessionAggregates {
+    /// The session aggregates to be processed.
+    pub aggregates: Vec<SessionAggregates>,
+    /// The client SDK which sent the aggregated session updates.
+    pub client: Option<String>,
+    /// Scoping information for the session aggregates.
+    pub scoping: Scoping,
+}
+
 /// CPU-intensive processing tasks for envelopes.
 #[derive(Debug)]
 pub enum EnvelopeProcessor {
@@ -441,6 +463,7 @@ pub enum EnvelopeProcessor {
     ProcessBatchedMetrics(Box<ProcessBatchedMetrics>),
     FlushBuckets(Box<FlushBuckets>),
     SubmitClientReports(Box<SubmitClientReports>),
+    SubmitSessionAggregates(Box<SubmitSessionAggregates>),
 }
 
 impl EnvelopeProcessor {
@@ -452,6 +475,7 @@ impl EnvelopeProcessor {
             EnvelopeProcessor::ProcessBatchedMetrics(_) => "ProcessBatchedMetrics",
             EnvelopeProcessor::FlushBuckets(_) => "FlushBuckets",
             EnvelopeProcessor::SubmitClientReports(_) => "SubmitClientReports",
+            EnvelopeProcessor::SubmitSessionAggregates(_) => "SubmitSessionAggregates",
         }
     }
 }
@@ -498,6 +522,14 @@ impl FromMessage<SubmitClientReports> for EnvelopeProcessor {
     }
 }
 
+impl FromMessage<SubmitSessionAggregates> for EnvelopeProcessor {
+    type Response = NoResponse;
+
+    fn from_message(message: SubmitSessionAggregates, _: ()) -> Self {
+        Self::SubmitSessionAggregates(Box::new(message))
+    }
+}
+
 /// The asynchronous thread pool used for scheduling processing tasks in the processor.
 pub type EnvelopeProcessorServicePool = AsyncPool<BoxFuture<'static, ()>>;
 
@@ -518,6 +550,7 @@ pub struct Addrs {
     #[cfg(feature = "processing")]
     pub store_forwarder: Option<Addr<Store>>,
     pub aggregator: Addr<Aggregator>,
+    pub session_aggregator: Option<Addr<AggregateSessions>>,
 }
 
 impl Default for Addrs {
@@ -530,6 +563,7 @@ impl Default for Addrs {
             #[cfg(feature = "processing")]
             store_forwarder: None,
             aggregator: Addr::dummy(),
+            session_aggregator: None,
         }
     }
 }
@@ -573,6 +607,28 @@ impl EnvelopeProcessorService {
             )
             .unwrap_or_else(GeoIpLookup::empty);
 
+        let geoip_lookup = match config.geoip_asn_path() {
+            Some(p) => match geoip_lookup.clone().with_asn(p) {
+                Ok(geoip) => geoip,
+                Err(err) => {
+                    relay_log::error!("failed to open GeoIP ASN db {p:?}: {err:?}");
+                    geoip_lookup
+                }
+            },
+            None => geoip_lookup,
+        };
+
+        let geoip_lookup = match config.geoip_anonymous_ip_path() {
+            Some(p) => match geoip_lookup.clone().with_anonymous_ip(p) {
+                Ok(geoip) => geoip,
+                Err(err) => {
+                    relay_log::error!("failed to open GeoIP anonymous IP db {p:?}: {err:?}");
+                    geoip_lookup
+                }
+            },
+            None => geoip_lookup,
+        };
+
         if let Some(build_epoch) = geoip_lookup.build_epoch() {
             relay_log::info!("Loaded GeoIP database (build: {build_epoch})");
         }
@@ -603,6 +659,7 @@ impl EnvelopeProcessorService {
                 &quota_limiter,
                 &geoip_lookup,
                 addrs.outcome_aggregator.clone(),
+                addrs.session_aggregator.clone(),
             ),
             cogs,
             addrs,
@@ -741,7 +798,7 @@ impl EnvelopeProcessorService {
 
             if let Some(output) = main {
                 // Only counting processing time for COGS at the moment.
-                self.submit_upstream(&mut Token::noop(), output, ctx);
+                self.submit_upstream(&mut Token::noop(), output, ctx).await;
             }
         }
     }
@@ -848,7 +905,7 @@ impl EnvelopeProcessorService {
     /// Submits a processor [`Output`] to the appropriate upstream.
     ///
     /// If processing is enabled, the upstream is Kafka.
-    fn submit_upstream(
+    async fn submit_upstream(
         &self,
         cogs: &mut Token,
         output: Outputs,
@@ -862,6 +919,9 @@ impl EnvelopeProcessorService {
         {
             use crate::processing::StoreHandle;
 
+            // Forwarding to the store cannot wait for capacity itself, so wait before forwarding.
+            store_forwarder.ready().await;
+
             let objectstore = self.inner.addrs.objectstore.as_ref();
             let handle = StoreHandle::new(store_forwarder, objectstore, ctx.global_config);
 
@@ -875,13 +935,14 @@ impl EnvelopeProcessorService {
         match output.serialize_envelope(ctx) {
             Ok(envelope) => {
                 let envelope = ManagedEnvelope::from(envelope);
-                self.submit_envelope_upstream(envelope, ctx.project_info.upstream.clone());
+                self.submit_envelope_upstream(envelope, ctx.project_info.upstream.clone())
+                    .await;
             }
             Err(_) => relay_log::error!("failed to serialize output to an envelope"),
         };
     }
 
-    fn submit_envelope_upstream(
+    async fn submit_envelope_upstream(
         &self,
         mut envelope: ManagedEnvelope,
         // Currently allowed to be optional as code is migrated to respect the upstream override
@@ -923,13 +984,14 @@ impl EnvelopeProcessorService {
                 self.inner
                     .addrs
                     .upstream_relay
-                    .send(SendRequest(SendEnvelope {
+                    .send_wait(SendRequest(SendEnvelope {
                         upstream,
                         envelope,
                         body,
                         http_encoding,
                         project_cache: self.inner.project_cache.clone(),
-                    }));
+                    }))
+                    .await;
             }
             Err(error) => {
                 // Errors are only logged for what we consider an internal discard reason. These
@@ -945,7 +1007,7 @@ impl EnvelopeProcessorService {
         }
     }
 
-    fn handle_submit_client_reports(&self, message: SubmitClientReports) {
+    async fn handle_submit_client_reports(&self, message: SubmitClientReports) {
         let SubmitClientReports {
             client_reports,
             scoping,
@@ -982,7 +1044,90 @@ impl EnvelopeProcessorService {
         }
 
         let envelope = ManagedEnvelope::new(envelope, self.inner.addrs.outcome_aggregator.clone());
-        self.submit_envelope_upstream(envelope, None);
+        self.submit_envelope_upstream(envelope, None).await;
+    }
+
+    async fn handle_submit_session_aggregates(
+        &self,
+        cogs: &mut Token,
+        message: SubmitSessionAggregates,
+    ) {
+        let SubmitSessionAggregates {
+            aggregates,
+            client,
+            scoping,
+        } = message;
+
+        relay_log::trace!(
+            "processing {} session aggregate(s) for project id {}",
+            aggregates.len(),
+            scoping.project_id
+        );
+
+        let upstream = self.inner.config.upstream();
+        let dsn = PartialDsn::outbound(&scoping, upstream);
+
+        let mut meta = RequestMeta::outbound(dsn);
+        if let Some(client) = client {
+            meta.set_client(client);
+        }
+
+        let mut envelope = Envelope::from_request(None, meta);
+        for aggregate in aggregates {
+            match aggregate.serialize() {
+                Ok(payload) => {
+                    let mut item = Item::new(ItemType::Sessions);
+                    item.set_payload(ContentType::Json, payload);
+                    envelope.add_item(item);
+                }
+                Err(error) => {
+                    relay_log::error!(
+                        error = &error as &dyn std::error::Error,
+                        "failed to serialize session aggregates"
+                    );
+                }
+            }
+        }
+
+        let mut envelope =
+            ManagedEnvelope::new(envelope, self.inner.addrs.outcome_aggregator.clone());
+        envelope.scope(scoping);
+
+        // The envelope checks already passed for the original session updates, the project
+        // config is only needed to process the aggregates.
+        let project = self
+            .inner
+            .project_cache
+            .ready(scoping.project_key, self.inner.config.query_timeout())
+            .await;
+
+        let (project_info, rate_limits) = match project {
+            Some(project) => match project.state() {
+                ProjectState::Enabled(info) => {
+                    (Arc::clone(info), project.rate_limits().current_limits())
+                }
+                ProjectState::Disabled => {
+                    envelope.reject(Outcome::Invalid(DiscardReason::ProjectId));
+                    return;
+                }
+                ProjectState::Dummy | ProjectState::Pending => {
+                    envelope.reject(Outcome::Invalid(DiscardReason::Internal));
+                    return;
+                }
+            },
+            None => {
+                envelope.reject(Outcome::Invalid(DiscardReason::Internal));
+                return;
+            }
+        };
+
+        let message = ProcessEnvelope {
+            envelope,
+            project_info,
+            rate_limits,
+            sampling_project_info: None,
+        };
+        self.handle_process_envelope(cogs, message).await;
     }
 
     fn check_buckets(
@@ -1231,11 +1376,13 @@ impl EnvelopeProcessorService {
 
             // The store forwarder takes care of bucket splitting internally, so we can submit the
             // entire list of buckets. There is no batching needed here.
-            store_forwarder.send(StoreMetrics {
-                buckets,
-                scoping,
-                retention,
-            });
+            store_forwarder
+                .send_wait(StoreMetrics {
+                    buckets,
+                    scoping,
+                    retention,
+                })
+                .await;
         }
     }
 
@@ -1248,7 +1395,7 @@ impl EnvelopeProcessorService {
     ///
     /// Rate limiting runs only in processing Relays as it requires access to the central Redis instance.
     /// Cached rate limits are applied in the project cache already.
-    fn encode_metrics_envelope(&self, message: FlushBuckets) {
+    async fn encode_metrics_envelope(&self, message: FlushBuckets) {
         let FlushBuckets {
             partition_key,
             buckets,
@@ -1289,7 +1436,8 @@ impl EnvelopeProcessorService {
                     distribution(RelayDistributions::BucketsPerBatch) = batch.len() as u64
                 );
 
-                self.submit_envelope_upstream(envelope, project_info.upstream.clone());
+                self.submit_envelope_upstream(envelope, project_info.upstream.clone())
+                    .await;
                 num_batches += 1;
             }
 
@@ -1300,7 +1448,7 @@ impl EnvelopeProcessorService {
     }
 
     /// Creates a [`SendMetricsRequest`] and sends it to the upstream relay.
-    fn send_global_partition(
+    async fn send_global_partition(
         &self,
         upstream: Option<UpstreamDescriptor>,
         partition_key: u32,
@@ -1331,7 +1479,11 @@ impl EnvelopeProcessorService {
             metric_outcomes: self.inner.metric_outcomes.clone(),
         };
 
-        self.inner.addrs.upstream_relay.send(SendRequest(request));
+        self.inner
+            .addrs
+            .upstream_relay
+            .send_wait(SendRequest(request))
+            .await;
     }
 
     /// Serializes metric buckets to JSON and sends them to the upstream via the global endpoint.
@@ -1344,7 +1496,7 @@ impl EnvelopeProcessorService {
     ///  - batching by configured size limit
     ///  - serialize to JSON
     ///  - submit directly to the upstream
-    fn encode_metrics_global(&self, message: FlushBuckets) {
+    async fn encode_metrics_global(&self, message: FlushBuckets) {
         let FlushBuckets {
             partition_key,
             buckets,
@@ -1380,7 +1532,8 @@ impl EnvelopeProcessorService {
                             project_info.upstream.clone(),
                             partition_key,
                             partition,
-                        );
+                        )
+                        .await;
                         remaining = Some(next);
                         partition_splits += 1;
                     }
@@ -1393,14 +1546,15 @@ impl EnvelopeProcessorService {
         }
 
         for (upstream, mut partition) in partitions {
-            self.send_global_partition(upstream, partition_key, &mut partition);
+            self.send_global_partition(upstream, partition_key, &mut partition)
+                .await;
         }
     }
 
     /// Removes all outcome metrics from `message` and sends them as client reports.
     ///
     /// Returns a new [`FlushBuckets`] message, without any outcome metrics remaining.
-    fn encode_metrics_client_reports(&self, mut message: FlushBuckets) -> FlushBuckets {
+    async fn encode_metrics_client_reports(&self, mut message: FlushBuckets) -> FlushBuckets {
         for ProjectBuckets {
             buckets, scoping, ..
         } in message.buckets.values_mut()
@@ -1410,7 +1564,8 @@ impl EnvelopeProcessorService {
             self.handle_submit_client_reports(SubmitClientReports {
                 client_reports,
                 scoping: *scoping,
-            });
+            })
+            .await;
         }
 
         message
@@ -1437,13 +1592,13 @@ impl EnvelopeProcessorService {
         if self.inner.config.emit_outcomes() == EmitOutcomes::AsClientReports {
             // Remove client reports from metrics to be sent, if configured as client reports
             // and send them separately.
-            message = self.encode_metrics_client_reports(message);
+            message = self.encode_metrics_client_reports(message).await;
         }
 
         if self.inner.config.http_global_metrics() {
-            self.encode_metrics_global(message)
+            self.encode_metrics_global(message).await
         } else {
-            self.encode_metrics_envelope(message)
+            self.encode_metrics_envelope(message).await
         }
     }
 
@@ -1470,7 +1625,12 @@ impl EnvelopeProcessorService {
                     self.handle_process_batched_metrics(&mut cogs, *m)
                 }
                 EnvelopeProcessor::FlushBuckets(m) => self.handle_flush_buckets(*m).await,
-                EnvelopeProcessor::SubmitClientReports(m) => self.handle_submit_client_reports(*m),
+                EnvelopeProcessor::SubmitClientReports(m) => {
+                    self.handle_submit_client_reports(*m).await
+                }
+                EnvelopeProcessor::SubmitSessionAggregates(m) => {
+                    self.handle_submit_session_aggregates(&mut cogs, *m).await
+                }
             }
         });
     }
@@ -1495,6 +1655,7 @@ impl EnvelopeProcessorService {
                 })
                 .fold(FeatureWeights::none(), FeatureWeights::merge),
             EnvelopeProcessor::SubmitClientReports(_) => AppFeature::ClientReports.into(),
+            EnvelopeProcessor::SubmitSessionAggregates(_) => AppFeature::Sessions.into(),
         }
     }
 }
@@ -1587,6 +1748,10 @@ impl UpstreamRequest for SendEnvelope {
             .header_opt("Origin", meta.origin().map(|url| url.as_str()))
             .header_opt("User-Agent", meta.user_agent())
             .header("X-Sentry-Auth", meta.auth_header())
+            .header_opt(
+                "X-Sentry-Ingest-Token",
+                meta.ingest_token().map(|token| token.to_string()),
+            )
             .header("X-Forwarded-For", meta.forwarded_for())
             .header("Content-Type", envelope::CONTENT_TYPE)
             .header_opt("X-Sentry-Relay-Shard", shard)
@@ -0,0 +1,392 @@
+//! Offline processing of envelopes without a running Relay.
+//!
+//! The [`OfflineProcessor`] runs the same pipeline as the [`EnvelopeProcessorService`], but
+//! instead of forwarding the results to the upstream, the store or the metrics aggregator, it
+//! collects them into a [`ProcessingReport`]. This is used by the `relay process` command to
+//! debug processing, PII scrubbing and sampling rules locally.
+
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use bytes::Bytes;
+use relay_config::Config;
+use relay_dynamic_config::{GlobalConfig, ProjectConfig};
+use relay_event_schema::protocol::EventId;
+use relay_metrics::Bucket;
+use relay_quotas::RateLimits;
+use relay_system::Addr;
+use serde::Serialize;
+use tokio::sync::mpsc;
+
+use super::{Addrs, EnvelopeProcessorService};
+use crate::envelope::Envelope;
+use crate::managed::ManagedEnvelope;
+use crate::metrics::MetricOutcomes;
+use crate::metrics_extraction::ExtractedMetrics;
+use crate::processing::{self, Forward as _, Output};
+use crate::services::global_config::GlobalConfigHandle;
+use crate::services::outcome::{Outcome, TrackOutcome};
+use crate::services::projects::cache::ProjectCacheHandle;
+use crate::services::projects::project::ProjectInfo;
+#[cfg(feature = "processing")]
+use crate::services::store::Store;
+use crate::utils::{SamplingResult, ThreadPoolBuilder};
+
+/// Processes envelopes with a fixed project and global config, without any services running.
+pub struct OfflineProcessor {
+    runtime: relay_system::Runtime,
+    config: Arc<Config>,
+    global_config: Arc<GlobalConfig>,
+    project_info: Arc<ProjectInfo>,
+    sampling_project_info: Option<Arc<ProjectInfo>>,
+    processor: EnvelopeProcessorService,
+    outcomes: mpsc::UnboundedReceiver<TrackOutcome>,
+    #[cfg(feature = "processing")]
+    store: mpsc::UnboundedReceiver<Store>,
+}
+
+impl OfflineProcessor {
+    /// Creates a new offline processor.
+    ///
+    /// The `project` is either a full project info as returned by the project configs endpoint,
+    /// or just the contents of its `config` field. The optional `sampling_project` is used as root
+    /// project for dynamic sampling and defaults to `project`. Without a `global_config`, the
+    /// default global config is used.
+    pub fn new(
+        config: Config,
+        project: &[u8],
+        sampling_project: Option<&[u8]>,
+        global_config: Option<&[u8]>,
+    ) -> anyhow::Result<Self> {
+        let config = Arc::new(config);
+        let project_info =
+            Arc::new(parse_project_info(project).context("failed to parse project config")?);
+        let sampling_project_info = sampling_project
+            .map(parse_project_info)
+            .transpose()
+            .context("failed to parse sampling project config")?
+            .map(Arc::new);
+        let global_config = match global_config {
+            Some(global_config) => {
+                serde_json::from_slice(global_config).context("failed to parse global config")?
+            }
+            None => GlobalConfig::default(),
+        };
+        let global_config = Arc::new(global_config);
+
+        let runtime = crate::service::create_runtime("offline-rt", 1);
+        let handle = runtime.block_on(async { tokio::runtime::Handle::current() });
+        let pool = ThreadPoolBuilder::new("processor", handle)
+            .num_threads(1)
+            .build()
+            .context("failed to create processor pool")?;
+
+        let (outcome_aggregator, outcomes) = Addr::custom();
+        #[cfg(feature = "processing")]
+        let (store_forwarder, store) = Addr::custom();
+
+        let addrs = Addrs {
+            outcome_aggregator: outcome_aggregator.clone(),
+            #[cfg(feature = "processing")]
+            store_forwarder: Some(store_forwarder),
+            ..Addrs::default()
+        };
+
+        let processor = EnvelopeProcessorService::new(
+            pool,
+            Arc::clone(&config),
+            GlobalConfigHandle::fixed((*global_config).clone()),
+            ProjectCacheHandle::detached(Arc::clone(&config)),
+            relay_cogs::Cogs::noop(),
+            #[cfg(feature = "processing")]
+            None,
+            addrs,
+            MetricOutcomes::new(outcome_aggregator),
+        );
+
+        Ok(Self {
+            runtime,
+            config,
+            global_config,
+            project_info,
+            sampling_project_info,
+            processor,
+            outcomes,
+            #[cfg(feature = "processing")]
+            store,
+        })
+    }
+
+    /// Parses and processes a single serialized envelope.
+    pub fn process(&mut self, payload: impl Into<Bytes>) -> anyhow::Result<ProcessingReport> {
+        let envelope = Envelope::parse_bytes(payload.into()).context("failed to parse envelope")?;
+        let event_id = envelope.event_id();
+
+        let mut report = ProcessingReport {
+            event_id,
+            sampling: None,
+            envelopes: Vec::new(),
+            #[cfg(feature = "processing")]
+            store: Vec::new(),
+            metrics: Vec::new(),
+            outcomes: Vec::new(),
+        };
+
+        // The managed envelope reports outcomes to the same channel as the processor.
+        let outcome_aggregator = self.processor.inner.addrs.outcome_aggregator.clone();
+        let mut envelope = ManagedEnvelope::new(envelope, outcome_aggregator);
+        let scoping = self.project_info.scope_request(envelope.meta());
+        envelope.scope(scoping);
+
+        let ctx = processing::Context {
+            config: &self.config,
+            global_config: &self.global_config,
+            project_info: &self.project_info,
+            sampling_project_info: Some(
+                self.sampling_project_info
+                    .as_deref()
+                    .unwrap_or(&self.project_info),
+            ),
+            rate_limits: &RateLimits::default(),
+        };
+
+        match self
+            .project_info
+            .check_envelope(envelope.envelope(), ctx.config)
+        {
+            Ok(()) => {
+                let sampling =
+                    processing::utils::dynamic_sampling::run(envelope.envelope().dsc(), None, &ctx);
+                report.sampling = Some(SamplingReport::new(sampling));
+
+                let outputs = self.runtime.block_on(self.processor.process(envelope, ctx));
+                self.collect_outputs(outputs, ctx, &mut report);
+            }
+            Err(reason) => envelope.reject(Outcome::Invalid(reason)),
+        }
+
+        while let Ok(outcome) = self.outcomes.try_recv() {
+            report.outcomes.push(OutcomeReport::new(outcome));
+        }
+
+        #[cfg(feature = "processing")]
+        while let Ok(message) = self.store.try_recv() {
+            report.store.push(StoreReport::new(message));
+        }
+
+        Ok(report)
+    }
+
+    fn collect_outputs(
+        &self,
+        outputs: Vec<Output<processing::Outputs>>,
+        ctx: processing::Context<'_>,
+        report: &mut ProcessingReport,
+    ) {
+        let ctx = ctx.to_forward();
+
+        for Output { main, metrics } in outputs {
+            if let Some(metrics) = metrics {
+                metrics.accept(|metrics| {
+                    let ExtractedMetrics {
+                        project_metrics,
+                        sampling_metrics,
+                    } = metrics;
+                    report.metrics.extend(project_metrics);
+                    report.metrics.extend(sampling_metrics);
+                });
+            }
+
+            let Some(output) = main else {
+                continue;
+            };
+
+            #[cfg(feature = "processing")]
+            if ctx.config.processing_enabled()
+                && let Some(store_forwarder) = &self.processor.inner.addrs.store_forwarder
+            {
+                let handle = processing::StoreHandle::new(store_forwarder, None, ctx.global_config);
+                output
+                    .forward_store(handle, ctx)
+                    .unwrap_or_else(|err| err.into_inner());
+                continue;
+            }
+
+            let Ok(envelope) = output.serialize_envelope(ctx) else {
+                relay_log::error!("failed to serialize output to an envelope");
+                continue;
+            };
+
+            let envelope = ManagedEnvelope::from(envelope);
+            match envelope.envelope().to_vec() {
+                Ok(bytes) => {
+                    report
+                        .envelopes
+                        .push(String::from_utf8_lossy(&bytes).into_owned());
+                    envelope.accept();
+                }
+                Err(error) => {
+                    relay_log::error!(
+                        error = &error as &dyn std::error::Error,
+                        "failed to serialize envelope payload"
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn parse_project_info(project: &[u8]) -> anyhow::Result<ProjectInfo> {
+    let value: serde_json::Value = serde_json::from_slice(project)?;
+
+    // A full project info always contains the project config in its `config` field, otherwise
+    // the value is a bare project config.
+    if value.get("config").is_some() {
+        return Ok(serde_json::from_value(value)?);
+    }
+
+    Ok(ProjectInfo {
+        config: serde_json::from_value::<ProjectConfig>(value)?,
+        ..Default::default()
+    })
+}
+
+/// The results of processing a single envelope with the [`OfflineProcessor`].
+#[derive(Debug, Serialize)]
+pub struct ProcessingReport {
+    /// The event id of the processed envelope.
+    pub event_id: Option<EventId>,
+    /// The dynamic sampling decision for the envelope's trace.
+    ///
+    /// `None` if the envelope was rejected before sampling was evaluated.
+    pub sampling: Option<SamplingReport>,
+    /// Resulting envelopes in their serialized form, as they would be sent to the upstream.
+    pub envelopes: Vec<String>,
+    /// Messages that would be sent to the store, and eventually Kafka, in processing mode.
+    #[cfg(feature = "processing")]
+    pub store: Vec<StoreReport>,
+    /// Metric buckets extracted from the envelope.
+    pub metrics: Vec<Bucket>,
+    /// Outcomes emitted while processing the envelope.
+    pub outcomes: Vec<OutcomeReport>,
+}
+
+/// The dynamic sampling decision reported in a [`ProcessingReport`].
+#[derive(Debug, Serialize)]
+pub struct SamplingReport {
+    /// Whether the trace is kept or dropped.
+    pub decision: &'static str,
+    /// The sample rate of the matching rules, if any rule matched.
+    pub sample_rate: Option<f64>,
+    /// Comma-separated identifiers of the matching sampling rules.
+    pub matched_rules: Option<String>,
+}
+
+impl SamplingReport {
+    fn new(result: SamplingResult) -> Self {
+        Self {
+            decision: result.decision().as_str(),
+            sample_rate: result.sample_rate(),
+            matched_rules: match result {
+                SamplingResult::Match(sampling_match) => {
+                    Some(sampling_match.into_matched_rules().to_string())
+                }
+                SamplingResult::NoMatch | SamplingResult::Pending => None,
+            },
+        }
+    }
+}
+
+/// An outcome reported in a [`ProcessingReport`].
+#[derive(Debug, Serialize)]
+pub struct OutcomeReport {
+    /// The data category of the outcome.
+    pub category: &'static str,
+    /// The number of items or bytes in the category.
+    pub quantity: u64,
+    /// The outcome identifier, for example `filtered` or `invalid`.
+    pub outcome: String,
+    /// The reason code of the outcome.
+    pub reason: Option<String>,
+}
+
+impl OutcomeReport {
+    fn new(outcome: TrackOutcome) -> Self {
+        Self {
+            category: outcome.category.name(),
+            quantity: outcome.quantity,
+            outcome: outcome.outcome.to_string(),
+            reason: outcome.outcome.to_reason().map(|r| r.into_owned()),
+        }
+    }
+}
+
+/// A store message reported in a [`ProcessingReport`].
+#[cfg(feature = "processing")]
+#[derive(Debug, Serialize)]
+pub struct StoreReport {
+    /// The kind of store message, for example `envelope` or `trace_item`.
+    pub kind: &'static str,
+    /// Debug representation of the message contents.
+    pub message: String,
+}
+
+#[cfg(feature = "processing")]
+impl StoreReport {
+    fn new(message: Store) -> Self {
+        let report = Self {
+            kind: message.variant(),
+            message: format!("{message:#?}"),
+        };
+
+        // Mark the message as handled, it would otherwise emit internal outcomes on drop.
+        match message {
+            Store::Envelope(message) => message.envelope.accept(),
+            Store::Metrics(_) => {}
+            Store::TraceItem(message) => message.accept(|_| ()),
+            Store::Span(message) => message.accept(|_| ()),
+            Store::ProfileChunk(message) => message.accept(|_| ()),
+            Store::Replay(message) => message.accept(|_| ()),
+            Store::Attachment(message) => message.accept(|_| ()),
+            Store::UserReport(message) => message.accept(|_| ()),
+            Store::Profile(message) => message.accept(|_| ()),
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENVELOPE: &[u8] = br#"{"event_id":"9ec79c33ec9942ab8353589fcb2e04dc","dsn":"https://e12d836b15bb49d7bbf99e64295d995b:@sentry.io/42"}
+{"type":"event"}
+{"message":"hello world","level":"error"}
+"#;
+
+    #[test]
+    fn test_process_envelope() {
+        let mut processor = OfflineProcessor::new(Config::default(), b"{}", None, None).unwrap();
+        let report = processor.process(ENVELOPE).unwrap();
+
+        assert_eq!(
+            report.event_id,
+            Some("9ec79c33ec9942ab8353589fcb2e04dc".parse().unwrap())
+        );
+        assert_eq!(report.envelopes.len(), 1);
+        assert!(report.outcomes.is_empty());
+    }
+
+    #[test]
+    fn test_process_invalid_project_id() {
+        let project = br#"{"projectId": 21, "config": {}}"#;
+        let mut processor = OfflineProcessor::new(Config::default(), project, None, None).unwrap();
+        let report = processor.process(ENVELOPE).unwrap();
+
+        assert!(report.sampling.is_none());
+        assert!(report.envelopes.is_empty());
+        assert_eq!(report.outcomes.len(), 1);
+        assert_eq!(report.outcomes[0].reason.as_deref(), Some("project_id"));
+    }
+}
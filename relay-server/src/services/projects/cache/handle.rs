@@ -26,6 +26,19 @@ pub struct ProjectCacheHandle {
 }
 
 impl ProjectCacheHandle {
+    /// Creates a handle which is not connected to a [`ProjectCacheService`](super::ProjectCacheService).
+    ///
+    /// All projects remain pending and fetches are discarded. This is used to run the processor
+    /// without any services, where the project info is passed with each envelope.
+    pub fn detached(config: Arc<Config>) -> Self {
+        Self {
+            shared: Default::default(),
+            config,
+            service: Addr::dummy(),
+            project_changes: broadcast::channel(1).0,
+        }
+    }
+
     /// Returns the current project state for the `project_key`.
     pub fn get(&self, project_key: ProjectKey) -> Project<'_> {
         let project = self.shared.get_or_create(project_key);
@@ -12,9 +12,10 @@ use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 use url::Url;
 
-use crate::envelope::Envelope;
+use crate::envelope::{Envelope, Item};
 use crate::extractors::RequestMeta;
 use crate::services::outcome::DiscardReason;
+use crate::services::projects::project::ingest_token;
 
 /// Information about an enabled project.
 ///
@@ -124,6 +125,7 @@ impl ProjectInfo {
     ///  - Disabled project keys (DSN)
     ///  - Feature flags
     ///  - Trusted Relay signature invalid
+    ///  - Ingestion token invalid or missing
     pub fn check_envelope(
         &self,
         envelope: &Envelope,
@@ -156,7 +158,8 @@ impl ProjectInfo {
             return Err(DiscardReason::FeatureDisabled(*disabled_feature));
         }
 
-        self.check_envelope_signature(envelope, config)
+        self.check_envelope_signature(envelope, config)?;
+        self.check_envelope_ingest_token(envelope)
     }
 
     /// Checks if the envelope signature is valid given the configuration.
@@ -199,6 +202,51 @@ impl ProjectInfo {
         }
     }
 
+    /// Checks if the ingestion token of the envelope is valid for this project.
+    ///
+    /// The token must be signed by one of the configured keys, must not be expired and must be
+    /// issued for the public key of the request. If the token restricts data categories, the
+    /// primary data category of every item must be allowed. User and release bindings are checked
+    /// against the payload of every item, see [`ingest_token::matches_bindings`].
+    ///
+    /// Envelopes without a token are only rejected if the project requires tokens.
+    fn check_envelope_ingest_token(&self, envelope: &Envelope) -> Result<(), DiscardReason> {
+        let settings = &self.config.ingest_tokens;
+        let meta = envelope.meta();
+
+        let Some(token) = meta.ingest_token() else {
+            return match settings.required {
+                true => Err(DiscardReason::MissingIngestToken),
+                false => Ok(()),
+            };
+        };
+
+        let claims = token
+            .verify(&settings.keys, envelope.received_at())
+            .map_err(|_| DiscardReason::InvalidIngestToken)?;
+
+        if claims.public_key != meta.public_key().as_str() {
+            return Err(DiscardReason::InvalidIngestToken);
+        }
+
+        if !claims.categories.is_empty() {
+            let is_allowed = |item: &Item| match item.quantities().first() {
+                Some((category, _)) => claims.categories.iter().any(|c| c == category.name()),
+                None => true,
+            };
+
+            if !envelope.items().all(is_allowed) {
+                return Err(DiscardReason::InvalidIngestToken);
+            }
+        }
+
+        if !ingest_token::matches_bindings(envelope, claims) {
+            return Err(DiscardReason::InvalidIngestToken);
+        }
+
+        Ok(())
+    }
+
     /// Returns `true` if the given project ID matches this project.
     ///
     /// If the project state has not been loaded, this check is skipped because the project
@@ -363,3 +411,158 @@ impl From<&str> for Revision {
         Self(Some(value.into()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use relay_auth::{IngestToken, IngestTokenClaims, PublicKey, generate_key_pair};
+    use relay_common::time::UnixTimestamp;
+
+    use super::*;
+
+    const PUBLIC_KEY: &str = "e12d836b15bb49d7bbf99e64295d995b";
+
+    fn project_info(keys: Vec<PublicKey>, required: bool) -> ProjectInfo {
+        let mut info = ProjectInfo::default();
+        info.config.ingest_tokens.keys = keys;
+        info.config.ingest_tokens.required = required;
+        info
+    }
+
+    fn claims() -> IngestTokenClaims {
+        IngestTokenClaims {
+            public_key: PUBLIC_KEY.to_owned(),
+            expires: UnixTimestamp::now() + std::time::Duration::from_secs(60),
+            categories: vec![],
+            user_id: None,
+            release: None,
+        }
+    }
+
+    /// Creates an envelope with an event, an optional ingestion token and an optional DSC.
+    fn envelope(token: Option<&IngestToken>, trace: Option<serde_json::Value>) -> Box<Envelope> {
+        let mut headers = serde_json::json!({
+            "event_id": "9ec79c33ec9942ab8353589fcb2e04dc",
+            "dsn": format!("https://{PUBLIC_KEY}:@sentry.io/42"),
+        });
+        if let Some(token) = token {
+            headers["ingest_token"] = token.to_string().into();
+        }
+        if let Some(trace) = trace {
+            headers["trace"] = trace;
+        }
+
+        let bytes = format!("{headers}\n{{\"type\":\"event\"}}\n{{}}\n");
+        Envelope::parse_bytes(Bytes::from(bytes)).unwrap()
+    }
+
+    fn trace(user_id: &str, release: &str) -> serde_json::Value {
+        serde_json::json!({
+            "trace_id": "89143b0763095bd9c9955e8175d1fb23",
+            "public_key": PUBLIC_KEY,
+            "user_id": user_id,
+            "release": release,
+        })
+    }
+
+    fn sign(claims: IngestTokenClaims) -> (IngestToken, PublicKey) {
+        let (sk, pk) = generate_key_pair();
+        (IngestToken::sign(claims, &sk), pk)
+    }
+
+    #[test]
+    fn test_ingest_token_missing() {
+        let envelope = envelope(None, None);
+
+        let info = project_info(vec![], false);
+        assert!(info.check_envelope_ingest_token(&envelope).is_ok());
+
+        let info = project_info(vec![], true);
+        assert!(matches!(
+            info.check_envelope_ingest_token(&envelope),
+            Err(DiscardReason::MissingIngestToken)
+        ));
+    }
+
+    #[test]
+    fn test_ingest_token_valid() {
+        let (token, pk) = sign(claims());
+        let envelope = envelope(Some(&token), None);
+
+        let info = project_info(vec![pk], true);
+        assert!(info.check_envelope_ingest_token(&envelope).is_ok());
+    }
+
+    #[test]
+    fn test_ingest_token_after_serialization() {
+        let (token, pk) = sign(claims());
+        let envelope = envelope(Some(&token), None);
+
+        // Envelopes are serialized when they are spooled to disk.
+        let envelope = Envelope::parse_bytes(envelope.to_vec().unwrap().into()).unwrap();
+
+        let info = project_info(vec![pk], true);
+        assert!(info.check_envelope_ingest_token(&envelope).is_ok());
+    }
+
+    #[test]
+    fn test_ingest_token_invalid() {
+        let (token, _) = sign(claims());
+        let other_key: PublicKey = "JOaR2bHZ31zYjFojC7UhPOidzfT3qOQgT9WEBw1JAKU"
+            .parse()
+            .unwrap();
+
+        let expired = IngestTokenClaims {
+            expires: UnixTimestamp::from_secs(1),
+            ..claims()
+        };
+        let (expired, expired_pk) = sign(expired);
+
+        let other_project = IngestTokenClaims {
+            public_key: "a94ae32be2584e0bbd7a4cbb95971fee".to_owned(),
+            ..claims()
+        };
+        let (other_project, other_project_pk) = sign(other_project);
+
+        let other_category = IngestTokenClaims {
+            categories: vec!["transaction".to_owned()],
+            ..claims()
+        };
+        let (other_category, other_category_pk) = sign(other_category);
+
+        for (token, pk) in [
+            (token, other_key),
+            (expired, expired_pk),
+            (other_project, other_project_pk),
+            (other_category, other_category_pk),
+        ] {
+            let info = project_info(vec![pk], false);
+            assert!(matches!(
+                info.check_envelope_ingest_token(&envelope(Some(&token), None)),
+                Err(DiscardReason::InvalidIngestToken)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_ingest_token_bindings() {
+        let bound = IngestTokenClaims {
+            user_id: Some("user".to_owned()),
+            release: Some("1.0.0".to_owned()),
+            ..claims()
+        };
+        let (token, pk) = sign(bound);
+        let info = project_info(vec![pk], false);
+
+        // The event payload does not carry the bound release and user, regardless of the DSC.
+        for envelope in [
+            envelope(Some(&token), None),
+            envelope(Some(&token), Some(trace("user", "1.0.0"))),
+        ] {
+            assert!(matches!(
+                info.check_envelope_ingest_token(&envelope),
+                Err(DiscardReason::InvalidIngestToken)
+            ));
+        }
+    }
+}
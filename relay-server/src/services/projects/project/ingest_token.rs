@@ -0,0 +1,286 @@
+//! Verification of the release and user bindings of ingestion tokens.
+//!
+//! Bindings are checked against the payloads of envelope items, since the envelope headers are
+//! entirely under the control of the client. Items which do not carry a release or user cannot be
+//! verified and are rejected.
+
+use std::borrow::Cow;
+
+use relay_auth::IngestTokenClaims;
+use serde::Deserialize;
+
+use crate::envelope::{ContentType, Envelope, Item, ItemType};
+
+/// Returns `true` if all items in the envelope match the release and user bound by the token.
+pub fn matches_bindings(envelope: &Envelope, claims: &IngestTokenClaims) -> bool {
+    let bindings = Bindings {
+        release: claims.release.as_deref(),
+        user_id: claims.user_id.as_deref(),
+    };
+
+    if bindings.release.is_none() && bindings.user_id.is_none() {
+        return true;
+    }
+
+    // The dynamic sampling context is optional, but it must not contradict the bindings.
+    if let Some(dsc) = envelope.dsc() {
+        let contradicts = |bound: Option<&str>, value: Option<&str>| matches!((bound, value), (Some(bound), Some(value)) if bound != value);
+        let user_id = Some(dsc.user.user_id.as_str()).filter(|id| !id.is_empty());
+        if contradicts(bindings.release, dsc.release.as_deref())
+            || contradicts(bindings.user_id, user_id)
+        {
+            return false;
+        }
+    }
+
+    let has_event = envelope
+        .items()
+        .any(|item| matches!(item.ty(), ItemType::Event | ItemType::Transaction));
+
+    envelope.items().all(|item| match item.ty() {
+        ItemType::Event | ItemType::Transaction => {
+            serde_json::from_slice::<EventPayload>(&item.payload())
+                .is_ok_and(|event| bindings.matches(&event.values()))
+        }
+        ItemType::Log | ItemType::Span | ItemType::TraceMetric => {
+            matches_container(item, &bindings)
+        }
+        // Attachments inherit the bindings of the event they are sent with.
+        ItemType::Attachment => has_event,
+        // Client reports only contain outcomes of the SDK.
+        ItemType::ClientReport => true,
+        _ => false,
+    })
+}
+
+/// Checks all items of a log, span or trace metric container against the bindings.
+fn matches_container(item: &Item, bindings: &Bindings<'_>) -> bool {
+    if !item.content_type().is_some_and(ContentType::is_container) {
+        return false;
+    }
+
+    serde_json::from_slice::<ContainerPayload>(&item.payload()).is_ok_and(|container| {
+        container
+            .items
+            .iter()
+            .all(|item| bindings.matches(&item.attributes.values()))
+    })
+}
+
+/// The release and user bound by an ingestion token.
+struct Bindings<'a> {
+    release: Option<&'a str>,
+    user_id: Option<&'a str>,
+}
+
+impl Bindings<'_> {
+    /// Returns `true` if the values match all bindings.
+    ///
+    /// Missing values never match a binding.
+    fn matches(&self, values: &Values<'_>) -> bool {
+        let matches = |bound: Option<&str>, value: &Option<Cow<'_, str>>| match bound {
+            Some(bound) => value.as_deref() == Some(bound),
+            None => true,
+        };
+
+        matches(self.release, &values.release) && matches(self.user_id, &values.user_id)
+    }
+}
+
+/// The release and user of an item.
+struct Values<'a> {
+    release: Option<Cow<'a, str>>,
+    user_id: Option<Cow<'a, str>>,
+}
+
+/// Converts a JSON value to a string the same way the event protocol treats lenient strings.
+fn lenient_string(value: &serde_json::Value) -> Option<Cow<'_, str>> {
+    match value {
+        serde_json::Value::String(s) => Some(Cow::Borrowed(s)),
+        serde_json::Value::Number(n) => Some(Cow::Owned(n.to_string())),
+        _ => None,
+    }
+}
+
+/// The subset of an event payload that contains the release and user.
+#[derive(Debug, Deserialize)]
+struct EventPayload {
+    #[serde(default)]
+    release: Option<serde_json::Value>,
+    #[serde(default)]
+    user: Option<EventUser>,
+}
+
+impl EventPayload {
+    fn values(&self) -> Values<'_> {
+        Values {
+            release: self.release.as_ref().and_then(lenient_string),
+            user_id: self
+                .user
+                .as_ref()
+                .and_then(|user| user.id.as_ref())
+                .and_then(lenient_string),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct EventUser {
+    #[serde(default)]
+    id: Option<serde_json::Value>,
+}
+
+/// The subset of a log, span or trace metric container that contains the release and user.
+#[derive(Debug, Deserialize)]
+struct ContainerPayload {
+    items: Vec<ContainerItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContainerItem {
+    #[serde(default)]
+    attributes: ItemAttributes,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ItemAttributes {
+    #[serde(default, rename = "sentry.release")]
+    release: Option<AttributeValue>,
+    #[serde(default, rename = "user.id")]
+    user_id: Option<AttributeValue>,
+}
+
+impl ItemAttributes {
+    fn values(&self) -> Values<'_> {
+        Values {
+            release: self.release.as_ref().and_then(AttributeValue::value),
+            user_id: self.user_id.as_ref().and_then(AttributeValue::value),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AttributeValue {
+    value: serde_json::Value,
+}
+
+impl AttributeValue {
+    fn value(&self) -> Option<Cow<'_, str>> {
+        lenient_string(&self.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use relay_common::time::UnixTimestamp;
+
+    use super::*;
+
+    fn claims() -> IngestTokenClaims {
+        IngestTokenClaims {
+            public_key: "e12d836b15bb49d7bbf99e64295d995b".to_owned(),
+            expires: UnixTimestamp::now() + std::time::Duration::from_secs(60),
+            categories: vec![],
+            user_id: Some("user".to_owned()),
+            release: Some("1.0.0".to_owned()),
+        }
+    }
+
+    fn envelope(items: &str) -> Box<Envelope> {
+        let bytes = format!(
+            "{{\"dsn\":\"https://e12d836b15bb49d7bbf99e64295d995b:@sentry.io/42\"}}\n{items}"
+        );
+        Envelope::parse_bytes(Bytes::from(bytes)).unwrap()
+    }
+
+    #[test]
+    fn test_unbound() {
+        let claims = IngestTokenClaims {
+            user_id: None,
+            release: None,
+            ..claims()
+        };
+        let envelope = envelope("{\"type\":\"session\"}\n{}\n");
+        assert!(matches_bindings(&envelope, &claims));
+    }
+
+    #[test]
+    fn test_event() {
+        let matching = envelope(
+            "{\"type\":\"event\"}\n{\"release\":\"1.0.0\",\"user\":{\"id\":\"user\"}}\n\
+             {\"type\":\"attachment\",\"length\":3}\nabc\n",
+        );
+        assert!(matches_bindings(&matching, &claims()));
+
+        for items in [
+            "{\"type\":\"event\"}\n{\"release\":\"1.0.0\"}\n",
+            "{\"type\":\"event\"}\n{\"release\":\"2.0.0\",\"user\":{\"id\":\"user\"}}\n",
+            "{\"type\":\"transaction\"}\n{\"release\":\"1.0.0\",\"user\":{\"id\":\"other\"}}\n",
+            "{\"type\":\"event\"}\nnot json\n",
+        ] {
+            assert!(!matches_bindings(&envelope(items), &claims()), "{items}");
+        }
+    }
+
+    #[test]
+    fn test_numeric_user_id() {
+        let claims = IngestTokenClaims {
+            user_id: Some("42".to_owned()),
+            ..claims()
+        };
+        let envelope =
+            envelope("{\"type\":\"event\"}\n{\"release\":\"1.0.0\",\"user\":{\"id\":42}}\n");
+        assert!(matches_bindings(&envelope, &claims));
+    }
+
+    #[test]
+    fn test_container() {
+        let attributes = |release: &str, user_id: &str| {
+            format!(
+                "{{\"attributes\":{{\
+                 \"sentry.release\":{{\"type\":\"string\",\"value\":\"{release}\"}},\
+                 \"user.id\":{{\"type\":\"string\",\"value\":\"{user_id}\"}}}}}}"
+            )
+        };
+        let container = |items: &[String]| {
+            format!(
+                "{{\"type\":\"log\",\"item_count\":{},\"content_type\":\"application/vnd.sentry.items.log+json\"}}\n{{\"items\":[{}]}}\n",
+                items.len(),
+                items.join(",")
+            )
+        };
+
+        let matching = container(&[attributes("1.0.0", "user"), attributes("1.0.0", "user")]);
+        assert!(matches_bindings(&envelope(&matching), &claims()));
+
+        let mismatch = container(&[attributes("1.0.0", "user"), attributes("1.0.0", "other")]);
+        assert!(!matches_bindings(&envelope(&mismatch), &claims()));
+
+        let missing = container(&["{}".to_owned()]);
+        assert!(!matches_bindings(&envelope(&missing), &claims()));
+    }
+
+    #[test]
+    fn test_uncheckable_items() {
+        for items in [
+            "{\"type\":\"attachment\",\"length\":3}\nabc\n",
+            "{\"type\":\"session\"}\n{\"attrs\":{\"release\":\"1.0.0\"}}\n",
+            "{\"type\":\"log\"}\n{}\n",
+        ] {
+            assert!(!matches_bindings(&envelope(items), &claims()), "{items}");
+        }
+    }
+
+    #[test]
+    fn test_dsc_mismatch() {
+        let envelope = Envelope::parse_bytes(Bytes::from(
+            "{\"dsn\":\"https://e12d836b15bb49d7bbf99e64295d995b:@sentry.io/42\",\
+             \"trace\":{\"trace_id\":\"89143b0763095bd9c9955e8175d1fb23\",\
+             \"public_key\":\"e12d836b15bb49d7bbf99e64295d995b\",\"release\":\"2.0.0\"}}\n\
+             {\"type\":\"event\"}\n{\"release\":\"1.0.0\",\"user\":{\"id\":\"user\"}}\n",
+        ))
+        .unwrap();
+        assert!(!matches_bindings(&envelope, &claims()));
+    }
+}
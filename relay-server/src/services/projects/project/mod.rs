@@ -5,6 +5,7 @@ use relay_base_schema::project::ProjectKey;
 use relay_quotas::Scoping;
 
 mod info;
+mod ingest_token;
 mod serialize;
 
 pub use self::info::*;
@@ -45,13 +45,13 @@ impl ProxyProcessorService {
         }
     }
 
-    fn handle_process_envelope(&self, message: ProcessEnvelope) {
+    async fn handle_process_envelope(&self, message: ProcessEnvelope) {
         let wait_time = message.envelope.age();
         metric!(timer(RelayTimers::EnvelopeWaitTime) = wait_time);
-        self.submit_upstream(message.envelope);
+        self.submit_upstream(message.envelope).await;
     }
 
-    fn handle_submit_client_reports(&self, message: SubmitClientReports) {
+    async fn handle_submit_client_reports(&self, message: SubmitClientReports) {
         let SubmitClientReports {
             client_reports,
             scoping,
@@ -78,10 +78,10 @@ impl ProxyProcessorService {
         }
 
         let envelope = ManagedEnvelope::new(envelope, self.addrs.outcome_aggregator.clone());
-        self.submit_upstream(envelope);
+        self.submit_upstream(envelope).await;
     }
 
-    fn submit_upstream(&self, mut envelope: ManagedEnvelope) {
+    async fn submit_upstream(&self, mut envelope: ManagedEnvelope) {
         if envelope.envelope_mut().is_empty() {
             envelope.accept();
             return;
@@ -95,13 +95,16 @@ impl ProxyProcessorService {
 
         match result {
             Ok(body) => {
-                self.addrs.upstream_relay.send(SendRequest(SendEnvelope {
-                    upstream: None,
-                    envelope,
-                    body,
-                    http_encoding,
-                    project_cache: self.project_cache.clone(),
-                }));
+                self.addrs
+                    .upstream_relay
+                    .send_wait(SendRequest(SendEnvelope {
+                        upstream: None,
+                        envelope,
+                        body,
+                        http_encoding,
+                        project_cache: self.project_cache.clone(),
+                    }))
+                    .await;
             }
             Err(error) => {
                 // Errors are only logged for what we consider an internal discard reason. These
@@ -117,18 +120,25 @@ impl ProxyProcessorService {
         }
     }
 
-    fn handle_message(&self, message: EnvelopeProcessor) {
+    async fn handle_message(&self, message: EnvelopeProcessor) {
         let ty = message.variant();
 
         metric!(timer(RelayTimers::ProcessMessageDuration), message = ty, {
             match message {
-                EnvelopeProcessor::ProcessEnvelope(m) => self.handle_process_envelope(*m),
-                EnvelopeProcessor::SubmitClientReports(m) => self.handle_submit_client_reports(*m),
+                EnvelopeProcessor::ProcessEnvelope(m) => self.handle_process_envelope(*m).await,
+                EnvelopeProcessor::SubmitClientReports(m) => {
+                    self.handle_submit_client_reports(*m).await
+                }
                 EnvelopeProcessor::ProcessBatchedMetrics(_)
                 | EnvelopeProcessor::ProcessProjectMetrics(_)
                 | EnvelopeProcessor::FlushBuckets(_) => {
                     relay_log::error!("internal error: Metrics not supported in Proxy mode");
                 }
+                EnvelopeProcessor::SubmitSessionAggregates(_) => {
+                    relay_log::error!(
+                        "internal error: Session aggregation not supported in Proxy mode"
+                    );
+                }
             }
         });
     }
@@ -139,7 +149,7 @@ impl Service for ProxyProcessorService {
 
     async fn run(self, mut rx: relay_system::Receiver<Self::Interface>) {
         while let Some(message) = rx.recv().await {
-            self.handle_message(message);
+            self.handle_message(message).await;
         }
     }
 }
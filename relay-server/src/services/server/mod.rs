@@ -209,7 +209,7 @@ impl Service for HttpServer {
 
         if let Some(internal_listener) = internal_listener {
             let public = make_app(service.clone(), crate::endpoints::public_routes);
-            let internal = make_app(service, crate::endpoints::internal_routes);
+            let internal = make_app(service, crate::endpoints::dedicated_internal_routes);
 
             tokio::try_join!(
                 serve(listener, public, &config),
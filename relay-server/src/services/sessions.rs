@@ -0,0 +1,352 @@
+//! This module contains the service that pre-aggregates session updates.
+//!
+//! Server SDKs may send a session update for every request they handle, each of them starting and
+//! ending a session at the same time. Instead of forwarding these updates one by one, Relay can
+//! aggregate them into [`SessionAggregates`] over a short window, which results in the same
+//! release health data upstream.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use chrono::{DateTime, DurationRound, Utc};
+use relay_config::Config;
+use relay_event_schema::protocol::{
+    AbnormalMechanism, IpAddr, SessionAggregateItem, SessionAggregates, SessionAttributes,
+    SessionStatus, SessionUpdate,
+};
+use relay_quotas::Scoping;
+use relay_system::{Addr, FromMessage, Interface, NoResponse, Service};
+
+use crate::managed::Managed;
+use crate::processing::sessions::AggregatableSessions;
+use crate::services::processor::{EnvelopeProcessor, SubmitSessionAggregates};
+use crate::utils::SleepHandle;
+
+/// Aggregates session updates before they are processed.
+///
+/// All updates must be aggregatable, see [`is_aggregatable`].
+#[derive(Debug)]
+pub struct AggregateSessions {
+    /// The session updates to aggregate.
+    pub sessions: Managed<AggregatableSessions>,
+    /// The client SDK which sent the session updates.
+    pub client: Option<String>,
+}
+
+impl Interface for AggregateSessions {}
+
+impl FromMessage<Self> for AggregateSessions {
+    type Response = NoResponse;
+
+    fn from_message(message: Self, _: ()) -> Self {
+        message
+    }
+}
+
+/// Returns `true` if the session update can be represented in a [`SessionAggregateItem`].
+///
+/// Only updates which start and end a session at the same time can be aggregated without
+/// changing the extracted release health data. Updates with an abnormal mechanism are excluded,
+/// as aggregates cannot carry one.
+pub fn is_aggregatable(update: &SessionUpdate) -> bool {
+    update.init
+        && update.abnormal_mechanism == AbnormalMechanism::None
+        && status_counter(update).is_some()
+}
+
+/// Returns the counter of a [`SessionAggregateItem`] which corresponds to the status of `update`.
+///
+/// Exited sessions with errors are counted as errored, which matches how errors are extracted
+/// from an individual session update.
+fn status_counter(update: &SessionUpdate) -> Option<fn(&mut SessionAggregateItem) -> &mut u32> {
+    Some(match update.status {
+        SessionStatus::Exited if update.errors > 0 => |item| &mut item.errored,
+        SessionStatus::Exited => |item| &mut item.exited,
+        SessionStatus::Errored => |item| &mut item.errored,
+        SessionStatus::Abnormal => |item| &mut item.abnormal,
+        SessionStatus::Unhandled => |item| &mut item.unhandled,
+        SessionStatus::Crashed => |item| &mut item.crashed,
+        SessionStatus::Ok | SessionStatus::Unknown(_) => return None,
+    })
+}
+
+/// Service implementing the [`AggregateSessions`] interface.
+///
+/// Session updates are bucketed by their scoping, client and attributes and flushed as
+/// [`SessionAggregates`] to the [`EnvelopeProcessor`] after the configured flush interval.
+#[derive(Debug)]
+pub struct SessionAggregatorService {
+    processor: Addr<EnvelopeProcessor>,
+    buckets: BTreeMap<BucketKey, BTreeMap<ItemKey, SessionAggregateItem>>,
+    flush_interval: Duration,
+    flush_handle: SleepHandle,
+}
+
+impl SessionAggregatorService {
+    /// Creates a new session aggregator, which submits aggregates to the `processor`.
+    pub fn new(config: &Config, processor: Addr<EnvelopeProcessor>) -> Self {
+        Self {
+            processor,
+            buckets: Default::default(),
+            flush_interval: config.sessions_flush_interval(),
+            flush_handle: SleepHandle::idle(),
+        }
+    }
+
+    fn handle_message(&mut self, message: AggregateSessions) {
+        let AggregateSessions { sessions, client } = message;
+        let scoping = sessions.scoping();
+        let updates = sessions.accept(|sessions| sessions.updates);
+
+        for update in updates {
+            let Some(counter) = status_counter(&update) else {
+                relay_log::error!("session update cannot be aggregated");
+                continue;
+            };
+
+            let started = truncate_to_minute(update.started);
+            let SessionAttributes {
+                release,
+                environment,
+                ip_address,
+                user_agent,
+            } = update.attributes;
+
+            let bucket_key = BucketKey {
+                scoping,
+                client: client.clone(),
+                release,
+                environment,
+                ip_address,
+                user_agent,
+            };
+            let item_key = ItemKey {
+                started,
+                distinct_id: update.distinct_id,
+            };
+
+            let item = self
+                .buckets
+                .entry(bucket_key)
+                .or_default()
+                .entry(item_key)
+                .or_insert_with_key(|key| SessionAggregateItem {
+                    started: key.started,
+                    distinct_id: key.distinct_id.clone(),
+                    exited: 0,
+                    errored: 0,
+                    abnormal: 0,
+                    unhandled: 0,
+                    crashed: 0,
+                });
+
+            *counter(item) += 1;
+        }
+
+        match self.flush_interval.is_zero() {
+            true => self.do_flush(),
+            false => self.flush_handle.set_if_idle(self.flush_interval),
+        }
+    }
+
+    fn do_flush(&mut self) {
+        let mut by_scoping = BTreeMap::<_, Vec<SessionAggregates>>::new();
+        for (bucket_key, items) in std::mem::take(&mut self.buckets) {
+            let BucketKey {
+                scoping,
+                client,
+                release,
+                environment,
+                ip_address,
+                user_agent,
+            } = bucket_key;
+
+            by_scoping
+                .entry((scoping, client))
+                .or_default()
+                .push(SessionAggregates {
+                    aggregates: items.into_values().collect(),
+                    attributes: SessionAttributes {
+                        release,
+                        environment,
+                        ip_address,
+                        user_agent,
+                    },
+                });
+        }
+
+        for ((scoping, client), aggregates) in by_scoping {
+            self.processor.send(SubmitSessionAggregates {
+                aggregates,
+                client,
+                scoping,
+            });
+        }
+    }
+
+    fn handle_shutdown(&mut self) {
+        self.flush_interval = Duration::ZERO;
+        self.do_flush();
+    }
+}
+
+impl Service for SessionAggregatorService {
+    type Interface = AggregateSessions;
+
+    async fn run(mut self, mut rx: relay_system::Receiver<Self::Interface>) {
+        let mut shutdown = relay_system::Controller::shutdown_handle();
+        relay_log::info!("session aggregator started");
+
+        loop {
+            tokio::select! {
+                biased;
+
+                () = &mut self.flush_handle => self.do_flush(),
+                Some(message) = rx.recv() => self.handle_message(message),
+                _ = shutdown.notified() => self.handle_shutdown(),
+
+                else => break,
+            }
+        }
+        self.do_flush();
+        relay_log::info!("session aggregator stopped");
+    }
+}
+
+/// Attributes shared by all items of a [`SessionAggregates`] payload.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct BucketKey {
+    scoping: Scoping,
+    client: Option<String>,
+    release: String,
+    environment: Option<String>,
+    ip_address: Option<IpAddr>,
+    user_agent: Option<String>,
+}
+
+/// Identifies a single [`SessionAggregateItem`] within a bucket.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct ItemKey {
+    started: DateTime<Utc>,
+    distinct_id: Option<String>,
+}
+
+fn truncate_to_minute(timestamp: DateTime<Utc>) -> DateTime<Utc> {
+    timestamp
+        .duration_trunc(chrono::Duration::minutes(1))
+        .unwrap_or(timestamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use relay_base_schema::organization::OrganizationId;
+    use relay_base_schema::project::{ProjectId, ProjectKey};
+
+    use super::*;
+
+    fn scoping() -> Scoping {
+        Scoping {
+            organization_id: OrganizationId::new(1),
+            project_id: ProjectId::new(42),
+            project_key: ProjectKey::parse("a94ae32be2584e0bbd7a4cbb95971fee").unwrap(),
+            key_id: Some(17),
+        }
+    }
+
+    fn update(json: &str) -> SessionUpdate {
+        SessionUpdate::parse(json.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_is_aggregatable() {
+        let exited = update(
+            r#"{"init":true,"started":"2025-01-01T10:00:30Z","status":"exited","attrs":{"release":"1.0"}}"#,
+        );
+        assert!(is_aggregatable(&exited));
+
+        let ongoing = update(
+            r#"{"init":true,"started":"2025-01-01T10:00:30Z","status":"ok","attrs":{"release":"1.0"}}"#,
+        );
+        assert!(!is_aggregatable(&ongoing));
+
+        let continued = update(
+            r#"{"started":"2025-01-01T10:00:30Z","status":"exited","attrs":{"release":"1.0"}}"#,
+        );
+        assert!(!is_aggregatable(&continued));
+
+        let anr = update(
+            r#"{"init":true,"started":"2025-01-01T10:00:30Z","status":"abnormal","abnormal_mechanism":"anr_foreground","attrs":{"release":"1.0"}}"#,
+        );
+        assert!(!is_aggregatable(&anr));
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_sessions() {
+        let (processor, mut processor_rx) = Addr::custom();
+        let mut service = SessionAggregatorService::new(&Config::default(), processor);
+
+        let updates = [
+            r#"{"init":true,"started":"2025-01-01T10:00:30Z","status":"exited","attrs":{"release":"1.0"}}"#,
+            r#"{"init":true,"started":"2025-01-01T10:00:45Z","status":"exited","errors":2,"attrs":{"release":"1.0"}}"#,
+            r#"{"init":true,"started":"2025-01-01T10:00:59Z","status":"crashed","attrs":{"release":"1.0"}}"#,
+            r#"{"init":true,"started":"2025-01-01T10:01:00Z","status":"exited","attrs":{"release":"1.0"}}"#,
+            r#"{"init":true,"started":"2025-01-01T10:00:00Z","status":"unhandled","attrs":{"release":"1.0","environment":"prod"}}"#,
+        ];
+
+        let (sessions, mut handle) = Managed::for_test(AggregatableSessions {
+            updates: updates.into_iter().map(update).collect(),
+        })
+        .scoping(scoping())
+        .build();
+
+        service.handle_message(AggregateSessions {
+            sessions,
+            client: Some("sentry.python/2.0.0".to_owned()),
+        });
+        handle.assert_no_outcomes();
+        service.do_flush();
+
+        let Some(EnvelopeProcessor::SubmitSessionAggregates(message)) = processor_rx.recv().await
+        else {
+            panic!("expected session aggregates");
+        };
+
+        assert_eq!(message.scoping, scoping());
+        assert_eq!(message.client.as_deref(), Some("sentry.python/2.0.0"));
+        insta::assert_json_snapshot!(message.aggregates, @r#"
+        [
+          {
+            "aggregates": [
+              {
+                "started": "2025-01-01T10:00:00Z",
+                "exited": 1,
+                "errored": 1,
+                "crashed": 1
+              },
+              {
+                "started": "2025-01-01T10:01:00Z",
+                "exited": 1
+              }
+            ],
+            "attrs": {
+              "release": "1.0"
+            }
+          },
+          {
+            "aggregates": [
+              {
+                "started": "2025-01-01T10:00:00Z",
+                "unhandled": 1
+              }
+            ],
+            "attrs": {
+              "release": "1.0",
+              "environment": "prod"
+            }
+          }
+        ]
+        "#);
+
+        assert!(service.buckets.is_empty());
+    }
+}
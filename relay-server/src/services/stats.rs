@@ -150,6 +150,13 @@ impl RelayStats {
         }
     }
 
+    async fn user_agent_metrics(&self) {
+        metric!(
+            gauge(RelayGauges::UserAgentDefinitions) = 1,
+            version = &relay_ua::definitions_version(),
+        );
+    }
+
     #[cfg(feature = "processing")]
     fn async_redis_connection(client: &AsyncRedisClient, name: &str) {
         Self::stats_metrics(client.stats(), name);
@@ -235,7 +242,8 @@ impl Service for RelayStats {
                 self.service_metrics(),
                 self.tokio_metrics(),
                 self.redis_clients(),
-                self.async_pools_metrics()
+                self.async_pools_metrics(),
+                self.user_agent_metrics()
             );
             ticker.tick().await;
         }
@@ -23,13 +23,13 @@ use relay_config::Config;
 use relay_event_schema::protocol::{EventId, SpanV2, datetime_to_timestamp};
 use relay_kafka::{ClientError, KafkaClient, KafkaTopic, Message, SerializationOutput};
 use relay_metrics::{
-    Bucket, BucketView, BucketViewValue, BucketsView, ByNamespace, GaugeValue, MetricName,
-    MetricNamespace, SetView,
+    Bucket, BucketView, BucketViewValue, BucketsView, ByNamespace, DistributionSketch, GaugeValue,
+    MetricName, MetricNamespace, SetView,
 };
 use relay_protocol::{Annotated, FiniteF64, SerializableAnnotated};
 use relay_quotas::Scoping;
 use relay_statsd::metric;
-use relay_system::{FromMessage, Interface, NoResponse, Service};
+use relay_system::{FromMessage, Interface, LoadShed, NoResponse, Service};
 use relay_threading::AsyncPool;
 
 use crate::envelope::{AttachmentPlaceholder, AttachmentType, ContentType, Item, ItemType};
@@ -292,7 +292,7 @@ pub enum Store {
 
 impl Store {
     /// Returns the name of the message variant.
-    fn variant(&self) -> &'static str {
+    pub(crate) fn variant(&self) -> &'static str {
         match self {
             Store::Envelope(_) => "envelope",
             Store::Metrics(_) => "metrics",
@@ -407,6 +407,13 @@ impl StoreService {
         })
     }
 
+    /// Returns a handler that accounts for messages dropped from a full store queue.
+    pub fn loadshed(&self) -> StoreLoadShed {
+        StoreLoadShed {
+            metric_outcomes: self.metric_outcomes.clone(),
+        }
+    }
+
     fn handle_message(&self, message: Store) {
         let ty = message.variant();
         relay_statsd::metric!(timer(RelayTimers::StoreServiceDuration), message = ty, {
@@ -904,6 +911,7 @@ impl StoreService {
                     .map_err(StoreError::EncodingFailed)?,
             ),
             BucketViewValue::Gauge(g) => MetricValue::Gauge(g),
+            BucketViewValue::DistributionSketch(s) => MetricValue::DistributionSketch(s),
         };
 
         Ok(MetricKafkaMessage {
@@ -1298,6 +1306,52 @@ impl Service for StoreService {
     }
 }
 
+/// Rejects messages dropped from the [`StoreService`] queue with an internal outcome.
+///
+/// See [`StoreService::loadshed`].
+#[derive(Debug)]
+pub struct StoreLoadShed {
+    metric_outcomes: MetricOutcomes,
+}
+
+impl LoadShed<Store> for StoreLoadShed {
+    fn handle_loadshed(&self, message: Store) {
+        relay_log::debug!(
+            tags.message = message.variant(),
+            "dropped message from full store queue"
+        );
+
+        let outcome = Outcome::Invalid(DiscardReason::Internal);
+        match message {
+            Store::Envelope(StoreEnvelope { mut envelope }) => envelope.reject(outcome),
+            Store::Metrics(StoreMetrics {
+                buckets, scoping, ..
+            }) => self.metric_outcomes.track(scoping, &buckets, outcome),
+            Store::TraceItem(managed) => {
+                let _ = managed.reject_err(outcome);
+            }
+            Store::Span(managed) => {
+                let _ = managed.reject_err(outcome);
+            }
+            Store::ProfileChunk(managed) => {
+                let _ = managed.reject_err(outcome);
+            }
+            Store::Replay(managed) => {
+                let _ = managed.reject_err(outcome);
+            }
+            Store::Attachment(managed) => {
+                let _ = managed.reject_err(outcome);
+            }
+            Store::UserReport(managed) => {
+                let _ = managed.reject_err(outcome);
+            }
+            Store::Profile(managed) => {
+                let _ = managed.reject_err(outcome);
+            }
+        }
+    }
+}
+
 /// Task executed by [`StoreService`].
 pub struct StoreTask {
     service: Arc<StoreService>,
@@ -1511,6 +1565,8 @@ enum MetricValue<'a> {
     Set(ArrayEncoding<'a, SetView<'a>>),
     #[serde(rename = "g")]
     Gauge(GaugeValue),
+    #[serde(rename = "ds")]
+    DistributionSketch(&'a DistributionSketch),
 }
 
 impl MetricValue<'_> {
@@ -1520,6 +1576,7 @@ impl MetricValue<'_> {
             Self::Distribution(_) => "distribution",
             Self::Set(_) => "set",
             Self::Gauge(_) => "gauge",
+            Self::DistributionSketch(_) => "distribution_sketch",
         }
     }
 
@@ -25,7 +25,8 @@ use relay_quotas::{
 };
 use relay_statsd::metric;
 use relay_system::{
-    AsyncResponse, FromMessage, Interface, MessageResponse, NoResponse, Sender, Service,
+    AsyncResponse, FromMessage, Interface, LoadShed, MessageResponse, NoResponse, Sender, Service,
+    TaskId,
 };
 pub use reqwest::Method;
 use reqwest::header;
@@ -1645,6 +1646,45 @@ impl UpstreamRelayService {
         // Broker and other actual components are implemented in the Service's `spawn_handler`.
         Self { config }
     }
+
+    /// Returns a handler that responds to requests dropped from a full upstream queue.
+    ///
+    /// Must be called from within the Tokio runtime.
+    pub fn loadshed(&self) -> UpstreamLoadShed {
+        UpstreamLoadShed {
+            handle: tokio::runtime::Handle::current(),
+        }
+    }
+}
+
+/// Responds to requests dropped from the [`UpstreamRelayService`] queue with an error.
+///
+/// Requests are responded with [`UpstreamRequestError::ChannelClosed`], which rejects tracked
+/// envelopes with an internal outcome. See [`UpstreamRelayService::loadshed`].
+#[derive(Debug)]
+pub struct UpstreamLoadShed {
+    handle: tokio::runtime::Handle,
+}
+
+impl LoadShed<UpstreamRelay> for UpstreamLoadShed {
+    fn handle_loadshed(&self, message: UpstreamRelay) {
+        match message {
+            // Dropping the sender resolves the response with an error.
+            UpstreamRelay::IsAuthenticated(_, _) | UpstreamRelay::IsNetworkOutage(_, _) => (),
+            UpstreamRelay::SendRequest(request) => {
+                relay_log::debug!(
+                    tags.route = request.route(),
+                    "dropped request from full upstream queue"
+                );
+                let future = request.respond(Err(UpstreamRequestError::ChannelClosed));
+                relay_system::spawn_in(
+                    &self.handle,
+                    TaskId::for_service::<UpstreamRelayService>(),
+                    future,
+                );
+            }
+        }
+    }
 }
 
 impl Service for UpstreamRelayService {
@@ -0,0 +1,233 @@
+//! This module contains the service that keeps user agent parser definitions up to date.
+//!
+//! Relay ships with built-in definitions for the user agent parser. They can be replaced with
+//! definitions from a file configured in `user_agent.definitions_path` and from the global config,
+//! which takes precedence. Definitions are compiled and validated in the background before they
+//! are swapped in, invalid definitions are discarded and the previous definitions remain active.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use relay_config::Config;
+use relay_dynamic_config::UserAgentDefinitions;
+use relay_statsd::metric;
+use relay_system::Service;
+use relay_ua::Definitions;
+use tokio::sync::watch;
+
+use crate::services::global_config;
+use crate::statsd::RelayCounters;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Service loading user agent parser definitions at runtime.
+#[derive(Debug)]
+pub struct UserAgentDefinitionsService {
+    definitions_path: Option<PathBuf>,
+    global_config: watch::Receiver<global_config::Status>,
+}
+
+impl UserAgentDefinitionsService {
+    /// Creates a new service, which loads definitions from the config and global config updates.
+    pub fn new(config: &Config, global_config: watch::Receiver<global_config::Status>) -> Self {
+        Self {
+            definitions_path: config.user_agent_definitions_path().map(Into::into),
+            global_config,
+        }
+    }
+}
+
+impl Service for UserAgentDefinitionsService {
+    type Interface = ();
+
+    async fn run(mut self, _rx: relay_system::Receiver<Self::Interface>) {
+        // Definitions from the file are the fallback when the global config has none.
+        let fallback = match self.definitions_path {
+            Some(path) => load("file", move || {
+                let regexes = std::fs::read(&path)?;
+                let version = path.file_name().unwrap_or_default().to_string_lossy();
+                Ok(Definitions::parse(version, &regexes)?)
+            })
+            .await
+            .map(Arc::new),
+            None => None,
+        };
+
+        let mut state = DefinitionsState::new(fallback);
+        if let Some(fallback) = &state.fallback {
+            relay_ua::set_definitions(Arc::clone(fallback));
+        }
+
+        loop {
+            let definitions = match &*self.global_config.borrow_and_update() {
+                global_config::Status::Ready(config) => config.user_agent_definitions.clone(),
+                global_config::Status::Pending => None,
+            };
+
+            match state.update(definitions).await {
+                Some(Change::Set(definitions)) => relay_ua::set_definitions(definitions),
+                Some(Change::Reset) => relay_ua::reset_definitions(),
+                None => (),
+            }
+
+            if self.global_config.changed().await.is_err() {
+                break;
+            }
+        }
+    }
+}
+
+/// A change of the active user agent definitions.
+#[derive(Debug)]
+enum Change {
+    /// Replaces the active definitions.
+    Set(Arc<Definitions>),
+    /// Restores the built-in definitions.
+    Reset,
+}
+
+/// Tracks the definitions from both sources and determines when they have to be swapped.
+#[derive(Debug)]
+struct DefinitionsState {
+    /// Definitions loaded from the file.
+    fallback: Option<Arc<Definitions>>,
+    /// The version of the last definitions received with the global config, even if invalid.
+    global_version: Option<String>,
+}
+
+impl DefinitionsState {
+    fn new(fallback: Option<Arc<Definitions>>) -> Self {
+        Self {
+            fallback,
+            global_version: None,
+        }
+    }
+
+    /// Loads the definitions of a new global config, if their version changed.
+    ///
+    /// If the global config contains definitions which cannot be compiled, the previous
+    /// definitions remain active. Once the global config no longer contains definitions, the
+    /// definitions from the file or the built-in definitions are restored.
+    async fn update(&mut self, definitions: Option<UserAgentDefinitions>) -> Option<Change> {
+        match definitions {
+            Some(definitions) if self.global_version.as_ref() != Some(&definitions.version) => {
+                self.global_version = Some(definitions.version.clone());
+
+                let loaded = load("global_config", move || {
+                    let regexes = definitions.regexes.as_bytes();
+                    Ok(Definitions::parse(definitions.version, regexes)?)
+                })
+                .await;
+
+                loaded.map(|definitions| Change::Set(Arc::new(definitions)))
+            }
+            Some(_) => None,
+            None if self.global_version.is_some() => {
+                self.global_version = None;
+                Some(match &self.fallback {
+                    Some(fallback) => Change::Set(Arc::clone(fallback)),
+                    None => Change::Reset,
+                })
+            }
+            None => None,
+        }
+    }
+}
+
+/// Compiles definitions on a blocking thread and reports the result.
+async fn load<F>(source: &'static str, f: F) -> Option<Definitions>
+where
+    F: FnOnce() -> Result<Definitions, BoxError> + Send + 'static,
+{
+    let result = match tokio::task::spawn_blocking(f).await {
+        Ok(result) => result,
+        Err(error) => Err(error.into()),
+    };
+
+    match result {
+        Ok(definitions) => {
+            relay_log::info!(
+                version = definitions.version(),
+                "loaded user agent definitions from {source}"
+            );
+            metric!(
+                counter(RelayCounters::UserAgentDefinitionsLoaded) += 1,
+                source = source,
+                result = "success",
+            );
+            Some(definitions)
+        }
+        Err(error) => {
+            relay_log::error!(
+                error = error.as_ref() as &dyn std::error::Error,
+                "failed to load user agent definitions from {source}"
+            );
+            metric!(
+                counter(RelayCounters::UserAgentDefinitionsLoaded) += 1,
+                source = source,
+                result = "invalid",
+            );
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REGEXES: &str = r#"
+user_agent_parsers:
+  - regex: '(Chrome)/(\d+)\.(\d+)\.(\d+)'
+os_parsers: []
+device_parsers: []
+"#;
+
+    fn definitions(version: &str, regexes: &str) -> Option<UserAgentDefinitions> {
+        Some(UserAgentDefinitions {
+            version: version.to_owned(),
+            regexes: regexes.to_owned(),
+        })
+    }
+
+    fn version(change: Option<Change>) -> Option<String> {
+        match change? {
+            Change::Set(definitions) => Some(definitions.version().to_owned()),
+            Change::Reset => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_definitions_state() {
+        let mut state = DefinitionsState::new(None);
+        assert!(state.update(None).await.is_none());
+
+        let change = state.update(definitions("v1", REGEXES)).await;
+        assert_eq!(version(change).as_deref(), Some("v1"));
+
+        // Definitions are only compiled again when the version changes.
+        assert!(state.update(definitions("v1", REGEXES)).await.is_none());
+
+        // Invalid definitions keep the previous definitions.
+        assert!(state.update(definitions("v2", "invalid")).await.is_none());
+        assert!(state.update(definitions("v2", REGEXES)).await.is_none());
+
+        // Removing the definitions from the global config restores the built-in definitions.
+        let change = state.update(None).await;
+        assert!(matches!(change, Some(Change::Reset)));
+        assert!(state.update(None).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_definitions_state_fallback() {
+        let fallback = Definitions::parse("file", REGEXES.as_bytes()).unwrap();
+        let mut state = DefinitionsState::new(Some(Arc::new(fallback)));
+
+        let change = state.update(definitions("v1", REGEXES)).await;
+        assert_eq!(version(change).as_deref(), Some("v1"));
+
+        // Removing the definitions from the global config falls back to the file.
+        let change = state.update(None).await;
+        assert_eq!(version(change).as_deref(), Some("file"));
+    }
+}
@@ -30,6 +30,11 @@ pub enum RelayGauges {
     /// The state of Relay with respect to the upstream connection.
     /// Possible values are `0` for normal operations and `1` for a network outage.
     NetworkOutage,
+    /// Always `1`, reports the user agent parser definitions in use.
+    ///
+    /// This metric is tagged with:
+    /// - `version`: The version of the definitions, `builtin` for the built-in definitions.
+    UserAgentDefinitions,
     /// Number of elements in the envelope buffer across all the stacks.
     ///
     /// This metric is tagged with:
@@ -91,6 +96,7 @@ impl GaugeMetric for RelayGauges {
             Self::AsyncPoolUtilization => "async_pool.utilization",
             Self::AsyncPoolActivity => "async_pool.activity",
             Self::NetworkOutage => "upstream.network_outage",
+            Self::UserAgentDefinitions => "user_agent.definitions",
             Self::BufferEnvelopesCount => "buffer.envelopes_count",
             Self::BufferStackCount => "buffer.stack_count",
             Self::BufferDiskUsed => "buffer.disk_used",
@@ -784,6 +790,26 @@ pub enum RelayCounters {
     /// While [`RelayCounters::Outcomes`] tracks the number of times aggregated outcomes
     /// have been emitted, this counter tracks the total quantity of individual outcomes.
     OutcomeQuantity,
+    /// Number of attempts to load user agent parser definitions at runtime.
+    ///
+    /// This metric is tagged with:
+    ///  - `source`: Either `file` or `global_config`.
+    ///  - `result`: Either `success` or `invalid`.
+    UserAgentDefinitionsLoaded,
+    /// Number of attempts to load attribute overrides of `sentry-conventions` at runtime.
+    ///
+    /// This metric is tagged with:
+    ///  - `source`: Either `file` or `global_config`.
+    ///  - `result`: Either `success` or `invalid`.
+    AttributeOverridesLoaded,
+    /// Number of items which matched an inbound filter in report-only mode.
+    ///
+    /// These items are not dropped, the metric allows to evaluate a filter before enabling it.
+    ///
+    /// This metric is tagged with:
+    ///  - `filter`: The filter which matched, equivalent to the reason of `filtered` outcomes.
+    ///  - `category`: The data category of the item.
+    ReportOnlyFilter,
     /// Number of project state HTTP requests.
     ///
     /// Relay updates projects in batches. Every update cycle, Relay requests
@@ -938,6 +964,11 @@ pub enum RelayCounters {
     /// This metric is tagged with:
     ///  - `aggregator`: The name of the metrics aggregator (usually `"default"`).
     BucketsDropped,
+    /// Number of buckets restored from an aggregator snapshot on startup.
+    ///
+    /// This metric is tagged with:
+    ///  - `aggregator`: The name of the metrics aggregator (usually `"default"`).
+    BucketsRestored,
     /// Incremented every time a segment exceeds the expected limit.
     ReplayExceededSegmentLimit,
     /// Incremented every time the server accepts a new connection.
@@ -1039,6 +1070,9 @@ impl CounterMetric for RelayCounters {
             RelayCounters::BufferServiceLoopIteration => "buffer.service_loop_iteration",
             RelayCounters::Outcomes => "events.outcomes",
             RelayCounters::OutcomeQuantity => "events.outcome_quantity",
+            RelayCounters::ReportOnlyFilter => "events.report_only_filter",
+            RelayCounters::UserAgentDefinitionsLoaded => "user_agent.definitions_loaded",
+            RelayCounters::AttributeOverridesLoaded => "conventions.attribute_overrides_loaded",
             RelayCounters::ProjectStateRequest => "project_state.request",
             #[cfg(feature = "processing")]
             RelayCounters::ProjectStateRedis => "project_state.redis.requests",
@@ -1064,6 +1098,7 @@ impl CounterMetric for RelayCounters {
             RelayCounters::CogsUsage => "cogs.usage",
             RelayCounters::ProjectStateFlushMetricsNoProject => "project_state.metrics.no_project",
             RelayCounters::BucketsDropped => "metrics.buckets.dropped",
+            RelayCounters::BucketsRestored => "metrics.buckets.restored",
             RelayCounters::ReplayExceededSegmentLimit => "replay.segment_limit_exceeded",
             RelayCounters::ServerSocketAccept => "server.http.accepted",
             RelayCounters::ServerConnectionIdleTimeout => "server.http.idle_timeout",
@@ -113,6 +113,7 @@ pub async fn create_test_processor(config: Config) -> EnvelopeProcessorService {
             #[cfg(feature = "processing")]
             objectstore: None,
             aggregator,
+            session_aggregator: None,
         },
         metric_outcomes,
     )
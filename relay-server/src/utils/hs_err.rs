@@ -0,0 +1,1233 @@
+//! Parsing of HotSpot JVM fatal error logs (`hs_err_pid<N>.log`).
+//!
+//! When the JVM crashes, it writes a text report containing the signal or error that caused the
+//! crash, the stack of the crashing thread with native and Java frames, all threads, the loaded
+//! libraries, VM arguments, environment variables and information about the operating system. See
+//! [`process_hs_err_log`] for how this is converted into an event.
+
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+
+use chrono::NaiveDateTime;
+use regex::Regex;
+use relay_event_schema::protocol::{
+    Addr, Contexts, DebugId, DebugImage, DebugMeta, Event, Exception, ExtraValue, Frame,
+    JsonLenientString, LenientString, Level, Mechanism, MechanismMeta, NativeDebugImage, OsContext,
+    PosixSignal, RawStacktrace, RuntimeContext, Stacktrace, Thread, ThreadId, Timestamp, Values,
+};
+use relay_protocol::{Annotated, Object, Value};
+
+use crate::envelope::{AttachmentType, ContentType, Item};
+use crate::services::processor::ProcessingError;
+
+/// Replacement for redacted values in the forwarded log.
+const REDACTED: &str = "[Filtered]";
+
+/// Prefixes of lines that contain the command line of the JVM process.
+const COMMAND_LINE_PREFIXES: [&str; 3] = ["Command Line:", "jvm_args:", "java_command:"];
+
+/// Matches the error line of signals and Windows exceptions, for example
+/// `SIGSEGV (0xb) at pc=0x00007f8a1c2b3c4d, pid=12345, tid=12346`.
+static ERROR_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?P<name>[A-Z][A-Z0-9_]+) \((?P<code>0x[0-9a-fA-F]+)\) at pc=(?P<pc>0x[0-9a-fA-F]+)",
+    )
+    .unwrap()
+});
+
+/// Matches the `siginfo` line, for example
+/// `siginfo: si_signo: 11 (SIGSEGV), si_code: 1 (SEGV_MAPERR), si_addr: 0x0000000000000000`.
+static SIGINFO_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"si_signo: (?P<number>\d+) \((?P<name>\w+)\), si_code: (?P<code>-?\d+) \((?P<code_name>\w+)\)",
+    )
+    .unwrap()
+});
+
+/// Matches thread descriptions, for example
+/// `JavaThread "main" daemon [_thread_in_native, id=12346, stack(0x...,0x...)]`.
+static THREAD_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"^\w+(?: "(?P<name>[^"]*)")?(?: daemon)?(?: \[(?P<state>_thread_\w+))?.*?\bid=(?P<id>\d+)"#,
+    )
+    .unwrap()
+});
+
+/// Matches the JRE version, for example
+/// `OpenJDK Runtime Environment (17.0.2+8) (build 17.0.2+8-86)`.
+static JRE_VERSION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?P<name>.*?) ?\((?P<version>[^)]+)\)(?: \(build (?P<build>[^)]*)\))?").unwrap()
+});
+
+/// A thread listed in the log.
+#[derive(Debug, Default, PartialEq)]
+struct HsErrThread<'a> {
+    id: Option<u64>,
+    name: Option<&'a str>,
+    state: Option<&'a str>,
+    current: bool,
+}
+
+/// A loaded library from the `Dynamic libraries` section.
+#[derive(Debug, PartialEq)]
+struct HsErrLibrary<'a> {
+    path: &'a str,
+    start: u64,
+    end: Option<u64>,
+    ty: ImageType,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ImageType {
+    Elf,
+    MachO,
+    Pe,
+}
+
+/// A stack frame of the crashing thread, in the order of the log (innermost first).
+#[derive(Debug, PartialEq)]
+enum HsErrFrame<'a> {
+    /// A native or VM frame, with the library and offset of the instruction.
+    Native {
+        library: Option<(&'a str, u64)>,
+        address: Option<u64>,
+        function: Option<&'a str>,
+    },
+    /// An interpreted or compiled Java frame.
+    Java {
+        class: Option<&'a str>,
+        method: &'a str,
+        module: Option<&'a str>,
+    },
+}
+
+/// The information parsed from a HotSpot fatal error log.
+#[derive(Debug, Default)]
+struct HsErrLog<'a> {
+    error: Option<&'a str>,
+    error_details: Vec<&'a str>,
+    jre_version: Option<&'a str>,
+    java_vm: Option<&'a str>,
+    problematic_frame: Option<&'a str>,
+    time: Option<&'a str>,
+    current_thread: Option<HsErrThread<'a>>,
+    siginfo: Option<&'a str>,
+    native_frames: Vec<HsErrFrame<'a>>,
+    java_frames: Vec<HsErrFrame<'a>>,
+    threads: Vec<HsErrThread<'a>>,
+    libraries: Vec<HsErrLibrary<'a>>,
+    jvm_args: Option<&'a str>,
+    java_command: Option<&'a str>,
+    environment: Vec<(&'a str, &'a str)>,
+    os: Option<&'a str>,
+    os_release: BTreeMap<&'a str, &'a str>,
+    uname: Option<&'a str>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Section {
+    None,
+    NativeFrames,
+    JavaFrames,
+    JavaThreads,
+    OtherThreads,
+    Libraries,
+    VmArguments,
+    Environment,
+    Os,
+}
+
+impl<'a> HsErrLog<'a> {
+    /// Parses a fatal error log.
+    ///
+    /// Returns `None` if the text does not start with the header of a fatal error log.
+    fn parse(log: &'a str) -> Option<Self> {
+        let mut parsed = Self::default();
+        let mut lines = log.lines().map(|line| line.trim_end()).peekable();
+
+        let mut header = Vec::new();
+        while let Some(line) = lines.next_if(|line| line.is_empty() || line.starts_with('#')) {
+            match line.trim_start_matches('#').trim() {
+                "" => {}
+                line => header.push(line),
+            }
+        }
+        parsed.parse_header(&header)?;
+
+        let mut section = Section::None;
+        for line in lines {
+            if line.is_empty() {
+                section = Section::None;
+                continue;
+            }
+
+            if let Some(thread) = line.strip_prefix("Current thread (") {
+                parsed.current_thread = thread
+                    .split_once("):")
+                    .and_then(|(_, thread)| parse_thread(thread.trim_start(), true));
+            } else if line.starts_with("Native frames:") {
+                section = Section::NativeFrames;
+            } else if line.starts_with("Java frames:") {
+                section = Section::JavaFrames;
+            } else if line.starts_with("Java Threads:") {
+                section = Section::JavaThreads;
+            } else if line.starts_with("Other Threads:") {
+                section = Section::OtherThreads;
+            } else if line.starts_with("Dynamic libraries:") {
+                section = Section::Libraries;
+            } else if line.starts_with("VM Arguments:") {
+                section = Section::VmArguments;
+            } else if line.starts_with("Environment Variables:") {
+                section = Section::Environment;
+            } else if let Some(os) = line.strip_prefix("OS:") {
+                section = Section::Os;
+                parsed.parse_os_line(os.trim());
+            } else if let Some(uname) = line.strip_prefix("uname:") {
+                section = Section::None;
+                parsed.uname = Some(uname.trim());
+            } else if let Some(siginfo) = line.strip_prefix("siginfo:") {
+                parsed.siginfo = Some(siginfo.trim());
+            } else if let Some(time) = line.strip_prefix("Time:") {
+                parsed.time = Some(time.trim());
+            } else {
+                match section {
+                    Section::None => {}
+                    Section::NativeFrames => parsed.native_frames.extend(parse_frame(line)),
+                    Section::JavaFrames => parsed.java_frames.extend(parse_frame(line)),
+                    Section::JavaThreads | Section::OtherThreads => {
+                        let current = line.starts_with("=>");
+                        let line = line.trim_start_matches("=>").trim_start();
+                        let line = match line.split_once(' ') {
+                            Some((address, thread)) if address.starts_with("0x") => thread,
+                            _ => line,
+                        };
+                        parsed.threads.extend(parse_thread(line, current));
+                    }
+                    Section::Libraries => parsed.libraries.extend(parse_library(line)),
+                    Section::VmArguments => {
+                        if let Some(args) = line.strip_prefix("jvm_args:") {
+                            parsed.jvm_args = Some(args.trim());
+                        } else if let Some(command) = line.strip_prefix("java_command:") {
+                            parsed.java_command = Some(command.trim());
+                        }
+                    }
+                    Section::Environment => {
+                        if let Some((key, value)) = line.split_once('=') {
+                            parsed.environment.push((key.trim(), value.trim()));
+                        }
+                    }
+                    Section::Os => parsed.parse_os_line(line),
+                }
+            }
+        }
+
+        Some(parsed)
+    }
+
+    /// Parses the lines of the header, stripped from leading `#`.
+    fn parse_header(&mut self, header: &[&'a str]) -> Option<()> {
+        let mut lines = header.iter().copied();
+
+        let first = lines.next()?;
+        if first.starts_with("A fatal error has been detected") {
+            self.error = lines.next();
+        } else if first.starts_with("There is insufficient memory") {
+            self.error = Some(first);
+        } else {
+            return None;
+        }
+
+        while let Some(line) = lines.next() {
+            if let Some(version) = line.strip_prefix("JRE version:") {
+                self.jre_version = Some(version.trim());
+            } else if let Some(vm) = line.strip_prefix("Java VM:") {
+                self.java_vm = Some(vm.trim());
+            } else if line.starts_with("Problematic frame:") {
+                self.problematic_frame = lines.next();
+            } else if self.jre_version.is_none() {
+                self.error_details.push(line);
+            }
+        }
+
+        Some(())
+    }
+
+    fn parse_os_line(&mut self, line: &'a str) {
+        match line.split_once('=') {
+            Some((key, value)) => {
+                self.os_release.insert(key, value.trim_matches('"'));
+            }
+            None if self.os.is_none() && !line.is_empty() => self.os = Some(line),
+            None => {}
+        }
+    }
+
+    /// Returns the type and value of the exception.
+    fn exception(&self) -> (String, String) {
+        let error = self.error.unwrap_or_default();
+        // The pid and tid are not relevant for the error and only add cardinality.
+        let error = error.split(", pid=").next().unwrap_or_default();
+
+        if let Some(captures) = ERROR_REGEX.captures(error) {
+            let value = match self.problematic_frame {
+                Some(frame) => format!("{error} in {}", collapse_whitespace(frame)),
+                None => error.to_owned(),
+            };
+            return (captures["name"].to_owned(), value);
+        }
+
+        let ty = if error.starts_with("There is insufficient memory") {
+            "OutOfMemoryError"
+        } else if let Some((ty, _)) = error.split_once(" (") {
+            ty
+        } else {
+            "Fatal Error"
+        };
+
+        let value = self.error_details.first().copied().unwrap_or(error);
+        (ty.to_owned(), value.to_owned())
+    }
+
+    fn signal(&self) -> Option<PosixSignal> {
+        if let Some(captures) = self.siginfo.and_then(|s| SIGINFO_REGEX.captures(s)) {
+            return Some(PosixSignal {
+                number: captures["number"].parse().ok().into(),
+                code: captures["code"].parse().ok().into(),
+                name: Annotated::new(captures["name"].to_owned()),
+                code_name: Annotated::new(captures["code_name"].to_owned()),
+            });
+        }
+
+        let captures = ERROR_REGEX.captures(self.error?)?;
+        let name = &captures["name"];
+        if !name.starts_with("SIG") {
+            return None;
+        }
+
+        Some(PosixSignal {
+            number: i64::from_str_radix(captures["code"].trim_start_matches("0x"), 16)
+                .ok()
+                .into(),
+            name: Annotated::new(name.to_owned()),
+            ..Default::default()
+        })
+    }
+
+    fn timestamp(&self) -> Option<Timestamp> {
+        // Only the time of JDK 9+ logs contains a timezone, for example:
+        // `Mon Jan  2 12:00:00 2025 UTC elapsed time: 1.234 seconds (0d 0h 0m 1s)`.
+        let (time, _) = self.time?.split_once(" elapsed time")?;
+        let time = time
+            .strip_suffix(" UTC")
+            .or_else(|| time.strip_suffix(" GMT"))?;
+        let time = NaiveDateTime::parse_from_str(time, "%a %b %e %H:%M:%S %Y").ok()?;
+        Some(Timestamp(time.and_utc()))
+    }
+
+    fn os_context(&self) -> Option<OsContext> {
+        let mut os = OsContext::default();
+
+        if let Some(uname) = self.uname {
+            let mut parts = uname.split_whitespace();
+            os.name = parts.next().map(str::to_owned).into();
+            os.kernel_version = parts.next().map(str::to_owned).into();
+        }
+
+        let release = |keys: &[&str]| {
+            keys.iter()
+                .find_map(|key| self.os_release.get(key))
+                .map(|value| (*value).to_owned())
+        };
+        os.distribution_name = release(&["ID", "DISTRIB_ID"])
+            .map(|name| name.to_lowercase())
+            .into();
+        os.distribution_version = release(&["VERSION_ID", "DISTRIB_RELEASE"]).into();
+        os.distribution_pretty_name = release(&["PRETTY_NAME", "DISTRIB_DESCRIPTION"]).into();
+
+        if os.name.value().is_none() {
+            os.raw_description = self.os.map(str::to_owned).into();
+        }
+
+        (os != OsContext::default()).then_some(os)
+    }
+
+    fn runtime_context(&self) -> Option<RuntimeContext> {
+        let jre_version = self.jre_version?;
+        let mut runtime = RuntimeContext {
+            raw_description: Annotated::new(jre_version.to_owned()),
+            ..Default::default()
+        };
+
+        if let Some(captures) = JRE_VERSION_REGEX.captures(jre_version) {
+            let name = captures["name"].trim();
+            runtime.name = Annotated::new(if name.is_empty() { "Java" } else { name }.to_owned());
+            runtime.version = Annotated::new(captures["version"].to_owned());
+            runtime.build = captures
+                .name("build")
+                .map(|build| build.as_str())
+                .filter(|build| !build.is_empty())
+                .map(|build| LenientString(build.to_owned()))
+                .into();
+        }
+
+        if let Some(vm) = self.java_vm {
+            runtime.other.insert(
+                "vm".to_owned(),
+                Annotated::new(Value::String(vm.to_owned())),
+            );
+        }
+
+        Some(runtime)
+    }
+
+    /// Returns VM arguments as a structured object, splitting system properties into key-value
+    /// pairs so that data scrubbing rules match on their names.
+    fn vm_arguments(&self) -> Option<Object<Value>> {
+        let mut flags = Vec::new();
+        let mut properties = Object::new();
+        for arg in self.jvm_args.into_iter().flat_map(str::split_whitespace) {
+            match arg.strip_prefix("-D").and_then(|p| p.split_once('=')) {
+                Some((key, value)) => {
+                    properties.insert(key.to_owned(), Annotated::new(value.to_owned().into()));
+                }
+                None => flags.push(Annotated::new(Value::String(arg.to_owned()))),
+            }
+        }
+
+        let mut vm_arguments = Object::new();
+        if !flags.is_empty() {
+            vm_arguments.insert("flags".to_owned(), Annotated::new(Value::Array(flags)));
+        }
+        if !properties.is_empty() {
+            vm_arguments.insert(
+                "properties".to_owned(),
+                Annotated::new(Value::Object(properties)),
+            );
+        }
+        if let Some(command) = self.java_command {
+            vm_arguments.insert(
+                "java_command".to_owned(),
+                Annotated::new(Value::String(command.to_owned())),
+            );
+        }
+
+        (!vm_arguments.is_empty()).then_some(vm_arguments)
+    }
+
+    fn debug_images(&self) -> Vec<(&'a str, DebugImage)> {
+        // Merge all mappings of a library into a single image.
+        let mut merged: Vec<HsErrLibrary<'a>> = Vec::new();
+        for library in &self.libraries {
+            match merged.iter_mut().find(|l| l.path == library.path) {
+                Some(existing) => {
+                    existing.start = existing.start.min(library.start);
+                    existing.end = existing.end.max(library.end);
+                }
+                None => merged.push(HsErrLibrary { ..*library }),
+            }
+        }
+
+        merged
+            .into_iter()
+            .map(|library| {
+                let image = Box::new(NativeDebugImage {
+                    code_file: Annotated::new(library.path.into()),
+                    // Fatal error logs do not contain build ids.
+                    debug_id: Annotated::new(DebugId(Default::default())),
+                    image_addr: Annotated::new(Addr(library.start)),
+                    image_size: library
+                        .end
+                        .and_then(|end| end.checked_sub(library.start))
+                        .into(),
+                    ..Default::default()
+                });
+
+                let image = match library.ty {
+                    ImageType::Elf => DebugImage::Elf(image),
+                    ImageType::MachO => DebugImage::MachO(image),
+                    ImageType::Pe => DebugImage::Pe(image),
+                };
+                (library.path, image)
+            })
+            .collect()
+    }
+}
+
+/// Parses a thread description, returning `None` for unrecognized lines.
+fn parse_thread(line: &str, current: bool) -> Option<HsErrThread<'_>> {
+    let captures = THREAD_REGEX.captures(line)?;
+    Some(HsErrThread {
+        id: captures["id"].parse().ok(),
+        name: captures.name("name").map(|m| m.as_str()),
+        state: captures.name("state").map(|m| m.as_str()),
+        current,
+    })
+}
+
+/// Parses a library from the `Dynamic libraries` section.
+///
+/// Supports the Linux format of `/proc/self/maps`, where only mappings of files are considered, as
+/// well as the macOS and Windows formats starting with the load address.
+fn parse_library(line: &str) -> Option<HsErrLibrary<'_>> {
+    if let Some(line) = line.strip_prefix("0x") {
+        let (start, rest) = line.split_once(char::is_whitespace)?;
+        let start = u64::from_str_radix(start, 16).ok()?;
+        let rest = rest.trim_start();
+
+        return match rest.strip_prefix("- 0x") {
+            Some(rest) => {
+                let (end, path) = rest.split_once(char::is_whitespace)?;
+                let end = u64::from_str_radix(end, 16)
+                    .ok()
+                    .filter(|&end| end >= start)?;
+                Some(HsErrLibrary {
+                    path: path.trim(),
+                    start,
+                    end: Some(end),
+                    ty: ImageType::Pe,
+                })
+            }
+            None => Some(HsErrLibrary {
+                path: rest,
+                start,
+                end: None,
+                ty: ImageType::MachO,
+            }),
+        };
+    }
+
+    // 7f0c3d000000-7f0c3d021000 r-xp 00000000 08:01 1234   /usr/lib/libc.so.6
+    let mut parts = line.splitn(6, char::is_whitespace);
+    let (start, end) = parts.next()?.split_once('-')?;
+    // Skip permissions, offset, device and inode. All mappings of a file are considered, since
+    // non-executable mappings determine the base address of the library.
+    let path = parts.nth(4)?.trim();
+    if !path.starts_with('/') || path.starts_with("/dev/") {
+        return None;
+    }
+
+    let start = u64::from_str_radix(start, 16).ok()?;
+    let end = u64::from_str_radix(end, 16)
+        .ok()
+        .filter(|&end| end >= start)?;
+
+    Some(HsErrLibrary {
+        path,
+        start,
+        end: Some(end),
+        ty: ImageType::Elf,
+    })
+}
+
+/// Parses a stack frame, returning `None` for unrecognized lines.
+///
+/// Frames are prefixed with their type: `C` for native code, `V` and `v` for VM code, `j` for
+/// interpreted, `J` for compiled and `A` for AOT compiled Java code.
+fn parse_frame(line: &str) -> Option<HsErrFrame<'_>> {
+    let (kind, rest) = line.split_at_checked(1)?;
+    let rest = rest.trim_start();
+
+    match kind {
+        "C" | "V" | "v" => {
+            if let Some(rest) = rest.strip_prefix('[') {
+                let (library, function) = rest.split_once(']')?;
+                let library = library
+                    .rsplit_once("+0x")
+                    .and_then(|(name, offset)| Some((name, u64::from_str_radix(offset, 16).ok()?)));
+                let function = function.trim();
+                let function = function.rsplit_once("+0x").map_or(function, |(f, _)| f);
+                Some(HsErrFrame::Native {
+                    library,
+                    address: None,
+                    function: (!function.is_empty()).then_some(function),
+                })
+            } else if let Some(address) = rest.strip_prefix("0x") {
+                let address = address.split_whitespace().next()?;
+                Some(HsErrFrame::Native {
+                    library: None,
+                    address: Some(u64::from_str_radix(address, 16).ok()?),
+                    function: None,
+                })
+            } else if !rest.is_empty() && !rest.starts_with("...") {
+                Some(HsErrFrame::Native {
+                    library: None,
+                    address: None,
+                    function: Some(rest),
+                })
+            } else {
+                None
+            }
+        }
+        "j" | "J" | "A" => {
+            // j  com.example.Crash.main([Ljava/lang/String;)V+5 app@1.0
+            // J 1234 c2 java.lang.String.hashCode()I java.base@17.0.2 (60 bytes) @ 0x... [...]
+            let mut tokens = rest.split_whitespace();
+            let descriptor = tokens.find(|token| token.contains('('))?;
+            let module = tokens.next().filter(|token| !token.starts_with('('));
+
+            let (name, _) = descriptor.split_once('(')?;
+            let (class, method) = match name.rsplit_once('.') {
+                Some((class, method)) => (Some(class), method),
+                None => (None, name),
+            };
+
+            Some(HsErrFrame::Java {
+                class,
+                method,
+                module,
+            })
+        }
+        _ => None,
+    }
+}
+
+fn collapse_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn build_frame(frame: &HsErrFrame<'_>, images: &[(&str, DebugImage)], offset: usize) -> Frame {
+    match *frame {
+        HsErrFrame::Native {
+            library,
+            address,
+            function,
+        } => {
+            let mut frame = Frame {
+                function: function.map(str::to_owned).into(),
+                instruction_addr: address.map(Addr).into(),
+                platform: Annotated::new("native".to_owned()),
+                ..Default::default()
+            };
+
+            if let Some((library, library_offset)) = library {
+                frame.package = Annotated::new(library.to_owned());
+
+                let index = images
+                    .iter()
+                    .position(|(path, _)| path.rsplit(['/', '\\']).next() == Some(library));
+                if let Some(index) = index {
+                    frame.instruction_addr = Annotated::new(Addr(library_offset));
+                    frame.addr_mode = Annotated::new(format!("rel:{}", offset + index));
+                }
+            }
+
+            frame
+        }
+        HsErrFrame::Java {
+            class,
+            method,
+            module,
+        } => Frame {
+            function: Annotated::new(method.to_owned()),
+            module: class.map(str::to_owned).into(),
+            package: module.map(str::to_owned).into(),
+            platform: Annotated::new("java".to_owned()),
+            ..Default::default()
+        },
+    }
+}
+
+/// Writes the contents of a fatal error log into the event.
+fn write_event(event: &mut Event, log: &HsErrLog<'_>) {
+    event.platform = Annotated::new("native".to_owned());
+    event.level.get_or_insert_with(|| Level::Fatal);
+    if event.timestamp.value().is_none() {
+        event.timestamp = log.timestamp().into();
+    }
+
+    let debug_meta = event.debug_meta.get_or_insert_with(DebugMeta::default);
+    let debug_images = debug_meta.images.get_or_insert_with(Vec::new);
+    let offset = debug_images.len();
+    let images = log.debug_images();
+
+    let frames = match log.native_frames.is_empty() {
+        true => &log.java_frames,
+        false => &log.native_frames,
+    };
+    let frames = frames
+        .iter()
+        .rev()
+        .map(|frame| Annotated::new(build_frame(frame, &images, offset)))
+        .collect::<Vec<_>>();
+
+    debug_images.extend(images.into_iter().map(|(_, image)| Annotated::new(image)));
+
+    let current_thread_id = log.current_thread.as_ref().and_then(|thread| thread.id);
+    let (ty, value) = log.exception();
+    let exception = Exception {
+        ty: Annotated::new(ty),
+        value: Annotated::new(JsonLenientString(value)),
+        stacktrace: match frames.is_empty() {
+            true => Annotated::empty(),
+            false => Annotated::new(Stacktrace(RawStacktrace {
+                frames: Annotated::new(frames),
+                ..Default::default()
+            })),
+        },
+        thread_id: current_thread_id.map(ThreadId::Int).into(),
+        mechanism: Annotated::new(Mechanism {
+            ty: Annotated::new("hs_err".to_owned()),
+            handled: Annotated::new(false),
+            meta: log
+                .signal()
+                .map(|signal| MechanismMeta {
+                    signal: Annotated::new(signal),
+                    ..Default::default()
+                })
+                .into(),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    event.exceptions = Annotated::new(Values::new(vec![Annotated::new(exception)]));
+
+    let mut threads = log
+        .threads
+        .iter()
+        .filter(|thread| thread.id.is_some())
+        .collect::<Vec<_>>();
+    if let Some(current) = &log.current_thread
+        && !threads.iter().any(|thread| thread.id == current.id)
+    {
+        threads.insert(0, current);
+    }
+    let threads = threads
+        .into_iter()
+        .map(|thread| {
+            let current = thread.current || (thread.id.is_some() && thread.id == current_thread_id);
+            Annotated::new(Thread {
+                id: thread.id.map(ThreadId::Int).into(),
+                name: thread.name.map(str::to_owned).into(),
+                state: thread.state.map(str::to_owned).into(),
+                crashed: Annotated::new(current),
+                current: Annotated::new(current),
+                main: Annotated::new(thread.name == Some("main")),
+                ..Default::default()
+            })
+        })
+        .collect::<Vec<_>>();
+    if !threads.is_empty() {
+        event.threads = Annotated::new(Values::new(threads));
+    }
+
+    let contexts = event.contexts.get_or_insert_with(Contexts::new);
+    if let Some(os) = log.os_context()
+        && !contexts.contains::<OsContext>()
+    {
+        contexts.add(os);
+    }
+    if let Some(runtime) = log.runtime_context()
+        && !contexts.contains::<RuntimeContext>()
+    {
+        contexts.add(runtime);
+    }
+
+    // VM arguments and environment variables are stored in `extra`, which is subject to data
+    // scrubbing.
+    let extra = event.extra.get_or_insert_with(Object::new);
+    if let Some(vm_arguments) = log.vm_arguments() {
+        extra.insert(
+            "vm_arguments".to_owned(),
+            Annotated::new(ExtraValue(Value::Object(vm_arguments))),
+        );
+    }
+    if !log.environment.is_empty() {
+        let environment = log
+            .environment
+            .iter()
+            .map(|(key, value)| {
+                let value = Annotated::new(Value::String((*value).to_owned()));
+                ((*key).to_owned(), value)
+            })
+            .collect();
+        extra.insert(
+            "environment".to_owned(),
+            Annotated::new(ExtraValue(Value::Object(environment))),
+        );
+    }
+}
+
+/// Redacts the command line and the values of environment variables in the log.
+///
+/// This information is moved to the event where it is subject to data scrubbing, so it must not
+/// be retained in the attachment.
+fn redact(log: &str) -> String {
+    let mut redacted = String::with_capacity(log.len());
+    let mut in_environment = false;
+
+    for line in log.split_inclusive('\n') {
+        let content = line.trim_end();
+        let line_ending = &line[content.len()..];
+
+        if content.is_empty() {
+            in_environment = false;
+        } else if content.starts_with("Environment Variables:") {
+            in_environment = true;
+        } else if in_environment && let Some((key, _)) = content.split_once('=') {
+            redacted.push_str(key);
+            redacted.push('=');
+            redacted.push_str(REDACTED);
+            redacted.push_str(line_ending);
+            continue;
+        } else if let Some(prefix) = COMMAND_LINE_PREFIXES
+            .iter()
+            .find(|prefix| content.starts_with(*prefix))
+        {
+            redacted.push_str(prefix);
+            redacted.push(' ');
+            redacted.push_str(REDACTED);
+            redacted.push_str(line_ending);
+            continue;
+        }
+
+        redacted.push_str(line);
+    }
+
+    redacted
+}
+
+/// Converts a HotSpot fatal error log into a native crash event.
+///
+/// The error or signal of the crash becomes the exception with the stack trace of the crashing
+/// thread, including native and Java frames. Threads, loaded libraries, OS and runtime information
+/// are added to the event. VM arguments and environment variables are stored in `extra`, where
+/// they are subject to data scrubbing, and redacted from the log.
+///
+/// The log is converted into a regular attachment, since it no longer needs processing.
+pub fn process_hs_err_log(event: &mut Event, item: &mut Item) -> Result<(), ProcessingError> {
+    let payload = item.payload();
+    let log = String::from_utf8_lossy(&payload);
+    let parsed = HsErrLog::parse(&log).ok_or(ProcessingError::InvalidHsErrLog)?;
+
+    write_event(event, &parsed);
+
+    let redacted = redact(&log);
+    item.set_attachment_type(AttachmentType::Attachment);
+    item.set_payload(ContentType::Text, redacted);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use relay_protocol::SerializableAnnotated;
+
+    use super::*;
+
+    const LINUX_LOG: &str = r#"#
+# A fatal error has been detected by the Java Runtime Environment:
+#
+#  SIGSEGV (0xb) at pc=0x00007f8a1c29c1d4, pid=12345, tid=12346
+#
+# JRE version: OpenJDK Runtime Environment (17.0.2+8) (build 17.0.2+8-86)
+# Java VM: OpenJDK 64-Bit Server VM (17.0.2+8-86, mixed mode, sharing, tiered, compressed oops, compressed class ptrs, g1 gc, linux-amd64)
+# Problematic frame:
+# C  [libnative.so+0x1234]  Java_com_example_Native_crash+0x14
+#
+# Core dump will be written. Default location: Core dumps may be processed with "/usr/share/apport/apport %p %s %c %d %P %E" (or dumping to /app/core.12345)
+#
+# If you would like to submit a bug report, please visit:
+#   https://bugreport.java.com/bugreport/crash.jsp
+# The crash happened outside the Java Virtual Machine in native code.
+# See problematic frame for where to report the bug.
+#
+
+---------------  S U M M A R Y ------------
+
+Command Line: -Xmx512m -Dapp.secret=hunter2 com.example.Crash --token abc
+
+Host: Intel(R) Xeon(R) CPU @ 2.20GHz, 4 cores, 15G, Ubuntu 22.04.1 LTS
+Time: Thu Jan  2 12:00:00 2025 UTC elapsed time: 1.234 seconds (0d 0h 0m 1s)
+
+---------------  T H R E A D  ---------------
+
+Current thread (0x00007f8a14012345):  JavaThread "main" [_thread_in_native, id=12346, stack(0x00007f8a1c000000,0x00007f8a1c100000)]
+
+Stack: [0x00007f8a1c000000,0x00007f8a1c100000],  sp=0x00007f8a1c0fe1a0,  free space=1016k
+Native frames: (J=compiled Java code, j=interpreted, Vv=VM code, C=native code)
+C  [libnative.so+0x1234]  Java_com_example_Native_crash+0x14
+j  com.example.Native.crash()V+0 app@1.0
+J 1234 c2 com.example.Crash.run(I)V (60 bytes) @ 0x00007f8a0d4a5b3c [0x00007f8a0d4a5a00+0x000000000000013c]
+v  ~StubRoutines::call_stub
+V  [libjvm.so+0x8a2b1c]  JavaCalls::call_helper(JavaValue*, methodHandle const&, JavaCallArguments*, JavaThread*)+0x2ac
+C  [libjli.so+0x4a1c]  JavaMain+0xd4c
+C  0x00007f8a1c2a0000
+
+siginfo: si_signo: 11 (SIGSEGV), si_code: 1 (SEGV_MAPERR), si_addr: 0x0000000000000000
+
+---------------  P R O C E S S  ---------------
+
+Java Threads: ( => current thread )
+=>0x00007f8a14012345 JavaThread "main" [_thread_in_native, id=12346, stack(0x00007f8a1c000000,0x00007f8a1c100000)]
+  0x00007f8a1412e000 JavaThread "Reference Handler" daemon [_thread_blocked, id=12350, stack(0x00007f8a0c000000,0x00007f8a0c100000)]
+
+Other Threads:
+  0x00007f8a14128000 VMThread "VM Thread" [stack: 0x00007f8a0c200000,0x00007f8a0c300000] [id=12349]
+
+Dynamic libraries:
+55d4c3a00000-55d4c3a01000 r--p 00000000 08:01 1001                       /usr/lib/jvm/java-17/bin/java
+55d4c3a01000-55d4c3a02000 r-xp 00001000 08:01 1001                       /usr/lib/jvm/java-17/bin/java
+7f8a1c200000-7f8a1c228000 r--p 00000000 08:01 2002                       /usr/lib/x86_64-linux-gnu/libc.so.6
+7f8a1c228000-7f8a1c3bd000 r-xp 00028000 08:01 2002                       /usr/lib/x86_64-linux-gnu/libc.so.6
+7f8a1d000000-7f8a1d001000 r-xp 00000000 08:01 3003                       /app/lib/libnative.so
+7f8a1e000000-7f8a1f000000 r-xp 00000000 08:01 4004                       /usr/lib/jvm/java-17/lib/server/libjvm.so
+7f8a20000000-7f8a20021000 rw-p 00000000 00:00 0
+7ffd5c1f0000-7ffd5c211000 rw-p 00000000 00:00 0                          [stack]
+
+VM Arguments:
+jvm_args: -Xmx512m -Dapp.secret=hunter2
+java_command: com.example.Crash --token abc
+java_class_path (initial): /app/app.jar
+Launcher Type: SUN_STANDARD
+
+Environment Variables:
+JAVA_HOME=/usr/lib/jvm/java-17
+DB_PASSWORD=secret
+LANG=C.UTF-8
+
+---------------  S Y S T E M  ---------------
+
+OS:
+DISTRIB_ID=Ubuntu
+DISTRIB_RELEASE=22.04
+DISTRIB_CODENAME=jammy
+DISTRIB_DESCRIPTION="Ubuntu 22.04.1 LTS"
+uname: Linux 5.15.0-56-generic #62-Ubuntu SMP Tue Nov 22 19:54:14 UTC 2022 x86_64
+"#;
+
+    fn event_from_log(log: &str) -> Annotated<Event> {
+        let log = HsErrLog::parse(log).unwrap();
+        let mut event = Event::default();
+        write_event(&mut event, &log);
+        Annotated::new(event)
+    }
+
+    #[test]
+    fn test_linux_log() {
+        let event = event_from_log(LINUX_LOG);
+
+        insta::assert_json_snapshot!(SerializableAnnotated(&event), @r#"
+        {
+          "level": "fatal",
+          "platform": "native",
+          "timestamp": 1735819200.0,
+          "contexts": {
+            "os": {
+              "name": "Linux",
+              "kernel_version": "5.15.0-56-generic",
+              "distribution_name": "ubuntu",
+              "distribution_version": "22.04",
+              "distribution_pretty_name": "Ubuntu 22.04.1 LTS",
+              "type": "os"
+            },
+            "runtime": {
+              "name": "OpenJDK Runtime Environment",
+              "version": "17.0.2+8",
+              "build": "17.0.2+8-86",
+              "raw_description": "OpenJDK Runtime Environment (17.0.2+8) (build 17.0.2+8-86)",
+              "vm": "OpenJDK 64-Bit Server VM (17.0.2+8-86, mixed mode, sharing, tiered, compressed oops, compressed class ptrs, g1 gc, linux-amd64)",
+              "type": "runtime"
+            }
+          },
+          "exception": {
+            "values": [
+              {
+                "type": "SIGSEGV",
+                "value": "SIGSEGV (0xb) at pc=0x00007f8a1c29c1d4 in C [libnative.so+0x1234] Java_com_example_Native_crash+0x14",
+                "stacktrace": {
+                  "frames": [
+                    {
+                      "platform": "native",
+                      "instruction_addr": "0x7f8a1c2a0000"
+                    },
+                    {
+                      "function": "JavaMain",
+                      "package": "libjli.so",
+                      "platform": "native"
+                    },
+                    {
+                      "function": "JavaCalls::call_helper(JavaValue*, methodHandle const&, JavaCallArguments*, JavaThread*)",
+                      "package": "libjvm.so",
+                      "platform": "native",
+                      "instruction_addr": "0x8a2b1c",
+                      "addr_mode": "rel:3"
+                    },
+                    {
+                      "function": "~StubRoutines::call_stub",
+                      "platform": "native"
+                    },
+                    {
+                      "function": "run",
+                      "module": "com.example.Crash",
+                      "platform": "java"
+                    },
+                    {
+                      "function": "crash",
+                      "module": "com.example.Native",
+                      "package": "app@1.0",
+                      "platform": "java"
+                    },
+                    {
+                      "function": "Java_com_example_Native_crash",
+                      "package": "libnative.so",
+                      "platform": "native",
+                      "instruction_addr": "0x1234",
+                      "addr_mode": "rel:2"
+                    }
+                  ]
+                },
+                "thread_id": 12346,
+                "mechanism": {
+                  "type": "hs_err",
+                  "handled": false,
+                  "meta": {
+                    "signal": {
+                      "number": 11,
+                      "code": 1,
+                      "name": "SIGSEGV",
+                      "code_name": "SEGV_MAPERR"
+                    }
+                  }
+                }
+              }
+            ]
+          },
+          "threads": {
+            "values": [
+              {
+                "id": 12346,
+                "name": "main",
+                "crashed": true,
+                "current": true,
+                "main": true,
+                "state": "_thread_in_native"
+              },
+              {
+                "id": 12350,
+                "name": "Reference Handler",
+                "crashed": false,
+                "current": false,
+                "main": false,
+                "state": "_thread_blocked"
+              },
+              {
+                "id": 12349,
+                "name": "VM Thread",
+                "crashed": false,
+                "current": false,
+                "main": false
+              }
+            ]
+          },
+          "extra": {
+            "environment": {
+              "DB_PASSWORD": "secret",
+              "JAVA_HOME": "/usr/lib/jvm/java-17",
+              "LANG": "C.UTF-8"
+            },
+            "vm_arguments": {
+              "flags": [
+                "-Xmx512m"
+              ],
+              "java_command": "com.example.Crash --token abc",
+              "properties": {
+                "app.secret": "hunter2"
+              }
+            }
+          },
+          "debug_meta": {
+            "images": [
+              {
+                "code_file": "/usr/lib/jvm/java-17/bin/java",
+                "debug_id": "00000000-0000-0000-0000-000000000000",
+                "image_addr": "0x55d4c3a00000",
+                "image_size": 8192,
+                "type": "elf"
+              },
+              {
+                "code_file": "/usr/lib/x86_64-linux-gnu/libc.so.6",
+                "debug_id": "00000000-0000-0000-0000-000000000000",
+                "image_addr": "0x7f8a1c200000",
+                "image_size": 1822720,
+                "type": "elf"
+              },
+              {
+                "code_file": "/app/lib/libnative.so",
+                "debug_id": "00000000-0000-0000-0000-000000000000",
+                "image_addr": "0x7f8a1d000000",
+                "image_size": 4096,
+                "type": "elf"
+              },
+              {
+                "code_file": "/usr/lib/jvm/java-17/lib/server/libjvm.so",
+                "debug_id": "00000000-0000-0000-0000-000000000000",
+                "image_addr": "0x7f8a1e000000",
+                "image_size": 16777216,
+                "type": "elf"
+              }
+            ]
+          }
+        }
+        "#);
+    }
+
+    #[test]
+    fn test_internal_error() {
+        let log = r#"#
+# A fatal error has been detected by the Java Runtime Environment:
+#
+#  Internal Error (g1ConcurrentMark.cpp:1234), pid=1, tid=2
+#  guarantee(_finger <= _region_limit) failed: invalid finger
+#
+# JRE version:  (21.0.1+12) (build )
+# Java VM: OpenJDK 64-Bit Server VM (21.0.1+12-29, mixed mode, tiered, g1 gc, bsd-aarch64)
+# Problematic frame:
+# V  [libjvm.dylib+0x5a4b3c]  G1CMTask::do_marking_step(double, bool, bool)+0x1c
+#
+
+Current thread (0x000000013a80a000):  ConcurrentGCThread "G1 Conc#0" [stack: 0x000000016d4b0000,0x000000016d6b3000] [id=21763]
+
+Native frames: (J=compiled Java code, j=interpreted, Vv=VM code, C=native code)
+V  [libjvm.dylib+0x5a4b3c]  G1CMTask::do_marking_step(double, bool, bool)+0x1c
+C  [libsystem_pthread.dylib+0x6f94]  _pthread_start+0x88
+
+Dynamic libraries:
+0x0000000104b8c000 	/Library/Java/JavaVirtualMachines/jdk-21.jdk/Contents/Home/lib/server/libjvm.dylib
+0x000000019a6b4000 	/usr/lib/system/libsystem_pthread.dylib
+
+OS: macOS 14.2.1 (23C71)
+"#;
+
+        let event = event_from_log(log);
+        let exception = &event.value().unwrap().exceptions.value().unwrap().values;
+        let exception = exception.value().unwrap()[0].value().unwrap();
+        assert_eq!(exception.ty.as_str(), Some("Internal Error"));
+        assert_eq!(
+            exception.value.as_str(),
+            Some("guarantee(_finger <= _region_limit) failed: invalid finger")
+        );
+
+        let frames = &exception.stacktrace.value().unwrap().frames;
+        let frame = frames.value().unwrap().last().unwrap().value().unwrap();
+        assert_eq!(
+            frame.function.as_str(),
+            Some("G1CMTask::do_marking_step(double, bool, bool)")
+        );
+        assert_eq!(frame.addr_mode.as_str(), Some("rel:0"));
+
+        let contexts = event.value().unwrap().contexts.value().unwrap();
+        let runtime = contexts.get::<RuntimeContext>().unwrap();
+        assert_eq!(runtime.name.as_str(), Some("Java"));
+        assert_eq!(runtime.version.as_str(), Some("21.0.1+12"));
+        let os = contexts.get::<OsContext>().unwrap();
+        assert_eq!(os.raw_description.as_str(), Some("macOS 14.2.1 (23C71)"));
+
+        let threads = event.value().unwrap().threads.value().unwrap();
+        let thread = threads.values.value().unwrap()[0].value().unwrap();
+        assert_eq!(thread.name.as_str(), Some("G1 Conc#0"));
+        assert_eq!(thread.crashed.value(), Some(&true));
+    }
+
+    #[test]
+    fn test_out_of_memory() {
+        let log = r#"#
+# There is insufficient memory for the Java Runtime Environment to continue.
+# Native memory allocation (mmap) failed to map 2555904 bytes for committing reserved memory.
+# Possible reasons:
+#   The system is out of physical RAM or swap space
+#
+#  Out of Memory Error (os_linux.cpp:2798), pid=4455, tid=4456
+#
+# JRE version: OpenJDK Runtime Environment (8.0_352-b08) (build 1.8.0_352-b08)
+# Java VM: OpenJDK 64-Bit Server VM (25.352-b08 mixed mode linux-amd64 compressed oops)
+"#;
+
+        let event = event_from_log(log);
+        let exception = &event.value().unwrap().exceptions.value().unwrap().values;
+        let exception = exception.value().unwrap()[0].value().unwrap();
+        assert_eq!(exception.ty.as_str(), Some("OutOfMemoryError"));
+        assert_eq!(
+            exception.value.as_str(),
+            Some(
+                "Native memory allocation (mmap) failed to map 2555904 bytes for committing reserved memory."
+            )
+        );
+        assert!(exception.stacktrace.value().is_none());
+    }
+
+    #[test]
+    fn test_windows_libraries() {
+        let library = parse_library(
+            r"0x00007ff6e3a20000 - 0x00007ff6e3a2a000 	C:\Program Files\Java\jdk-17\bin\java.exe",
+        );
+        assert_eq!(
+            library,
+            Some(HsErrLibrary {
+                path: r"C:\Program Files\Java\jdk-17\bin\java.exe",
+                start: 0x7ff6e3a20000,
+                end: Some(0x7ff6e3a2a000),
+                ty: ImageType::Pe,
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_library_ranges() {
+        assert_eq!(
+            parse_library(
+                "7f0c3d021000-7f0c3d000000 r-xp 00000000 08:01 1234   /usr/lib/libc.so.6"
+            ),
+            None
+        );
+        assert_eq!(
+            parse_library(r"0x00007ff6e3a2a000 - 0x00007ff6e3a20000 	C:\Windows\java.exe"),
+            None
+        );
+
+        // A Mach-O image without an end merged with a mapping below its start.
+        let log = HsErrLog {
+            libraries: vec![
+                HsErrLibrary {
+                    path: "/usr/lib/libjvm.dylib",
+                    start: 0x2000,
+                    end: None,
+                    ty: ImageType::MachO,
+                },
+                HsErrLibrary {
+                    path: "/usr/lib/libjvm.dylib",
+                    start: 0x3000,
+                    end: Some(0x1000),
+                    ty: ImageType::MachO,
+                },
+            ],
+            ..Default::default()
+        };
+        let images = log.debug_images();
+        let DebugImage::MachO(image) = &images[0].1 else {
+            panic!("expected a Mach-O image");
+        };
+        assert_eq!(image.image_size.value(), None);
+    }
+
+    #[test]
+    fn test_not_an_hs_err_log() {
+        assert!(HsErrLog::parse("Exception in thread \"main\" java.lang.Error").is_none());
+        assert!(HsErrLog::parse("").is_none());
+    }
+
+    #[test]
+    fn test_redact() {
+        let redacted = redact(LINUX_LOG);
+
+        assert!(!redacted.contains("hunter2"));
+        assert!(!redacted.contains("--token"));
+        assert!(!redacted.contains("DB_PASSWORD=secret"));
+        assert!(redacted.contains("DB_PASSWORD=[Filtered]\n"));
+        assert!(redacted.contains("java_command: [Filtered]\n"));
+        assert!(redacted.contains("java_class_path (initial): /app/app.jar\n"));
+        assert_eq!(
+            HsErrLog::parse(&redacted).unwrap().error,
+            HsErrLog::parse(LINUX_LOG).unwrap().error
+        );
+    }
+}
@@ -20,6 +20,8 @@ mod thread_pool;
 pub mod tus;
 
 mod forward;
+#[cfg(feature = "processing")]
+mod hs_err;
 mod memory;
 #[cfg(feature = "processing")]
 mod native;
@@ -31,6 +33,8 @@ pub use self::debug::*;
 pub use self::dynamic_sampling::*;
 pub use self::error::*;
 pub use self::forward::*;
+#[cfg(feature = "processing")]
+pub use self::hs_err::*;
 pub use self::memory::*;
 pub use self::multipart::*;
 #[cfg(feature = "processing")]
@@ -1,4 +1,4 @@
-//! Structs and functions needed to ingest OpenTelemetry spans.
+//! Structs and functions needed to ingest OpenTelemetry and Zipkin spans.
 
 #![warn(missing_docs)]
 #![doc(
@@ -11,6 +11,10 @@ pub use crate::name::name_for_attributes;
 pub use crate::op::derive_op_for_v2_span;
 pub use crate::otel_to_sentry_v2::otel_to_sentry_span as otel_to_sentry_span_v2;
 pub use crate::v1_to_v2::span_v1_to_span_v2;
+pub use crate::zipkin_to_sentry_v2::{
+    InvalidZipkinSpan, ZipkinAnnotation, ZipkinEndpoint, ZipkinSpan, ZipkinSpanKind,
+    proto as zipkin_proto, zipkin_to_sentry_span as zipkin_to_sentry_span_v2,
+};
 
 pub use opentelemetry_proto::tonic::trace::v1 as otel_trace;
 
@@ -19,3 +23,4 @@ mod name;
 mod op;
 mod otel_to_sentry_v2;
 mod v1_to_v2;
+mod zipkin_to_sentry_v2;
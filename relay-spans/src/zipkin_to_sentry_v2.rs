@@ -0,0 +1,774 @@
+//! Transforms Zipkin v2 spans to Sentry V2 spans.
+//!
+//! See the [Zipkin v2 API](https://zipkin.io/zipkin-api/#/default/post_spans) for the JSON
+//! model and [`zipkin.proto`](https://github.com/openzipkin/zipkin-api/blob/master/zipkin.proto)
+//! for the proto3 model.
+
+use std::collections::BTreeMap;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+use chrono::{TimeDelta, TimeZone, Utc};
+use relay_conventions::attributes::{
+    SENTRY__IS_REMOTE, SENTRY__KIND, SENTRY__ORIGIN, SENTRY__SEGMENT__ID, SENTRY__SEGMENT__NAME,
+    SENTRY__STATUS__MESSAGE,
+};
+use relay_event_schema::protocol::{
+    Attribute, AttributeType, Attributes, SpanId, SpanKind, SpanV2 as SentrySpanV2, SpanV2Status,
+    Timestamp, TraceId,
+};
+use relay_protocol::{Annotated, Value};
+use serde::Deserialize;
+
+/// A span in the Zipkin v2 model.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZipkinSpan {
+    /// Randomly generated trace id, encoded as 16 or 32 lowercase hex characters.
+    pub trace_id: String,
+    /// The parent span id, absent for root spans.
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    /// Unique 64-bit identifier of the span, encoded as 16 lowercase hex characters.
+    pub id: String,
+    /// The span kind, absent for local spans.
+    #[serde(default)]
+    pub kind: Option<ZipkinSpanKind>,
+    /// The logical operation this span represents.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Epoch microseconds of the start of this span.
+    #[serde(default)]
+    pub timestamp: Option<u64>,
+    /// Duration of the span in microseconds.
+    #[serde(default)]
+    pub duration: Option<u64>,
+    /// The host that recorded this span.
+    #[serde(default)]
+    pub local_endpoint: Option<ZipkinEndpoint>,
+    /// The other side of the connection for RPC or messaging spans.
+    #[serde(default)]
+    pub remote_endpoint: Option<ZipkinEndpoint>,
+    /// Events explaining latency with a timestamp.
+    #[serde(default)]
+    pub annotations: Vec<ZipkinAnnotation>,
+    /// Tags give the span context for search, viewing and analysis.
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+    /// Set to `true` if the span was forcibly sampled.
+    #[serde(default)]
+    pub debug: Option<bool>,
+    /// Set to `true` if the server side of a span shares its id with the client side.
+    #[serde(default)]
+    pub shared: Option<bool>,
+}
+
+/// The kind of a [`ZipkinSpan`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ZipkinSpanKind {
+    /// The client side of an RPC.
+    Client,
+    /// The server side of an RPC.
+    Server,
+    /// The producer of a message.
+    Producer,
+    /// The consumer of a message.
+    Consumer,
+}
+
+/// The network context of a node in the service graph.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZipkinEndpoint {
+    /// Lower-case label of this node in the service graph.
+    #[serde(default)]
+    pub service_name: Option<String>,
+    /// The text representation of the IPv4 address.
+    #[serde(default)]
+    pub ipv4: Option<String>,
+    /// The text representation of the IPv6 address.
+    #[serde(default)]
+    pub ipv6: Option<String>,
+    /// The port of the endpoint.
+    #[serde(default)]
+    pub port: Option<u16>,
+}
+
+/// An event explaining latency with a timestamp.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+pub struct ZipkinAnnotation {
+    /// Epoch microseconds of this event.
+    pub timestamp: u64,
+    /// Usually a short tag indicating an event, like `"error"`.
+    pub value: String,
+}
+
+/// Zipkin proto3 messages, as defined in `zipkin.proto`.
+pub mod proto {
+    use super::*;
+
+    /// A list of spans with possibly different trace ids, in no particular order.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ListOfSpans {
+        /// The contained spans.
+        #[prost(message, repeated, tag = "1")]
+        pub spans: Vec<Span>,
+    }
+
+    /// A span in the Zipkin proto3 model.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Span {
+        /// Randomly generated trace id, 8 or 16 bytes.
+        #[prost(bytes = "vec", tag = "1")]
+        pub trace_id: Vec<u8>,
+        /// The parent span id, empty for root spans.
+        #[prost(bytes = "vec", tag = "2")]
+        pub parent_id: Vec<u8>,
+        /// Unique 8 byte identifier of the span.
+        #[prost(bytes = "vec", tag = "3")]
+        pub id: Vec<u8>,
+        /// The span kind.
+        #[prost(enumeration = "Kind", tag = "4")]
+        pub kind: i32,
+        /// The logical operation this span represents.
+        #[prost(string, tag = "5")]
+        pub name: String,
+        /// Epoch microseconds of the start of this span.
+        #[prost(fixed64, tag = "6")]
+        pub timestamp: u64,
+        /// Duration of the span in microseconds.
+        #[prost(uint64, tag = "7")]
+        pub duration: u64,
+        /// The host that recorded this span.
+        #[prost(message, optional, tag = "8")]
+        pub local_endpoint: Option<Endpoint>,
+        /// The other side of the connection for RPC or messaging spans.
+        #[prost(message, optional, tag = "9")]
+        pub remote_endpoint: Option<Endpoint>,
+        /// Events explaining latency with a timestamp.
+        #[prost(message, repeated, tag = "10")]
+        pub annotations: Vec<Annotation>,
+        /// Tags give the span context for search, viewing and analysis.
+        #[prost(btree_map = "string, string", tag = "11")]
+        pub tags: BTreeMap<String, String>,
+        /// Set to `true` if the span was forcibly sampled.
+        #[prost(bool, tag = "12")]
+        pub debug: bool,
+        /// Set to `true` if the server side of a span shares its id with the client side.
+        #[prost(bool, tag = "13")]
+        pub shared: bool,
+    }
+
+    /// The kind of a [`Span`].
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum Kind {
+        /// Local span, without a remote side.
+        Unspecified = 0,
+        /// The client side of an RPC.
+        Client = 1,
+        /// The server side of an RPC.
+        Server = 2,
+        /// The producer of a message.
+        Producer = 3,
+        /// The consumer of a message.
+        Consumer = 4,
+    }
+
+    /// The network context of a node in the service graph.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Endpoint {
+        /// Lower-case label of this node in the service graph.
+        #[prost(string, tag = "1")]
+        pub service_name: String,
+        /// 4 byte representation of the IPv4 address.
+        #[prost(bytes = "vec", tag = "2")]
+        pub ipv4: Vec<u8>,
+        /// 16 byte representation of the IPv6 address.
+        #[prost(bytes = "vec", tag = "3")]
+        pub ipv6: Vec<u8>,
+        /// The port of the endpoint.
+        #[prost(int32, tag = "4")]
+        pub port: i32,
+    }
+
+    /// An event explaining latency with a timestamp.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Annotation {
+        /// Epoch microseconds of this event.
+        #[prost(fixed64, tag = "1")]
+        pub timestamp: u64,
+        /// Usually a short tag indicating an event, like `"error"`.
+        #[prost(string, tag = "2")]
+        pub value: String,
+    }
+
+    fn non_empty(s: String) -> Option<String> {
+        (!s.is_empty()).then_some(s)
+    }
+
+    fn non_zero(value: u64) -> Option<u64> {
+        (value != 0).then_some(value)
+    }
+
+    impl From<Span> for ZipkinSpan {
+        fn from(span: Span) -> Self {
+            let kind = match Kind::try_from(span.kind) {
+                Ok(Kind::Client) => Some(ZipkinSpanKind::Client),
+                Ok(Kind::Server) => Some(ZipkinSpanKind::Server),
+                Ok(Kind::Producer) => Some(ZipkinSpanKind::Producer),
+                Ok(Kind::Consumer) => Some(ZipkinSpanKind::Consumer),
+                Ok(Kind::Unspecified) | Err(_) => None,
+            };
+
+            Self {
+                trace_id: hex::encode(span.trace_id),
+                parent_id: non_empty(hex::encode(span.parent_id)),
+                id: hex::encode(span.id),
+                kind,
+                name: non_empty(span.name),
+                timestamp: non_zero(span.timestamp),
+                duration: non_zero(span.duration),
+                local_endpoint: span.local_endpoint.map(Into::into),
+                remote_endpoint: span.remote_endpoint.map(Into::into),
+                annotations: span.annotations.into_iter().map(Into::into).collect(),
+                tags: span.tags,
+                debug: span.debug.then_some(true),
+                shared: span.shared.then_some(true),
+            }
+        }
+    }
+
+    impl From<Endpoint> for ZipkinEndpoint {
+        fn from(endpoint: Endpoint) -> Self {
+            let ipv4 = <[u8; 4]>::try_from(endpoint.ipv4.as_slice())
+                .ok()
+                .map(|ip| Ipv4Addr::from(ip).to_string());
+            let ipv6 = <[u8; 16]>::try_from(endpoint.ipv6.as_slice())
+                .ok()
+                .map(|ip| Ipv6Addr::from(ip).to_string());
+
+            Self {
+                service_name: non_empty(endpoint.service_name),
+                ipv4,
+                ipv6,
+                port: u16::try_from(endpoint.port).ok().filter(|&port| port != 0),
+            }
+        }
+    }
+
+    impl From<Annotation> for ZipkinAnnotation {
+        fn from(annotation: Annotation) -> Self {
+            Self {
+                timestamp: annotation.timestamp,
+                value: annotation.value,
+            }
+        }
+    }
+}
+
+/// Error returned for Zipkin spans which cannot be converted.
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidZipkinSpan {
+    /// The trace id is not a valid hex encoded 64-bit or 128-bit id.
+    #[error("invalid trace id")]
+    InvalidTraceId,
+    /// The timestamp or duration of the span is out of range.
+    #[error("invalid timestamp or duration")]
+    InvalidTimestamp,
+}
+
+/// Transform a Zipkin v2 span to a Sentry span V2.
+///
+/// Attributes follow the conventions of [`otel_to_sentry_span`](crate::otel_to_sentry_span_v2):
+/// * The local endpoint's service name is stored as `resource.service.name`, the addresses of
+///   the local and remote endpoints as `network.local.*` and `network.peer.*` attributes.
+/// * 64-bit trace ids are left padded with zeros to 128-bit.
+/// * An `error` tag marks the span as failed, its value is used as status message.
+/// * Annotations are stored in the `zipkin.annotations` attribute in chronological order.
+///
+/// Server and consumer spans, as well as spans sharing their id with the client side, continue a
+/// trace from a remote service and are therefore treated as segments.
+///
+/// All tags are carried over as string attributes.
+///
+/// Spans with an invalid trace id or with timestamps out of range are rejected.
+pub fn zipkin_to_sentry_span(zipkin_span: ZipkinSpan) -> Result<SentrySpanV2, InvalidZipkinSpan> {
+    let ZipkinSpan {
+        trace_id,
+        parent_id,
+        id,
+        kind,
+        name,
+        timestamp,
+        duration,
+        local_endpoint,
+        remote_endpoint,
+        mut annotations,
+        tags,
+        debug,
+        shared,
+    } = zipkin_span;
+
+    let trace_id = TraceId::from_str(&format!("{trace_id:0>32}"))
+        .map_err(|_| InvalidZipkinSpan::InvalidTraceId)?;
+    let span_id: Annotated<SpanId> = SpanId::from_str(&format!("{id:0>16}")).into();
+    let parent_span_id = match parent_id.as_deref() {
+        None | Some("") => Annotated::empty(),
+        Some(parent_id) => SpanId::from_str(&format!("{parent_id:0>16}")).into(),
+    };
+
+    let start_timestamp = timestamp
+        .map(|ts| {
+            i64::try_from(ts)
+                .ok()
+                .and_then(|ts| Utc.timestamp_micros(ts).single())
+                .ok_or(InvalidZipkinSpan::InvalidTimestamp)
+        })
+        .transpose()?;
+    let end_timestamp = start_timestamp
+        .map(|start| {
+            i64::try_from(duration.unwrap_or_default())
+                .ok()
+                .map(TimeDelta::microseconds)
+                .and_then(|duration| start.checked_add_signed(duration))
+                .ok_or(InvalidZipkinSpan::InvalidTimestamp)
+        })
+        .transpose()?;
+
+    let mut sentry_attributes = Attributes::new();
+    sentry_attributes.insert(SENTRY__ORIGIN, "auto.zipkin.spans".to_owned());
+
+    if let Some(endpoint) = local_endpoint {
+        if let Some(service_name) = endpoint.service_name {
+            sentry_attributes.insert("resource.service.name", service_name);
+        }
+        insert_address(
+            &mut sentry_attributes,
+            "network.local",
+            endpoint.ipv4,
+            endpoint.ipv6,
+        );
+        if let Some(port) = endpoint.port {
+            sentry_attributes.insert("network.local.port", i64::from(port));
+        }
+    }
+
+    if let Some(endpoint) = remote_endpoint {
+        if let Some(service_name) = endpoint.service_name {
+            sentry_attributes.insert("peer.service", service_name);
+        }
+        insert_address(
+            &mut sentry_attributes,
+            "network.peer",
+            endpoint.ipv4,
+            endpoint.ipv6,
+        );
+        if let Some(port) = endpoint.port {
+            sentry_attributes.insert("network.peer.port", i64::from(port));
+        }
+    }
+
+    let mut name = name.filter(|name| !name.is_empty());
+    let mut status = SpanV2Status::Ok;
+    for (key, value) in tags {
+        match key.as_str() {
+            key if key.starts_with("db") => {
+                name = name.or(Some("db".to_owned()));
+            }
+            "http.method" | "http.request.method" => {
+                let http_op = match kind {
+                    Some(ZipkinSpanKind::Server) => "http.server",
+                    Some(ZipkinSpanKind::Client) => "http.client",
+                    _ => "http",
+                };
+                name = name.or(Some(http_op.to_owned()));
+            }
+            "error" => {
+                status = SpanV2Status::Error;
+                if !value.is_empty() {
+                    sentry_attributes.insert(SENTRY__STATUS__MESSAGE, value.clone());
+                }
+            }
+            _ => (),
+        }
+
+        sentry_attributes.insert(key, value);
+    }
+
+    if !annotations.is_empty() {
+        annotations.sort_by_key(|annotation| annotation.timestamp);
+        let values = annotations
+            .into_iter()
+            .map(|annotation| Annotated::new(Value::String(annotation.value)))
+            .collect();
+        sentry_attributes.0.insert(
+            "zipkin.annotations".to_owned(),
+            Annotated::new(Attribute::new(AttributeType::Array, Value::Array(values))),
+        );
+    }
+
+    if debug == Some(true) {
+        sentry_attributes.insert("zipkin.debug", true);
+    }
+
+    let is_remote = shared == Some(true)
+        || matches!(
+            kind,
+            Some(ZipkinSpanKind::Server | ZipkinSpanKind::Consumer)
+        );
+    sentry_attributes.insert(SENTRY__IS_REMOTE, is_remote);
+    sentry_attributes.insert(SENTRY__KIND, zipkin_to_sentry_kind(kind).to_string());
+
+    // Like for OTeL spans, remote spans and root spans are segment spans.
+    let is_root_span = parent_span_id.value().is_none();
+    let is_segment = is_root_span || is_remote;
+
+    if is_segment {
+        if let Some(span_id) = span_id.value() {
+            sentry_attributes.insert(SENTRY__SEGMENT__ID, span_id.to_string());
+        }
+        if let Some(ref segment_name) = name {
+            sentry_attributes.insert(SENTRY__SEGMENT__NAME, segment_name.clone());
+        }
+    }
+
+    Ok(SentrySpanV2 {
+        name: name.into(),
+        trace_id: trace_id.into(),
+        span_id,
+        parent_span_id,
+        is_segment: is_segment.into(),
+        start_timestamp: start_timestamp.map(Timestamp).into(),
+        end_timestamp: end_timestamp.map(Timestamp).into(),
+        status: status.into(),
+        attributes: Annotated::new(sentry_attributes),
+        ..Default::default()
+    })
+}
+
+/// Inserts the IPv4 address, or the IPv6 address if there is none, as `<prefix>.address`.
+fn insert_address(
+    attributes: &mut Attributes,
+    prefix: &str,
+    ipv4: Option<String>,
+    ipv6: Option<String>,
+) {
+    if let Some(address) = ipv4.or(ipv6) {
+        attributes.insert(format!("{prefix}.address"), address);
+    }
+}
+
+fn zipkin_to_sentry_kind(kind: Option<ZipkinSpanKind>) -> SpanKind {
+    match kind {
+        None => SpanKind::Internal,
+        Some(ZipkinSpanKind::Client) => SpanKind::Client,
+        Some(ZipkinSpanKind::Server) => SpanKind::Server,
+        Some(ZipkinSpanKind::Producer) => SpanKind::Producer,
+        Some(ZipkinSpanKind::Consumer) => SpanKind::Consumer,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prost::Message as _;
+    use relay_protocol::SerializableAnnotated;
+
+    use super::*;
+
+    fn parse(json: &str) -> Vec<ZipkinSpan> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn parse_span() {
+        let spans = parse(
+            r#"[{
+                "traceId": "5af7183fb1d4cf5f",
+                "parentId": "6b221d5bc9e6496c",
+                "id": "352bff9a74ca9ad2",
+                "kind": "CLIENT",
+                "name": "get /api",
+                "timestamp": 1556604172355737,
+                "duration": 1431,
+                "localEndpoint": {"serviceName": "frontend", "ipv4": "192.168.99.1", "port": 8080},
+                "remoteEndpoint": {"serviceName": "backend", "ipv6": "::1", "port": 9000},
+                "annotations": [
+                    {"timestamp": 1556604172356000, "value": "wr"},
+                    {"timestamp": 1556604172355800, "value": "ws"}
+                ],
+                "tags": {"http.method": "GET", "http.path": "/api", "error": "timeout"},
+                "debug": true
+            }]"#,
+        );
+
+        let span =
+            Annotated::new(zipkin_to_sentry_span(spans.into_iter().next().unwrap()).unwrap());
+        insta::assert_json_snapshot!(SerializableAnnotated(&span), @r#"
+        {
+          "trace_id": "00000000000000005af7183fb1d4cf5f",
+          "parent_span_id": "6b221d5bc9e6496c",
+          "span_id": "352bff9a74ca9ad2",
+          "name": "get /api",
+          "status": "error",
+          "is_segment": false,
+          "start_timestamp": 1556604172.355737,
+          "end_timestamp": 1556604172.357168,
+          "attributes": {
+            "error": {
+              "type": "string",
+              "value": "timeout"
+            },
+            "http.method": {
+              "type": "string",
+              "value": "GET"
+            },
+            "http.path": {
+              "type": "string",
+              "value": "/api"
+            },
+            "network.local.address": {
+              "type": "string",
+              "value": "192.168.99.1"
+            },
+            "network.local.port": {
+              "type": "integer",
+              "value": 8080
+            },
+            "network.peer.address": {
+              "type": "string",
+              "value": "::1"
+            },
+            "network.peer.port": {
+              "type": "integer",
+              "value": 9000
+            },
+            "peer.service": {
+              "type": "string",
+              "value": "backend"
+            },
+            "resource.service.name": {
+              "type": "string",
+              "value": "frontend"
+            },
+            "sentry.is_remote": {
+              "type": "boolean",
+              "value": false
+            },
+            "sentry.kind": {
+              "type": "string",
+              "value": "client"
+            },
+            "sentry.origin": {
+              "type": "string",
+              "value": "auto.zipkin.spans"
+            },
+            "sentry.status.message": {
+              "type": "string",
+              "value": "timeout"
+            },
+            "zipkin.annotations": {
+              "type": "array",
+              "value": [
+                "ws",
+                "wr"
+              ]
+            },
+            "zipkin.debug": {
+              "type": "boolean",
+              "value": true
+            }
+          }
+        }
+        "#);
+    }
+
+    #[test]
+    fn parse_shared_server_span() {
+        let spans = parse(
+            r#"[{
+                "traceId": "463ac35c9f6413ad48485a3953bb6124",
+                "parentId": "6b221d5bc9e6496c",
+                "id": "352bff9a74ca9ad2",
+                "kind": "SERVER",
+                "name": "get /api",
+                "timestamp": 1556604172355737,
+                "duration": 1000,
+                "shared": true
+            }]"#,
+        );
+
+        let span = zipkin_to_sentry_span(spans.into_iter().next().unwrap()).unwrap();
+        assert_eq!(span.is_segment.value(), Some(&true));
+        assert_eq!(
+            span.trace_id.value().unwrap().to_string(),
+            "463ac35c9f6413ad48485a3953bb6124"
+        );
+
+        let attributes = span.attributes.value().unwrap();
+        assert_eq!(
+            attributes
+                .get_value(SENTRY__SEGMENT__ID)
+                .and_then(Value::as_str),
+            Some("352bff9a74ca9ad2")
+        );
+        assert_eq!(
+            attributes.get_value(SENTRY__KIND).and_then(Value::as_str),
+            Some("server")
+        );
+    }
+
+    #[test]
+    fn parse_local_span() {
+        let spans = parse(
+            r#"[{
+                "traceId": "5af7183fb1d4cf5f",
+                "parentId": "6b221d5bc9e6496c",
+                "id": "352bff9a74ca9ad2",
+                "name": "compute",
+                "timestamp": 1556604172355737
+            }]"#,
+        );
+
+        let span = zipkin_to_sentry_span(spans.into_iter().next().unwrap()).unwrap();
+        assert_eq!(span.is_segment.value(), Some(&false));
+        assert_eq!(span.start_timestamp.value(), span.end_timestamp.value());
+
+        let attributes = span.attributes.value().unwrap();
+        assert_eq!(
+            attributes.get_value(SENTRY__KIND).and_then(Value::as_str),
+            Some("internal")
+        );
+    }
+
+    #[test]
+    fn reject_duration_out_of_range() {
+        for duration in [u64::MAX, i64::MAX as u64] {
+            let span = ZipkinSpan {
+                trace_id: "5af7183fb1d4cf5f".to_owned(),
+                id: "352bff9a74ca9ad2".to_owned(),
+                timestamp: Some(1556604172355737),
+                duration: Some(duration),
+                ..Default::default()
+            };
+
+            assert!(matches!(
+                zipkin_to_sentry_span(span),
+                Err(InvalidZipkinSpan::InvalidTimestamp)
+            ));
+        }
+    }
+
+    #[test]
+    fn reject_timestamp_out_of_range() {
+        let span = ZipkinSpan {
+            trace_id: "5af7183fb1d4cf5f".to_owned(),
+            id: "352bff9a74ca9ad2".to_owned(),
+            timestamp: Some(u64::MAX),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            zipkin_to_sentry_span(span),
+            Err(InvalidZipkinSpan::InvalidTimestamp)
+        ));
+    }
+
+    #[test]
+    fn reject_invalid_trace_id() {
+        for trace_id in ["not a trace id", "", "5af7183fb1d4cf5f5af7183fb1d4cf5f00"] {
+            let span = ZipkinSpan {
+                trace_id: trace_id.to_owned(),
+                id: "352bff9a74ca9ad2".to_owned(),
+                ..Default::default()
+            };
+
+            assert!(matches!(
+                zipkin_to_sentry_span(span),
+                Err(InvalidZipkinSpan::InvalidTraceId)
+            ));
+        }
+    }
+
+    #[test]
+    fn parse_proto_span() {
+        let list = proto::ListOfSpans {
+            spans: vec![proto::Span {
+                trace_id: hex::decode("5af7183fb1d4cf5f").unwrap(),
+                id: hex::decode("352bff9a74ca9ad2").unwrap(),
+                kind: proto::Kind::Consumer as i32,
+                name: "process".to_owned(),
+                timestamp: 1556604172355737,
+                duration: 1431,
+                local_endpoint: Some(proto::Endpoint {
+                    service_name: "worker".to_owned(),
+                    ipv4: vec![10, 0, 0, 1],
+                    ipv6: Vec::new(),
+                    port: 0,
+                }),
+                annotations: vec![proto::Annotation {
+                    timestamp: 1556604172355800,
+                    value: "received".to_owned(),
+                }],
+                tags: BTreeMap::from([("queue".to_owned(), "jobs".to_owned())]),
+                ..Default::default()
+            }],
+        };
+
+        let list = proto::ListOfSpans::decode(list.encode_to_vec().as_slice()).unwrap();
+        let span = zipkin_to_sentry_span(list.spans.into_iter().next().unwrap().into()).unwrap();
+        let span = Annotated::new(span);
+        insta::assert_json_snapshot!(SerializableAnnotated(&span), @r#"
+        {
+          "trace_id": "00000000000000005af7183fb1d4cf5f",
+          "span_id": "352bff9a74ca9ad2",
+          "name": "process",
+          "status": "ok",
+          "is_segment": true,
+          "start_timestamp": 1556604172.355737,
+          "end_timestamp": 1556604172.357168,
+          "attributes": {
+            "network.local.address": {
+              "type": "string",
+              "value": "10.0.0.1"
+            },
+            "queue": {
+              "type": "string",
+              "value": "jobs"
+            },
+            "resource.service.name": {
+              "type": "string",
+              "value": "worker"
+            },
+            "sentry.is_remote": {
+              "type": "boolean",
+              "value": true
+            },
+            "sentry.kind": {
+              "type": "string",
+              "value": "consumer"
+            },
+            "sentry.origin": {
+              "type": "string",
+              "value": "auto.zipkin.spans"
+            },
+            "sentry.segment.id": {
+              "type": "string",
+              "value": "352bff9a74ca9ad2"
+            },
+            "sentry.segment.name": {
+              "type": "string",
+              "value": "process"
+            },
+            "zipkin.annotations": {
+              "type": "array",
+              "value": [
+                "received"
+              ]
+            }
+          }
+        }
+        "#);
+    }
+}
@@ -87,14 +87,14 @@ where
     type Interface = S::Interface;
 
     async fn run(mut self, mut rx: super::Receiver<Self::Interface>) {
+        // Messages dropped from a bounded input queue are load-shed like the backlog.
+        rx.set_loadshed(self.inner.clone());
+
         loop {
             relay_log::trace!("Concurrent service loop iteration");
 
             let has_capacity = self.pending.len() < self.max_concurrency;
-            let should_consume = has_capacity || {
-                let backlog = rx.queue_size.load(std::sync::atomic::Ordering::Relaxed);
-                backlog > self.max_backlog as u64
-            };
+            let should_consume = has_capacity || rx.len() > self.max_backlog as u64;
 
             tokio::select! {
                 // Bias towards handling responses so that there's space for new incoming requests.
@@ -0,0 +1,290 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use tokio::sync::Notify;
+
+/// Behavior of a [bounded channel](crate::bounded_channel) once its capacity is exhausted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Backpressure {
+    /// Senders wait for capacity.
+    ///
+    /// Only [`Addr::send_wait`](crate::Addr::send_wait) and [`Addr::ready`](crate::Addr::ready)
+    /// suspend until there is space in the queue. Since [`Addr::send`](crate::Addr::send) cannot
+    /// wait, it still enqueues the message and temporarily exceeds the capacity, while
+    /// [`Addr::try_send`](crate::Addr::try_send) fails.
+    #[default]
+    Wait,
+    /// Messages sent to a full queue are rejected with a [`SendError`](crate::SendError).
+    Reject,
+    /// Messages are always accepted, but the oldest messages are dropped from the queue.
+    ///
+    /// Senders drop messages as soon as the queue exceeds its capacity. Dropped messages are
+    /// passed to the [`LoadShed`](crate::LoadShed) hook of the [`Receiver`](crate::Receiver), if
+    /// one is installed.
+    DropOldest,
+}
+
+/// Shared state of a service channel between its addresses and receiver.
+#[derive(Debug)]
+pub(crate) struct Mailbox {
+    /// Number of messages currently in the queue.
+    size: AtomicU64,
+    /// Maximum number of messages in the queue, `u64::MAX` for unbounded channels.
+    capacity: u64,
+    /// Behavior once the capacity is exhausted.
+    backpressure: Backpressure,
+    /// Wakes senders waiting for capacity.
+    notify: Notify,
+    /// Total number of messages dropped by the backpressure policy.
+    dropped: AtomicU64,
+    /// Number of messages received since the last call to [`Self::update_wait`].
+    received_accumulated: AtomicU64,
+    /// Wait time of messages received since the last call to [`Self::update_wait`].
+    wait_accumulated_ns: AtomicU64,
+    /// Average wait time of messages received during the last update interval.
+    wait_ns: AtomicU64,
+}
+
+impl Mailbox {
+    /// Creates state for an unbounded channel.
+    pub fn unbounded() -> Self {
+        Self::new(u64::MAX, Backpressure::default())
+    }
+
+    /// Creates state for a bounded channel with the given capacity and policy.
+    pub fn bounded(capacity: usize, backpressure: Backpressure) -> Self {
+        Self::new(capacity.try_into().unwrap_or(u64::MAX), backpressure)
+    }
+
+    fn new(capacity: u64, backpressure: Backpressure) -> Self {
+        Self {
+            size: AtomicU64::new(0),
+            capacity,
+            backpressure,
+            notify: Notify::new(),
+            dropped: AtomicU64::new(0),
+            received_accumulated: AtomicU64::new(0),
+            wait_accumulated_ns: AtomicU64::new(0),
+            wait_ns: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the number of messages currently in the queue.
+    pub fn len(&self) -> u64 {
+        self.size.load(Ordering::Relaxed)
+    }
+
+    /// Returns the capacity of the queue, or `None` if it is unbounded.
+    pub fn capacity(&self) -> Option<u64> {
+        (self.capacity != u64::MAX).then_some(self.capacity)
+    }
+
+    /// Returns the total number of messages dropped due to backpressure.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Returns the average time messages spent in the queue during the last update interval.
+    pub fn wait(&self) -> Duration {
+        Duration::from_nanos(self.wait_ns.load(Ordering::Relaxed))
+    }
+
+    /// Reserves a slot for a message sent without waiting.
+    ///
+    /// Returns `false` if the message must be rejected.
+    pub fn reserve(&self) -> bool {
+        match self.backpressure {
+            Backpressure::Reject => self.try_reserve(),
+            Backpressure::Wait | Backpressure::DropOldest => {
+                self.size.fetch_add(1, Ordering::SeqCst);
+                true
+            }
+        }
+    }
+
+    /// Reserves a slot for a message only if the queue has capacity left.
+    ///
+    /// Messages sent to a channel with [`Backpressure::DropOldest`] are always accepted.
+    pub fn try_reserve(&self) -> bool {
+        if self.backpressure == Backpressure::DropOldest {
+            self.size.fetch_add(1, Ordering::SeqCst);
+            return true;
+        }
+
+        let capacity = self.capacity;
+        let result = self
+            .size
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |size| {
+                (size < capacity).then_some(size + 1)
+            });
+
+        if result.is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+
+        result.is_ok()
+    }
+
+    /// Waits until a slot has been reserved or `is_closed` returns `true`.
+    ///
+    /// Returns `false` if the channel closed before a slot could be reserved.
+    pub async fn reserve_wait(&self, is_closed: impl Fn() -> bool) -> bool {
+        if self.backpressure != Backpressure::Wait {
+            return self.reserve();
+        }
+
+        loop {
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if is_closed() {
+                return false;
+            }
+
+            let capacity = self.capacity;
+            let reserved = self
+                .size
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |size| {
+                    (size < capacity).then_some(size + 1)
+                })
+                .is_ok();
+
+            if reserved {
+                return true;
+            }
+
+            notified.await;
+        }
+    }
+
+    /// Releases a slot that was reserved but never used for a message.
+    pub fn unreserve(&self) {
+        self.size.fetch_sub(1, Ordering::SeqCst);
+        self.notify.notify_one();
+    }
+
+    /// Wakes up all senders waiting for capacity after the channel has closed.
+    pub fn close(&self) {
+        self.notify.notify_waiters();
+    }
+
+    /// Waits until the queue has capacity left or `is_closed` returns `true`.
+    ///
+    /// Unlike [`Self::reserve_wait`], this does not reserve a slot.
+    pub async fn wait_capacity(&self, is_closed: impl Fn() -> bool) {
+        if self.backpressure != Backpressure::Wait {
+            return;
+        }
+
+        loop {
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if is_closed() || self.len() < self.capacity {
+                return;
+            }
+
+            notified.await;
+        }
+    }
+
+    /// Records a message that was dropped from the queue to restore its capacity.
+    pub fn shed(&self) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+        self.unreserve();
+    }
+
+    /// Records a message taken from the queue by the receiver after waiting for `wait`.
+    pub fn release(&self, wait: Duration) {
+        let wait_ns = wait.as_nanos().try_into().unwrap_or(u64::MAX);
+        self.received_accumulated.fetch_add(1, Ordering::Relaxed);
+        self.wait_accumulated_ns
+            .fetch_add(wait_ns, Ordering::Relaxed);
+        self.unreserve();
+    }
+
+    /// Recomputes the average wait time from messages received since the last update.
+    ///
+    /// If no messages were received, the wait time is only reset when the queue is empty, since a
+    /// stalled queue should not report a wait time of zero.
+    pub fn update_wait(&self) {
+        let received = self.received_accumulated.swap(0, Ordering::Relaxed);
+        let wait_ns = self.wait_accumulated_ns.swap(0, Ordering::Relaxed);
+
+        if let Some(wait_ns) = wait_ns.checked_div(received) {
+            self.wait_ns.store(wait_ns, Ordering::Relaxed);
+        } else if self.len() == 0 {
+            self.wait_ns.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reject() {
+        let mailbox = Mailbox::bounded(2, Backpressure::Reject);
+
+        assert!(mailbox.reserve());
+        assert!(mailbox.reserve());
+        assert!(!mailbox.reserve());
+        assert_eq!(mailbox.len(), 2);
+        assert_eq!(mailbox.dropped(), 1);
+
+        mailbox.release(Duration::ZERO);
+        assert!(mailbox.reserve());
+    }
+
+    #[test]
+    fn test_wait_overcommits() {
+        let mailbox = Mailbox::bounded(1, Backpressure::Wait);
+
+        assert!(mailbox.reserve());
+        assert!(mailbox.reserve());
+        assert!(!mailbox.try_reserve());
+        assert_eq!(mailbox.len(), 2);
+    }
+
+    #[test]
+    fn test_drop_oldest() {
+        let mailbox = Mailbox::bounded(1, Backpressure::DropOldest);
+
+        assert!(mailbox.try_reserve());
+        assert!(mailbox.try_reserve());
+        assert_eq!(mailbox.len(), 2);
+
+        mailbox.shed();
+        assert_eq!(mailbox.len(), 1);
+        assert_eq!(mailbox.dropped(), 1);
+    }
+
+    #[test]
+    fn test_update_wait() {
+        let mailbox = Mailbox::unbounded();
+        assert_eq!(mailbox.capacity(), None);
+
+        mailbox.reserve();
+        mailbox.reserve();
+        mailbox.reserve();
+        mailbox.release(Duration::from_millis(10));
+        mailbox.release(Duration::from_millis(30));
+
+        mailbox.update_wait();
+        assert_eq!(mailbox.wait(), Duration::from_millis(20));
+
+        // The queue is stalled, the previous wait time is retained.
+        mailbox.update_wait();
+        assert_eq!(mailbox.wait(), Duration::from_millis(20));
+
+        mailbox.release(Duration::from_millis(5));
+        mailbox.update_wait();
+        assert_eq!(mailbox.wait(), Duration::from_millis(5));
+
+        mailbox.update_wait();
+        assert_eq!(mailbox.wait(), Duration::ZERO);
+    }
+}
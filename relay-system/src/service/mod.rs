@@ -1,26 +1,30 @@
+use std::collections::VecDeque;
 use std::fmt;
 use std::future::Future;
 use std::marker::PhantomData;
 use std::pin::Pin;
-use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 use std::task::{Context, Poll};
 use std::time::Duration;
 
 use futures::FutureExt;
 use futures::future::{BoxFuture, Shared};
-use tokio::sync::{mpsc, oneshot};
-use tokio::time::MissedTickBehavior;
+use tokio::sync::{Notify, mpsc, oneshot};
+use tokio::time::{Instant, MissedTickBehavior};
 
 use crate::statsd::SystemGauges;
 use crate::{TaskId, spawn};
 
 mod concurrent;
+mod mailbox;
 mod registry;
 mod simple;
 mod status;
 
 pub use self::concurrent::{ConcurrentService, LoadShed};
+pub use self::mailbox::Backpressure;
+pub(crate) use self::mailbox::Mailbox;
 pub(crate) use self::registry::Registry as ServiceRegistry;
 pub use self::registry::{ServiceId, ServiceMetrics, ServicesMetrics};
 pub use self::simple::SimpleService;
@@ -734,8 +738,8 @@ impl<M, R: MessageResponse> Clone for Recipient<M, R> {
 /// Addresses can be freely cloned. When the last clone is dropped, the message channel of the
 /// service closes permanently, which signals to the service that it can shut down.
 pub struct Addr<I: Interface> {
-    tx: mpsc::UnboundedSender<I>,
-    queue_size: Arc<AtomicU64>,
+    tx: Tx<I>,
+    mailbox: Arc<Mailbox>,
 }
 
 impl<I: Interface> Addr<I> {
@@ -743,22 +747,73 @@ impl<I: Interface> Addr<I> {
     ///
     /// Depending on the message's response behavior, this either returns a future resolving to the
     /// return value, or does not return anything for fire-and-forget messages. The communication
-    /// channel with the service is unbounded, so backlogs could occur when sending too many
-    /// messages.
+    /// channel with the service is unbounded by default, so backlogs could occur when sending too
+    /// many messages. On [bounded channels](bounded_channel), the message is handled according to
+    /// the channel's [`Backpressure`] policy.
     ///
-    /// Sending asynchronous messages can fail with `Err(SendError)` if the service has shut down.
-    /// The result of asynchronous messages does not have to be awaited. The message will be
-    /// delivered and handled regardless:
+    /// Sending asynchronous messages can fail with `Err(SendError)` if the service has shut down
+    /// or the message was rejected. The result of asynchronous messages does not have to be
+    /// awaited. The message will be delivered and handled regardless:
     pub fn send<M>(&self, message: M) -> <I::Response as MessageResponse>::Output
     where
         I: FromMessage<M>,
     {
         let (tx, rx) = I::Response::channel();
-        self.queue_size.fetch_add(1, Ordering::SeqCst);
-        self.tx.send(I::from_message(message, tx)).ok(); // it's ok to drop, the response will fail
+        if self.mailbox.reserve() {
+            // it's ok to drop, the response will fail
+            self.tx.send(I::from_message(message, tx), &self.mailbox);
+        }
+        rx
+    }
+
+    /// Sends a message to the service if its queue has capacity left.
+    ///
+    /// Unlike [`send`](Self::send), this never exceeds the capacity of a
+    /// [bounded channel](bounded_channel), regardless of its [`Backpressure`] policy, except for
+    /// [`Backpressure::DropOldest`] which always accepts new messages. Returns `Err(SendError)` if
+    /// the queue is full or the service has shut down.
+    pub fn try_send<M>(
+        &self,
+        message: M,
+    ) -> Result<<I::Response as MessageResponse>::Output, SendError>
+    where
+        I: FromMessage<M>,
+    {
+        if self.is_closed() || !self.mailbox.try_reserve() {
+            return Err(SendError);
+        }
+
+        let (tx, rx) = I::Response::channel();
+        self.tx.send(I::from_message(message, tx), &self.mailbox);
+        Ok(rx)
+    }
+
+    /// Sends a message to the service after waiting for capacity in its queue.
+    ///
+    /// On [bounded channels](bounded_channel) with [`Backpressure::Wait`], this suspends until the
+    /// service has taken enough messages from its queue. In all other cases, this behaves like
+    /// [`send`](Self::send).
+    pub async fn send_wait<M>(&self, message: M) -> <I::Response as MessageResponse>::Output
+    where
+        I: FromMessage<M>,
+    {
+        let (tx, rx) = I::Response::channel();
+        if self.mailbox.reserve_wait(|| self.is_closed()).await {
+            self.tx.send(I::from_message(message, tx), &self.mailbox);
+        }
         rx
     }
 
+    /// Waits until the queue has capacity left for another message.
+    ///
+    /// On [bounded channels](bounded_channel) with [`Backpressure::Wait`], this suspends until the
+    /// queue is no longer full. Use this before a batch of [`send`](Self::send) calls from code
+    /// that cannot wait itself. The capacity is not reserved, so concurrent senders can still fill
+    /// the queue in the meanwhile. In all other cases, this returns immediately.
+    pub async fn ready(&self) {
+        self.mailbox.wait_capacity(|| self.is_closed()).await
+    }
+
     /// Returns a handle that can receive a given message independent of the interface.
     ///
     /// See [`Recipient`] for more information and examples.
@@ -771,12 +826,12 @@ impl<I: Interface> Addr<I> {
         }
     }
 
-    /// Returns wether the queue is currently empty.
+    /// Returns whether the queue is currently empty.
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
 
-    /// Returns wether the queue is closed.
+    /// Returns whether the queue is closed.
     ///
     /// This happens when the receiving service has stopped running.
     pub fn is_closed(&self) -> bool {
@@ -785,7 +840,12 @@ impl<I: Interface> Addr<I> {
 
     /// Returns the current queue size.
     pub fn len(&self) -> u64 {
-        self.queue_size.load(Ordering::Relaxed)
+        self.mailbox.len()
+    }
+
+    /// Returns the capacity of the queue, or `None` if the queue is unbounded.
+    pub fn capacity(&self) -> Option<u64> {
+        self.mailbox.capacity()
     }
 
     /// Custom address used for testing.
@@ -795,8 +855,8 @@ impl<I: Interface> Addr<I> {
         let (tx, rx) = mpsc::unbounded_channel();
         (
             Addr {
-                tx,
-                queue_size: Default::default(),
+                tx: Tx::Custom(tx),
+                mailbox: Arc::new(Mailbox::unbounded()),
             },
             rx,
         )
@@ -812,7 +872,7 @@ impl<I: Interface> fmt::Debug for Addr<I> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Addr")
             .field("open", &!self.tx.is_closed())
-            .field("queue_size", &self.queue_size.load(Ordering::Relaxed))
+            .field("queue_size", &self.mailbox.len())
             .finish()
     }
 }
@@ -823,7 +883,7 @@ impl<I: Interface> Clone for Addr<I> {
     fn clone(&self) -> Self {
         Self {
             tx: self.tx.clone(),
-            queue_size: self.queue_size.clone(),
+            mailbox: self.mailbox.clone(),
         }
     }
 }
@@ -856,17 +916,214 @@ impl<M: 'static + Send> SendDispatch<M> for tokio::sync::mpsc::UnboundedSender<M
     }
 }
 
+/// A message in the queue of a service, along with the time it was sent.
+struct Queued<I> {
+    message: I,
+    sent_at: Instant,
+}
+
+/// Sending half of a service channel.
+enum Tx<I> {
+    /// The channel of a service created through [`channel`] or [`bounded_channel`].
+    Service(mpsc::UnboundedSender<Queued<I>>),
+    /// The channel of a service created through [`bounded_channel`] with
+    /// [`Backpressure::DropOldest`].
+    Shedding(SheddingTx<I>),
+    /// A raw channel created through [`Addr::custom`].
+    Custom(mpsc::UnboundedSender<I>),
+}
+
+impl<I> Tx<I> {
+    /// Enqueues a message for which a slot has been reserved in the mailbox.
+    ///
+    /// Custom channels keep counting messages after the receiver has been dropped, so that
+    /// [`Addr::dummy`] can be used to inspect the number of sent messages.
+    fn send(&self, message: I, mailbox: &Mailbox) {
+        let queued = Queued {
+            message,
+            sent_at: Instant::now(),
+        };
+
+        match self {
+            Self::Service(tx) => {
+                if tx.send(queued).is_err() {
+                    mailbox.unreserve();
+                }
+            }
+            Self::Shedding(tx) => {
+                if !tx.0.push(queued, mailbox) {
+                    mailbox.unreserve();
+                }
+            }
+            Self::Custom(tx) => {
+                tx.send(queued.message).ok();
+            }
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        match self {
+            Self::Service(tx) => tx.is_closed(),
+            Self::Shedding(tx) => tx.0.closed.load(Ordering::SeqCst),
+            Self::Custom(tx) => tx.is_closed(),
+        }
+    }
+}
+
+impl<I> Clone for Tx<I> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Service(tx) => Self::Service(tx.clone()),
+            Self::Shedding(tx) => Self::Shedding(tx.clone()),
+            Self::Custom(tx) => Self::Custom(tx.clone()),
+        }
+    }
+}
+
+/// Receiving half of a service channel.
+enum Rx<I> {
+    /// See [`Tx::Service`].
+    Service(mpsc::UnboundedReceiver<Queued<I>>),
+    /// See [`Tx::Shedding`].
+    Shedding(Arc<SheddingQueue<I>>),
+}
+
+impl<I> Rx<I> {
+    async fn recv(&mut self) -> Option<Queued<I>> {
+        match self {
+            Self::Service(rx) => rx.recv().await,
+            Self::Shedding(queue) => queue.recv().await,
+        }
+    }
+
+    fn close(&mut self) {
+        match self {
+            Self::Service(rx) => rx.close(),
+            Self::Shedding(queue) => queue.close(),
+        }
+    }
+}
+
+/// The queue of a channel with [`Backpressure::DropOldest`].
+///
+/// Senders drop the oldest message as soon as the queue exceeds its capacity, so the queue stays
+/// bounded even while the receiver is busy and does not poll for new messages.
+struct SheddingQueue<I> {
+    queue: Mutex<VecDeque<Queued<I>>>,
+    capacity: usize,
+    /// Wakes the receiver for new messages and when the last sender is dropped.
+    notify: Notify,
+    /// Number of senders, the channel closes once the last sender is dropped.
+    senders: AtomicUsize,
+    /// Set when the receiver has been dropped.
+    closed: AtomicBool,
+    /// Hook for dropped messages, see [`Receiver::set_loadshed`].
+    loadshed: Mutex<Option<Box<dyn LoadShed<I> + Send + Sync>>>,
+}
+
+impl<I> SheddingQueue<I> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            queue: Mutex::new(VecDeque::new()),
+            capacity,
+            notify: Notify::new(),
+            senders: AtomicUsize::new(0),
+            closed: AtomicBool::new(false),
+            loadshed: Mutex::new(None),
+        }
+    }
+
+    fn queue(&self) -> MutexGuard<'_, VecDeque<Queued<I>>> {
+        self.queue.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Appends a message and drops the oldest message if the queue is full.
+    ///
+    /// Returns `false` if the receiver has been dropped.
+    fn push(&self, queued: Queued<I>, mailbox: &Mailbox) -> bool {
+        if self.closed.load(Ordering::SeqCst) {
+            return false;
+        }
+
+        let shed = {
+            let mut queue = self.queue();
+            queue.push_back(queued);
+            match queue.len() > self.capacity {
+                true => queue.pop_front(),
+                false => None,
+            }
+        };
+        self.notify.notify_one();
+
+        if let Some(shed) = shed {
+            mailbox.shed();
+            let loadshed = self.loadshed.lock().unwrap_or_else(PoisonError::into_inner);
+            if let Some(loadshed) = &*loadshed {
+                loadshed.handle_loadshed(shed.message);
+            }
+        }
+
+        true
+    }
+
+    async fn recv(&self) -> Option<Queued<I>> {
+        loop {
+            let notified = self.notify.notified();
+
+            if let Some(queued) = self.queue().pop_front() {
+                return Some(queued);
+            }
+
+            if self.senders.load(Ordering::SeqCst) == 0 {
+                return None;
+            }
+
+            notified.await;
+        }
+    }
+
+    fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        self.queue().clear();
+    }
+}
+
+/// A sender to a [`SheddingQueue`], which keeps track of the number of senders.
+struct SheddingTx<I>(Arc<SheddingQueue<I>>);
+
+impl<I> SheddingTx<I> {
+    fn new(queue: Arc<SheddingQueue<I>>) -> Self {
+        queue.senders.fetch_add(1, Ordering::SeqCst);
+        Self(queue)
+    }
+}
+
+impl<I> Clone for SheddingTx<I> {
+    fn clone(&self) -> Self {
+        Self::new(self.0.clone())
+    }
+}
+
+impl<I> Drop for SheddingTx<I> {
+    fn drop(&mut self) {
+        if self.0.senders.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.notify.notify_one();
+        }
+    }
+}
+
 /// Inbound channel for messages sent through an [`Addr`].
 ///
 /// This channel is meant to be polled in a [`Service`].
 ///
 /// Instances are created automatically when [spawning](ServiceSpawn) a service, or can be
-/// created through [`channel`]. The channel closes when all associated [`Addr`]s are dropped.
+/// created through [`channel`] and [`bounded_channel`]. The channel closes when all associated
+/// [`Addr`]s are dropped.
 pub struct Receiver<I: Interface> {
-    rx: mpsc::UnboundedReceiver<I>,
+    rx: Rx<I>,
     name: &'static str,
     interval: tokio::time::Interval,
-    queue_size: Arc<AtomicU64>,
+    mailbox: Arc<Mailbox>,
 }
 
 impl<I: Interface> Receiver<I> {
@@ -886,41 +1143,108 @@ impl<I: Interface> Receiver<I> {
                 biased;
 
                 _ = self.interval.tick() => {
-                    let backlog = self.queue_size.load(Ordering::Relaxed);
+                    let backlog = self.mailbox.len();
                     relay_statsd::metric!(
                         gauge(SystemGauges::ServiceBackPressure) = backlog,
                         service = self.name
                     );
+                    self.mailbox.update_wait();
                 },
-                message = self.rx.recv() => {
-                    self.queue_size.fetch_sub(1, Ordering::SeqCst);
-                    return message;
+                queued = self.rx.recv() => {
+                    let Queued { message, sent_at } = queued?;
+                    self.mailbox.release(sent_at.elapsed());
+                    return Some(message);
                 },
             }
         }
     }
+
+    /// Installs a hook for messages dropped by [`Backpressure::DropOldest`].
+    ///
+    /// Without a hook, dropped messages are discarded silently. The hook is invoked by the sender
+    /// of the message that caused the drop. This has no effect on channels with other backpressure
+    /// policies.
+    pub fn set_loadshed(&mut self, loadshed: impl LoadShed<I> + Send + Sync + 'static) {
+        if let Rx::Shedding(ref queue) = self.rx {
+            *queue
+                .loadshed
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner) = Some(Box::new(loadshed));
+        }
+    }
+
+    /// Returns the current queue size.
+    pub fn len(&self) -> u64 {
+        self.mailbox.len()
+    }
+
+    /// Returns whether the queue is currently empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
 
 impl<I: Interface> fmt::Debug for Receiver<I> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Receiver")
             .field("name", &self.name)
-            .field("queue_size", &self.queue_size.load(Ordering::Relaxed))
+            .field("queue_size", &self.mailbox.len())
             .finish()
     }
 }
 
+impl<I: Interface> Drop for Receiver<I> {
+    fn drop(&mut self) {
+        // Wake up all senders waiting for capacity, they will observe the closed channel.
+        self.rx.close();
+        self.mailbox.close();
+    }
+}
+
 /// Creates an unbounded channel for communicating with a [`Service`].
 ///
 /// The `Addr` as the sending part provides public access to the service, while the `Receiver`
 /// should remain internal to the service.
 pub fn channel<I: Interface>(name: &'static str) -> (Addr<I>, Receiver<I>) {
-    let queue_size = Arc::new(AtomicU64::new(0));
     let (tx, rx) = mpsc::unbounded_channel();
+    channel_with(name, Mailbox::unbounded(), Tx::Service(tx), Rx::Service(rx))
+}
+
+/// Creates a bounded channel for communicating with a [`Service`].
+///
+/// The queue of the channel holds up to `capacity` messages. Once it is full, messages are handled
+/// according to the [`Backpressure`] policy. See [`channel`] for more information.
+pub fn bounded_channel<I: Interface>(
+    name: &'static str,
+    capacity: usize,
+    backpressure: Backpressure,
+) -> (Addr<I>, Receiver<I>) {
+    let mailbox = Mailbox::bounded(capacity, backpressure);
+
+    match backpressure {
+        Backpressure::Wait | Backpressure::Reject => {
+            let (tx, rx) = mpsc::unbounded_channel();
+            channel_with(name, mailbox, Tx::Service(tx), Rx::Service(rx))
+        }
+        Backpressure::DropOldest => {
+            let queue = Arc::new(SheddingQueue::new(capacity));
+            let tx = SheddingTx::new(queue.clone());
+            channel_with(name, mailbox, Tx::Shedding(tx), Rx::Shedding(queue))
+        }
+    }
+}
+
+fn channel_with<I: Interface>(
+    name: &'static str,
+    mailbox: Mailbox,
+    tx: Tx<I>,
+    rx: Rx<I>,
+) -> (Addr<I>, Receiver<I>) {
+    let mailbox = Arc::new(mailbox);
 
     let addr = Addr {
         tx,
-        queue_size: queue_size.clone(),
+        mailbox: mailbox.clone(),
     };
 
     let mut interval = tokio::time::interval(BACKLOG_INTERVAL);
@@ -930,7 +1254,7 @@ pub fn channel<I: Interface>(name: &'static str) -> (Addr<I>, Receiver<I>) {
         rx,
         name,
         interval,
-        queue_size,
+        mailbox,
     };
 
     (addr, receiver)
@@ -1099,6 +1423,7 @@ impl<T: ServiceSpawn + ?Sized> ServiceSpawnExt for T {
 pub struct ServiceObj {
     name: &'static str,
     future: BoxFuture<'static, ()>,
+    mailbox: Arc<Mailbox>,
 }
 
 impl ServiceObj {
@@ -1106,6 +1431,7 @@ impl ServiceObj {
     pub fn new<S: Service>(service: S, rx: Receiver<S::Interface>) -> Self {
         Self {
             name: S::name(),
+            mailbox: Arc::clone(&rx.mailbox),
             future: service.run(rx).boxed(),
         }
     }
@@ -1215,4 +1541,98 @@ mod tests {
             ]
         );
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_bounded_reject() {
+        let (addr, mut rx) = bounded_channel::<MockMessage>("mock", 2, Backpressure::Reject);
+
+        assert!(addr.try_send(MockMessage).is_ok());
+        addr.send(MockMessage);
+        assert_eq!(addr.try_send(MockMessage).unwrap_err(), SendError);
+        addr.send(MockMessage); // silently rejected
+        assert_eq!(addr.len(), 2);
+        assert_eq!(rx.mailbox.dropped(), 2);
+
+        assert!(rx.recv().await.is_some());
+        assert!(addr.try_send(MockMessage).is_ok());
+        assert_eq!(addr.len(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_bounded_wait() {
+        let (addr, mut rx) = bounded_channel::<MockMessage>("mock", 1, Backpressure::Wait);
+
+        addr.send_wait(MockMessage).await;
+        assert!(addr.try_send(MockMessage).is_err());
+
+        let mut pending = std::pin::pin!(addr.send_wait(MockMessage));
+        assert!(futures::poll!(pending.as_mut()).is_pending());
+
+        tokio::time::advance(Duration::from_millis(10)).await;
+        assert!(rx.recv().await.is_some());
+        assert!(futures::poll!(pending.as_mut()).is_ready());
+        assert_eq!(addr.len(), 1);
+
+        rx.recv().await;
+        rx.mailbox.update_wait();
+        assert_eq!(rx.mailbox.wait(), Duration::from_millis(5));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_bounded_wait_closed() {
+        let (addr, rx) = bounded_channel::<MockMessage>("mock", 1, Backpressure::Wait);
+
+        addr.send(MockMessage);
+        let mut pending = std::pin::pin!(addr.send_wait(MockMessage));
+        assert!(futures::poll!(pending.as_mut()).is_pending());
+
+        drop(rx);
+        assert!(futures::poll!(pending.as_mut()).is_ready());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_bounded_drop_oldest() {
+        #[derive(Debug)]
+        struct Numbered(u32);
+
+        impl Interface for Numbered {}
+
+        impl FromMessage<Self> for Numbered {
+            type Response = NoResponse;
+
+            fn from_message(message: Self, _: ()) -> Self {
+                message
+            }
+        }
+
+        #[derive(Clone, Default)]
+        struct Shed(Arc<std::sync::Mutex<Vec<u32>>>);
+
+        impl LoadShed<Numbered> for Shed {
+            fn handle_loadshed(&self, message: Numbered) {
+                self.0.lock().unwrap().push(message.0);
+            }
+        }
+
+        let (addr, mut rx) = bounded_channel("mock", 2, Backpressure::DropOldest);
+        let shed = Shed::default();
+        rx.set_loadshed(shed.clone());
+
+        for i in 0..5 {
+            assert!(addr.try_send(Numbered(i)).is_ok());
+        }
+
+        // Messages are dropped by the sender, before the receiver polls.
+        assert_eq!(addr.len(), 2);
+        assert_eq!(*shed.0.lock().unwrap(), [0, 1, 2]);
+
+        assert_eq!(rx.recv().await.unwrap().0, 3);
+        assert_eq!(rx.recv().await.unwrap().0, 4);
+        assert_eq!(*shed.0.lock().unwrap(), [0, 1, 2]);
+        assert_eq!(rx.mailbox.dropped(), 3);
+        assert!(addr.is_empty());
+
+        drop(addr);
+        assert!(rx.recv().await.is_none());
+    }
 }
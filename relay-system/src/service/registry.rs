@@ -5,6 +5,7 @@ use std::time::Duration;
 
 use crate::monitor::MonitoredFuture;
 
+use crate::service::Mailbox;
 use crate::service::status::{ServiceJoinHandle, ServiceStatusJoinHandle};
 use crate::{RawMetrics, ServiceObj, TaskId};
 
@@ -38,6 +39,18 @@ pub struct ServiceMetrics {
     /// The measure is only updated when the service is polled. A service which
     /// spends a long time idle may not have this measure updated for a long time.
     pub utilization: u8,
+    /// Number of messages currently waiting in the inbound queue of the service.
+    pub queue_size: u64,
+    /// Maximum number of messages in the inbound queue, `None` if the queue is unbounded.
+    pub queue_capacity: Option<u64>,
+    /// Average time messages waited in the inbound queue before the service received them.
+    ///
+    /// This value is recomputed periodically from the messages received since the last update.
+    pub queue_wait: Duration,
+    /// Total amount of messages dropped from the inbound queue due to backpressure.
+    ///
+    /// This number is monotonically increasing. It is never decremented or reset to zero.
+    pub dropped_messages: u64,
 }
 
 /// A per runtime unique identifier for a started service.
@@ -103,6 +116,7 @@ impl Inner {
     ) -> ServiceJoinHandle {
         let task_id = TaskId::from(&service);
         let group = self.services.entry(task_id).or_default();
+        let mailbox = service.mailbox;
 
         // Services are allowed to process as much work as possible before yielding to other,
         // lower priority tasks. We want to prioritize service backlogs over creating more work
@@ -114,7 +128,7 @@ impl Inner {
         let task_handle = crate::runtime::spawn_in(handle, task_id, future);
         let (status_handle, handle) = crate::service::status::split(task_handle);
 
-        group.add(metrics, status_handle);
+        group.add(metrics, mailbox, status_handle);
 
         handle
     }
@@ -133,6 +147,10 @@ impl Inner {
                         service.metrics.total_duration_ns.load(Ordering::Relaxed),
                     ),
                     utilization: service.metrics.utilization.load(Ordering::Relaxed),
+                    queue_size: service.mailbox.len(),
+                    queue_capacity: service.mailbox.capacity(),
+                    queue_wait: service.mailbox.wait(),
+                    dropped_messages: service.mailbox.dropped(),
                 };
 
                 (id, metrics)
@@ -164,7 +182,12 @@ struct ServiceGroup {
 
 impl ServiceGroup {
     /// Adds a started service to the service group.
-    pub fn add(&mut self, metrics: Arc<RawMetrics>, handle: ServiceStatusJoinHandle) {
+    pub fn add(
+        &mut self,
+        metrics: Arc<RawMetrics>,
+        mailbox: Arc<Mailbox>,
+        handle: ServiceStatusJoinHandle,
+    ) {
         // Cleanup the group, evicting all finished services, while we're at it.
         self.instances.retain(|s| !s.handle.is_finished());
 
@@ -174,6 +197,7 @@ impl ServiceGroup {
         let service = ServiceInstance {
             instance_id,
             metrics,
+            mailbox,
             handle,
         };
 
@@ -196,6 +220,10 @@ struct ServiceInstance {
     /// The handle gives raw access to all tracked metrics, these metrics
     /// should be treated as **read-only**.
     metrics: Arc<RawMetrics>,
+    /// The inbound queue of the service instance.
+    ///
+    /// Like the metrics, the queue state should be treated as **read-only**.
+    mailbox: Arc<Mailbox>,
     /// A handle to the service instance.
     ///
     /// The handle has information about the completion status of the service.
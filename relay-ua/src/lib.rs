@@ -1,55 +1,252 @@
 //! User agent parser with built-in rules.
 //!
+//! The built-in rules can be replaced at runtime with [`set_definitions`], for example to pick up
+//! new browsers or bots without a release of Relay.
+//!
 //! # Test Performance
 //!
 //! Adding user agent parsing to your module will incur a latency penalty on first use. Because of
 //! this, integration tests could fail. To fix this, you will need to add a timeout to your
 //! consumer.
 
-use std::sync::LazyLock;
+use std::fmt;
+use std::sync::{Arc, LazyLock};
 
+use arc_swap::ArcSwapOption;
 use uaparser::{Parser, UserAgentParser};
 
 #[doc(inline)]
 pub use uaparser::{Device, OS, UserAgent};
 
-/// The global [`UserAgentParser`] already configured with a user agent database.
+/// The version reported for the built-in definitions.
+pub const BUILTIN_VERSION: &str = "builtin";
+
+/// A user agent which all valid definitions must recognize.
 ///
-/// For usage, see [`Parser`].
-static UA_PARSER: LazyLock<UserAgentParser> = LazyLock::new(|| {
+/// Definitions which parse this as an unknown browser are most likely empty or truncated.
+const REFERENCE_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+    (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+
+/// The family of user agents which did not match any rule.
+const UNKNOWN_FAMILY: &str = "Other";
+
+/// The built-in definitions, compiled on first use.
+static BUILTIN: LazyLock<Definitions> = LazyLock::new(|| {
     let ua_regexes = include_bytes!("../uap-core/regexes.yaml");
-    UserAgentParser::builder()
+    let parser = UserAgentParser::builder()
         .with_unicode_support(false)
         .build_from_bytes(ua_regexes)
-        .expect("Could not create UserAgent. You are probably using a bad build of relay.")
+        .expect("Could not create UserAgent. You are probably using a bad build of relay.");
+
+    Definitions {
+        version: BUILTIN_VERSION.to_owned(),
+        parser,
+    }
 });
 
+/// Definitions loaded at runtime, which take precedence over [`BUILTIN`].
+static RUNTIME: Runtime = Runtime::new();
+
+/// Holds definitions loaded at runtime and falls back to [`BUILTIN`] without them.
+struct Runtime(ArcSwapOption<Definitions>);
+
+impl Runtime {
+    const fn new() -> Self {
+        Self(ArcSwapOption::const_empty())
+    }
+
+    fn set(&self, definitions: Arc<Definitions>) {
+        self.0.store(Some(definitions));
+    }
+
+    fn reset(&self) {
+        self.0.store(None);
+    }
+
+    fn version(&self) -> String {
+        match &*self.0.load() {
+            Some(definitions) => definitions.version.clone(),
+            None => BUILTIN_VERSION.to_owned(),
+        }
+    }
+
+    fn with_parser<T>(&self, f: impl FnOnce(&UserAgentParser) -> T) -> T {
+        match &*self.0.load() {
+            Some(definitions) => f(&definitions.parser),
+            None => f(&BUILTIN.parser),
+        }
+    }
+}
+
+/// An error returned when parsing [`Definitions`].
+#[derive(Debug)]
+pub enum DefinitionsError {
+    /// The definitions are malformed or contain invalid patterns.
+    Invalid(uaparser::Error),
+    /// The definitions do not recognize common user agents.
+    Unrecognized,
+}
+
+impl fmt::Display for DefinitionsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid(error) => write!(f, "invalid user agent definitions: {error}"),
+            Self::Unrecognized => write!(f, "user agent definitions do not recognize browsers"),
+        }
+    }
+}
+
+impl std::error::Error for DefinitionsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Invalid(error) => Some(error),
+            Self::Unrecognized => None,
+        }
+    }
+}
+
+/// Compiled user agent parser definitions.
+pub struct Definitions {
+    version: String,
+    parser: UserAgentParser,
+}
+
+impl Definitions {
+    /// Compiles and validates definitions in the format of uap-core's `regexes.yaml`.
+    ///
+    /// Like the built-in definitions, compiling a full set of definitions takes a few seconds.
+    pub fn parse(version: impl Into<String>, regexes: &[u8]) -> Result<Self, DefinitionsError> {
+        let parser = UserAgentParser::builder()
+            .with_unicode_support(false)
+            .build_from_bytes(regexes)
+            .map_err(DefinitionsError::Invalid)?;
+
+        if parser.parse_user_agent(REFERENCE_USER_AGENT).family == UNKNOWN_FAMILY {
+            return Err(DefinitionsError::Unrecognized);
+        }
+
+        Ok(Self {
+            version: version.into(),
+            parser,
+        })
+    }
+
+    /// Returns the version of these definitions.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+}
+
+impl fmt::Debug for Definitions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Definitions")
+            .field("version", &self.version)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Replaces the definitions used by all parse functions.
+///
+/// The definitions are swapped atomically, concurrent calls to the parse functions either use
+/// the previous or the new definitions.
+pub fn set_definitions(definitions: impl Into<Arc<Definitions>>) {
+    RUNTIME.set(definitions.into());
+}
+
+/// Restores the built-in definitions.
+pub fn reset_definitions() {
+    RUNTIME.reset();
+}
+
+/// Returns the version of the definitions currently in use.
+///
+/// This is [`BUILTIN_VERSION`] unless definitions were replaced with [`set_definitions`].
+pub fn definitions_version() -> String {
+    RUNTIME.version()
+}
+
+/// Runs `f` with the parser of the definitions currently in use.
+fn with_parser<T>(f: impl FnOnce(&UserAgentParser) -> T) -> T {
+    RUNTIME.with_parser(f)
+}
+
 /// Initializes the user agent parser.
 ///
 /// This loads and compiles user agent patterns, which takes a few seconds to complete. The user
 /// agent parser initializes on-demand when using one of the parse methods. This function forces
 /// initialization at a convenient point without introducing unwanted delays.
 pub fn init_parser() {
-    LazyLock::force(&UA_PARSER);
+    LazyLock::force(&BUILTIN);
 }
 
 /// Returns the family and version of a user agent client.
 ///
 /// Defaults to an empty user agent.
 pub fn parse_user_agent(user_agent: &str) -> UserAgent<'_> {
-    UA_PARSER.parse_user_agent(user_agent)
+    with_parser(|parser| parser.parse_user_agent(user_agent))
 }
 
 /// Returns the family, brand, and model of the device of the requesting client.
 ///
 /// Defaults to an empty device.
 pub fn parse_device(user_agent: &str) -> Device<'_> {
-    UA_PARSER.parse_device(user_agent)
+    with_parser(|parser| parser.parse_device(user_agent))
 }
 
 /// Returns the family and version of the operating system of the requesting client.
 ///
 /// Defaults to an empty operating system.
 pub fn parse_os(user_agent: &str) -> OS<'_> {
-    UA_PARSER.parse_os(user_agent)
+    with_parser(|parser| parser.parse_os(user_agent))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REGEXES: &str = r#"
+user_agent_parsers:
+  - regex: '(Chrome)/(\d+)\.(\d+)\.(\d+)'
+os_parsers:
+  - regex: '(Windows NT) (\d+)\.(\d+)'
+    os_replacement: 'Windows'
+device_parsers: []
+"#;
+
+    #[test]
+    fn test_runtime_definitions() {
+        // Use a separate runtime, so that other tests keep parsing with the global definitions.
+        let runtime = Runtime::new();
+        assert_eq!(runtime.version(), BUILTIN_VERSION);
+
+        let definitions = Definitions::parse("test", REGEXES.as_bytes()).unwrap();
+        assert_eq!(definitions.version(), "test");
+
+        runtime.set(Arc::new(definitions));
+        assert_eq!(runtime.version(), "test");
+
+        let user_agent = runtime.with_parser(|p| p.parse_user_agent(REFERENCE_USER_AGENT));
+        assert_eq!(user_agent.family, "Chrome");
+        assert_eq!(user_agent.major.as_deref(), Some("120"));
+
+        let os = runtime.with_parser(|p| p.parse_os(REFERENCE_USER_AGENT));
+        assert_eq!(os.family, "Windows");
+
+        runtime.reset();
+        assert_eq!(runtime.version(), BUILTIN_VERSION);
+        assert_eq!(definitions_version(), BUILTIN_VERSION);
+    }
+
+    #[test]
+    fn test_invalid_definitions() {
+        let result = Definitions::parse("test", b"user_agent_parsers: [");
+        assert!(matches!(result, Err(DefinitionsError::Invalid(_))));
+
+        let result = Definitions::parse("test", b"user_agent_parsers: [{regex: '('}]");
+        assert!(matches!(result, Err(DefinitionsError::Invalid(_))));
+
+        let empty = "user_agent_parsers: []\nos_parsers: []\ndevice_parsers: []";
+        let result = Definitions::parse("test", empty.as_bytes());
+        assert!(matches!(result, Err(DefinitionsError::Unrecognized)));
+    }
 }
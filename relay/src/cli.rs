@@ -12,6 +12,8 @@ use uuid::Uuid;
 
 use crate::cliapp::make_app;
 use crate::healthcheck::healthcheck;
+use crate::process::process;
+use crate::spool::manage_spool;
 use crate::utils::get_theme;
 use crate::{setup, utils};
 
@@ -66,6 +68,10 @@ pub fn execute() -> Result<()> {
         manage_credentials(config, matches)
     } else if let Some(matches) = matches.subcommand_matches("healthcheck") {
         healthcheck(&config, matches)
+    } else if let Some(matches) = matches.subcommand_matches("process") {
+        process(config, matches)
+    } else if let Some(matches) = matches.subcommand_matches("spool") {
+        manage_spool(&config, matches)
     } else if let Some(matches) = matches.subcommand_matches("run") {
         // override config with run command args
         let arg_config = extract_config_args(matches);
@@ -345,4 +345,154 @@ pub fn make_app() -> Command {
                         .required(false),
                 )
         )
+        .subcommand(
+            Command::new("spool")
+                .about("Inspect and repair the envelope spool")
+                .after_help(
+                    "This command operates on the SQLite spool files of the envelope \
+                     buffer.  Relay must not be running while the spool is modified. \
+                     By default, all partitions configured in `spool.envelopes` are \
+                     used.",
+                )
+                .subcommand_required(true)
+                .arg(
+                    Arg::new("partition")
+                        .long("partition")
+                        .global(true)
+                        .value_parser(clap::value_parser!(u8))
+                        .help("Only use the spool file of this partition."),
+                )
+                .arg(
+                    Arg::new("file")
+                        .long("file")
+                        .global(true)
+                        .value_hint(ValueHint::FilePath)
+                        .value_parser(ValueParser::path_buf())
+                        .conflicts_with("partition")
+                        .help("Use this spool file instead of the configured spool."),
+                )
+                .subcommand(
+                    Command::new("list")
+                        .about("List envelope counts, sizes and ages per project key pair"),
+                )
+                .subcommand(
+                    spool_filter_args(Command::new("export"))
+                        .about("Export envelopes to files without removing them")
+                        .arg(
+                            Arg::new("output")
+                                .long("output")
+                                .short('o')
+                                .required(true)
+                                .value_hint(ValueHint::DirPath)
+                                .value_parser(ValueParser::path_buf())
+                                .help("The directory to write envelope files to."),
+                        ),
+                )
+                .subcommand(
+                    spool_filter_args(Command::new("delete"))
+                        .about("Delete envelopes by project key or age")
+                        .arg(
+                            Arg::new("all")
+                                .long("all")
+                                .action(ArgAction::SetTrue)
+                                .help("Allow deleting all envelopes if no filter is given."),
+                        ),
+                )
+                .subcommand(
+                    Command::new("compact")
+                        .about("Reclaim unused disk space of the spool files"),
+                )
+                .subcommand(
+                    Command::new("import")
+                        .about("Import envelopes from the spool of another relay")
+                        .arg(
+                            Arg::new("source")
+                                .value_name("PATH")
+                                .required(true)
+                                .num_args(1..)
+                                .value_hint(ValueHint::AnyPath)
+                                .value_parser(ValueParser::path_buf())
+                                .help("Spool files or spool directories to import."),
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("process")
+                .about("Process envelopes offline")
+                .after_help(
+                    "This command runs envelopes through the processing pipeline \
+                     without connecting to the upstream or any other service. It \
+                     prints a JSON report per envelope containing the resulting \
+                     envelopes or store messages, outcomes, extracted metrics and \
+                     the dynamic sampling decision.  Inputs can be envelope files, \
+                     directories of envelope files, or NDJSON files with one \
+                     envelope per line encoded as JSON string.",
+                )
+                .arg(
+                    Arg::new("input")
+                        .value_name("PATH")
+                        .required(true)
+                        .num_args(1..)
+                        .value_hint(ValueHint::AnyPath)
+                        .value_parser(ValueParser::path_buf())
+                        .help("Envelope files, directories or NDJSON files to process."),
+                )
+                .arg(
+                    Arg::new("project_config")
+                        .long("project-config")
+                        .short('p')
+                        .required(true)
+                        .value_hint(ValueHint::FilePath)
+                        .value_parser(ValueParser::path_buf())
+                        .help("Path to the project config or full project info as JSON."),
+                )
+                .arg(
+                    Arg::new("sampling_project_config")
+                        .long("sampling-project-config")
+                        .value_hint(ValueHint::FilePath)
+                        .value_parser(ValueParser::path_buf())
+                        .help(
+                            "Path to the project config of the trace root. Defaults to \
+                             the project config.",
+                        ),
+                )
+                .arg(
+                    Arg::new("global_config")
+                        .long("global-config")
+                        .short('g')
+                        .value_hint(ValueHint::FilePath)
+                        .value_parser(ValueParser::path_buf())
+                        .help("Path to the global config as JSON. Defaults to an empty config."),
+                )
+                .arg(
+                    Arg::new("pretty")
+                        .long("pretty")
+                        .action(ArgAction::SetTrue)
+                        .help("Pretty print the JSON reports."),
+                ),
+        )
+}
+
+/// Adds arguments to select envelopes in the spool.
+fn spool_filter_args(command: Command) -> Command {
+    command
+        .arg(
+            Arg::new("own_key")
+                .long("own-key")
+                .value_name("PROJECT_KEY")
+                .help("Only select envelopes of this project key."),
+        )
+        .arg(
+            Arg::new("sampling_key")
+                .long("sampling-key")
+                .value_name("PROJECT_KEY")
+                .help("Only select envelopes with this sampling project key."),
+        )
+        .arg(
+            Arg::new("older_than")
+                .long("older-than")
+                .value_name("SECONDS")
+                .value_parser(clap::value_parser!(u64))
+                .help("Only select envelopes received more than this many seconds ago."),
+        )
 }
@@ -124,11 +124,11 @@
 mod cli;
 mod cliapp;
 mod healthcheck;
+mod process;
 mod setup;
+mod spool;
 mod utils;
 
-use std::process;
-
 use relay_log::Hub;
 
 #[global_allocator]
@@ -144,5 +144,5 @@ pub fn main() {
     };
 
     Hub::current().client().map(|x| x.close(None));
-    process::exit(exit_code);
+    std::process::exit(exit_code);
 }
@@ -0,0 +1,186 @@
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::ArgMatches;
+use relay_config::Config;
+use relay_server::OfflineProcessor;
+use serde_json::json;
+
+/// Processes envelopes from the command line without network access.
+///
+/// Writes one JSON report per envelope to stdout.
+pub fn process(config: Config, matches: &ArgMatches) -> Result<()> {
+    let project = read_file(matches.get_one::<PathBuf>("project_config"))?
+        .context("`project_config` is required")?;
+    let sampling_project = read_file(matches.get_one::<PathBuf>("sampling_project_config"))?;
+    let global_config = read_file(matches.get_one::<PathBuf>("global_config"))?;
+    let pretty = matches.get_flag("pretty");
+
+    let mut processor = OfflineProcessor::new(
+        config,
+        &project,
+        sampling_project.as_deref(),
+        global_config.as_deref(),
+    )?;
+
+    let mut stdout = io::stdout().lock();
+    let mut process_input = |source: String, payload: Vec<u8>| -> Result<()> {
+        let output = match processor.process(payload) {
+            Ok(report) => json!({ "source": source, "report": report }),
+            Err(error) => json!({ "source": source, "error": format!("{error:#}") }),
+        };
+
+        if pretty {
+            serde_json::to_writer_pretty(&mut stdout, &output)?;
+        } else {
+            serde_json::to_writer(&mut stdout, &output)?;
+        }
+        writeln!(stdout)?;
+        Ok(())
+    };
+
+    for path in matches
+        .get_many::<PathBuf>("input")
+        .context("`input` is required")?
+    {
+        for_each_input(path, &mut process_input)?;
+    }
+
+    Ok(())
+}
+
+fn read_file(path: Option<&PathBuf>) -> Result<Option<Vec<u8>>> {
+    path.map(|path| fs::read(path).with_context(|| format!("failed to read {}", path.display())))
+        .transpose()
+}
+
+/// Reads envelopes from a file, a directory or a NDJSON file and passes them to `f`.
+///
+/// Directories are read non-recursively in file name order. Files with a `.ndjson` or `.jsonl`
+/// extension contain one envelope per line, encoded as JSON string. Envelopes are read one at a
+/// time, so that large inputs do not have to fit into memory.
+fn for_each_input(path: &Path, f: &mut impl FnMut(String, Vec<u8>) -> Result<()>) -> Result<()> {
+    if path.is_dir() {
+        let mut entries = fs::read_dir(path)
+            .with_context(|| format!("failed to read directory {}", path.display()))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        entries.sort();
+
+        for entry in entries.iter().filter(|entry| entry.is_file()) {
+            for_each_input(entry, f)?;
+        }
+
+        return Ok(());
+    }
+
+    let is_ndjson = path
+        .extension()
+        .is_some_and(|ext| ext == "ndjson" || ext == "jsonl");
+
+    if !is_ndjson {
+        let contents =
+            fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+        return f(path.display().to_string(), contents);
+    }
+
+    let file = File::open(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let mut reader = BufReader::new(file);
+    let mut line = Vec::new();
+
+    for index in 1.. {
+        line.clear();
+        let read = reader
+            .read_until(b'\n', &mut line)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        if read == 0 {
+            break;
+        }
+
+        if line.iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+
+        let source = format!("{}:{index}", path.display());
+        let envelope: String = serde_json::from_slice(&line)
+            .with_context(|| format!("expected an envelope as JSON string in {source}"))?;
+        f(source, envelope.into_bytes())?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(path: &Path) -> Vec<(String, String)> {
+        let mut inputs = Vec::new();
+        for_each_input(path, &mut |source, payload| {
+            inputs.push((source, String::from_utf8(payload).unwrap()));
+            Ok(())
+        })
+        .unwrap();
+        inputs
+    }
+
+    #[test]
+    fn test_single_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("envelope.txt");
+        fs::write(&path, "{}\n{\"type\":\"event\"}\n{}\n").unwrap();
+
+        assert_eq!(
+            collect(&path),
+            [(
+                path.display().to_string(),
+                "{}\n{\"type\":\"event\"}\n{}\n".to_owned()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_ndjson() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("envelopes.ndjson");
+        fs::write(&path, "\"first\\n\"\n\n\"second\"\n").unwrap();
+
+        let source = |index| format!("{}:{index}", path.display());
+        assert_eq!(
+            collect(&path),
+            [
+                (source(1), "first\n".to_owned()),
+                (source(3), "second".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ndjson_invalid_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("envelopes.jsonl");
+        fs::write(&path, "{}\n").unwrap();
+
+        let result = for_each_input(&path, &mut |_, _| Ok(()));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("b.txt"), "second").unwrap();
+        fs::write(dir.path().join("a.txt"), "first").unwrap();
+        fs::write(dir.path().join("c.jsonl"), "\"third\"\n").unwrap();
+        // Directories are not read recursively.
+        fs::create_dir(dir.path().join("nested")).unwrap();
+        fs::write(dir.path().join("nested").join("d.txt"), "ignored").unwrap();
+
+        let payloads: Vec<_> = collect(dir.path())
+            .into_iter()
+            .map(|(_, payload)| payload)
+            .collect();
+        assert_eq!(payloads, ["first", "second", "third"]);
+    }
+}
@@ -0,0 +1,228 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use clap::ArgMatches;
+use relay_config::Config;
+use relay_server::{SpoolFilter, SqliteSpoolFile};
+
+/// Manages the envelope spool of a stopped Relay.
+pub fn manage_spool(config: &Config, matches: &ArgMatches) -> Result<()> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+
+    let paths = spool_paths(config, matches)?;
+
+    runtime.block_on(async {
+        if let Some(_matches) = matches.subcommand_matches("list") {
+            list(&paths).await
+        } else if let Some(matches) = matches.subcommand_matches("export") {
+            export(&paths, matches).await
+        } else if let Some(matches) = matches.subcommand_matches("delete") {
+            delete(&paths, matches).await
+        } else if let Some(_matches) = matches.subcommand_matches("compact") {
+            compact(&paths).await
+        } else if let Some(matches) = matches.subcommand_matches("import") {
+            import(&paths, matches).await
+        } else {
+            unreachable!();
+        }
+    })
+}
+
+/// Returns the spool files selected on the command line.
+fn spool_paths(config: &Config, matches: &ArgMatches) -> Result<Vec<PathBuf>> {
+    if let Some(path) = matches.get_one::<PathBuf>("file") {
+        return Ok(vec![path.clone()]);
+    }
+
+    let partitions = match matches.get_one::<u8>("partition") {
+        Some(partition) => vec![*partition],
+        None => (0..config.spool_partitions().get()).collect(),
+    };
+
+    partitions
+        .into_iter()
+        .map(|partition| {
+            config
+                .spool_envelopes_path(partition)
+                .context("no spool path configured, use `--file` to select a spool file")
+        })
+        .collect()
+}
+
+fn filter(matches: &ArgMatches) -> Result<SpoolFilter> {
+    let mut filter = SpoolFilter {
+        own_key: matches
+            .get_one::<String>("own_key")
+            .map(|k| k.parse())
+            .transpose()?,
+        sampling_key: matches
+            .get_one::<String>("sampling_key")
+            .map(|k| k.parse())
+            .transpose()?,
+        received_before: None,
+    };
+
+    if let Some(older_than) = matches.get_one::<u64>("older_than") {
+        filter = filter.older_than(Duration::from_secs(*older_than));
+    }
+
+    Ok(filter)
+}
+
+async fn list(paths: &[PathBuf]) -> Result<()> {
+    for path in paths {
+        if !path.exists() {
+            continue;
+        }
+
+        let spool = SqliteSpoolFile::open(path).await?;
+        let summaries = spool.summary().await?;
+        spool.close().await;
+
+        println!("{}", path.display());
+        println!(
+            "  {:<32}  {:<32}  {:>10}  {:>12}  {:<25}  {:<25}",
+            "own key", "sampling key", "envelopes", "bytes", "oldest", "newest"
+        );
+        for summary in summaries {
+            println!(
+                "  {:<32}  {:<32}  {:>10}  {:>12}  {:<25}  {:<25}",
+                summary.project_key_pair.own_key,
+                summary.project_key_pair.sampling_key,
+                summary.count,
+                summary.size,
+                summary.oldest.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+                summary.newest.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn export(paths: &[PathBuf], matches: &ArgMatches) -> Result<()> {
+    let filter = filter(matches)?;
+    let output = matches
+        .get_one::<PathBuf>("output")
+        .context("`output` is required")?;
+    fs::create_dir_all(output)?;
+
+    let mut exported = 0;
+    for path in paths.iter().filter(|path| path.exists()) {
+        let spool = SqliteSpoolFile::open(path).await?;
+        let prefix = file_name(path);
+
+        spool
+            .for_each(&filter, |envelope| {
+                let name = format!(
+                    "{prefix}-{}-{}-{exported}.envelope",
+                    envelope.own_key(),
+                    envelope.received_at().timestamp_millis(),
+                );
+                let mut file = fs::File::create(output.join(name))?;
+                file.write_all(&envelope.into_payload()?)?;
+                exported += 1;
+                Ok(())
+            })
+            .await?;
+
+        spool.close().await;
+    }
+
+    println!("Exported {exported} envelopes to {}", output.display());
+    Ok(())
+}
+
+async fn delete(paths: &[PathBuf], matches: &ArgMatches) -> Result<()> {
+    let filter = filter(matches)?;
+    if filter.is_empty() && !matches.get_flag("all") {
+        bail!("refusing to delete all envelopes without `--all`");
+    }
+
+    for path in paths.iter().filter(|path| path.exists()) {
+        let spool = SqliteSpoolFile::open(path).await?;
+        let deleted = spool.delete(&filter).await?;
+        spool.close().await;
+
+        println!("Deleted {deleted} envelopes from {}", path.display());
+    }
+
+    Ok(())
+}
+
+async fn compact(paths: &[PathBuf]) -> Result<()> {
+    for path in paths.iter().filter(|path| path.exists()) {
+        let before = fs::metadata(path)?.len();
+
+        let spool = SqliteSpoolFile::open(path).await?;
+        spool.vacuum().await?;
+        spool.close().await;
+
+        let after = fs::metadata(path)?.len();
+        println!(
+            "Compacted {} from {before} to {after} bytes",
+            path.display()
+        );
+    }
+
+    Ok(())
+}
+
+async fn import(paths: &[PathBuf], matches: &ArgMatches) -> Result<()> {
+    let [target] = paths else {
+        bail!("select a single target spool with `--partition` or `--file`");
+    };
+
+    let mut sources = Vec::new();
+    for source in matches
+        .get_many::<PathBuf>("source")
+        .context("`source` is required")?
+    {
+        collect_sources(source, &mut sources)?;
+    }
+
+    let spool = SqliteSpoolFile::create(target).await?;
+    for source in sources {
+        let imported = spool.import(&source).await?;
+        println!("Imported {imported} envelopes from {}", source.display());
+    }
+    spool.close().await;
+
+    Ok(())
+}
+
+/// Collects spool files from a file or a spool directory.
+///
+/// The write-ahead log and shared memory files of SQLite are skipped, they are merged into the
+/// database when it is opened.
+fn collect_sources(path: &Path, sources: &mut Vec<PathBuf>) -> Result<()> {
+    if !path.is_dir() {
+        sources.push(path.to_owned());
+        return Ok(());
+    }
+
+    let mut entries = fs::read_dir(path)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+
+    for entry in entries {
+        let name = file_name(&entry);
+        if entry.is_file() && !name.ends_with("-wal") && !name.ends_with("-shm") {
+            sources.push(entry);
+        }
+    }
+
+    Ok(())
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
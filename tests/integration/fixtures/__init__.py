@@ -229,6 +229,34 @@ class SentryLike:
 
         response.raise_for_status()
 
+    def send_zipkin_spans(
+        self,
+        project_id,
+        json=None,
+        bytes=None,
+        headers=None,
+        dsn_key_idx=0,
+        dsn_key=None,
+    ):
+
+        if dsn_key is None:
+            dsn_key = self.get_dsn_public_key(project_id, dsn_key_idx)
+
+        url = f"/api/{project_id}/integration/zipkin/api/v2/spans?sentry_key={dsn_key}"
+
+        if json:
+            headers = {
+                "Content-Type": "application/json",
+                **(headers or {}),
+            }
+
+            response = self.post(url, headers=headers, json=json)
+        else:
+            response = self.post(url, headers=headers, data=bytes)
+
+        response.raise_for_status()
+        return response
+
     def send_otel_logs(
         self,
         project_id,
@@ -275,6 +303,44 @@ class SentryLike:
         response.raise_for_status()
         return response
 
+    def send_syslog_logs(
+        self,
+        project_id,
+        data=None,
+        headers=None,
+        dsn_key_idx=0,
+        dsn_key=None,
+    ):
+
+        if dsn_key is None:
+            dsn_key = self.get_dsn_public_key(project_id, dsn_key_idx)
+
+        url = f"/api/{project_id}/integration/syslog/logs?sentry_key={dsn_key}"
+
+        response = self.post(url, headers=headers, data=data)
+
+        response.raise_for_status()
+        return response
+
+    def send_elasticsearch_bulk(
+        self,
+        project_id,
+        data=None,
+        headers=None,
+        dsn_key_idx=0,
+        dsn_key=None,
+    ):
+
+        if dsn_key is None:
+            dsn_key = self.get_dsn_public_key(project_id, dsn_key_idx)
+
+        url = f"/api/{project_id}/integration/elasticsearch/_bulk?sentry_key={dsn_key}"
+
+        response = self.post(url, headers=headers, data=data)
+
+        response.raise_for_status()
+        return response
+
     def send_options(self, project_id, headers=None, dsn_key_idx=0):
         headers = {
             "X-Sentry-Auth": self.get_auth_header(project_id, dsn_key_idx),
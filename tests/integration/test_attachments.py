@@ -8,6 +8,7 @@ from requests.exceptions import HTTPError
 from sentry_sdk.envelope import Envelope, Item, PayloadRef
 
 from .asserts import matches_any
+from .consts import DUMMY_UPLOAD_LOCATION
 from .test_store import make_transaction
 
 
@@ -783,3 +784,64 @@ def test_size_limits(mini_sentry, relay, limit, expected_status_code):
     )
 
     assert response.status_code == expected_status_code
+
+
+@pytest.mark.parametrize("feature_enabled", [False, True])
+def test_envelope_attachment_uploads(mini_sentry, relay, dummy_upload, feature_enabled):
+    project_id = 42
+    project_config = mini_sentry.add_full_project_config(project_id)
+    if feature_enabled:
+        project_config["config"].setdefault("features", []).append(
+            "projects:relay-envelope-attachment-uploads"
+        )
+    mini_sentry.global_config["options"]["relay.endpoint-fetch-config.enabled"] = True
+
+    relay = relay(mini_sentry, {"upload": {"envelope_attachment_threshold": 100}})
+
+    # Uploads are only decided on cached project configs, send an event to fetch the config.
+    relay.send_event(project_id)
+    mini_sentry.get_captured_envelope()
+
+    large_content = b"x" * 1000
+    envelope = Envelope(headers=[["event_id", "515539018c9b4260a6f999572f1661ee"]])
+    envelope.add_event({"message": "Hello, World!"})
+    envelope.add_item(
+        Item(
+            payload=PayloadRef(bytes=b"small"),
+            type="attachment",
+            filename="small.txt",
+        )
+    )
+    envelope.add_item(
+        Item(
+            payload=PayloadRef(bytes=large_content),
+            type="attachment",
+            filename="large.txt",
+            content_type="text/plain",
+        )
+    )
+    relay.send_envelope(project_id, envelope)
+
+    envelope = mini_sentry.get_captured_envelope()
+    by_name = {
+        item.headers.get("filename"): item
+        for item in envelope.items
+        if item.headers.get("type") == "attachment"
+    }
+
+    assert by_name["small.txt"].payload.bytes == b"small"
+
+    large = by_name["large.txt"]
+    if feature_enabled:
+        assert (
+            large.headers["content_type"]
+            == "application/vnd.sentry.attachment-ref+json"
+        )
+        assert large.headers["attachment_length"] == len(large_content)
+        assert json.loads(large.payload.bytes) == {
+            "location": DUMMY_UPLOAD_LOCATION,
+            "content_type": "text/plain",
+        }
+    else:
+        assert large.headers["content_type"] == "text/plain"
+        assert large.payload.bytes == large_content
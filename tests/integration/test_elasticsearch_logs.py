@@ -0,0 +1,150 @@
+from datetime import datetime, timezone
+import json
+
+from .asserts import matches_any, time_within_delta
+
+from sentry_relay.consts import DataCategory
+
+
+def _bulk(*actions):
+    return "".join(json.dumps(line) + "\n" for line in actions)
+
+
+def test_elasticsearch_bulk(
+    mini_sentry, relay, relay_with_processing, outcomes_consumer, items_consumer
+):
+    """Test log ingestion through the Elasticsearch bulk API."""
+    items_consumer = items_consumer()
+    outcomes_consumer = outcomes_consumer()
+    project_id = 42
+    project_config = mini_sentry.add_full_project_config(project_id)
+    project_config["config"]["features"] = ["organizations:ourlogs-ingestion"]
+
+    relay = relay(relay_with_processing())
+
+    payload = _bulk(
+        {"create": {"_index": "logs", "_id": "1"}},
+        {
+            "@timestamp": datetime.now(timezone.utc).isoformat(),
+            "message": "Connection refused",
+            "log": {"level": "error", "logger": "db"},
+            "kubernetes": {"pod": {"name": "api-1"}},
+        },
+        {"delete": {"_id": "2"}},
+    )
+
+    response = relay.send_elasticsearch_bulk(
+        project_id,
+        data=payload,
+        headers={"Content-Type": "application/x-ndjson"},
+    )
+
+    assert response.json() == {
+        "took": matches_any(),
+        "errors": True,
+        "items": [
+            {
+                "create": {
+                    "_index": "logs",
+                    "_id": "1",
+                    "status": 201,
+                    "result": "created",
+                }
+            },
+            {
+                "delete": {
+                    "_index": "sentry",
+                    "_id": "2",
+                    "status": 400,
+                    "error": {
+                        "type": "action_request_validation_exception",
+                        "reason": "delete actions are not supported",
+                    },
+                }
+            },
+        ],
+    }
+
+    items = items_consumer.get_items(n=1)
+    assert items == [
+        {
+            "organizationId": "1",
+            "projectId": "42",
+            "traceId": matches_any(),
+            "itemId": matches_any(),
+            "itemType": "TRACE_ITEM_TYPE_LOG",
+            "timestamp": matches_any(),
+            "attributes": {
+                "sentry.origin": {"stringValue": "auto.log_drain.elasticsearch"},
+                "sentry.body": {"stringValue": "Connection refused"},
+                "sentry.severity_text": {"stringValue": "error"},
+                "log.logger": {"stringValue": "db"},
+                "kubernetes.pod.name": {"stringValue": "api-1"},
+                "sentry.observed_timestamp_nanos": {"stringValue": matches_any()},
+                "sentry.timestamp_precise": {
+                    "intValue": time_within_delta(expect_resolution="ns")
+                },
+                "sentry.payload_size_bytes": {"intValue": matches_any()},
+                "sentry._meta.fields.trace_id": {
+                    "stringValue": '{"meta":{"":{"rem":[["trace_id.missing","s"]]}}}'
+                },
+            },
+            "clientSampleRate": 1.0,
+            "serverSampleRate": 1.0,
+            "retentionDays": 90,
+            "received": matches_any(),
+            "downsampledRetentionDays": 90,
+            "outcomes": {
+                "categoryCount": [
+                    {
+                        "dataCategory": DataCategory.LOG_ITEM.value,
+                        "quantity": "1",
+                    },
+                    {
+                        "dataCategory": DataCategory.LOG_BYTE.value,
+                        "quantity": matches_any(),
+                    },
+                ],
+                "keyId": "123",
+            },
+        }
+    ]
+
+
+def test_elasticsearch_bulk_rate_limited(mini_sentry, relay):
+    """Rate limited documents are reported as `429` items."""
+    project_id = 42
+    project_config = mini_sentry.add_full_project_config(project_id)
+    project_config["config"]["features"] = ["organizations:ourlogs-ingestion"]
+    project_config["config"]["quotas"] = [
+        {
+            "id": "logs-limit",
+            "categories": ["log_item"],
+            "limit": 0,
+            "reasonCode": "logs_exceeded",
+        }
+    ]
+
+    relay = relay(mini_sentry)
+
+    payload = _bulk({"index": {}}, {"message": "hello"})
+
+    # The first request populates the rate limits.
+    relay.send_elasticsearch_bulk(project_id, data=payload)
+    response = relay.send_elasticsearch_bulk(project_id, data=payload)
+
+    body = response.json()
+    assert body["errors"] is True
+    assert body["items"] == [
+        {
+            "index": {
+                "_index": "sentry",
+                "_id": matches_any(),
+                "status": 429,
+                "error": {
+                    "type": "es_rejected_execution_exception",
+                    "reason": "rate limited",
+                },
+            }
+        }
+    ]
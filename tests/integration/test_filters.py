@@ -453,6 +453,39 @@ def test_global_filters_drop_events(
     assert outcomes[0]["reason"] == "premature-releases"
 
 
+def test_report_only_filters_keep_events(
+    mini_sentry, relay_with_processing, events_consumer, outcomes_consumer
+):
+    events_consumer = events_consumer()
+    outcomes_consumer = outcomes_consumer()
+
+    mini_sentry.global_config["filters"] = {
+        "version": 1,
+        "filters": [
+            {
+                "id": "premature-releases",
+                "isEnabled": True,
+                "reportOnly": True,
+                "condition": {
+                    "op": "eq",
+                    "name": "event.release",
+                    "value": "0.0.0",
+                },
+            }
+        ],
+    }
+
+    project_id = 42
+    mini_sentry.add_full_project_config(project_id)
+    relay = relay_with_processing()
+
+    relay.send_event(project_id, {"release": "0.0.0"})
+
+    event, _ = events_consumer.get_event()
+    assert ["report_only_filter", "premature-releases"] in event["tags"]
+    outcomes_consumer.assert_empty()
+
+
 def profile_transaction_item():
     now = datetime.datetime.now(datetime.UTC)
     transaction = {
@@ -0,0 +1,125 @@
+import base64
+import json
+import time
+
+import pytest
+from cryptography.hazmat.primitives import serialization
+from cryptography.hazmat.primitives.asymmetric.ed25519 import Ed25519PrivateKey
+from requests import HTTPError
+from sentry_sdk.envelope import Envelope
+
+
+def b64(data):
+    return base64.urlsafe_b64encode(data).rstrip(b"=").decode()
+
+
+def key_pair():
+    secret_key = Ed25519PrivateKey.generate()
+    public_key = secret_key.public_key().public_bytes(
+        serialization.Encoding.Raw, serialization.PublicFormat.Raw
+    )
+    return secret_key, b64(public_key)
+
+
+def sign_token(secret_key, public_key, expires_in=60, **claims):
+    claims = {"pk": public_key, "exp": int(time.time()) + expires_in, **claims}
+    encoded_claims = b64(json.dumps(claims).encode())
+    signature = b64(secret_key.sign(encoded_claims.encode()))
+    return f"{encoded_claims}.{signature}"
+
+
+def project_config(mini_sentry, project_id, keys, required=True):
+    config = mini_sentry.add_basic_project_config(project_id)
+    config["config"]["ingestTokens"] = {"keys": keys, "required": required}
+    return mini_sentry.get_dsn_public_key(project_id)
+
+
+def test_ingest_token_required(mini_sentry, relay):
+    """
+    Tests that projects requiring ingestion tokens accept valid tokens and reject requests
+    without a token.
+    """
+    project_id = 42
+    secret_key, public_key = key_pair()
+    dsn_key = project_config(mini_sentry, project_id, [public_key])
+
+    relay = relay(mini_sentry)
+
+    relay.send_event(project_id, {"message": "missing token"})
+
+    outcome = mini_sentry.get_client_report(timeout=1)
+    assert outcome["discarded_events"] == [
+        {"reason": "missing_ingest_token", "category": "error", "quantity": 1}
+    ]
+
+    with pytest.raises(HTTPError, match="403 Client Error"):
+        relay.send_event(project_id, {"message": "missing token"})
+
+    token = sign_token(secret_key, dsn_key)
+    relay.send_event(
+        project_id,
+        {"message": "valid token"},
+        headers={"X-Sentry-Ingest-Token": token},
+    )
+
+    event = mini_sentry.get_captured_envelope().get_event()
+    assert event["logentry"]["formatted"] == "valid token"
+
+
+@pytest.mark.parametrize(
+    "claims",
+    [
+        pytest.param({"expires_in": -60}, id="expired"),
+        pytest.param({"cat": ["transaction"]}, id="category"),
+        pytest.param({"uid": "jane"}, id="user_without_dsc"),
+    ],
+)
+def test_ingest_token_invalid(mini_sentry, relay, claims):
+    """
+    Tests that expired tokens and tokens that do not permit the submitted data are rejected.
+    """
+    project_id = 42
+    secret_key, public_key = key_pair()
+    dsn_key = project_config(mini_sentry, project_id, [public_key], required=False)
+
+    relay = relay(mini_sentry)
+
+    token = sign_token(secret_key, dsn_key, **claims)
+    headers = {"X-Sentry-Ingest-Token": token}
+    relay.send_event(project_id, {"message": "invalid token"}, headers=headers)
+
+    outcome = mini_sentry.get_client_report(timeout=1)
+    assert outcome["discarded_events"] == [
+        {"reason": "invalid_ingest_token", "category": "error", "quantity": 1}
+    ]
+
+    with pytest.raises(HTTPError, match="403 Client Error"):
+        relay.send_event(project_id, {"message": "invalid token"}, headers=headers)
+
+
+def test_ingest_token_envelope_auth(mini_sentry, relay):
+    """
+    Tests that the ingestion token is retained if the request is authenticated with the DSN in
+    the envelope headers instead of an auth header.
+    """
+    project_id = 42
+    secret_key, public_key = key_pair()
+    dsn_key = project_config(mini_sentry, project_id, [public_key])
+
+    relay = relay(mini_sentry)
+
+    envelope = Envelope(headers={"dsn": relay.get_dsn(project_id)})
+    envelope.add_event({"message": "envelope auth"})
+
+    response = relay.post(
+        f"/api/{project_id}/envelope/",
+        data=envelope.serialize(),
+        headers={
+            "Content-Type": "application/x-sentry-envelope",
+            "X-Sentry-Ingest-Token": sign_token(secret_key, dsn_key),
+        },
+    )
+    response.raise_for_status()
+
+    event = mini_sentry.get_captured_envelope().get_event()
+    assert event["logentry"]["formatted"] == "envelope auth"
@@ -181,3 +181,50 @@ def test_monitor_post_json_body(mini_sentry, relay):
             "timezone": "America/Los_Angles",
         },
     }
+
+
+def test_monitor_invalid_config_with_processing(
+    mini_sentry, relay_with_processing, monitors_consumer, outcomes_consumer
+):
+    relay = relay_with_processing()
+    mini_sentry.add_basic_project_config(42)
+    monitors_consumer = monitors_consumer()
+    outcomes_consumer = outcomes_consumer()
+
+    check_in = generate_check_in("my-monitor")
+    check_in["monitor_config"] = {
+        "schedule": {"type": "crontab", "value": "0 * * *"},
+    }
+    relay.send_check_in(42, check_in)
+
+    # The check-in is kept, only the invalid monitor config is dropped.
+    check_in, _ = monitors_consumer.get_check_in()
+    assert check_in == {
+        "check_in_id": "a460c25ff2554577b920fcfacae4e5eb",
+        "monitor_slug": "my-monitor",
+        "status": "in_progress",
+        "duration": 21.0,
+    }
+
+    outcomes_consumer.assert_empty()
+
+
+def test_monitor_config_normalized_with_processing(
+    mini_sentry, relay_with_processing, monitors_consumer
+):
+    relay = relay_with_processing()
+    mini_sentry.add_basic_project_config(42)
+    monitors_consumer = monitors_consumer()
+
+    check_in = generate_check_in("my-monitor")
+    check_in["monitor_config"] = {
+        "schedule": {"type": "crontab", "value": "@daily"},
+        "timezone": "europe/vienna",
+    }
+    relay.send_check_in(42, check_in)
+
+    check_in, _ = monitors_consumer.get_check_in()
+    assert check_in["monitor_config"] == {
+        "schedule": {"type": "crontab", "value": "0 0 * * *"},
+        "timezone": "Europe/Vienna",
+    }
@@ -495,6 +495,53 @@ def test_ourlog_extraction_with_string_pii_scrubbing(
     }
 
 
+def test_ourlog_attribute_policy(mini_sentry, relay):
+    project_id = 42
+    project_config = mini_sentry.add_full_project_config(project_id)
+    project_config["config"]["features"] = [
+        "organizations:ourlogs-ingestion",
+    ]
+    project_config["config"]["trimming"] = {
+        "log": {
+            "attributes": {
+                "rename": {"legacy.name": "app.name"},
+                "deny": ["internal.*"],
+                "rules": [{"keys": ["app.*"], "maxLength": 8}],
+            }
+        }
+    }
+
+    relay_instance = relay(mini_sentry, options=TEST_CONFIG)
+    ts = datetime.now(timezone.utc)
+
+    envelope = envelope_with_sentry_logs(
+        {
+            "timestamp": ts.timestamp(),
+            "trace_id": "5b8efff798038103d269b633813fc60c",
+            "span_id": "eee19b7ec3c1b174",
+            "level": "info",
+            "body": "Test log",
+            "attributes": {
+                "legacy.name": {"value": "checkout-service", "type": "string"},
+                "internal.debug": {"value": True, "type": "boolean"},
+            },
+        }
+    )
+
+    relay_instance.send_envelope(project_id, envelope)
+
+    envelope = mini_sentry.get_captured_envelope()
+    item_payload = json.loads(envelope.items[0].payload.bytes.decode())
+    item = item_payload["items"][0]
+
+    assert "legacy.name" not in item["attributes"]
+    assert item["attributes"]["app.name"] == {"type": "string", "value": "check..."}
+    assert item["attributes"]["internal.debug"] is None
+    assert item["_meta"]["attributes"]["internal.debug"] == {
+        "": {"rem": [["attribute_policy", "x"]]}
+    }
+
+
 def test_ourlog_extraction_default_pii_scrubbing_attributes(
     mini_sentry,
     relay,
@@ -1753,3 +1753,52 @@ def test_outcomes_as_metrics_forwarded_non_internal(
     )
 
     outcomes_consumer.assert_empty()
+
+
+def test_outcome_ledger(relay, mini_sentry, random_port):
+    """
+    Tests that outcomes are recorded in the local ledger and can be queried on the internal port.
+    """
+    config = {
+        "relay": {"internal_port": random_port()},
+        "outcomes": {"emit_outcomes": True, "ledger": {"enabled": True}},
+    }
+
+    project_config = mini_sentry.add_full_project_config(42)
+    project_config["config"]["filterSettings"] = {"errorMessages": {"patterns": ["*"]}}
+    public_key = project_config["publicKeys"][0]["publicKey"]
+
+    relay = relay(mini_sentry, config)
+    relay.send_event(42, {"message": "hello world"})
+
+    # Outcomes are recorded asynchronously.
+    for _ in range(50):
+        response = relay.get(
+            f"/api/relay/outcomes/?project_key={public_key}", is_internal=True
+        )
+        assert response.ok
+        outcomes = response.json()["outcomes"]
+        if outcomes:
+            break
+        time.sleep(0.1)
+
+    assert outcomes == [
+        {
+            "timestamp": time_within_delta(delta=timedelta(minutes=1)),
+            "project_key": public_key,
+            "category": "error",
+            "outcome": "filtered",
+            "reason": "error-message",
+            "quantity": 1,
+        }
+    ]
+
+    other_key = "a94ae32be2584e0bbd7a4cbb95971fee"
+    response = relay.get(
+        f"/api/relay/outcomes/?project_key={other_key}", is_internal=True
+    )
+    assert response.json() == {"outcomes": []}
+
+    # The ledger is never exposed on the public port.
+    response = relay.get(f"/api/relay/outcomes/?project_key={public_key}")
+    assert response.status_code == 404
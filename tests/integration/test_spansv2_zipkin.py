@@ -0,0 +1,79 @@
+from datetime import datetime, timezone
+
+from .asserts import time_within
+
+
+def test_zipkin_span_ingestion(
+    mini_sentry,
+    relay,
+    relay_with_processing,
+    spans_consumer,
+):
+    spans_consumer = spans_consumer()
+    relay = relay(relay_with_processing())
+
+    project_id = 42
+    mini_sentry.add_full_project_config(project_id)
+
+    ts = datetime.now(timezone.utc)
+    timestamp = int((ts.timestamp() - 1.0) * 1e6)
+
+    response = relay.send_zipkin_spans(
+        project_id,
+        json=[
+            {
+                "traceId": "5af7183fb1d4cf5f",
+                "parentId": "6b221d5bc9e6496c",
+                "id": "352bff9a74ca9ad2",
+                "kind": "SERVER",
+                "name": "get /api",
+                "timestamp": timestamp,
+                "duration": 500000,
+                "localEndpoint": {"serviceName": "backend", "ipv4": "10.0.0.2"},
+                "remoteEndpoint": {"ipv4": "10.0.0.1", "port": 51234},
+                "tags": {"http.method": "GET", "http.path": "/api"},
+                "shared": True,
+            }
+        ],
+    )
+    assert response.status_code == 202
+
+    span = spans_consumer.get_span()
+    assert span["trace_id"] == "00000000000000005af7183fb1d4cf5f"
+    assert span["span_id"] == "352bff9a74ca9ad2"
+    assert span["parent_span_id"] == "6b221d5bc9e6496c"
+    assert span["name"] == "get /api"
+    assert span["is_segment"] is True
+    assert span["status"] == "ok"
+    assert span["start_timestamp"] == time_within(ts.timestamp() - 1.0)
+    assert span["end_timestamp"] == time_within(ts.timestamp() - 0.5)
+
+    attributes = span["attributes"]
+    for key, value in {
+        "http.method": "GET",
+        "http.path": "/api",
+        "network.local.address": "10.0.0.2",
+        "network.peer.address": "10.0.0.1",
+        "resource.service.name": "backend",
+        "sentry.kind": "server",
+        "sentry.origin": "auto.zipkin.spans",
+        "sentry.segment.id": "352bff9a74ca9ad2",
+    }.items():
+        assert attributes[key] == {"type": "string", "value": value}
+    assert attributes["network.peer.port"] == {"type": "integer", "value": 51234}
+    assert attributes["sentry.is_remote"] == {"type": "boolean", "value": True}
+
+
+def test_zipkin_unsupported_content_type(mini_sentry, relay):
+    relay = relay(mini_sentry)
+
+    project_id = 42
+    mini_sentry.add_full_project_config(project_id)
+    dsn_key = mini_sentry.get_dsn_public_key(project_id)
+
+    response = relay.post(
+        f"/api/{project_id}/integration/zipkin/api/v2/spans?sentry_key={dsn_key}",
+        headers={"Content-Type": "text/plain"},
+        data=b"[]",
+    )
+    assert response.status_code == 415
@@ -0,0 +1,142 @@
+from datetime import datetime, timezone
+
+from .asserts import matches_any, time_within_delta
+
+from sentry_relay.consts import DataCategory
+
+
+def _logplex(*messages):
+    """Frames syslog messages with octet counting, like logplex HTTPS drains."""
+    return b"".join(b"%d %s" % (len(m), m) for m in (m.encode() for m in messages))
+
+
+def _expected_item(body, severity_text, attributes):
+    return {
+        "organizationId": "1",
+        "projectId": "42",
+        "traceId": matches_any(),
+        "itemId": matches_any(),
+        "itemType": "TRACE_ITEM_TYPE_LOG",
+        "timestamp": matches_any(),
+        "attributes": {
+            "sentry.origin": {"stringValue": "auto.log_drain.syslog"},
+            "sentry.body": {"stringValue": body},
+            "sentry.severity_text": {"stringValue": severity_text},
+            "sentry.observed_timestamp_nanos": {"stringValue": matches_any()},
+            "sentry.timestamp_precise": {
+                "intValue": time_within_delta(expect_resolution="ns")
+            },
+            "sentry.payload_size_bytes": {"intValue": matches_any()},
+            "sentry._meta.fields.trace_id": {
+                "stringValue": '{"meta":{"":{"rem":[["trace_id.missing","s"]]}}}'
+            },
+            **attributes,
+        },
+        "clientSampleRate": 1.0,
+        "serverSampleRate": 1.0,
+        "retentionDays": 90,
+        "received": matches_any(),
+        "downsampledRetentionDays": 90,
+        "outcomes": {
+            "categoryCount": [
+                {
+                    "dataCategory": DataCategory.LOG_ITEM.value,
+                    "quantity": "1",
+                },
+                {
+                    "dataCategory": DataCategory.LOG_BYTE.value,
+                    "quantity": matches_any(),
+                },
+            ],
+            "keyId": "123",
+        },
+    }
+
+
+def test_syslog_logs_logplex(
+    mini_sentry, relay, relay_with_processing, outcomes_consumer, items_consumer
+):
+    """Test syslog ingestion with logplex framing, as sent by Heroku."""
+    items_consumer = items_consumer()
+    outcomes_consumer = outcomes_consumer()
+    project_id = 42
+    project_config = mini_sentry.add_full_project_config(project_id)
+    project_config["config"]["features"] = ["organizations:ourlogs-ingestion"]
+
+    relay = relay(relay_with_processing())
+
+    now = datetime.now(timezone.utc).isoformat()
+    payload = _logplex(
+        f"<190>1 {now} host app web.1 - State changed from starting to up\n",
+        f'<131>1 {now} web-1 api 4242 request [origin ip="10.0.0.1"] Request failed',
+    )
+
+    relay.send_syslog_logs(
+        project_id,
+        data=payload,
+        headers={"Content-Type": "application/logplex-1"},
+    )
+
+    items = items_consumer.get_items(n=2)
+    assert items == [
+        _expected_item(
+            "State changed from starting to up",
+            "info",
+            {
+                "syslog.facility": {"stringValue": "local7"},
+                "syslog.severity": {"stringValue": "info"},
+                "syslog.hostname": {"stringValue": "host"},
+                "syslog.app_name": {"stringValue": "app"},
+                "syslog.proc_id": {"stringValue": "web.1"},
+            },
+        ),
+        _expected_item(
+            "Request failed",
+            "error",
+            {
+                "syslog.facility": {"stringValue": "local0"},
+                "syslog.severity": {"stringValue": "err"},
+                "syslog.hostname": {"stringValue": "web-1"},
+                "syslog.app_name": {"stringValue": "api"},
+                "syslog.proc_id": {"stringValue": "4242"},
+                "syslog.msg_id": {"stringValue": "request"},
+                "syslog.structured_data.origin.ip": {"stringValue": "10.0.0.1"},
+            },
+        ),
+    ]
+
+
+def test_syslog_logs_text(
+    mini_sentry, relay, relay_with_processing, outcomes_consumer, items_consumer
+):
+    """Test syslog ingestion with newline delimited messages."""
+    items_consumer = items_consumer()
+    outcomes_consumer = outcomes_consumer()
+    project_id = 42
+    project_config = mini_sentry.add_full_project_config(project_id)
+    project_config["config"]["features"] = ["organizations:ourlogs-ingestion"]
+
+    relay = relay(relay_with_processing())
+
+    now = datetime.now(timezone.utc).isoformat()
+    payload = f"<12>1 {now} host app - - - Disk almost full\ninvalid message\n"
+
+    relay.send_syslog_logs(
+        project_id,
+        data=payload,
+        headers={"Content-Type": "text/plain"},
+    )
+
+    items = items_consumer.get_items(n=1)
+    assert items == [
+        _expected_item(
+            "Disk almost full",
+            "warn",
+            {
+                "syslog.facility": {"stringValue": "user"},
+                "syslog.severity": {"stringValue": "warning"},
+                "syslog.hostname": {"stringValue": "host"},
+                "syslog.app_name": {"stringValue": "app"},
+            },
+        ),
+    ]